@@ -0,0 +1,131 @@
+//! Short notes and star ratings attached to files, one
+//! `path<TAB>note[<TAB>stars]` per line in
+//! `~/.local/share/browrs/annotations`.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    io,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+/// The most stars a file can have.
+pub const MAX_RATING: u8 = 5;
+
+/// Star ratings by path, shared with the listings that sort and filter by
+/// them.
+pub type Ratings = Rc<HashMap<PathBuf, u8>>;
+
+#[derive(Debug, Clone, Default)]
+pub struct Annotations {
+    /// Where the notes are saved; `None` keeps them in memory only.
+    file: Option<PathBuf>,
+    notes: BTreeMap<PathBuf, String>,
+    ratings: Ratings,
+}
+
+impl Annotations {
+    pub fn default_file() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("browrs").join("annotations"))
+    }
+
+    /// Reads the notes and ratings kept in `file`, starting empty if it doesn't exist.
+    pub fn load(file: PathBuf) -> Self {
+        let mut notes = BTreeMap::new();
+        let mut ratings = HashMap::new();
+        for line in std::fs::read_to_string(&file).unwrap_or_default().lines() {
+            let mut fields = line.split('\t');
+            let (Some(path), Some(note)) = (fields.next(), fields.next()) else {
+                continue;
+            };
+            if !note.trim().is_empty() {
+                notes.insert(PathBuf::from(path), note.trim().to_string());
+            }
+            let stars = fields
+                .next()
+                .and_then(|stars| stars.trim().parse::<u8>().ok());
+            if let Some(stars) = stars.filter(|stars| (1..=MAX_RATING).contains(stars)) {
+                ratings.insert(PathBuf::from(path), stars);
+            }
+        }
+        Self {
+            file: Some(file),
+            notes,
+            ratings: Rc::new(ratings),
+        }
+    }
+
+    pub fn file(&self) -> Option<&Path> {
+        self.file.as_deref()
+    }
+
+    pub fn get(&self, path: &Path) -> Option<&str> {
+        self.notes.get(path).map(String::as_str)
+    }
+
+    /// Sets the note on `path` and saves; an empty note removes it.
+    pub fn set(&mut self, path: PathBuf, note: &str) -> io::Result<()> {
+        // Kept on one line, so the file stays one note per line
+        let note = note.split_whitespace().collect::<Vec<_>>().join(" ");
+        if note.is_empty() {
+            self.notes.remove(&path);
+        } else {
+            self.notes.insert(path, note);
+        }
+        self.save()
+    }
+
+    /// Stars given to `path`, 0 if it has none.
+    pub fn rating(&self, path: &Path) -> u8 {
+        self.ratings.get(path).copied().unwrap_or(0)
+    }
+
+    pub fn ratings(&self) -> Ratings {
+        Rc::clone(&self.ratings)
+    }
+
+    /// Gives each of `paths` `stars`, 0 taking their rating away, and saves.
+    pub fn rate(&mut self, paths: &[PathBuf], stars: u8) -> io::Result<()> {
+        let ratings = Rc::make_mut(&mut self.ratings);
+        for path in paths {
+            if stars == 0 {
+                ratings.remove(path);
+            } else {
+                ratings.insert(path.clone(), stars.min(MAX_RATING));
+            }
+        }
+        self.save()
+    }
+
+    /// Notes on entries anywhere under `root`, by path relative to it.
+    pub fn under(&self, root: &Path) -> Vec<(PathBuf, String)> {
+        self.notes
+            .range(root.to_path_buf()..)
+            .map_while(|(path, note)| {
+                let relative = path.strip_prefix(root).ok()?;
+                Some((relative.to_path_buf(), note.clone()))
+            })
+            .filter(|(relative, _)| !relative.as_os_str().is_empty())
+            .collect()
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let Some(file) = &self.file else {
+            return Ok(());
+        };
+        if let Some(parent) = file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let paths: BTreeSet<&PathBuf> = self.notes.keys().chain(self.ratings.keys()).collect();
+        let mut contents = String::new();
+        for path in paths {
+            let note = self.notes.get(path).map_or("", String::as_str);
+            contents.push_str(&format!("{}\t{}", path.to_string_lossy(), note));
+            match self.ratings.get(path) {
+                Some(stars) => contents.push_str(&format!("\t{}\n", stars)),
+                None => contents.push('\n'),
+            }
+        }
+        std::fs::write(file, contents)
+    }
+}
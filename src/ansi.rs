@@ -0,0 +1,171 @@
+//! Terminal escape sequences in text, such as the colors in saved command
+//! output, turned into styled lines. SGR colors and attributes are kept;
+//! every other sequence is dropped.
+
+use ratatui::{
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+};
+
+const ESC: char = '\x1b';
+const BEL: char = '\x07';
+
+/// Whether `text` has escape sequences, which must not reach the terminal
+/// as they are.
+pub fn has_escapes(text: &str) -> bool {
+    text.contains([ESC, BEL])
+}
+
+/// Converts text line by line. Styles carry over from one line to the
+/// next, as they do in a terminal.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Parser {
+    style: Style,
+}
+
+impl Parser {
+    pub fn line(&mut self, line: &str) -> Line<'static> {
+        let mut spans = Vec::new();
+        let mut text = String::new();
+        let mut chars = line.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c == BEL {
+                continue;
+            }
+            if c != ESC {
+                text.push(c);
+                continue;
+            }
+            match chars.next() {
+                // Control sequence: parameters, then a final byte
+                Some('[') => {
+                    let mut params = String::new();
+                    let mut last = None;
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            last = Some(c);
+                            break;
+                        }
+                        params.push(c);
+                    }
+                    if last == Some('m') {
+                        if !text.is_empty() {
+                            spans.push(Span::styled(std::mem::take(&mut text), self.style));
+                        }
+                        self.apply(&params);
+                    }
+                }
+                // Operating system command, such as a title or a link: up
+                // to BEL or ESC \
+                Some(']') => {
+                    while let Some(c) = chars.next() {
+                        if c == BEL || (c == ESC && chars.next_if_eq(&'\\').is_some()) {
+                            break;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        if !text.is_empty() {
+            spans.push(Span::styled(text, self.style));
+        }
+        Line::from(spans)
+    }
+
+    /// Applies the `;`-separated parameters of an SGR sequence.
+    fn apply(&mut self, params: &str) {
+        if params.is_empty() {
+            self.style = Style::default();
+            return;
+        }
+        let mut codes = params
+            .split([';', ':'])
+            .map(|code| code.parse::<u16>().unwrap_or(0));
+        while let Some(code) = codes.next() {
+            let style = self.style;
+            self.style = match code {
+                0 => Style::default(),
+                1 => style.add_modifier(Modifier::BOLD),
+                2 => style.add_modifier(Modifier::DIM),
+                3 => style.add_modifier(Modifier::ITALIC),
+                4 => style.add_modifier(Modifier::UNDERLINED),
+                5 | 6 => style.add_modifier(Modifier::SLOW_BLINK),
+                7 => style.add_modifier(Modifier::REVERSED),
+                8 => style.add_modifier(Modifier::HIDDEN),
+                9 => style.add_modifier(Modifier::CROSSED_OUT),
+                22 => style.remove_modifier(Modifier::BOLD | Modifier::DIM),
+                23 => style.remove_modifier(Modifier::ITALIC),
+                24 => style.remove_modifier(Modifier::UNDERLINED),
+                25 => style.remove_modifier(Modifier::SLOW_BLINK),
+                27 => style.remove_modifier(Modifier::REVERSED),
+                28 => style.remove_modifier(Modifier::HIDDEN),
+                29 => style.remove_modifier(Modifier::CROSSED_OUT),
+                30..=37 => style.fg(indexed(code - 30)),
+                38 => match extended(&mut codes) {
+                    Some(color) => style.fg(color),
+                    None => style,
+                },
+                39 => style.fg(Color::Reset),
+                40..=47 => style.bg(indexed(code - 40)),
+                48 => match extended(&mut codes) {
+                    Some(color) => style.bg(color),
+                    None => style,
+                },
+                49 => style.bg(Color::Reset),
+                90..=97 => style.fg(indexed(code - 90 + 8)),
+                100..=107 => style.bg(indexed(code - 100 + 8)),
+                _ => style,
+            };
+        }
+    }
+}
+
+/// The style the parameters of an SGR sequence, like `01;34`, set.
+pub fn style(params: &str) -> Style {
+    let mut parser = Parser::default();
+    parser.apply(params);
+    parser.style
+}
+
+/// One of the 16 basic colors.
+fn indexed(index: u16) -> Color {
+    match index {
+        0 => Color::Black,
+        1 => Color::Red,
+        2 => Color::Green,
+        3 => Color::Yellow,
+        4 => Color::Blue,
+        5 => Color::Magenta,
+        6 => Color::Cyan,
+        7 => Color::Gray,
+        8 => Color::DarkGray,
+        9 => Color::LightRed,
+        10 => Color::LightGreen,
+        11 => Color::LightYellow,
+        12 => Color::LightBlue,
+        13 => Color::LightMagenta,
+        14 => Color::LightCyan,
+        _ => Color::White,
+    }
+}
+
+/// The color after a 38 or 48: `5;n` from the 256-color palette or
+/// `2;r;g;b`.
+fn extended(codes: &mut impl Iterator<Item = u16>) -> Option<Color> {
+    match codes.next()? {
+        5 => {
+            let index = codes.next()?;
+            Some(if index < 16 {
+                indexed(index)
+            } else {
+                Color::Indexed(index.min(255) as u8)
+            })
+        }
+        2 => {
+            let mut channel = || codes.next().map(|c| c.min(255) as u8);
+            Some(Color::Rgb(channel()?, channel()?, channel()?))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,4734 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
+    io::Write,
+    path::{Path, PathBuf},
+    process::Command,
+    rc::Rc,
+    sync::mpsc::{self, Receiver},
+    time::{Duration, Instant, SystemTime},
+};
+
+use crossterm::event::{
+    self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers, MouseButton, MouseEvent,
+    MouseEventKind,
+};
+use ratatui::{
+    DefaultTerminal, Frame,
+    buffer::Buffer,
+    layout::{Constraint, Direction, Margin, Position, Rect},
+    style::{Modifier, Style, Stylize},
+    symbols::border,
+    text::{Line, Span, Text},
+    widgets::{self, Block, Clear, Paragraph, Row, Table, Widget, Wrap},
+};
+
+#[cfg(unix)]
+use crate::attrs;
+use crate::{
+    annotations::Annotations,
+    archive,
+    bookmarks::Bookmarks,
+    cargo::{Project, Task},
+    cleanup::{self, Candidate},
+    config::{self, Config},
+    datetime, desktop, diff, download, editor,
+    entry::{Entry, Special},
+    error::{self, BrowrsError},
+    export::{self, Report},
+    file_actions::{self, FileAction},
+    file_colors, git,
+    graphics::{self, Protocol},
+    grep::Grep,
+    hex::HexView,
+    history, init_terminal,
+    jobs::{self, Jobs},
+    keymap::{Action, Lookup},
+    keys::Chord,
+    kinds::Kind,
+    line_count::LineCounts,
+    log,
+    npm::Package,
+    opener, ops,
+    pane::{self, Listing, Pane},
+    permissions::{PermissionsAction, PermissionsEditor},
+    picker::{Picker, PickerAction, PickerKind},
+    prefetch::Previews,
+    preview::{self, Preview},
+    previewer::Previewers,
+    prompt::{Prompt, PromptAction, PromptKind},
+    qr::{self, QrView},
+    quick_edit::{EditAction, QuickEdit},
+    recall::{self, Recall},
+    restore_terminal,
+    search::Search,
+    sendto::{self, SendTarget},
+    serve::{Server, Share},
+    settings::{self, Setting},
+    smart::{QueryKind, SmartFolder, SmartFolders},
+    snapshots,
+    sort::Sort,
+    tab::{Side, Tab},
+    tasks::{self, Target},
+    theme, touch, trash, vfs,
+    visits::Visits,
+    watch::Watcher,
+};
+
+/// The projects a directory belongs to, each found by its manifest.
+#[derive(Debug, Clone, Default)]
+struct Projects {
+    cargo: Option<Project>,
+    npm: Option<Package>,
+}
+
+/// How long the keys have to stop for before the previews around the cursor
+/// are read ahead.
+const PREFETCH_DELAY: Duration = Duration::from_millis(150);
+
+/// How long a message stays in the status bar.
+const STATUS_TIME: Duration = Duration::from_secs(5);
+/// How long the free space shown in the status bar is trusted for.
+const FREE_SPACE_AGE: Duration = Duration::from_secs(5);
+
+/// How often the config file is checked for changes.
+const CONFIG_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A watcher for the directories shown, unless `watch_interval_ms` is 0.
+fn watcher(config: &Config) -> Option<Watcher> {
+    (config.watch_interval_ms > 0)
+        .then(|| Watcher::start(Duration::from_millis(config.watch_interval_ms)))
+}
+
+/// A modification time as listed, e.g. `2024-05-01 13:45`.
+fn format_time(time: SystemTime) -> String {
+    let time = datetime::local(time);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}",
+        time.year, time.month, time.day, time.hour, time.minute
+    )
+}
+
+/// Completes the path after `cd ` on the command line.
+fn complete_command(input: &str) -> (String, Vec<String>) {
+    match input.strip_prefix("cd ") {
+        Some(path) => {
+            let (path, names) = pane::complete_dir(path.trim_start());
+            (format!("cd {}", path), names)
+        }
+        None => (input.to_string(), vec![]),
+    }
+}
+
+/// Clicks on the same entry closer together than this open it.
+const DOUBLE_CLICK: Duration = Duration::from_millis(400);
+/// Presses of the same arrow closer together than this come from a held
+/// key, which the terminal repeats a few dozen times a second.
+const KEY_REPEAT: Duration = Duration::from_millis(100);
+/// Lines moved by a notch of the mouse wheel.
+const SCROLL_LINES: usize = 3;
+/// Most visited directories suggested after the bookmarks and in the goto
+/// prompt.
+const SUGGESTED_PLACES: usize = 5;
+
+/// What the arrow keys move.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    List,
+    Preview,
+}
+
+/// The rows of a pane's entries on screen, starting with entry `first`.
+#[derive(Debug, Clone, Copy)]
+struct ListArea {
+    pane: usize,
+    rect: Rect,
+    first: usize,
+}
+
+#[derive(Debug)]
+pub struct App {
+    tabs: Vec<Tab>,
+    active: usize,
+    preview: Option<Preview>,
+    /// Bumped whenever the preview changes, to tell images apart cheaply.
+    preview_id: u64,
+    /// Previews read before, or ahead of the cursor.
+    previews: RefCell<Previews>,
+    /// The preview the ones around it were last read ahead for.
+    prefetched: Option<u64>,
+    /// When the last key or click came, to read ahead once they stop.
+    last_input: Instant,
+    graphics: Protocol,
+    /// Where the last render left room for the preview image.
+    image_area: Cell<Option<Rect>>,
+    /// First entry of the focused pane on screen as last drawn.
+    first_visible: Cell<usize>,
+    /// Where each pane's entries and the preview were last drawn, for
+    /// telling what a mouse click landed on.
+    list_areas: RefCell<Vec<ListArea>>,
+    preview_area: Cell<Option<Rect>>,
+    /// Whether the keys move through the listing or scroll the preview.
+    focus: Focus,
+    /// Lines of the preview scrolled past.
+    preview_scroll: u16,
+    /// Entries whose preview took too long to read; they aren't read again
+    /// until retried, which could leave another thread stuck.
+    timed_out: RefCell<HashSet<PathBuf>>,
+    /// When and on which pane and entry the last click landed, to tell a
+    /// double-click.
+    last_click: Option<(Instant, usize, usize)>,
+    /// Preview image currently drawn with a graphics protocol, and where.
+    shown_image: Option<(u64, Rect)>,
+    /// The terminal window's title as last set.
+    shown_title: Option<String>,
+    /// Whether the terminal has focus, once it has said so.
+    terminal_focused: Option<bool>,
+    show_hidden: bool,
+    sort: Sort,
+    dirs_first: bool,
+    hide_ignored: bool,
+    config: Config,
+    /// The config file to reload when it changes, with the modification
+    /// time it was last read at.
+    config_file: Option<(PathBuf, Option<SystemTime>)>,
+    /// Looks out for the directories shown changing on disk.
+    watcher: Option<Watcher>,
+    config_checked: Instant,
+    /// Options changed on the settings screen since they were last saved.
+    unsaved_settings: Vec<&'static str>,
+    editor_choices: HashMap<String, String>,
+    bookmarks: Bookmarks,
+    smart_folders: SmartFolders,
+    annotations: Annotations,
+    visits: Visits,
+    line_counts: LineCounts,
+    /// Earlier commands, searches, filters and paths gone to.
+    recall: Recall,
+    prompt: Option<Prompt>,
+    picker: Option<Picker>,
+    search: Option<Search>,
+    /// Content search of the current tree, open over the listing.
+    grep: Option<Grep>,
+    /// Entries collected from anywhere during the session, to act on
+    /// together.
+    scratch: Vec<PathBuf>,
+    /// Whether the open prompt acts on the scratch rather than the pane.
+    scratch_batch: bool,
+    server: Option<Server>,
+    server_panel: bool,
+    jobs: Jobs,
+    /// Whether the progress panel is shown over the listing.
+    jobs_panel: bool,
+    /// Where the cleanup scan running as a job sends what it found.
+    cleanup_scan: Option<Receiver<Vec<Candidate>>>,
+    /// Where the last command started with its output kept sends it.
+    command_run: Option<Receiver<jobs::Output>>,
+    /// The projects around a directory, as last looked up.
+    projects: RefCell<Option<(PathBuf, Projects)>>,
+    /// Git status of the directories shown, looked up when a pane gets to
+    /// one and again when the listing is refreshed.
+    git: RefCell<HashMap<PathBuf, Option<Rc<git::Status>>>>,
+    quick_edit: Option<QuickEdit>,
+    hex_view: Option<HexView>,
+    qr_view: Option<QrView>,
+    permissions: Option<PermissionsEditor>,
+    /// Keys typed so far of a multi-key binding such as `g g`.
+    pending_keys: Vec<Chord>,
+    /// The last Up or Down, when it came and how many came in a row before
+    /// it, to tell a held key.
+    held_key: Option<(Action, Instant, usize)>,
+    /// Every pane of a tab as split, with the preview as an overlay.
+    dual_pane: bool,
+    /// Listing entries as a table of their details.
+    detailed: bool,
+    /// User names by uid, for the owner column.
+    users: RefCell<HashMap<u32, String>>,
+    /// The last message, shown in the status bar for a while.
+    status: Option<(String, Instant)>,
+    /// Free space on the current directory's filesystem, and when it was
+    /// looked up.
+    free_space: RefCell<Option<(PathBuf, Instant, Option<u64>)>>,
+    preview_overlay: bool,
+    /// Whether the preview pane is drawn next to a single pane.
+    show_preview: bool,
+    /// Set by `--choose-file`: opening a file picks it and exits.
+    choose_file: bool,
+    chosen: Option<PathBuf>,
+    /// The directory quit in with `Q`, for the shell to change to.
+    exit_dir: Option<PathBuf>,
+    redraw: bool,
+    exit: bool,
+}
+
+impl App {
+    /// Opens `start`, or the start directory from the user's config.
+    pub fn new(start: Option<PathBuf>) -> error::Result<Self> {
+        let (config, errors) = Config::load();
+        let start = start
+            .or_else(|| config.start_dir.clone().filter(|dir| dir.is_dir()))
+            .or_else(dirs::home_dir)
+            .unwrap_or_else(|| PathBuf::from("."));
+        log::info!(
+            "browrs {} starting in {}",
+            env!("CARGO_PKG_VERSION"),
+            start.display()
+        );
+        let mut app = Self::with_config(config, start)?;
+        if let Some(file) = Bookmarks::default_file() {
+            app.bookmarks = Bookmarks::load(file);
+        }
+        if let Some(file) = SmartFolders::default_file() {
+            app.smart_folders = SmartFolders::load(file);
+        }
+        if let Some(file) = Annotations::default_file() {
+            app.set_annotations(Annotations::load(file));
+        }
+        if let Some(file) = Visits::default_file() {
+            app.set_visits(Visits::in_file(file));
+        }
+        if let Some(dir) = Recall::default_dir() {
+            app.set_recall(Recall::in_dir(dir));
+        }
+        if let Some(file) = Config::path() {
+            app.watch_config(file);
+        }
+        if !errors.is_empty() {
+            app.show_config_errors(errors);
+        }
+        Ok(app)
+    }
+
+    /// Opens `start` with the given settings instead of the user's.
+    pub fn with_config(config: Config, start: PathBuf) -> error::Result<Self> {
+        let mut recall = Recall::default();
+        recall.size = config.history_size;
+        recall.save = config.save_history;
+        let watcher = watcher(&config);
+        let listing = Listing {
+            show_hidden: config.show_hidden,
+            sort: config.sort.clone(),
+            dirs_first: config.dirs_first,
+            ratings: Default::default(),
+            hide_ignored: config.hide_ignored,
+        };
+        Ok(Self {
+            tabs: vec![Tab::open(start, listing.clone())?],
+            active: 0,
+            preview: None,
+            preview_id: 0,
+            previews: RefCell::new(Previews::new()),
+            prefetched: None,
+            last_input: Instant::now(),
+            graphics: Protocol::detect(config.image_protocol.as_deref()),
+            dual_pane: config.dual_pane,
+            detailed: config.detailed,
+            users: RefCell::default(),
+            status: None,
+            free_space: RefCell::default(),
+            image_area: Cell::new(None),
+            first_visible: Cell::new(0),
+            list_areas: RefCell::new(Vec::new()),
+            preview_area: Cell::new(None),
+            focus: Focus::List,
+            preview_scroll: 0,
+            timed_out: RefCell::default(),
+            last_click: None,
+            shown_image: None,
+            shown_title: None,
+            terminal_focused: None,
+            show_hidden: listing.show_hidden,
+            sort: listing.sort,
+            dirs_first: listing.dirs_first,
+            hide_ignored: listing.hide_ignored,
+            config,
+            config_file: None,
+            watcher,
+            config_checked: Instant::now(),
+            unsaved_settings: Vec::new(),
+            editor_choices: HashMap::new(),
+            bookmarks: Bookmarks::default(),
+            smart_folders: SmartFolders::default(),
+            annotations: Annotations::default(),
+            visits: Visits::default(),
+            line_counts: LineCounts::default(),
+            recall,
+            prompt: None,
+            picker: None,
+            search: None,
+            grep: None,
+            scratch: vec![],
+            scratch_batch: false,
+            server: None,
+            server_panel: false,
+            jobs: Jobs::new(),
+            jobs_panel: false,
+            quick_edit: None,
+            cleanup_scan: None,
+            command_run: None,
+            projects: RefCell::new(None),
+            git: RefCell::default(),
+            hex_view: None,
+            qr_view: None,
+            permissions: None,
+            pending_keys: Vec::new(),
+            held_key: None,
+            preview_overlay: false,
+            show_preview: true,
+            choose_file: false,
+            chosen: None,
+            exit_dir: None,
+            redraw: false,
+            exit: false,
+        })
+    }
+
+    /// Reloads the settings from `file` whenever it changes. [`App::new`]
+    /// watches the user's config file.
+    pub fn watch_config(&mut self, file: PathBuf) {
+        let modified = std::fs::metadata(&file).and_then(|m| m.modified()).ok();
+        self.config_file = Some((file, modified));
+    }
+
+    /// Uses `bookmarks` instead of the in-memory list [`App::with_config`]
+    /// starts with.
+    pub fn set_bookmarks(&mut self, bookmarks: Bookmarks) {
+        self.bookmarks = bookmarks;
+    }
+
+    /// Like [`App::set_bookmarks`], for the smart folders.
+    pub fn set_smart_folders(&mut self, smart_folders: SmartFolders) {
+        self.smart_folders = smart_folders;
+    }
+
+    /// Like [`App::set_bookmarks`], for the directory visits counted to
+    /// suggest places.
+    pub fn set_visits(&mut self, visits: Visits) {
+        self.visits = visits;
+    }
+
+    /// Like [`App::set_bookmarks`], for the notes attached to files.
+    pub fn set_annotations(&mut self, annotations: Annotations) {
+        self.annotations = annotations;
+        self.refresh();
+        self.update_preview();
+    }
+
+    /// Keeps prompt history in `recall` instead of the memory-only one
+    /// [`App::with_config`] starts with. Its size and whether it saves come
+    /// from the config.
+    pub fn set_recall(&mut self, recall: Recall) {
+        self.recall = recall;
+        self.recall.size = self.config.history_size;
+        self.recall.save = self.config.save_history;
+    }
+
+    pub fn set_show_hidden(&mut self, show: bool) {
+        if self.show_hidden != show {
+            self.toggle_hidden();
+        }
+    }
+
+    /// Leaves the preview pane out, giving the listing the whole width; the
+    /// preview overlay still shows it.
+    pub fn hide_preview(&mut self) {
+        self.show_preview = false;
+    }
+
+    /// Makes opening a file pick it and exit instead of starting the editor,
+    /// leaving the path in [`App::chosen`].
+    pub fn choose_file(&mut self) {
+        self.choose_file = true;
+    }
+
+    pub fn chosen(&self) -> Option<&Path> {
+        self.chosen.as_deref()
+    }
+
+    /// The directory to leave the shell in, if the app was quit with `Q`.
+    pub fn exit_dir(&self) -> Option<&Path> {
+        self.exit_dir.as_deref()
+    }
+
+    fn tab(&self) -> &Tab {
+        &self.tabs[self.active]
+    }
+
+    fn tab_mut(&mut self) -> &mut Tab {
+        &mut self.tabs[self.active]
+    }
+
+    fn listing(&self) -> Listing {
+        Listing {
+            show_hidden: self.show_hidden,
+            sort: self.sort.clone(),
+            dirs_first: self.dirs_first,
+            ratings: self.annotations.ratings(),
+            hide_ignored: self.hide_ignored,
+        }
+    }
+
+    /// The focused pane of the current tab.
+    pub fn pane(&self) -> &Pane {
+        self.tab().pane()
+    }
+
+    fn pane_mut(&mut self) -> &mut Pane {
+        self.tab_mut().pane_mut()
+    }
+
+    pub fn run(&mut self, terminal: &mut DefaultTerminal) -> error::Result<()> {
+        while !self.exit {
+            self.step(terminal).map_err(BrowrsError::Terminal)?;
+        }
+        // A chosen file may be one of them
+        if self.chosen.is_none() {
+            vfs::remove_temporary();
+        }
+        Ok(())
+    }
+
+    /// Draws a frame, then waits for and handles the next event.
+    fn step(&mut self, terminal: &mut DefaultTerminal) -> std::io::Result<()> {
+        if std::mem::take(&mut self.redraw) {
+            // An external program took over the screen
+            terminal.clear()?;
+            self.shown_image = None;
+            self.shown_title = None;
+        }
+        terminal.draw(|frame| self.draw(frame))?;
+        self.draw_image(terminal)?;
+        self.set_window_title()?;
+        self.handle_event()
+    }
+
+    /// The terminal window's title for the current directory, from the
+    /// `terminal_title` template.
+    pub fn window_title(&self) -> Option<String> {
+        let template = self.config.terminal_title.as_ref()?;
+        let dir = &self.pane().current_dir;
+        let name = dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| dir.display().to_string());
+        let title = template
+            .replace("{path}", &dir.display().to_string())
+            .replace("{shortpath}", &pane::short_path(dir))
+            .replace("{name}", &name);
+        Some(title.chars().filter(|c| !c.is_control()).collect())
+    }
+
+    /// Tells the terminal the window title when it changes.
+    fn set_window_title(&mut self) -> std::io::Result<()> {
+        let title = self.window_title();
+        if title.is_some() && title != self.shown_title {
+            crossterm::execute!(
+                std::io::stdout(),
+                crossterm::terminal::SetTitle(title.as_deref().unwrap_or_default())
+            )?;
+            self.shown_title = title;
+        }
+        Ok(())
+    }
+
+    pub fn handle_event(&mut self) -> std::io::Result<()> {
+        // Wake up periodically so time-based decorations stay current, and
+        // often while a search is still streaming in results
+        let timeout = match &self.search {
+            Some(search) if !search.is_done() => Duration::from_millis(50),
+            _ if self.grep.as_ref().is_some_and(Grep::is_running) => Duration::from_millis(50),
+            _ if self.jobs_panel => Duration::from_millis(100),
+            // Changes on disk are picked up between keys
+            _ if self.server_panel || !self.jobs.is_empty() || self.watcher.is_some() => {
+                Duration::from_millis(250)
+            }
+            _ => Duration::from_secs(1),
+        };
+        let ready = event::poll(timeout)?;
+        self.tick();
+        if ready {
+            self.handle(event::read()?);
+        }
+        Ok(())
+    }
+
+    /// Picks up search results and finished jobs from background threads.
+    pub fn tick(&mut self) {
+        if let Some(search) = self.search.as_mut() {
+            search.poll();
+        }
+        if let Some(grep) = self.grep.as_mut() {
+            grep.poll();
+        }
+        for finished in self.jobs.poll() {
+            self.finish_job(finished);
+        }
+        if let Some(receiver) = &self.cleanup_scan
+            && let Ok(candidates) = receiver.try_recv()
+        {
+            self.cleanup_scan = None;
+            if !candidates.is_empty() {
+                self.open_cleanup(candidates, 0);
+            }
+        }
+        if let Some(receiver) = &self.command_run
+            && let Ok(output) = receiver.try_recv()
+        {
+            self.command_run = None;
+            self.open_command_output(output);
+        }
+        if self.jobs.is_empty() {
+            self.jobs_panel = false;
+        }
+        if self.config_checked.elapsed() >= CONFIG_CHECK_INTERVAL {
+            self.config_checked = Instant::now();
+            self.check_config();
+        }
+        self.reload_changed();
+        self.prefetch();
+    }
+
+    /// Once the keys stop, reads the previews of the entries next to the
+    /// cursor, and the first ones in a directory under it, in the
+    /// background.
+    fn prefetch(&mut self) {
+        self.previews.borrow_mut().poll();
+        if self.prefetched == Some(self.preview_id) || self.last_input.elapsed() < PREFETCH_DELAY {
+            return;
+        }
+        self.prefetched = Some(self.preview_id);
+        let pane = self.pane();
+        let path_at = |index: usize| {
+            pane.entries
+                .get(index)
+                .filter(|entry| !entry.is_parent())
+                .map(|entry| pane.current_dir.join(&entry.name))
+        };
+        let timed_out = self.timed_out.borrow();
+        let paths: Vec<PathBuf> = [pane.selected + 1, pane.selected.wrapping_sub(1)]
+            .into_iter()
+            .filter_map(path_at)
+            .filter(|path| !timed_out.contains(path) && !vfs::in_archive(path))
+            .collect();
+        let inside = path_at(pane.selected)
+            .filter(|path| pane.entries[pane.selected].is_dir && !vfs::in_archive(path));
+        self.previews.borrow_mut().prefetch(
+            paths,
+            inside,
+            self.show_hidden,
+            self.config.preview_max_size,
+            self.config.theme,
+            self.previewers(),
+        );
+    }
+
+    /// Re-reads the panes showing a directory that changed on disk, and
+    /// keeps the watcher on the directories shown now.
+    fn reload_changed(&mut self) {
+        let Some(watcher) = &self.watcher else {
+            return;
+        };
+        let changed = watcher.changed();
+        watcher.watch(
+            self.tabs
+                .iter()
+                .flat_map(|t| &t.panes)
+                .map(|pane| pane.current_dir.clone())
+                .filter(|dir| !vfs::in_archive(dir))
+                .collect(),
+        );
+        if changed.is_empty() {
+            return;
+        }
+        log::debug!("changed on disk: {:?}", changed);
+        let listing = self.listing();
+        for dir in &changed {
+            self.git.borrow_mut().remove(dir);
+        }
+        for pane in self.tabs.iter_mut().flat_map(|t| &mut t.panes) {
+            if changed.contains(&pane.current_dir) {
+                pane.reread(listing.clone());
+            }
+        }
+        if changed.contains(&self.pane().current_dir) {
+            self.update_preview();
+        }
+    }
+
+    /// Reloads the config file if it was modified since it was last read.
+    fn check_config(&mut self) {
+        let Some((file, last)) = &self.config_file else {
+            return;
+        };
+        let Ok(modified) = std::fs::metadata(file).and_then(|m| m.modified()) else {
+            return;
+        };
+        if *last == Some(modified) {
+            return;
+        }
+        let file = file.clone();
+        self.config_file = Some((file.clone(), Some(modified)));
+        let content = match std::fs::read_to_string(&file) {
+            Ok(content) => content,
+            Err(e) => {
+                self.show_error(BrowrsError::io("read", &file, e));
+                return;
+            }
+        };
+        log::info!("reloading {}", file.display());
+        let (config, errors) = Config::parse_checked(&content);
+        self.apply_config(config);
+        if errors.is_empty() {
+            self.show_message(format!("⚙️ Reloaded {}", file.display()));
+        } else {
+            self.show_config_errors(errors);
+        }
+    }
+
+    /// Switches to new settings. Options that can also be toggled in the
+    /// app only change if the file changed them.
+    fn apply_config(&mut self, config: Config) {
+        let old = std::mem::replace(&mut self.config, config);
+        let new = &self.config;
+        if new.show_hidden != old.show_hidden {
+            self.show_hidden = new.show_hidden;
+        }
+        if new.sort != old.sort {
+            self.sort = new.sort.clone();
+        }
+        if new.dirs_first != old.dirs_first {
+            self.dirs_first = new.dirs_first;
+        }
+        if new.hide_ignored != old.hide_ignored {
+            self.hide_ignored = new.hide_ignored;
+        }
+        if new.dual_pane != old.dual_pane {
+            self.dual_pane = new.dual_pane;
+        }
+        if new.detailed != old.detailed {
+            self.detailed = new.detailed;
+        }
+        self.recall.size = new.history_size;
+        self.recall.save = new.save_history;
+        if new.image_protocol != old.image_protocol {
+            self.graphics = Protocol::detect(new.image_protocol.as_deref());
+        }
+        if new.watch_interval_ms != old.watch_interval_ms {
+            self.watcher = watcher(new);
+        }
+        if new.theme != old.theme || new.previewers != old.previewers {
+            // Previews are colored, and run through the previewers, as
+            // they are read
+            self.previews.borrow_mut().clear();
+        }
+        self.pending_keys.clear();
+        self.refresh();
+        self.update_preview();
+    }
+
+    fn show_config_errors(&mut self, errors: Vec<BrowrsError>) {
+        for error in &errors {
+            log::error!("{}", error);
+        }
+        let messages: Vec<String> = errors.iter().map(BrowrsError::message).collect();
+        self.show_message(messages.join("\n"));
+    }
+
+    pub fn handle(&mut self, event: Event) {
+        match event {
+            Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                self.last_input = Instant::now();
+                self.handle_key_event(key_event);
+                if self.tab().is_synced() {
+                    let listing = self.listing();
+                    self.tab_mut().mirror(listing);
+                }
+            }
+            Event::Paste(text) => self.handle_paste(text),
+            Event::Mouse(mouse) => {
+                self.last_input = Instant::now();
+                self.handle_mouse(mouse);
+            }
+            Event::FocusGained => self.terminal_focused = Some(true),
+            Event::FocusLost => self.terminal_focused = Some(false),
+            _ => {}
+        }
+    }
+
+    fn handle_mouse(&mut self, mouse: MouseEvent) {
+        let overlay = self.prompt.is_some()
+            || self.picker.is_some()
+            || self.search.is_some()
+            || self.grep.is_some()
+            || self.server_panel
+            || self.jobs_panel
+            || self.quick_edit.is_some()
+            || self.hex_view.is_some()
+            || self.qr_view.is_some()
+            || self.permissions.is_some();
+        if overlay {
+            return;
+        }
+        let position = Position::new(mouse.column, mouse.row);
+        let over_preview = self
+            .preview_area
+            .get()
+            .is_some_and(|area| area.contains(position));
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) if over_preview => self.focus = Focus::Preview,
+            MouseEventKind::Down(MouseButton::Left) => self.click(position),
+            MouseEventKind::ScrollUp if over_preview => self.scroll_preview(-(SCROLL_LINES as i32)),
+            MouseEventKind::ScrollDown if over_preview => self.scroll_preview(SCROLL_LINES as i32),
+            MouseEventKind::ScrollUp => self.scroll_list(position, false),
+            MouseEventKind::ScrollDown => self.scroll_list(position, true),
+            _ => {}
+        }
+    }
+
+    /// The pane drawn at `position`, and the entry on that row if any.
+    fn entry_at(&self, position: Position) -> Option<(usize, Option<usize>)> {
+        let areas = self.list_areas.borrow();
+        let area = areas.iter().find(|area| area.rect.contains(position))?;
+        let index = area.first + (position.y - area.rect.y) as usize;
+        let entries = self.tab().panes[area.pane].entries.len();
+        Some((area.pane, (index < entries).then_some(index)))
+    }
+
+    /// Focuses the clicked pane and selects the entry under the pointer;
+    /// a second click on it opens it.
+    fn click(&mut self, position: Position) {
+        let Some((pane, entry)) = self.entry_at(position) else {
+            return;
+        };
+        self.focus = Focus::List;
+        let focus_changed = self.tab().focus != pane;
+        self.tab_mut().focus = pane;
+        let Some(entry) = entry else {
+            self.last_click = None;
+            if focus_changed {
+                self.update_preview();
+            }
+            return;
+        };
+        let double = self
+            .last_click
+            .is_some_and(|(at, p, e)| at.elapsed() < DOUBLE_CLICK && p == pane && e == entry);
+        self.move_to(entry);
+        if double {
+            self.last_click = None;
+            self.run_action(Action::Open);
+        } else {
+            self.last_click = Some((Instant::now(), pane, entry));
+        }
+    }
+
+    /// Moves the cursor of the pane under the pointer a few entries.
+    fn scroll_list(&mut self, position: Position, down: bool) {
+        let Some((pane, _)) = self.entry_at(position) else {
+            return;
+        };
+        if self.tab().focus != pane {
+            self.tab_mut().focus = pane;
+        }
+        let last = self.pane().entries.len().saturating_sub(1);
+        let selected = self.pane().selected;
+        let selected = if down {
+            (selected + SCROLL_LINES).min(last)
+        } else {
+            selected.saturating_sub(SCROLL_LINES)
+        };
+        self.move_to(selected);
+    }
+
+    /// Hands the arrow keys to the preview, if it is on screen.
+    fn focus_preview(&mut self) {
+        if self.preview_area.get().is_some() && self.preview.is_some() {
+            self.focus = Focus::Preview;
+        }
+    }
+
+    /// Entries of the focused pane on screen at once.
+    fn list_page(&self) -> usize {
+        let focus = self.tab().focus;
+        self.list_areas
+            .borrow()
+            .iter()
+            .find(|area| area.pane == focus)
+            .map_or(1, |area| (area.rect.height as usize).max(1))
+    }
+
+    fn scroll_preview(&mut self, lines: i32) {
+        let Some(preview) = self.preview.as_mut() else {
+            return;
+        };
+        let target = (self.preview_scroll as i32).saturating_add(lines).max(0) as usize;
+        let height = self
+            .preview_area
+            .get()
+            .map_or(0, |area| area.height as usize);
+        // A screenful past the target, so there is no gap below it
+        preview.load_until(target.saturating_add(height).min(u16::MAX as usize));
+        let last = preview
+            .text
+            .lines
+            .len()
+            .saturating_sub(1)
+            .min(u16::MAX as usize);
+        self.preview_scroll = target.min(last) as u16;
+    }
+
+    fn handle_key_event(&mut self, key_event: KeyEvent) {
+        if let Some(edit) = self.quick_edit.as_mut() {
+            match edit.handle_key(key_event) {
+                EditAction::None => {}
+                EditAction::Close => self.quick_edit = None,
+                EditAction::Save => self.save_quick_edit(),
+            }
+            return;
+        }
+
+        if let Some(view) = self.hex_view.as_mut() {
+            if view.handle_key(key_event) {
+                self.hex_view = None;
+            }
+            return;
+        }
+
+        if let Some(view) = self.qr_view.as_mut() {
+            if view.handle_key(key_event) {
+                self.qr_view = None;
+            }
+            return;
+        }
+
+        if let Some(editor) = self.permissions.as_mut() {
+            match editor.handle_key(key_event) {
+                PermissionsAction::None => {}
+                PermissionsAction::Cancel => self.permissions = None,
+                PermissionsAction::Apply(mode) => {
+                    self.permissions = None;
+                    #[cfg(unix)]
+                    self.apply_permissions(mode);
+                    #[cfg(not(unix))]
+                    let _ = mode;
+                }
+            }
+            return;
+        }
+
+        if let Some(prompt) = self.prompt.as_mut() {
+            match prompt.handle_key(key_event) {
+                PromptAction::None => {}
+                PromptAction::Cancel => {
+                    self.prompt = None;
+                    self.scratch_batch = false;
+                }
+                PromptAction::Submit(input) => {
+                    let kind = prompt.kind.clone();
+                    self.prompt = None;
+                    self.submit_prompt(kind, input);
+                }
+            }
+            return;
+        }
+
+        if self.jobs_panel {
+            match key_event.code {
+                KeyCode::Esc => self.jobs.cancel_all(),
+                KeyCode::Enter | KeyCode::Char('q') => self.jobs_panel = false,
+                _ => {}
+            }
+            return;
+        }
+
+        if self.server_panel {
+            match key_event.code {
+                KeyCode::Esc | KeyCode::Char('q') => self.server_panel = false,
+                KeyCode::Char('x') => self.stop_sharing(),
+                _ => {}
+            }
+            return;
+        }
+
+        if let Some(picker) = self.picker.as_mut() {
+            match picker.handle_key(key_event) {
+                PickerAction::None => {}
+                PickerAction::Cancel => self.picker = None,
+                PickerAction::Choose(index) => self.choose_picker_item(index),
+                PickerAction::Key(c) => self.picker_key(c),
+            }
+            return;
+        }
+
+        if self.search.is_some() {
+            self.handle_search_key(key_event);
+            return;
+        }
+        if self.grep.is_some() {
+            self.handle_grep_key(key_event);
+            return;
+        }
+
+        self.pending_keys.push(Chord::from_event(key_event));
+        let action = match self.config.keymap.lookup(&self.pending_keys) {
+            Lookup::Pending => return,
+            Lookup::Action(action) => Some(action),
+            Lookup::None => None,
+        };
+        let keys = std::mem::take(&mut self.pending_keys);
+        log::trace!("keys {:?} -> {:?}", keys, action);
+        match action {
+            Some(action) => self.run_action(action),
+            // A key that breaks off a sequence counts on its own
+            None if keys.len() > 1 => self.handle_key_event(key_event),
+            None if self.pane().filter.is_some() => self.quick_open(key_event),
+            None => {}
+        }
+    }
+
+    fn run_action(&mut self, action: Action) {
+        if self.focus == Focus::Preview {
+            let page = self
+                .preview_area
+                .get()
+                .map_or(1, |area| area.height.saturating_sub(2)) as i32;
+            match action {
+                Action::MoveUp => return self.scroll_preview(-1),
+                Action::MoveDown => return self.scroll_preview(1),
+                Action::PageUp => return self.scroll_preview(-page),
+                Action::PageDown => return self.scroll_preview(page),
+                Action::Top => return self.scroll_preview(-(self.preview_scroll as i32)),
+                Action::Bottom => return self.scroll_preview(i32::MAX / 2),
+                Action::FocusPreview | Action::SwitchPane | Action::ClearMarks => {
+                    self.focus = Focus::List;
+                    return;
+                }
+                // Anything else goes back to the listing
+                _ => self.focus = Focus::List,
+            }
+        }
+        if action.changes_files() && vfs::in_archive(&self.pane().current_dir) {
+            self.show_message("📦 Archives are read-only; c extracts entries".to_string());
+            return;
+        }
+        match action {
+            Action::Quit => self.exit(),
+            Action::QuitCd => {
+                self.exit_dir = Some(self.pane().current_dir.clone());
+                self.exit();
+            }
+            Action::Search => self.search = Some(self.start_search()),
+            Action::Grep => {
+                let root = self.pane().current_dir.clone();
+                self.grep = Some(Grep::new(root, self.show_hidden, self.config.walk.clone()));
+            }
+            Action::ToggleHidden => self.toggle_hidden(),
+            Action::ToggleIgnored => {
+                self.hide_ignored = !self.hide_ignored;
+                self.refresh();
+                self.update_preview();
+            }
+            Action::Filter => {
+                let current = self.pane().filter.clone().unwrap_or_default();
+                self.prompt = Some(
+                    Prompt::new(
+                        PromptKind::Filter,
+                        "Filter: *.rs, foo or empty to clear",
+                        current,
+                    )
+                    .with_recall(self.recall.entries(recall::Kind::Filter)),
+                );
+            }
+            Action::Sort => {
+                self.sort = self.sort.next(&self.config.sorts);
+                self.refresh();
+                self.update_preview();
+            }
+            Action::DirsFirst => {
+                self.dirs_first = !self.dirs_first;
+                self.refresh();
+                self.update_preview();
+            }
+            Action::Detailed => self.detailed = !self.detailed,
+            Action::Editor => self.prompt_editor(),
+            Action::QuickEdit => self.open_quick_edit(),
+            #[cfg(unix)]
+            Action::Permissions => self.open_permissions(),
+            #[cfg(unix)]
+            Action::Owner => self.prompt_owner(),
+            #[cfg(unix)]
+            Action::Attributes => self.prompt_attributes(),
+            #[cfg(not(unix))]
+            Action::Permissions | Action::Owner | Action::Attributes => {}
+            Action::MoveUp | Action::MoveDown => self.move_by(action),
+            Action::PageUp | Action::PageDown => {
+                let page = self.list_page();
+                let last = self.pane().entries.len().saturating_sub(1);
+                let selected = self.pane().selected;
+                self.move_to(if action == Action::PageUp {
+                    selected.saturating_sub(page)
+                } else {
+                    (selected + page).min(last)
+                });
+            }
+            Action::FocusPreview => self.focus_preview(),
+            Action::RetryPreview => {
+                if let Some(path) = self.selected_path() {
+                    self.timed_out.borrow_mut().remove(&path);
+                }
+                self.update_preview();
+            }
+            Action::Top | Action::Bottom => {
+                self.pane_mut().record_jump();
+                let last = self.pane().entries.len().saturating_sub(1);
+                self.move_to(if action == Action::Top { 0 } else { last });
+            }
+            Action::Open => self.open_selected(),
+            Action::OpenDefault => self.open_default(),
+            Action::Back => self.go_back(false),
+            Action::Forward => self.go_back(true),
+            Action::JumpBack => self.jump(false),
+            Action::JumpForward => self.jump(true),
+            Action::Mark => self.toggle_mark(),
+            Action::Visual => self.pane_mut().toggle_visual(),
+            Action::MarkAll => self.pane_mut().toggle_mark_all(),
+            Action::ClearMarks => self.pane_mut().clear_marks(),
+            Action::Collect => self.collect(),
+            Action::Scratch => self.open_scratch(),
+            Action::Refresh => self.reread(),
+            Action::Copy => self.prompt_transfer(PromptKind::Copy),
+            Action::Move => self.prompt_transfer(PromptKind::Move),
+            Action::Symlink => self.prompt_transfer(PromptKind::Link(false)),
+            Action::Hardlink => self.prompt_transfer(PromptKind::Link(true)),
+            Action::Rename => self.prompt_rename(),
+            Action::Trash => self.prompt_delete(false),
+            Action::Delete => self.prompt_delete(true),
+            Action::OpenTrash => self.open_trash(),
+            Action::Cleanup => self.start_cleanup_scan(),
+            Action::Cargo(task) => self.run_cargo(task),
+            Action::Scripts => self.open_scripts(),
+            Action::Tasks => self.open_tasks(),
+            Action::SendTo => self.open_send_to(),
+            Action::Touch => self.prompt_touch(),
+            Action::NewFile => {
+                self.prompt = Some(Prompt::new(
+                    PromptKind::NewFile,
+                    "New file (end with / for a directory)",
+                    "",
+                ))
+            }
+            Action::PasteFile => self.prompt_paste_file(),
+            Action::Extract => self.prompt_extract(),
+            Action::Compress => self.prompt_compress(),
+            Action::NewDir => {
+                self.prompt = Some(Prompt::new(PromptKind::NewDir, "New directory", ""))
+            }
+            Action::Reveal => self.reveal_selected(),
+            Action::Qr => self.show_qr(),
+            Action::Versions => self.open_versions(),
+            Action::Share => self.share(),
+            Action::Jobs => self.jobs_panel = !self.jobs.is_empty(),
+            Action::Annotate => self.prompt_annotate(),
+            Action::NewTab => self.open_tab(),
+            Action::CloseTab => self.close_tab(),
+            Action::NextTab => self.cycle_tab(true),
+            Action::PrevTab => self.cycle_tab(false),
+            Action::SwitchPane if self.dual_pane => {
+                self.tab_mut().switch_focus();
+                self.update_preview();
+            }
+            // With nowhere else to go, Tab goes to the preview
+            Action::SwitchPane if self.tabs.len() == 1 => self.focus_preview(),
+            Action::SwitchPane => self.cycle_tab(true),
+            Action::DualPane => self.dual_pane = !self.dual_pane,
+            Action::SplitSide => self.split(Direction::Horizontal),
+            Action::SplitBelow => self.split(Direction::Vertical),
+            Action::CloseSplit => {
+                if self.tab_mut().close_split() {
+                    self.update_preview();
+                } else {
+                    self.show_message("The last pane can't be closed".to_string());
+                }
+            }
+            Action::DiffPanes => self.diff_panes(),
+            Action::SyncPanes => {
+                let synced = self.tab_mut().toggle_sync();
+                self.dual_pane |= synced;
+                log::info!("panes synced: {}", synced);
+            }
+            Action::FocusLeft => self.focus_towards(Side::Left),
+            Action::FocusRight => self.focus_towards(Side::Right),
+            Action::FocusUp => self.focus_towards(Side::Up),
+            Action::FocusDown => self.focus_towards(Side::Down),
+            Action::PreviewOverlay if self.dual_pane || !self.show_preview => {
+                self.preview_overlay = !self.preview_overlay
+            }
+            Action::PreviewOverlay => {}
+            Action::Command => {
+                self.prompt = Some(
+                    Prompt::new(PromptKind::Command, "Command", "")
+                        .with_recall(self.recall.entries(recall::Kind::Command))
+                        .with_completion(complete_command),
+                );
+            }
+            Action::Goto => self.prompt_goto(),
+            Action::Bookmark => self.bookmark_current(),
+            Action::Bookmarks => self.open_bookmarks(),
+            Action::Actions => self.open_actions(),
+            Action::OnlyDirs => self.toggle_kind(Some(Kind::Dirs)),
+            Action::OnlyFiles => self.toggle_kind(Some(Kind::Files)),
+            Action::OnlyExecutables => self.toggle_kind(Some(Kind::Executables)),
+            Action::OnlyImages => self.toggle_kind(Some(Kind::Images)),
+            Action::OnlyArchives => self.toggle_kind(Some(Kind::Archives)),
+            Action::AllKinds => self.toggle_kind(None),
+            Action::Rate(stars) => self.rate(stars),
+            Action::MinRating(stars) => {
+                let listing = self.listing();
+                self.pane_mut().set_min_rating(stars, listing);
+                self.update_scroll();
+                self.update_preview();
+            }
+            Action::SaveFilter => match self.pane().filter.clone() {
+                Some(query) => self.prompt_save_smart_folder(SmartFolder {
+                    name: String::new(),
+                    kind: QueryKind::Filter,
+                    root: self.pane().current_dir.clone(),
+                    query,
+                }),
+                None => self.show_message(
+                    "Filter with f first, or press Ctrl+S in a search, to save a smart folder"
+                        .to_string(),
+                ),
+            },
+            Action::Settings => self.open_settings(),
+            Action::Log => self.open_log(),
+        }
+    }
+
+    /// While filtering, a digit opens the entry shown with that number.
+    fn quick_open(&mut self, key_event: KeyEvent) {
+        let KeyCode::Char(c) = key_event.code else {
+            return;
+        };
+        let Some(number) = c.to_digit(10).filter(|&n| n > 0) else {
+            return;
+        };
+        let numbered = self.pane().numbered(self.first_visible.get());
+        if let Some(&index) = numbered.get(number as usize - 1) {
+            self.move_to(index);
+            self.open_selected();
+        }
+    }
+
+    /// Moves the cursor up or down a step, going round from one end to the
+    /// other with `wrap_around` set. A held key stops at the end instead.
+    fn move_by(&mut self, action: Action) {
+        let (step, held) = self.move_step(action);
+        let last = self.pane().entries.len().saturating_sub(1);
+        let selected = self.pane().selected;
+        let wrap = self.config.wrap_around && !held;
+        let target = match action {
+            Action::MoveUp if selected > 0 => selected.saturating_sub(step),
+            Action::MoveUp if wrap => last,
+            Action::MoveDown if selected < last => (selected + step).min(last),
+            Action::MoveDown if wrap => 0,
+            _ => return,
+        };
+        self.move_to(target);
+    }
+
+    /// How many entries a move goes, and whether its key is being held: with
+    /// `scroll_acceleration` set, the longer a key repeats on a listing
+    /// longer than a page, the further each repeat goes, up to a page.
+    fn move_step(&mut self, action: Action) -> (usize, bool) {
+        let now = Instant::now();
+        let streak = match self.held_key {
+            Some((held, at, streak)) if held == action && now - at < KEY_REPEAT => streak + 1,
+            _ => 0,
+        };
+        self.held_key = Some((action, now, streak));
+        let page = self.list_page();
+        let acceleration = self.config.scroll_acceleration as usize;
+        if acceleration == 0 || self.pane().entries.len() <= page {
+            return (1, false);
+        }
+        let step = 1 + streak * acceleration / 10;
+        (step.min(page), streak > 0)
+    }
+
+    /// Puts the cursor on the entry at `index`.
+    fn move_to(&mut self, index: usize) {
+        let pane = self.pane_mut();
+        pane.selected = index;
+        pane.update_visual();
+        self.update_scroll();
+        self.update_preview();
+    }
+
+    fn handle_search_key(&mut self, key_event: KeyEvent) {
+        let Some(search) = self.search.as_mut() else {
+            return;
+        };
+        match key_event.code {
+            KeyCode::Esc => self.search = None,
+            KeyCode::Up => search.selected = search.selected.saturating_sub(1),
+            KeyCode::Down if search.selected + 1 < search.results.len() => search.selected += 1,
+            KeyCode::Backspace => search.pop(),
+            KeyCode::Tab => search.toggle_mark(),
+            KeyCode::Char(c) if key_event.modifiers.contains(KeyModifiers::CONTROL) => match c {
+                'p' | 'n' => search.recall(c == 'p'),
+                'a' => search.toggle_mark_all(),
+                // Batch operations on the marked results
+                'y' => self.prompt_transfer(PromptKind::Copy),
+                'd' => self.prompt_delete(false),
+                'o' => self.edit_search_results(),
+                's' => {
+                    let folder = SmartFolder {
+                        name: String::new(),
+                        kind: QueryKind::Search,
+                        root: search.root.clone(),
+                        query: search.query.clone(),
+                    };
+                    self.prompt_save_smart_folder(folder);
+                }
+                _ => {}
+            },
+            // Alt and a digit opens the result numbered with it
+            KeyCode::Char(c @ '1'..='9') if key_event.modifiers.contains(KeyModifiers::ALT) => {
+                if let Some(index) = c.to_digit(10).and_then(|n| search.numbered(n as usize)) {
+                    search.selected = index;
+                    self.open_search_match();
+                }
+            }
+            KeyCode::Char(c) => search.push(c),
+            KeyCode::Enter => self.open_search_match(),
+            _ => {}
+        }
+    }
+
+    fn handle_grep_key(&mut self, key_event: KeyEvent) {
+        let Some(grep) = self.grep.as_mut() else {
+            return;
+        };
+        match key_event.code {
+            KeyCode::Esc => self.grep = None,
+            KeyCode::Up => grep.selected = grep.selected.saturating_sub(1),
+            KeyCode::Down if grep.selected + 1 < grep.matches.len() => grep.selected += 1,
+            KeyCode::PageUp => grep.selected = grep.selected.saturating_sub(10),
+            KeyCode::PageDown => {
+                grep.selected = (grep.selected + 10).min(grep.matches.len().saturating_sub(1))
+            }
+            KeyCode::Backspace => grep.pop(),
+            KeyCode::Tab => grep.toggle_mark(),
+            KeyCode::Char(c) if key_event.modifiers.contains(KeyModifiers::CONTROL) => match c {
+                'a' => grep.toggle_mark_all(),
+                // Batch operations on the marked files, as for search
+                'y' => self.prompt_transfer(PromptKind::Copy),
+                'd' => self.prompt_delete(false),
+                'o' => self.edit_search_results(),
+                _ => {}
+            },
+            KeyCode::Char(c)
+                if !key_event
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) =>
+            {
+                grep.push(c)
+            }
+            KeyCode::Enter if grep.is_current() => self.open_grep_match(),
+            KeyCode::Enter => grep.start(),
+            _ => {}
+        }
+    }
+
+    /// Closes the grep with the cursor on the selected match's file, and
+    /// opens it in the editor at the matching line.
+    fn open_grep_match(&mut self) {
+        let Some(grep) = self.grep.take() else {
+            return;
+        };
+        let Some(found) = grep.selected_match() else {
+            self.grep = Some(grep);
+            return;
+        };
+        let target = grep.root.join(&found.path);
+        let (Some(parent), Some(name)) = (target.parent(), target.file_name()) else {
+            return;
+        };
+        let name = name.to_string_lossy().to_string();
+        self.change_dir(parent.to_path_buf());
+        self.select_name(&name);
+
+        let Some(command) = editor::resolve(&target, &self.editor_choices, &self.config) else {
+            self.show_message(format!(
+                "❌ No editor found\nTried: {}\nSet $EDITOR or press e to choose one",
+                editor::candidates(&target, &self.editor_choices, &self.config).join(", ")
+            ));
+            return;
+        };
+        let line = found.line;
+        match self.suspended(|| editor::open_at(&command, &target, line)) {
+            Ok(status) if !status.success() => self.show_error(BrowrsError::Command {
+                command,
+                source: std::io::Error::other(format!("exited with {}", status)),
+            }),
+            Err(source) => self.show_error(BrowrsError::Command { command, source }),
+            _ => {}
+        }
+        self.refresh();
+    }
+
+    /// A search of the current directory's tree.
+    fn start_search(&self) -> Search {
+        let root = self.pane().current_dir.clone();
+        Search::start(root.clone(), self.show_hidden, &self.config.walk)
+            .with_notes(self.annotations.under(&root))
+            .with_recall(self.recall.entries(recall::Kind::Search))
+    }
+
+    /// Opens the marked search or grep results, files only, in one editor.
+    fn edit_search_results(&mut self) {
+        let files: Vec<PathBuf> = self
+            .targets()
+            .into_iter()
+            .filter(|path| path.is_file())
+            .collect();
+        let Some(first) = files.first() else {
+            return;
+        };
+        let Some(command) = editor::resolve(first, &self.editor_choices, &self.config) else {
+            self.show_message(format!(
+                "❌ No editor found\nTried: {}\nSet $EDITOR or press e to choose one",
+                editor::candidates(first, &self.editor_choices, &self.config).join(", ")
+            ));
+            return;
+        };
+        self.search = None;
+        self.grep = None;
+        match self.suspended(|| editor::open_all(&command, &files)) {
+            Ok(status) if !status.success() => self.show_error(BrowrsError::Command {
+                command,
+                source: std::io::Error::other(format!("exited with {}", status)),
+            }),
+            Err(source) => self.show_error(BrowrsError::Command { command, source }),
+            _ => {}
+        }
+        self.refresh();
+    }
+
+    /// Closes the search with the cursor on the selected match.
+    fn open_search_match(&mut self) {
+        let Some(search) = self.search.take() else {
+            return;
+        };
+        self.remember(recall::Kind::Search, &search.query);
+        let target = search.selected_match().map(|m| search.root.join(&m.path));
+        if let Some(target) = target
+            && let (Some(parent), Some(name)) = (target.parent(), target.file_name())
+        {
+            let name = name.to_string_lossy().to_string();
+            self.change_dir(parent.to_path_buf());
+            self.select_name(&name);
+        }
+    }
+
+    fn open_selected(&mut self) {
+        let Some(entry) = self.pane().entries.get(self.pane().selected) else {
+            return;
+        };
+        if entry.is_parent() {
+            if let Some(parent) = self.pane().current_dir.parent() {
+                self.change_dir(parent.to_path_buf());
+            }
+            return;
+        }
+        let candidate = self.pane().current_dir.join(&entry.name);
+        if vfs::is_dir(&candidate) {
+            self.change_dir(candidate);
+            return;
+        }
+        let Some(candidate) = self.local_copy(candidate) else {
+            return;
+        };
+        if self.choose_file {
+            log::info!("chose {}", candidate.display());
+            self.chosen = Some(candidate);
+            self.exit();
+        } else if let Some(rule) = opener::find(&self.config.open_rules, &candidate).cloned() {
+            self.open_with_rule(&rule, &candidate);
+            self.change_dir(self.pane().current_dir.clone());
+        } else {
+            self.open_in_editor(&candidate);
+            self.change_dir(self.pane().current_dir.clone());
+        }
+    }
+
+    /// `path`, or a temporary copy of it if it is inside an archive, for
+    /// handing to another program.
+    fn local_copy(&mut self, path: PathBuf) -> Option<PathBuf> {
+        match vfs::extract_temporary(&path) {
+            Ok(path) => Some(path),
+            Err(e) => {
+                self.show_error(BrowrsError::io("extract", &path, e));
+                None
+            }
+        }
+    }
+
+    fn change_dir(&mut self, dir: PathBuf) {
+        log::info!("cd {}", dir.display());
+        let listing = self.listing();
+        let left = self.pane().current_dir.clone();
+        if let Err(e) = self.pane_mut().change_dir(dir, listing) {
+            self.show_error(e);
+            return;
+        }
+        // The repository may have changed since it was last looked at
+        self.git.borrow_mut().remove(&self.pane().current_dir);
+        if self.config.suggest_places && self.pane().current_dir != left {
+            let dir = self.pane().current_dir.clone();
+            if let Err(e) = self.visits.record(&dir) {
+                log::error!("couldn't count the visit to {}: {}", dir.display(), e);
+            }
+        }
+    }
+
+    /// The most visited directories that aren't bookmarked, with how many
+    /// times each was visited.
+    fn suggested_places(&self) -> Vec<(PathBuf, u32)> {
+        if !self.config.suggest_places {
+            return Vec::new();
+        }
+        self.visits
+            .top(SUGGESTED_PLACES, |dir| self.bookmarks.contains(dir))
+    }
+
+    /// Splits the focused pane, showing every pane of the tab.
+    fn split(&mut self, direction: Direction) {
+        let listing = self.listing();
+        match self.tab_mut().split(direction, listing) {
+            Ok(()) => {
+                self.dual_pane = true;
+                self.update_preview();
+            }
+            Err(e) => self.show_error(e),
+        }
+    }
+
+    fn toggle_kind(&mut self, kind: Option<Kind>) {
+        let listing = self.listing();
+        self.pane_mut().toggle_kind(kind, listing);
+        log::info!("kinds {:?}", self.pane().kinds);
+        self.update_scroll();
+        self.update_preview();
+    }
+
+    /// Gives the targeted entries `stars`, keeping the cursor where it is
+    /// even if they sort elsewhere now.
+    fn rate(&mut self, stars: u8) {
+        let targets = self.targets();
+        if targets.is_empty() {
+            return;
+        }
+        if let Err(e) = self.annotations.rate(&targets, stars) {
+            let file = self
+                .annotations
+                .file()
+                .unwrap_or(Path::new("annotations"))
+                .to_path_buf();
+            return self.show_error(BrowrsError::io("save", &file, e));
+        }
+        self.refresh();
+        self.update_scroll();
+    }
+
+    fn focus_towards(&mut self, side: Side) {
+        // Only the shape matters, not the size of the screen
+        let area = Rect::new(0, 0, 1000, 1000);
+        if self.dual_pane && self.tab_mut().focus_towards(side, area) {
+            self.update_preview();
+        }
+    }
+
+    /// Goes back to the previous directory, or forward again.
+    fn go_back(&mut self, forward: bool) {
+        let listing = self.listing();
+        let pane = self.pane_mut();
+        let moved = if forward {
+            pane.forward(listing)
+        } else {
+            pane.back(listing)
+        };
+        match moved {
+            Ok(true) => {
+                log::info!("cd {}", self.pane().current_dir.display());
+                self.git.borrow_mut().remove(&self.pane().current_dir);
+                self.update_scroll();
+                self.update_preview();
+            }
+            Ok(false) => {}
+            Err(e) => self.show_error(e),
+        }
+    }
+
+    /// Goes back to the place jumped from last, or forward again.
+    fn jump(&mut self, forward: bool) {
+        let listing = self.listing();
+        match self.pane_mut().jump(forward, listing) {
+            Ok(true) => {
+                log::info!("jump to {}", self.pane().current_dir.display());
+                self.git.borrow_mut().remove(&self.pane().current_dir);
+                self.update_scroll();
+                self.update_preview();
+            }
+            Ok(false) => {}
+            Err(e) => self.show_error(e),
+        }
+    }
+
+    /// Moves the cursor onto the entry named `name`, if it is listed.
+    pub fn select_name(&mut self, name: &str) {
+        if self.pane_mut().select_name(name) {
+            self.update_preview();
+        }
+    }
+
+    /// Keeps `input` for Up to bring back next time.
+    fn remember(&mut self, kind: recall::Kind, input: &str) {
+        if let Err(e) = self.recall.add(kind, input) {
+            log::error!("couldn't keep {:?} history: {}", kind, e);
+        }
+    }
+
+    fn toggle_hidden(&mut self) {
+        self.show_hidden = !self.show_hidden;
+        self.refresh();
+        self.update_preview();
+    }
+
+    fn selected_path(&self) -> Option<PathBuf> {
+        self.pane().selected_path()
+    }
+
+    /// What an operation acts on: the search results while searching,
+    /// otherwise the pane's marked entries or selection.
+    fn targets(&self) -> Vec<PathBuf> {
+        if self.scratch_batch {
+            return self
+                .scratch
+                .iter()
+                .filter(|path| path.symlink_metadata().is_ok())
+                .cloned()
+                .collect();
+        }
+        match (&self.search, &self.grep) {
+            (Some(search), _) => search.targets(),
+            (None, Some(grep)) => grep.targets(),
+            (None, None) => self.pane().targets(),
+        }
+    }
+
+    fn toggle_mark(&mut self) {
+        let tab = self.pane_mut();
+        if let Some(entry) = tab.entries.get_mut(tab.selected)
+            && !entry.is_parent()
+        {
+            entry.marked = !entry.marked;
+        }
+        if tab.selected + 1 < tab.entries.len() {
+            tab.selected += 1;
+            self.update_preview();
+        }
+    }
+
+    /// Puts the targeted entries in the scratch, or takes them out if they
+    /// are all there already.
+    fn collect(&mut self) {
+        let targets = self.targets();
+        if targets.is_empty() {
+            return;
+        }
+        if targets.iter().all(|path| self.scratch.contains(path)) {
+            self.scratch.retain(|path| !targets.contains(path));
+            self.show_message(format!(
+                "➖ Took {} out of the scratch, {} left",
+                match targets.len() {
+                    1 => "1 entry".to_string(),
+                    count => format!("{} entries", count),
+                },
+                self.scratch.len()
+            ));
+        } else {
+            let added = match targets.len() {
+                1 => "1 entry".to_string(),
+                count => format!("{} entries", count),
+            };
+            for path in targets {
+                if !self.scratch.contains(&path) {
+                    self.scratch.push(path);
+                }
+            }
+            self.show_message(format!(
+                "➕ Collected {}, {} in the scratch (Y to list)",
+                added,
+                self.scratch.len()
+            ));
+        }
+        self.pane_mut().clear_marks();
+    }
+
+    fn open_scratch(&mut self) {
+        if self.scratch.is_empty() {
+            self.picker = None;
+            self.show_message("The scratch is empty, press y to collect entries".to_string());
+            return;
+        }
+        let labels = self
+            .scratch
+            .iter()
+            .map(|path| {
+                if path.symlink_metadata().is_ok() {
+                    path.display().to_string()
+                } else {
+                    format!("{} (missing)", path.display())
+                }
+            })
+            .collect();
+        let selected = self.picker.as_ref().map_or(0, |p| p.selected);
+        let mut picker = Picker::new(
+            PickerKind::Scratch,
+            format!("Scratch ({})", self.scratch.len()),
+            "Enter go, d take out, x empty, c copy, m move, t trash, D delete, Esc close",
+            labels,
+        );
+        picker.selected = selected.min(picker.items.len().saturating_sub(1));
+        self.picker = Some(picker);
+    }
+
+    /// Closes the scratch and asks where to copy or move everything in it.
+    fn prompt_scratch_transfer(&mut self, kind: PromptKind) {
+        self.picker = None;
+        self.scratch_batch = true;
+        self.prompt_transfer(kind);
+        if self.prompt.is_none() {
+            self.scratch_batch = false;
+        }
+    }
+
+    /// Closes the scratch and asks before trashing or deleting it all.
+    fn prompt_scratch_delete(&mut self, permanent: bool) {
+        self.picker = None;
+        self.scratch_batch = true;
+        self.prompt_delete(permanent);
+        if self.prompt.is_none() {
+            self.scratch_batch = false;
+        }
+    }
+
+    /// Opens a new tab on the current directory, right after this one.
+    fn open_tab(&mut self) {
+        let mut tab = match Tab::open(self.pane().current_dir.clone(), self.listing()) {
+            Ok(tab) => tab,
+            Err(e) => return self.show_error(e),
+        };
+        let pane = tab.pane_mut();
+        pane.selected = self
+            .pane()
+            .selected
+            .min(pane.entries.len().saturating_sub(1));
+        self.active += 1;
+        self.tabs.insert(self.active, tab);
+        self.update_preview();
+    }
+
+    fn close_tab(&mut self) {
+        if self.tabs.len() > 1 {
+            self.tabs.remove(self.active);
+            self.active = self.active.min(self.tabs.len() - 1);
+            self.update_preview();
+        }
+    }
+
+    fn cycle_tab(&mut self, forward: bool) {
+        let count = self.tabs.len();
+        self.active = if forward {
+            (self.active + 1) % count
+        } else {
+            (self.active + count - 1) % count
+        };
+        self.update_preview();
+    }
+
+    fn prompt_transfer(&mut self, kind: PromptKind) {
+        let count = self.targets().len();
+        if count == 0 {
+            return;
+        }
+        let verb = match kind {
+            PromptKind::Copy => "Copy",
+            PromptKind::Link(false) => "Link",
+            PromptKind::Link(true) => "Hard link",
+            _ => "Move",
+        };
+        let title = if count == 1 {
+            format!("{} to (directory or new name)", verb)
+        } else {
+            format!("{} {} entries to", verb, count)
+        };
+        // In dual-pane mode the next pane is the natural destination
+        let dest = if self.dual_pane {
+            &self.tab().other_pane().current_dir
+        } else {
+            &self.pane().current_dir
+        };
+        self.prompt = Some(Prompt::new(kind, title, dest.display().to_string()));
+    }
+
+    fn prompt_rename(&mut self) {
+        let Some(entry) = self
+            .pane()
+            .entries
+            .get(self.pane().selected)
+            .filter(|e| !e.is_parent())
+        else {
+            return;
+        };
+        self.prompt = Some(Prompt::new(
+            PromptKind::Rename,
+            format!("Rename {}", entry.name),
+            entry.name.clone(),
+        ));
+    }
+
+    fn submit_rename(&mut self, new_name: String, overwrite: bool) {
+        let Some(source) = self.selected_path() else {
+            return;
+        };
+        let new_name = new_name.trim().to_string();
+        let dest = self.pane().current_dir.join(&new_name);
+        if new_name.is_empty() || dest == source {
+            return;
+        }
+        if !overwrite && dest.symlink_metadata().is_ok() {
+            self.prompt = Some(Prompt::confirm(
+                PromptKind::ConfirmRename(new_name.clone()),
+                "Overwrite",
+                format!("{} already exists, replace it?", new_name),
+            ));
+            return;
+        }
+        let mut report = ops::BatchReport::new("rename");
+        report.record(
+            &source,
+            ops::rename(&source, &dest, overwrite, self.config.keep_versions),
+        );
+        let renamed = report.errors.is_empty();
+        self.finish_operation(report);
+        if renamed {
+            self.select_name(&new_name);
+        }
+    }
+
+    fn reveal_selected(&mut self) {
+        let Some(path) = self.selected_path() else {
+            return;
+        };
+        self.show_message(match desktop::reveal(&path) {
+            Ok(tool) => format!("📂 Revealed {} in {}", path.display(), tool),
+            Err(e) => BrowrsError::io("reveal", &path, e).message(),
+        });
+    }
+
+    fn show_qr(&mut self) {
+        let Some(path) = self.selected_path() else {
+            return;
+        };
+        match QrView::open(&path) {
+            Some(view) => self.qr_view = Some(view),
+            None => self.show_error(BrowrsError::invalid(
+                path.display().to_string(),
+                format!("Longer than the {} bytes a QR code holds", qr::MAX_BYTES),
+            )),
+        }
+    }
+
+    /// Serves the marked entries, or the current directory, over HTTP. With a
+    /// server already running this just brings its panel back.
+    fn share(&mut self) {
+        if self.server.is_none() {
+            let marked = self.pane().marked_paths();
+            let share = if marked.is_empty() {
+                Share::Dir(self.pane().current_dir.clone())
+            } else {
+                Share::Entries(marked)
+            };
+            match Server::start(share, self.show_hidden) {
+                Ok(server) => self.server = Some(server),
+                Err(e) => {
+                    let dir = self.pane().current_dir.clone();
+                    self.show_error(BrowrsError::io("share", &dir, e));
+                    return;
+                }
+            }
+        }
+        self.server_panel = true;
+    }
+
+    fn stop_sharing(&mut self) {
+        self.server_panel = false;
+        if self.server.take().is_some() {
+            self.show_message("⏹ Stopped sharing".to_string());
+        }
+    }
+
+    /// Pasted text goes into an open prompt; a pasted URL on its own offers
+    /// to download it.
+    fn handle_paste(&mut self, text: String) {
+        if let Some(prompt) = self.prompt.as_mut() {
+            prompt.paste(&text);
+        } else if self.picker.is_none()
+            && self.search.is_none()
+            && self.grep.is_none()
+            && !self.server_panel
+            && download::is_url(&text)
+        {
+            let url = text.trim().to_string();
+            self.prompt = Some(Prompt::confirm(
+                PromptKind::ConfirmDownload(url.clone()),
+                "Download into this directory?",
+                url,
+            ));
+        }
+    }
+
+    fn submit_command(&mut self, input: String) {
+        let input = input.trim();
+        let (command, argument) = input.split_once(' ').unwrap_or((input, ""));
+        match command {
+            "" => {}
+            "download" | "dl" if !argument.trim().is_empty() => {
+                self.start_download(argument.trim().to_string())
+            }
+            "cd" if argument.trim().is_empty() => self.prompt_goto(),
+            "cd" => self.goto(argument.trim()),
+            "export" => {
+                let (name, path) = argument
+                    .trim()
+                    .split_once(' ')
+                    .unwrap_or((argument.trim(), ""));
+                match Report::from_name(name) {
+                    Some(report) if path.trim().is_empty() => self.prompt_export(report),
+                    Some(report) => self.export(report, path.trim()),
+                    None => self.show_error(BrowrsError::invalid(
+                        input,
+                        "Export listing, usage or duplicates",
+                    )),
+                }
+            }
+            _ if download::is_url(input) => self.start_download(input.to_string()),
+            _ => self.show_error(BrowrsError::invalid(input, "Unknown command")),
+        }
+    }
+
+    fn prompt_goto(&mut self) {
+        let dir = pane::tilde_path(&self.pane().current_dir);
+        let dir = if dir.ends_with('/') {
+            dir
+        } else {
+            format!("{}/", dir)
+        };
+        // The most visited come up after what was typed before, and are
+        // what Tab fills an empty input with
+        let visited: Vec<String> = if self.config.suggest_places {
+            let current = &self.pane().current_dir;
+            self.visits
+                .top(SUGGESTED_PLACES, |visited| visited == current)
+                .into_iter()
+                .map(|(visited, _)| format!("{}/", pane::tilde_path(&visited)))
+                .collect()
+        } else {
+            vec![]
+        };
+        let history = self.recall.entries(recall::Kind::Goto);
+        let recall = visited
+            .iter()
+            .rev()
+            .filter(|visited| !history.contains(visited))
+            .cloned()
+            .chain(history.iter().cloned())
+            .collect();
+        self.prompt = Some(
+            Prompt::new(PromptKind::Goto, "Go to (Tab completes)", dir)
+                .with_completion(pane::complete_dir)
+                .with_recall(recall)
+                .with_suggestions(visited),
+        );
+    }
+
+    /// Goes to the directory at `path`, or to the file's directory with the
+    /// file selected; relative paths are from the current directory.
+    fn goto(&mut self, path: &str) {
+        if path.is_empty() {
+            return;
+        }
+        let target = self.pane().current_dir.join(config::expand_tilde(path));
+        let metadata = match target.metadata() {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                self.show_error(BrowrsError::io("go to", &target, e));
+                return;
+            }
+        };
+        if metadata.is_dir() {
+            self.change_dir(target);
+        } else if let (Some(parent), Some(name)) = (target.parent(), target.file_name()) {
+            let name = name.to_string_lossy().to_string();
+            self.change_dir(parent.to_path_buf());
+            self.select_name(&name);
+        }
+        self.update_scroll();
+        self.update_preview();
+    }
+
+    fn prompt_export(&mut self, report: Report) {
+        let path = self
+            .pane()
+            .current_dir
+            .join(format!("browrs-{}.csv", report.name()));
+        self.prompt = Some(Prompt::new(
+            PromptKind::Export(report),
+            format!("Export the {} to (.csv, .json or .md)", report.name()),
+            path.display().to_string(),
+        ));
+    }
+
+    /// Writes the report to `path`, relative to the current directory. The
+    /// listing is at hand; the other reports are worked out as a job.
+    fn export(&mut self, report: Report, path: &str) {
+        let path = self.pane().current_dir.join(path);
+        if export::Format::of(&path).is_none() {
+            self.show_error(BrowrsError::invalid(
+                path.display().to_string(),
+                "End the name in .csv, .json or .md",
+            ));
+            return;
+        }
+        let dir = self.pane().current_dir.clone();
+        let show_hidden = self.show_hidden;
+        let policy = self.config.walk.clone();
+        let done = format!("📤 Exported the {} to {}", report.name(), path.display());
+        match report {
+            Report::Listing => {
+                let table = export::listing(&self.pane().entries);
+                match table.write(&path) {
+                    Ok(()) => {
+                        self.refresh();
+                        self.show_message(done);
+                    }
+                    Err(e) => self.show_error(BrowrsError::io("export to", &path, e)),
+                }
+            }
+            Report::DiskUsage => {
+                let title = format!("📤 Adding up the sizes in {}", dir.display());
+                self.jobs.spawn(jobs::Kind::Scan, title, move |progress| {
+                    let table =
+                        export::disk_usage(&dir, show_hidden, &policy, &|| progress.is_cancelled());
+                    if progress.is_cancelled() {
+                        return Err(std::io::Error::new(
+                            std::io::ErrorKind::Interrupted,
+                            "cancelled",
+                        ));
+                    }
+                    table.write(&path).map(|()| done)
+                });
+                self.jobs_panel = true;
+            }
+            Report::Duplicates => {
+                let title = format!("📤 Looking for duplicates under {}", dir.display());
+                self.jobs.spawn(jobs::Kind::Scan, title, move |progress| {
+                    let table = export::duplicates(
+                        &dir,
+                        show_hidden,
+                        &policy,
+                        &|done, total| progress.count(done, total),
+                        &|| progress.is_cancelled(),
+                    );
+                    if progress.is_cancelled() {
+                        return Err(std::io::Error::new(
+                            std::io::ErrorKind::Interrupted,
+                            "cancelled",
+                        ));
+                    }
+                    table.write(&path).map(|()| done)
+                });
+                self.jobs_panel = true;
+            }
+        }
+    }
+
+    fn prompt_extract(&mut self) {
+        let Some(path) = self.selected_path() else {
+            return;
+        };
+        if !path.is_file() {
+            self.show_error(BrowrsError::invalid(
+                path.display().to_string(),
+                "Not a zip or tar archive",
+            ));
+            return;
+        }
+        if archive::Format::detect(&path).is_none() {
+            // Anything else is worth a look at its bytes
+            match HexView::open(path.clone()) {
+                Ok(view) => self.hex_view = Some(view),
+                Err(e) => self.show_error(BrowrsError::io("read", &path, e)),
+            }
+            return;
+        }
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let title = format!("Extract {} into", name);
+        // A directory of its own, so nothing beside the archive is touched
+        let dir = self.pane().current_dir.join(archive::stem(&path));
+        let dir = dir.display().to_string();
+        self.prompt = Some(Prompt::new(PromptKind::Extract(path), title, dir));
+    }
+
+    fn prompt_compress(&mut self) {
+        let targets = self.targets();
+        let name = match targets.as_slice() {
+            [] => return,
+            [single] => single.file_name().unwrap_or_default().to_string_lossy(),
+            _ => self.pane().title().into(),
+        };
+        self.prompt = Some(Prompt::new(
+            PromptKind::Compress,
+            format!(
+                "Compress {} entries into (.zip, .tar.gz, .tar.xz, .tar.zst)",
+                targets.len()
+            ),
+            format!("{}.tar.gz", name),
+        ));
+    }
+
+    fn start_extract(&mut self, path: PathBuf, into: String) {
+        let into = self.pane().current_dir.join(into.trim());
+        let name = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        self.jobs.spawn(
+            jobs::Kind::Archive,
+            format!("📦 Extracting {}", name),
+            move |progress| {
+                std::fs::create_dir_all(&into)?;
+                let count = archive::extract_everything(&path, &into, progress)?;
+                Ok(format!(
+                    "📦 Extracted {} entries into {}",
+                    count,
+                    into.display()
+                ))
+            },
+        );
+        self.jobs_panel = true;
+    }
+
+    fn start_compress(&mut self, name: String) {
+        let name = name.trim();
+        if name.is_empty() {
+            return;
+        }
+        let dest = self.pane().current_dir.join(name);
+        let targets = self.targets();
+        self.jobs.spawn(
+            jobs::Kind::Archive,
+            format!("📦 Compressing into {}", name),
+            move |progress| {
+                archive::create(&dest, &targets, progress)?;
+                Ok(format!("📦 Created {}", dest.display()))
+            },
+        );
+        self.jobs_panel = true;
+    }
+
+    fn start_download(&mut self, url: String) {
+        if !download::is_url(&url) {
+            self.show_error(BrowrsError::invalid(url, "Not an http(s) URL"));
+            return;
+        }
+        let dir = self.pane().current_dir.clone();
+        self.jobs.spawn(
+            jobs::Kind::Download,
+            download::title(&url),
+            move |progress| download::download(&url, &dir, progress),
+        );
+    }
+
+    /// Creates `name` under the current directory, with the directories
+    /// leading to it, and puts the cursor on what now appears in the listing.
+    fn submit_new(&mut self, name: String, dir: bool) {
+        let name = name.trim();
+        let relative = Path::new(name);
+        let Some(first) = relative.components().next() else {
+            return;
+        };
+        let path = self.pane().current_dir.join(relative);
+        let dir = dir || name.ends_with('/');
+        if let Err(e) = ops::create(&path, dir) {
+            self.show_error(BrowrsError::io("create", &path, e));
+            return;
+        }
+        log::info!("created {}", path.display());
+        self.refresh();
+        if relative.is_relative() {
+            self.select_name(&first.as_os_str().to_string_lossy());
+        }
+        self.show_message(format!("✨ Created {}", path.display()));
+    }
+
+    /// Reads the clipboard, then asks for the name of the file to put its
+    /// text in.
+    fn prompt_paste_file(&mut self) {
+        let text = match desktop::clipboard_text(self.config.paste_command.as_deref()) {
+            Ok(text) => text,
+            Err(e) => {
+                self.show_error(BrowrsError::io("read", Path::new("the clipboard"), e));
+                return;
+            }
+        };
+        if text.is_empty() {
+            self.show_message("📋 The clipboard is empty".to_string());
+            return;
+        }
+        let dir = &self.pane().current_dir;
+        let name = (1..)
+            .map(|n| match n {
+                1 => "pasted.txt".to_string(),
+                n => format!("pasted-{}.txt", n),
+            })
+            .find(|name| dir.join(name).symlink_metadata().is_err())
+            .unwrap_or_default();
+        let title = match text.lines().count() {
+            1 => "New file from the clipboard's line".to_string(),
+            lines => format!("New file from the clipboard's {} lines", lines),
+        };
+        self.prompt = Some(Prompt::new(PromptKind::PasteFile(text), title, name));
+    }
+
+    fn submit_paste_file(&mut self, name: String, text: String) {
+        let name = name.trim();
+        let relative = Path::new(name);
+        let Some(first) = relative.components().next() else {
+            return;
+        };
+        let path = self.pane().current_dir.join(relative);
+        if let Err(e) = ops::create_file(&path, text.as_bytes()) {
+            self.show_error(BrowrsError::io("create", &path, e));
+            return;
+        }
+        log::info!("pasted {} bytes into {}", text.len(), path.display());
+        self.refresh();
+        if relative.is_relative() {
+            self.select_name(&first.as_os_str().to_string_lossy());
+        }
+        self.show_message(format!("📋 Pasted into {}", path.display()));
+    }
+
+    fn prompt_touch(&mut self) {
+        let count = self.targets().len();
+        if count == 0 {
+            return;
+        }
+        self.prompt = Some(Prompt::new(
+            PromptKind::Touch,
+            format!(
+                "Touch {} entries: [-a] now|-2h|@epoch|YYYY-MM-DD HH:MM",
+                count
+            ),
+            "now",
+        ));
+    }
+
+    fn submit_touch(&mut self, spec: String) {
+        let touch = match touch::Touch::parse(&spec, SystemTime::now()) {
+            Ok(touch) => touch,
+            Err(e) => {
+                self.show_error(BrowrsError::invalid("", e));
+                return;
+            }
+        };
+        let mut report = ops::BatchReport::new("touch");
+        for path in self.targets() {
+            report.record(&path, touch::touch(&path, &touch));
+        }
+        self.finish_operation(report);
+    }
+
+    fn prompt_delete(&mut self, permanent: bool) {
+        let targets = self.targets();
+        let subject = match targets.as_slice() {
+            [] => return,
+            [single] => single
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            many => format!("{} entries", many.len()),
+        };
+        self.prompt = Some(if permanent {
+            Prompt::confirm(
+                PromptKind::ConfirmDelete,
+                "Delete",
+                format!("Permanently delete {}?", subject),
+            )
+        } else {
+            Prompt::confirm(
+                PromptKind::ConfirmTrash,
+                "Trash",
+                format!("Move {} to the trash?", subject),
+            )
+        });
+    }
+
+    /// Looks for caches under the current directory in the background; the
+    /// picker opens once it is done.
+    fn start_cleanup_scan(&mut self) {
+        let root = self.pane().current_dir.clone();
+        let (sender, receiver) = mpsc::channel();
+        self.cleanup_scan = Some(receiver);
+        let title = format!("🧹 Looking for caches under {}", root.display());
+        let policy = self.config.walk.clone();
+        self.jobs.spawn(jobs::Kind::Scan, title, move |progress| {
+            let places = cleanup::Places::system();
+            let candidates = cleanup::scan(&root, &places, &policy, &|| progress.is_cancelled());
+            let total: u64 = candidates.iter().map(|c| c.size).sum();
+            let message = if candidates.is_empty() {
+                format!("🧹 No caches or build output under {}", root.display())
+            } else {
+                format!(
+                    "🧹 {} caches take {}",
+                    candidates.len(),
+                    jobs::format_size(total)
+                )
+            };
+            let _ = sender.send(candidates);
+            Ok(message)
+        });
+        self.jobs_panel = true;
+    }
+
+    fn open_cleanup(&mut self, candidates: Vec<Candidate>, selected: usize) {
+        let root = self.pane().current_dir.clone();
+        let labels = candidates
+            .iter()
+            .map(|candidate| {
+                let name = candidate
+                    .path
+                    .strip_prefix(&root)
+                    .unwrap_or(&candidate.path);
+                format!(
+                    "{:>10}  {}  ({})",
+                    jobs::format_size(candidate.size),
+                    name.display(),
+                    candidate.what
+                )
+            })
+            .collect::<Vec<_>>();
+        let total: u64 = candidates.iter().map(|c| c.size).sum();
+        let mut picker = Picker::new(
+            PickerKind::Cleanup(candidates),
+            format!("Cleanup: {} to free", jobs::format_size(total)),
+            "Enter trash, D delete, Esc close",
+            labels,
+        );
+        picker.selected = selected.min(picker.items.len().saturating_sub(1));
+        self.picker = Some(picker);
+    }
+
+    /// Asks before trashing or deleting the cleanup candidate at `index`.
+    fn prompt_cleanup(&mut self, index: usize, permanent: bool) {
+        let Some(PickerKind::Cleanup(candidates)) = self.picker.as_ref().map(|p| &p.kind) else {
+            return;
+        };
+        let Some(candidate) = candidates.get(index) else {
+            return;
+        };
+        let name = candidate
+            .path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let size = jobs::format_size(candidate.size);
+        self.prompt = Some(if permanent {
+            Prompt::confirm(
+                PromptKind::ConfirmCleanup { index, permanent },
+                "Delete",
+                format!("Permanently delete {} ({})?", name, size),
+            )
+        } else {
+            Prompt::confirm(
+                PromptKind::ConfirmCleanup { index, permanent },
+                "Trash",
+                format!("Move {} ({}) to the trash?", name, size),
+            )
+        });
+    }
+
+    /// Trashes or deletes a cleanup candidate and lists the rest.
+    fn submit_cleanup(&mut self, index: usize, permanent: bool) {
+        let Some(picker) = self.picker.take() else {
+            return;
+        };
+        let PickerKind::Cleanup(mut candidates) = picker.kind else {
+            self.picker = Some(picker);
+            return;
+        };
+        if index >= candidates.len() {
+            return;
+        }
+        let candidate = candidates.remove(index);
+        let targets = vec![candidate.path];
+        if permanent {
+            // Nothing worth keeping a copy of in the history
+            let title = format!("✖ Deleting {}", candidate.what);
+            self.start_operation(jobs::Kind::Delete, title, move |progress| {
+                ops::delete_all(&targets, 0, progress)
+            });
+        } else {
+            let title = format!("🗑 Trashing {}", candidate.what);
+            self.start_operation(jobs::Kind::Trash, title, move |progress| {
+                trash::trash_all(&targets, progress)
+            });
+        }
+        if !candidates.is_empty() {
+            self.open_cleanup(candidates, index);
+        }
+    }
+
+    fn open_trash(&mut self) {
+        let items = match trash::list() {
+            Ok(items) => items,
+            Err(e) => {
+                self.show_error(BrowrsError::io("read", Path::new("the trash"), e));
+                return;
+            }
+        };
+        let labels = items
+            .iter()
+            .map(|item| {
+                let origin = item
+                    .original
+                    .as_ref()
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_else(|| item.name.clone());
+                format!("{}  {}", item.deleted.replace('T', " "), origin)
+            })
+            .collect();
+        let selected = self.picker.as_ref().map_or(0, |p| p.selected);
+        let mut picker = Picker::new(
+            PickerKind::Trash(items),
+            "Trash",
+            "Enter restore, E empty, Esc close",
+            labels,
+        );
+        picker.selected = selected.min(picker.items.len().saturating_sub(1));
+        self.picker = Some(picker);
+    }
+
+    fn open_send_to(&mut self) {
+        let count = self.targets().len();
+        if count == 0 {
+            return;
+        }
+        let targets = sendto::targets(&self.config);
+        let labels = targets.iter().map(|t| t.label()).collect();
+        self.picker = Some(Picker::new(
+            PickerKind::SendTo(targets),
+            format!("Send {} entries to", count),
+            "Enter send, Esc close",
+            labels,
+        ));
+    }
+
+    fn open_versions(&mut self) {
+        let Some(path) = self.selected_path().filter(|p| p.is_file()) else {
+            self.open_deleted();
+            return;
+        };
+        let mut versions = snapshots::versions(&path);
+        versions.extend(history::versions(&path));
+        versions.sort_by_key(|v| std::cmp::Reverse(v.modified));
+        if versions.is_empty() {
+            self.show_message(format!(
+                "🕘 No earlier copies of {}\nLooked for .zfs/snapshot and .snapshots above it, and in the history",
+                path.display()
+            ));
+            return;
+        }
+        let current = std::fs::metadata(&path).ok();
+        let labels = versions
+            .iter()
+            .map(|version| {
+                let time = version
+                    .modified
+                    .map(|t| datetime::local(t).iso().replace('T', " "))
+                    .unwrap_or_default();
+                let same = current.as_ref().is_some_and(|m| {
+                    m.len() == version.size && m.modified().ok() == version.modified
+                });
+                format!(
+                    "{}  {:>10}  {}{}",
+                    time,
+                    jobs::format_size(version.size),
+                    version.snapshot,
+                    if same { "  (same as current)" } else { "" }
+                )
+            })
+            .collect();
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        self.picker = Some(Picker::new(
+            PickerKind::Versions(path.clone(), versions),
+            format!("Versions of {}", name),
+            "Enter diff, R restore, Esc close",
+            labels,
+        ));
+    }
+
+    /// Lists files deleted from under the current directory that the
+    /// history kept copies of.
+    fn open_deleted(&mut self) {
+        let dir = self.pane().current_dir.clone();
+        let deleted = history::deleted(&dir);
+        if deleted.is_empty() {
+            self.show_message(format!(
+                "🕘 No deleted files kept from {}\nSet keep_versions in the config to keep them",
+                dir.display()
+            ));
+            return;
+        }
+        let labels = deleted
+            .iter()
+            .map(|(original, version)| {
+                let name = original.strip_prefix(&dir).unwrap_or(original);
+                format!(
+                    "{}  {:>10}",
+                    name.display(),
+                    jobs::format_size(version.size)
+                )
+            })
+            .collect();
+        self.picker = Some(Picker::new(
+            PickerKind::Deleted(deleted),
+            format!("Deleted from {}", dir.display()),
+            "Enter restore, Esc close",
+            labels,
+        ));
+    }
+
+    fn bookmark_current(&mut self) {
+        let dir = self.pane().current_dir.clone();
+        match self.bookmarks.add(dir.clone()) {
+            Ok(true) => self.show_message(format!("🔖 Bookmarked {}", dir.display())),
+            Ok(false) => self.show_message(format!("🔖 {} is already bookmarked", dir.display())),
+            Err(e) => self.save_bookmarks_failed(e),
+        }
+    }
+
+    fn prompt_save_smart_folder(&mut self, folder: SmartFolder) {
+        let title = match folder.kind {
+            QueryKind::Search => format!("Save search \"{}\" as", folder.query),
+            QueryKind::Filter => format!("Save filter \"{}\" as", folder.query),
+        };
+        let name = folder.query.clone();
+        self.prompt = Some(Prompt::new(
+            PromptKind::SaveSmartFolder(folder),
+            title,
+            name,
+        ));
+    }
+
+    fn save_smart_folder(&mut self, mut folder: SmartFolder, name: String) {
+        folder.name = name.trim().to_string();
+        if folder.name.is_empty() {
+            return;
+        }
+        let name = folder.name.clone();
+        match self.smart_folders.add(folder) {
+            Ok(()) => self.show_message(format!("🔎 Saved smart folder {}", name)),
+            Err(e) => self.save_smart_folders_failed(e),
+        }
+    }
+
+    fn prompt_annotate(&mut self) {
+        let Some(path) = self.selected_path() else {
+            return;
+        };
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let title = format!("Note for {}", name);
+        let current = self.annotations.get(&path).unwrap_or_default().to_string();
+        self.prompt = Some(Prompt::new(PromptKind::Annotate(path), title, current));
+    }
+
+    fn annotate(&mut self, path: PathBuf, note: String) {
+        match self.annotations.set(path, &note) {
+            Ok(()) => self.update_preview(),
+            Err(e) => {
+                let file = self
+                    .annotations
+                    .file()
+                    .unwrap_or(Path::new("annotations"))
+                    .to_path_buf();
+                self.show_error(BrowrsError::io("save", &file, e));
+            }
+        }
+    }
+
+    fn save_smart_folders_failed(&mut self, e: std::io::Error) {
+        let file = self
+            .smart_folders
+            .file()
+            .unwrap_or(Path::new("smart_folders"))
+            .to_path_buf();
+        self.show_error(BrowrsError::io("save", &file, e));
+    }
+
+    /// Runs the folder's query afresh in its root.
+    fn open_smart_folder(&mut self, folder: SmartFolder) {
+        log::info!("opening smart folder {}", folder.name);
+        self.change_dir(folder.root);
+        match folder.kind {
+            QueryKind::Search => self.search = Some(self.start_search().with_query(&folder.query)),
+            QueryKind::Filter => {
+                let listing = self.listing();
+                self.pane_mut().set_filter(Some(folder.query), listing);
+            }
+        }
+        self.update_scroll();
+        self.update_preview();
+    }
+
+    fn save_bookmarks_failed(&mut self, e: std::io::Error) {
+        let file = self
+            .bookmarks
+            .file()
+            .unwrap_or(Path::new("bookmarks"))
+            .to_path_buf();
+        self.show_error(BrowrsError::io("save", &file, e));
+    }
+
+    fn open_bookmarks(&mut self) {
+        let labels = self
+            .bookmarks
+            .dirs
+            .iter()
+            .map(|dir| {
+                if dir.is_dir() {
+                    dir.display().to_string()
+                } else {
+                    format!("{} (missing)", dir.display())
+                }
+            })
+            .chain(self.smart_folders.folders.iter().map(SmartFolder::label))
+            .chain(
+                self.suggested_places()
+                    .into_iter()
+                    .map(|(dir, count)| match count {
+                        1 => format!("{}  ↻ 1 visit", dir.display()),
+                        count => format!("{}  ↻ {} visits", dir.display(), count),
+                    }),
+            )
+            .collect();
+        let selected = self.picker.as_ref().map_or(0, |p| p.selected);
+        let mut picker = Picker::new(
+            PickerKind::Bookmarks,
+            "Bookmarks",
+            "Enter go, d remove, Esc close",
+            labels,
+        );
+        picker.selected = selected.min(picker.items.len().saturating_sub(1));
+        self.picker = Some(picker);
+    }
+
+    fn open_settings(&mut self) {
+        let labels = settings::ALL
+            .iter()
+            .map(|setting| {
+                let value = setting.value(&self.config);
+                let value = if value.is_empty() {
+                    "(unset)".to_string()
+                } else {
+                    value
+                };
+                let star = if self.unsaved_settings.contains(&setting.key) {
+                    "*"
+                } else {
+                    " "
+                };
+                format!("{}{:<20} {}", star, setting.key, value)
+            })
+            .collect();
+        let title = match self.unsaved_settings.len() {
+            0 => "Settings".to_string(),
+            n => format!("Settings ({} unsaved)", n),
+        };
+        let selected = self.picker.as_ref().map_or(0, |p| p.selected);
+        let mut picker = Picker::new(
+            PickerKind::Settings,
+            title,
+            "Enter change, w save, Esc close",
+            labels,
+        );
+        picker.selected = selected.min(picker.items.len().saturating_sub(1));
+        self.picker = Some(picker);
+    }
+
+    /// Applies a value chosen on the settings screen and shows the screen
+    /// again.
+    fn change_setting(&mut self, setting: &'static Setting, value: String) {
+        let mut config = self.config.clone();
+        match setting.apply(&mut config, &value) {
+            Ok(()) => {
+                log::info!("setting {} = {}", setting.key, value);
+                self.apply_config(config);
+                if !self.unsaved_settings.contains(&setting.key) {
+                    self.unsaved_settings.push(setting.key);
+                }
+            }
+            Err(reason) => self.show_error(BrowrsError::invalid(
+                "",
+                format!("{}: {}", setting.key, reason),
+            )),
+        }
+        self.open_settings();
+    }
+
+    /// Writes the options changed on the settings screen into the config
+    /// file, leaving everything else in it as it was.
+    fn save_settings(&mut self) {
+        let Some(file) = self
+            .config_file
+            .as_ref()
+            .map(|(file, _)| file.clone())
+            .or_else(Config::path)
+        else {
+            return;
+        };
+        let mut content = match std::fs::read_to_string(&file) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => {
+                self.show_error(BrowrsError::io("read", &file, e));
+                return;
+            }
+        };
+        for key in &self.unsaved_settings {
+            if let Some(setting) = settings::find(key) {
+                content = settings::write(&content, setting, &setting.value(&self.config));
+            }
+        }
+        let written = file
+            .parent()
+            .map_or(Ok(()), std::fs::create_dir_all)
+            .and_then(|()| std::fs::write(&file, content));
+        if let Err(e) = written {
+            self.show_error(BrowrsError::io("save", &file, e));
+            return;
+        }
+        // Don't reload what was just written
+        if self.config_file.is_some() {
+            self.watch_config(file.clone());
+        }
+        self.picker = None;
+        self.show_message(format!(
+            "⚙️ Saved {} settings to {}",
+            self.unsaved_settings.len(),
+            file.display()
+        ));
+        self.unsaved_settings.clear();
+    }
+
+    /// The Rust and JavaScript projects the current directory is in,
+    /// looked up again only when the directory changes.
+    fn projects(&self) -> Projects {
+        let dir = &self.pane().current_dir;
+        let mut cached = self.projects.borrow_mut();
+        if let Some((cached_dir, projects)) = cached.as_ref()
+            && cached_dir == dir
+        {
+            return projects.clone();
+        }
+        let projects = if vfs::in_archive(dir) {
+            Projects::default()
+        } else {
+            Projects {
+                cargo: Project::find(dir),
+                npm: Package::find(dir),
+            }
+        };
+        *cached = Some((dir.clone(), projects.clone()));
+        projects
+    }
+
+    /// Git status of the repository `dir` is in, looked up again when a
+    /// pane changes to it. Directories no pane shows are forgotten.
+    fn git_status(&self, dir: &Path) -> Option<Rc<git::Status>> {
+        let mut cached = self.git.borrow_mut();
+        if let Some(status) = cached.get(dir) {
+            return status.clone();
+        }
+        let shown: Vec<&PathBuf> = self
+            .tabs
+            .iter()
+            .flat_map(|t| &t.panes)
+            .map(|p| &p.current_dir)
+            .collect();
+        cached.retain(|dir, _| shown.contains(&dir));
+        let status = (!vfs::in_archive(dir))
+            .then(|| git::Status::of(dir))
+            .flatten()
+            .map(Rc::new);
+        cached.insert(dir.to_path_buf(), status.clone());
+        status
+    }
+
+    fn run_cargo(&mut self, task: Task) {
+        let Some(project) = self.projects().cargo else {
+            self.show_error(BrowrsError::invalid(
+                self.pane().current_dir.display().to_string(),
+                "Not in a Cargo project",
+            ));
+            return;
+        };
+        let label = format!("cargo {}", task.name());
+        let title = format!("🦀 {} in {}", label, project.name);
+        self.run_command(title, label, task.command(&project.dir));
+    }
+
+    fn open_scripts(&mut self) {
+        let Some(package) = self.projects().npm else {
+            self.show_error(BrowrsError::invalid(
+                self.pane().current_dir.display().to_string(),
+                "Not in a JavaScript package",
+            ));
+            return;
+        };
+        if package.scripts.is_empty() {
+            self.show_message(format!("📦 {} has no scripts", package.name));
+            return;
+        }
+        let width = package.scripts.iter().map(|(name, _)| name.len()).max();
+        let labels = package
+            .scripts
+            .iter()
+            .map(|(name, command)| {
+                format!("{:<width$}  {}", name, command, width = width.unwrap_or(0))
+            })
+            .collect();
+        self.picker = Some(Picker::new(
+            PickerKind::Scripts(package.clone()),
+            format!("Scripts of {} ({})", package.name, package.manager),
+            "Enter run, Esc close",
+            labels,
+        ));
+    }
+
+    fn run_script(&mut self, package: Package, script: &str) {
+        let label = format!("{} run {}", package.manager, script);
+        let title = format!("📦 {} in {}", label, package.name);
+        self.run_command(title, label, package.command(script));
+    }
+
+    fn open_tasks(&mut self) {
+        let dir = self.pane().current_dir.clone();
+        let targets = if vfs::in_archive(&dir) {
+            Vec::new()
+        } else {
+            tasks::find(&dir)
+        };
+        if targets.is_empty() {
+            self.show_error(BrowrsError::invalid(
+                dir.display().to_string(),
+                "No Makefile or justfile targets here",
+            ));
+            return;
+        }
+        let width = targets.iter().map(|t| t.label().len()).max().unwrap_or(0);
+        let labels = targets
+            .iter()
+            .map(|target| match &target.description {
+                Some(description) => format!("{:<width$}  {}", target.label(), description),
+                None => target.label(),
+            })
+            .collect();
+        self.picker = Some(Picker::new(
+            PickerKind::Tasks(dir.clone(), targets),
+            format!("Tasks in {}", dir.display()),
+            "Enter run, t run in the terminal, Esc close",
+            labels,
+        ));
+    }
+
+    /// Runs a task with its output kept, or on the terminal.
+    fn run_task(&mut self, dir: PathBuf, target: Target, in_terminal: bool) {
+        self.picker = None;
+        if !in_terminal {
+            let label = target.label();
+            let title = format!("🛠 {} in {}", label, dir.display());
+            self.run_command(title, label, target.command(&dir));
+            return;
+        }
+        let command = target.label();
+        match self.suspended(|| target.run_in_terminal(&dir)) {
+            Ok(status) if !status.success() => self.show_error(BrowrsError::Command {
+                command,
+                source: std::io::Error::other(format!("exited with {}", status)),
+            }),
+            Err(source) => self.show_error(BrowrsError::Command { command, source }),
+            _ => {}
+        }
+        self.refresh();
+    }
+
+    /// Runs `command` as a job, its output opening in a picker once it is
+    /// done.
+    fn run_command(&mut self, title: String, label: String, mut command: Command) {
+        let (sender, receiver) = mpsc::channel();
+        self.command_run = Some(receiver);
+        self.jobs
+            .spawn(jobs::Kind::Command, title, move |progress| {
+                let output = jobs::capture(label, &mut command, progress)?;
+                let message = if output.success {
+                    Ok(format!("✔ {} finished", output.command))
+                } else {
+                    Err(std::io::Error::other(output.status.clone()))
+                };
+                let _ = sender.send(output);
+                message
+            });
+        self.jobs_panel = true;
+    }
+
+    fn open_command_output(&mut self, output: jobs::Output) {
+        let title = if output.success {
+            format!("{} ✔", output.command)
+        } else {
+            format!("{} ✖ {}", output.command, output.status)
+        };
+        let mut picker = Picker::new(
+            PickerKind::CommandOutput,
+            title,
+            "Enter show line, Esc close",
+            output.lines,
+        );
+        // The summary is at the end
+        picker.selected = picker.items.len().saturating_sub(1);
+        self.picker = Some(picker);
+    }
+
+    /// Lists what was logged this session, newest first.
+    fn open_log(&mut self) {
+        let lines: Vec<String> = log::recent().into_iter().rev().collect();
+        let file = log::path().map_or("no log file".to_string(), |p| p.display().to_string());
+        if lines.is_empty() {
+            self.show_message(format!(
+                "📜 Nothing logged yet ({})\nStart browrs with -v, -vv or -vvv to log more",
+                file
+            ));
+            return;
+        }
+        self.picker = Some(Picker::new(
+            PickerKind::Log,
+            format!("Log: {}", file),
+            "Enter show line, Esc close",
+            lines,
+        ));
+    }
+
+    /// Shows how the current file differs from an earlier copy.
+    fn show_version_diff(&mut self, version: &snapshots::Version, current: &Path) {
+        let name = current.file_name().unwrap_or_default().to_string_lossy();
+        let header = format!("🕘 {} in {} → current", name, version.snapshot);
+        self.show_diff(header, &version.path, current);
+    }
+
+    /// Diffs the selected file against the file of the same name in the
+    /// next pane, which is taken as the old version.
+    fn diff_panes(&mut self) {
+        let Some(path) = self.selected_path().filter(|p| p.is_file()) else {
+            self.show_message("Select a file to compare with the other pane".to_string());
+            return;
+        };
+        let name = path.file_name().unwrap_or_default();
+        let other_dir = &self.tab().other_pane().current_dir;
+        let other = other_dir.join(name);
+        if other == path {
+            self.show_message("The other pane shows the same directory".to_string());
+            return;
+        }
+        if !other.is_file() {
+            self.show_message(format!(
+                "No {} in {}",
+                name.to_string_lossy(),
+                other_dir.display()
+            ));
+            return;
+        }
+        let header = format!(
+            "± {}: {} → {}",
+            name.to_string_lossy(),
+            other_dir.display(),
+            self.pane().current_dir.display()
+        );
+        self.show_diff(header, &other, &path);
+        // The preview is an overlay when both panes are shown
+        self.preview_overlay = true;
+    }
+
+    /// Shows the line diff from `old` to `new` under `header`.
+    fn show_diff(&mut self, header: String, old: &Path, new: &Path) {
+        let read = |path: &Path| std::fs::read(path).map_err(|e| BrowrsError::io("read", path, e));
+        let (old, new) = match (read(old), read(new)) {
+            (Ok(old), Ok(new)) => (old, new),
+            (Err(e), _) | (_, Err(e)) => {
+                self.show_error(e);
+                return;
+            }
+        };
+        if old == new {
+            self.show_message(format!("{}\n\nIdentical", header));
+            return;
+        }
+        let changes = match (String::from_utf8(old), String::from_utf8(new)) {
+            (Ok(old), Ok(new)) => diff::lines(&old, &new).map(|c| diff::render(&c, 3)),
+            _ => None,
+        };
+        let Some(mut text) = changes else {
+            self.show_message(format!(
+                "{}\n\nFiles differ (binary or too large to diff)",
+                header
+            ));
+            return;
+        };
+        text.lines.insert(0, Line::from(header.bold()));
+        text.lines.insert(1, Line::default());
+        self.show_text(text);
+    }
+
+    fn choose_picker_item(&mut self, index: usize) {
+        let Some(picker) = &self.picker else {
+            return;
+        };
+        match &picker.kind {
+            PickerKind::SendTo(targets) => {
+                let Some(target) = targets.get(index).cloned() else {
+                    return;
+                };
+                self.picker = None;
+                let files = self.targets();
+                let (kind, title) = match &target {
+                    SendTarget::Folder { name, .. } => (
+                        jobs::Kind::Copy,
+                        format!("⧉ Copying {} entries to {}", files.len(), name),
+                    ),
+                    SendTarget::Command { name, .. } => (
+                        jobs::Kind::Command,
+                        format!("➜ Sending {} entries to {}", files.len(), name),
+                    ),
+                };
+                self.start_operation(kind, title, move |progress| {
+                    sendto::send(&target, &files, progress)
+                });
+            }
+            PickerKind::Trash(items) => {
+                let Some(item) = items.get(index).cloned() else {
+                    return;
+                };
+                match trash::restore(&item) {
+                    Ok(original) => {
+                        self.show_message(format!("♻ Restored {}", original.display()));
+                        if original.parent() == Some(self.pane().current_dir.as_path()) {
+                            self.change_dir(self.pane().current_dir.clone());
+                        }
+                    }
+                    Err(e) => {
+                        let path = item.original.unwrap_or_else(|| PathBuf::from(&item.name));
+                        self.show_error(BrowrsError::io("restore", &path, e));
+                    }
+                }
+                self.open_trash();
+            }
+            PickerKind::Versions(path, versions) => {
+                let Some(version) = versions.get(index).cloned() else {
+                    return;
+                };
+                let path = path.clone();
+                self.picker = None;
+                self.show_version_diff(&version, &path);
+            }
+            PickerKind::Bookmarks => {
+                let Some(dir) = self.bookmarks.dirs.get(index).cloned() else {
+                    let smart = index - self.bookmarks.dirs.len();
+                    if let Some(folder) = self.smart_folders.folders.get(smart).cloned() {
+                        self.picker = None;
+                        self.open_smart_folder(folder);
+                        return;
+                    }
+                    let suggested = smart - self.smart_folders.folders.len();
+                    if let Some((dir, _)) = self.suggested_places().into_iter().nth(suggested) {
+                        self.picker = None;
+                        self.change_dir(dir);
+                        self.update_scroll();
+                        self.update_preview();
+                    }
+                    return;
+                };
+                self.picker = None;
+                self.change_dir(dir);
+                self.update_scroll();
+                self.update_preview();
+            }
+            PickerKind::Settings => {
+                let Some(setting) = settings::ALL.get(index) else {
+                    return;
+                };
+                match setting.next(&self.config) {
+                    Some(value) => self.change_setting(setting, value),
+                    None => {
+                        self.prompt = Some(Prompt::new(
+                            PromptKind::Setting(setting.key),
+                            setting.key,
+                            setting.value(&self.config),
+                        ));
+                    }
+                }
+            }
+            PickerKind::OpenWith(path, commands) => {
+                let path = path.clone();
+                // The last entry is for typing a command
+                match commands.get(index).filter(|_| index + 1 < commands.len()) {
+                    Some(command) => {
+                        let command = command.clone();
+                        self.picker = None;
+                        self.run_on_file(command, &path);
+                    }
+                    None => {
+                        self.picker = None;
+                        self.prompt = Some(Prompt::new(
+                            PromptKind::OpenWith(path.clone()),
+                            format!(
+                                "Open {} with",
+                                path.file_name().unwrap_or_default().to_string_lossy()
+                            ),
+                            "",
+                        ));
+                    }
+                }
+            }
+            PickerKind::Actions(path, actions) => {
+                let Some(action) = actions.get(index).cloned() else {
+                    return;
+                };
+                let path = path.clone();
+                self.picker = None;
+                self.run_file_action(action, path);
+            }
+            PickerKind::Scripts(package) => {
+                let Some((script, _)) = package.scripts.get(index) else {
+                    return;
+                };
+                let (package, script) = (package.clone(), script.clone());
+                self.picker = None;
+                self.run_script(package, &script);
+            }
+            PickerKind::Tasks(dir, targets) => {
+                let Some(target) = targets.get(index).cloned() else {
+                    return;
+                };
+                self.run_task(dir.clone(), target, false);
+            }
+            PickerKind::Log | PickerKind::CommandOutput => {
+                let Some(line) = picker.items.get(index).cloned() else {
+                    return;
+                };
+                self.picker = None;
+                self.show_message(line);
+            }
+            PickerKind::Cleanup(_) => self.prompt_cleanup(index, false),
+            PickerKind::Scratch => {
+                let Some(path) = self.scratch.get(index).cloned() else {
+                    return;
+                };
+                let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+                    return;
+                };
+                if path.symlink_metadata().is_err() {
+                    self.show_message(format!("❌ {} is gone", path.display()));
+                    return;
+                }
+                let name = name.to_string_lossy().to_string();
+                self.picker = None;
+                self.change_dir(parent.to_path_buf());
+                self.select_name(&name);
+                self.update_scroll();
+            }
+            PickerKind::Deleted(deleted) => {
+                let Some((original, version)) = deleted.get(index).cloned() else {
+                    return;
+                };
+                self.picker = None;
+                let mut report = ops::BatchReport::new("restore");
+                report.record(&original, history::restore(&version.path, &original));
+                self.finish_operation(report);
+            }
+        }
+    }
+
+    fn picker_key(&mut self, c: char) {
+        let Some(picker) = &self.picker else {
+            return;
+        };
+        match (&picker.kind, c) {
+            (PickerKind::Settings, 'w') => self.save_settings(),
+            (PickerKind::Bookmarks, 'd') => {
+                let index = picker.selected;
+                let dirs = self.bookmarks.dirs.len();
+                let smart = dirs + self.smart_folders.folders.len();
+                if index < dirs {
+                    if let Err(e) = self.bookmarks.remove(index) {
+                        self.save_bookmarks_failed(e);
+                    }
+                } else if index < smart {
+                    if let Err(e) = self.smart_folders.remove(index - dirs) {
+                        self.save_smart_folders_failed(e);
+                    }
+                } else if let Some((dir, _)) =
+                    self.suggested_places().into_iter().nth(index - smart)
+                    && let Err(e) = self.visits.forget(&dir)
+                {
+                    log::error!("couldn't forget the visits to {}: {}", dir.display(), e);
+                }
+                self.open_bookmarks();
+            }
+            (PickerKind::Tasks(dir, targets), 't') => {
+                if let Some(target) = targets.get(picker.selected).cloned() {
+                    self.run_task(dir.clone(), target, true);
+                }
+            }
+            (PickerKind::Scratch, 'd') => {
+                if picker.selected < self.scratch.len() {
+                    self.scratch.remove(picker.selected);
+                }
+                self.open_scratch();
+            }
+            (PickerKind::Scratch, 'x') => {
+                self.scratch.clear();
+                self.open_scratch();
+            }
+            (PickerKind::Scratch, 'c') => self.prompt_scratch_transfer(PromptKind::Copy),
+            (PickerKind::Scratch, 'm') => self.prompt_scratch_transfer(PromptKind::Move),
+            (PickerKind::Scratch, 't') => self.prompt_scratch_delete(false),
+            (PickerKind::Scratch, 'D') => self.prompt_scratch_delete(true),
+            (PickerKind::Cleanup(_), 'D') => self.prompt_cleanup(picker.selected, true),
+            (PickerKind::Trash(items), 'E') if !items.is_empty() => {
+                self.prompt = Some(Prompt::confirm(
+                    PromptKind::ConfirmEmptyTrash,
+                    "Empty trash",
+                    format!("Permanently delete {} trashed entries?", items.len()),
+                ));
+            }
+            (PickerKind::Versions(path, versions), 'R') => {
+                let Some(version) = versions.get(picker.selected) else {
+                    return;
+                };
+                self.prompt = Some(Prompt::confirm(
+                    PromptKind::ConfirmRestoreVersion {
+                        version: version.path.clone(),
+                        dest: path.clone(),
+                    },
+                    format!(
+                        "Restore {}",
+                        path.file_name().unwrap_or_default().to_string_lossy()
+                    ),
+                    format!(
+                        "Copy from {}? The current file goes to the trash",
+                        version.snapshot
+                    ),
+                ));
+            }
+            _ => {}
+        }
+    }
+
+    /// Re-reads the listing after an operation and reports its outcome.
+    fn finish_operation(&mut self, report: ops::BatchReport) {
+        report.log();
+        self.refresh();
+        self.show_message(report.summary());
+    }
+
+    /// Runs a batch operation as a job, with the progress panel up until it
+    /// ends or is hidden; its report is shown when it is done.
+    fn start_operation<F>(&mut self, kind: jobs::Kind, title: String, work: F)
+    where
+        F: FnOnce(&jobs::Progress) -> ops::BatchReport + Send + 'static,
+    {
+        self.jobs.spawn(kind, title, move |progress| {
+            let report = work(progress);
+            report.log();
+            Ok(report.summary())
+        });
+        self.jobs_panel = true;
+    }
+
+    /// Re-reads every tab's listing, keeping the cursors on the same entries.
+    fn refresh(&mut self) {
+        self.git.borrow_mut().clear();
+        self.previews.borrow_mut().clear();
+        let listing = self.listing();
+        for pane in self.tabs.iter_mut().flat_map(|t| &mut t.panes) {
+            pane.reload(listing.clone());
+        }
+    }
+
+    /// Reads the focused pane's directory again, for changes made outside
+    /// browrs, leaving the cursor, marks and scroll where they were.
+    fn reread(&mut self) {
+        let dir = self.pane().current_dir.clone();
+        self.git.borrow_mut().remove(&dir);
+        self.previews.borrow_mut().clear();
+        self.timed_out.borrow_mut().clear();
+        let listing = self.listing();
+        self.pane_mut().reread(listing);
+        self.update_preview();
+        log::info!("re-read {}", dir.display());
+    }
+
+    fn finish_job(&mut self, finished: jobs::Finished) {
+        self.refresh();
+        let message = match finished.result {
+            Ok(message) => message,
+            Err(e) => e.message(),
+        };
+        self.notify_finished(finished.kind, &finished.title, finished.elapsed, &message);
+        self.show_message(message);
+    }
+
+    /// Sends a desktop notification for a job that took long enough to
+    /// have been left in the background.
+    fn notify_finished(&self, kind: jobs::Kind, title: &str, elapsed: Duration, message: &str) {
+        let after = self
+            .config
+            .notify
+            .get(kind.name())
+            .copied()
+            .unwrap_or(self.config.notify_after);
+        if after == 0 || elapsed < Duration::from_secs(after) || self.terminal_focused == Some(true)
+        {
+            return;
+        }
+        log::info!("notifying that {} ended", title);
+        if let Err(e) = desktop::notify(title, message, self.config.notifier.as_deref()) {
+            log::error!("couldn't notify that {} ended: {}", title, e);
+        }
+    }
+
+    fn prompt_editor(&mut self) {
+        let Some(path) = self.selected_path().filter(|p| p.is_file()) else {
+            return;
+        };
+        let file_type = editor::file_type(&path);
+        let label = if file_type.is_empty() {
+            "files without extension".to_string()
+        } else {
+            format!("*.{} files", file_type)
+        };
+        let current =
+            editor::resolve(&path, &self.editor_choices, &self.config).unwrap_or_default();
+        self.prompt = Some(Prompt::new(
+            PromptKind::Editor(file_type),
+            format!("Editor for {}", label),
+            current,
+        ));
+    }
+
+    fn open_quick_edit(&mut self) {
+        let Some(path) = self.selected_path().filter(|p| p.is_file()) else {
+            return;
+        };
+        match QuickEdit::open(path.clone()) {
+            Ok(edit) => self.quick_edit = Some(edit),
+            Err(e) => self.show_error(BrowrsError::io("edit", &path, e)),
+        }
+    }
+
+    /// Writes the quick edit back, keeping the old contents as a version.
+    fn save_quick_edit(&mut self) {
+        let Some(edit) = self.quick_edit.as_mut() else {
+            return;
+        };
+        let path = edit.path.clone();
+        let result = history::save(&path, self.config.keep_versions)
+            .and_then(|_| std::fs::write(&path, edit.contents()));
+        match result {
+            Ok(()) => {
+                edit.saved();
+                self.refresh();
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                self.show_message(format!("💾 Saved {}", name));
+            }
+            Err(e) => self.show_error(BrowrsError::io("save", &path, e)),
+        }
+    }
+
+    /// Opens the permissions dialog on the selected entry's mode, to set
+    /// it on every targeted entry.
+    #[cfg(unix)]
+    fn open_permissions(&mut self) {
+        use std::os::unix::fs::MetadataExt;
+
+        let targets = self.targets();
+        let Some(path) = self.selected_path().or_else(|| targets.first().cloned()) else {
+            return;
+        };
+        let mode = match std::fs::metadata(&path) {
+            Ok(metadata) => metadata.mode(),
+            Err(e) => return self.show_error(BrowrsError::io("read", &path, e)),
+        };
+        let subject = match targets.as_slice() {
+            [one] => one
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned(),
+            many => format!("{} entries", many.len()),
+        };
+        self.permissions = Some(PermissionsEditor::new(subject, mode));
+    }
+
+    #[cfg(unix)]
+    fn apply_permissions(&mut self, mode: u32) {
+        let change = attrs::AttrChange {
+            mode: Some(attrs::ModeSpec::Octal(mode)),
+            ..Default::default()
+        };
+        let report = attrs::apply(&change, &self.targets());
+        self.finish_operation(report);
+    }
+
+    /// Asks for the new owner and group of the targeted entries, starting
+    /// from the selected entry's.
+    #[cfg(unix)]
+    fn prompt_owner(&mut self) {
+        use std::os::unix::fs::MetadataExt;
+
+        let Some(path) = self.selected_path() else {
+            return;
+        };
+        let current = match std::fs::symlink_metadata(&path) {
+            Ok(metadata) => {
+                let user = attrs::user_name(metadata.uid());
+                let group = attrs::group_name(metadata.gid());
+                format!(
+                    "{}:{}",
+                    user.unwrap_or_else(|| metadata.uid().to_string()),
+                    group.unwrap_or_else(|| metadata.gid().to_string())
+                )
+            }
+            Err(e) => return self.show_error(BrowrsError::io("read", &path, e)),
+        };
+        self.prompt = Some(
+            Prompt::new(
+                PromptKind::Owner,
+                "Owner: user[:group], Tab completes",
+                current,
+            )
+            .with_completion(attrs::complete_owner),
+        );
+    }
+
+    /// Changes the owner, keeping the prompt up with what went wrong when
+    /// the name is unknown or the change isn't allowed.
+    #[cfg(unix)]
+    fn submit_owner(&mut self, spec: String) {
+        let reopen = |error: String| {
+            Prompt::new(
+                PromptKind::Owner,
+                "Owner: user[:group], Tab completes",
+                spec.clone(),
+            )
+            .with_completion(attrs::complete_owner)
+            .with_error(error)
+        };
+        let change = match attrs::AttrChange::owner(&spec) {
+            Ok(change) => change,
+            Err(e) => {
+                self.prompt = Some(reopen(e));
+                return;
+            }
+        };
+        let report = attrs::apply(&change, &self.targets());
+        report.log();
+        self.refresh();
+        match report.errors.first() {
+            Some(error) => {
+                let failed = match report.errors.len() {
+                    1 => String::new(),
+                    n => format!(" ({} entries failed)", n),
+                };
+                self.prompt = Some(reopen(format!("{}{}", error.message(), failed)));
+            }
+            None => self.show_message(report.summary()),
+        }
+    }
+
+    #[cfg(unix)]
+    fn prompt_attributes(&mut self) {
+        use std::os::unix::fs::MetadataExt;
+
+        let Some(path) = self.selected_path() else {
+            return;
+        };
+        let current = std::fs::symlink_metadata(&path)
+            .map(|m| format!("{:o}", m.mode() & 0o7777))
+            .unwrap_or_default();
+        self.prompt = Some(Prompt::new(
+            PromptKind::Attributes,
+            "Permissions: [-R] [mode] [owner[:group]]",
+            current,
+        ));
+    }
+
+    #[cfg(unix)]
+    fn submit_attributes(&mut self, spec: String, confirmed: bool) {
+        let roots = self.targets();
+        if roots.is_empty() {
+            return;
+        }
+        let change = match attrs::AttrChange::parse(&spec) {
+            Ok(change) => change,
+            Err(e) => {
+                self.show_error(BrowrsError::invalid("", e));
+                return;
+            }
+        };
+        let targets = attrs::targets(&roots, change.recursive);
+
+        if !confirmed {
+            let dirs = targets.iter().filter(|p| p.is_dir()).count();
+            self.prompt = Some(Prompt::confirm(
+                PromptKind::ConfirmAttributes(spec.clone()),
+                format!("Apply '{}'?", spec.trim()),
+                format!("Affects {} entries ({} directories)", targets.len(), dirs),
+            ));
+            return;
+        }
+
+        let report = attrs::apply(&change, &targets);
+        self.finish_operation(report);
+    }
+
+    fn submit_prompt(&mut self, kind: PromptKind, input: String) {
+        // Search and grep results acted on as a batch have served their
+        // purpose
+        let closes_search = matches!(
+            kind,
+            PromptKind::Copy
+                | PromptKind::Move
+                | PromptKind::ConfirmTrash
+                | PromptKind::ConfirmDelete
+        );
+        match kind {
+            PromptKind::Command => self.remember(recall::Kind::Command, &input),
+            PromptKind::Filter => self.remember(recall::Kind::Filter, &input),
+            PromptKind::Goto => self.remember(recall::Kind::Goto, &input),
+            _ => {}
+        }
+        match kind {
+            PromptKind::Editor(file_type) => {
+                let input = input.trim().to_string();
+                if input.is_empty() {
+                    self.editor_choices.remove(&file_type);
+                    return;
+                }
+                self.editor_choices.insert(file_type, input);
+                if let Some(path) = self.selected_path() {
+                    self.open_in_editor(&path);
+                }
+            }
+            PromptKind::Copy | PromptKind::Move => {
+                let dest = PathBuf::from(input.trim());
+                let dest = if dest.is_relative() {
+                    self.pane().current_dir.join(dest)
+                } else {
+                    dest
+                };
+                let targets = self.targets();
+                let title = format!("{} entries", targets.len());
+                let from_archive = targets.iter().any(|path| vfs::is_archive_member(path));
+                if kind == PromptKind::Move {
+                    self.start_operation(
+                        jobs::Kind::Move,
+                        format!("➜ Moving {}", title),
+                        move |progress| ops::move_all(&targets, &dest, progress),
+                    );
+                } else if self.config.verify_copies && !from_archive {
+                    self.start_operation(
+                        jobs::Kind::Copy,
+                        format!("⧉ Copying {}", title),
+                        move |progress| ops::copy_verified(&targets, &dest, progress),
+                    );
+                } else {
+                    self.start_operation(
+                        jobs::Kind::Copy,
+                        format!("⧉ Copying {}", title),
+                        move |progress| ops::copy_all(&targets, &dest, progress),
+                    );
+                }
+            }
+            PromptKind::Link(hard) => {
+                let dest = PathBuf::from(input.trim());
+                let dest = self.pane().current_dir.join(dest);
+                let report = ops::link_all(&self.targets(), &dest, hard);
+                self.finish_operation(report);
+            }
+            PromptKind::Rename => self.submit_rename(input, false),
+            PromptKind::ConfirmRename(new_name) => self.submit_rename(new_name, true),
+            PromptKind::Touch => self.submit_touch(input),
+            PromptKind::Extract(path) => self.start_extract(path, input),
+            PromptKind::Compress => self.start_compress(input),
+            PromptKind::NewFile => self.submit_new(input, false),
+            PromptKind::NewDir => self.submit_new(input, true),
+            PromptKind::PasteFile(text) => self.submit_paste_file(input, text),
+            PromptKind::Setting(key) => {
+                if let Some(setting) = settings::find(key) {
+                    self.change_setting(setting, input.trim().to_string());
+                }
+            }
+            PromptKind::Filter => {
+                let filter = Some(input.trim().to_string()).filter(|f| !f.is_empty());
+                log::info!("filter {:?}", filter);
+                let listing = self.listing();
+                self.pane_mut().set_filter(filter, listing);
+                self.update_scroll();
+                self.update_preview();
+            }
+            PromptKind::ConfirmTrash => {
+                let targets = self.targets();
+                let title = format!("🗑 Trashing {} entries", targets.len());
+                self.start_operation(jobs::Kind::Trash, title, move |progress| {
+                    trash::trash_all(&targets, progress)
+                });
+            }
+            PromptKind::ConfirmEmptyTrash => {
+                match trash::empty() {
+                    Ok(report) => self.show_message(report.summary()),
+                    Err(e) => self.show_error(BrowrsError::io("empty", Path::new("the trash"), e)),
+                }
+                self.open_trash();
+            }
+            PromptKind::ConfirmDelete => {
+                let targets = self.targets();
+                let keep = self.config.keep_versions;
+                let title = format!("✖ Deleting {} entries", targets.len());
+                self.start_operation(jobs::Kind::Delete, title, move |progress| {
+                    ops::delete_all(&targets, keep, progress)
+                });
+            }
+            #[cfg(unix)]
+            PromptKind::Attributes => self.submit_attributes(input, false),
+            #[cfg(unix)]
+            PromptKind::ConfirmAttributes(spec) => self.submit_attributes(spec, true),
+            #[cfg(unix)]
+            PromptKind::Owner => self.submit_owner(input),
+            #[cfg(not(unix))]
+            PromptKind::Attributes | PromptKind::ConfirmAttributes(_) | PromptKind::Owner => {}
+            PromptKind::Command => self.submit_command(input),
+            PromptKind::Goto => self.goto(input.trim()),
+            PromptKind::OpenWith(path) => {
+                let command = input.trim().to_string();
+                if !command.is_empty() {
+                    self.run_on_file(command, &path);
+                }
+            }
+            PromptKind::Annotate(path) => self.annotate(path, input),
+            PromptKind::SaveSmartFolder(folder) => self.save_smart_folder(folder, input),
+            PromptKind::ConfirmDownload(url) => self.start_download(url),
+            PromptKind::Export(report) => self.export(report, input.trim()),
+            PromptKind::ConfirmCleanup { index, permanent } => {
+                self.submit_cleanup(index, permanent)
+            }
+            PromptKind::ConfirmRestoreVersion { version, dest } => {
+                self.picker = None;
+                let mut report = ops::BatchReport::new("restore");
+                report.record(&dest, snapshots::restore(&version, &dest));
+                self.finish_operation(report);
+            }
+        }
+        if closes_search {
+            if self.scratch_batch {
+                self.scratch.clear();
+            } else {
+                self.search = None;
+                self.grep = None;
+            }
+        }
+        self.scratch_batch = false;
+    }
+
+    fn open_in_editor(&mut self, file_path: &PathBuf) {
+        let Some(command) = editor::resolve(file_path, &self.editor_choices, &self.config) else {
+            self.show_message(format!(
+                "❌ No editor found\nTried: {}\nSet $EDITOR or press e to choose one",
+                editor::candidates(file_path, &self.editor_choices, &self.config).join(", ")
+            ));
+            return;
+        };
+        self.run_on_file(command, file_path);
+    }
+
+    /// Opens `path` with the program an `[open]` rule names for it.
+    fn open_with_rule(&mut self, rule: &opener::Rule, path: &Path) {
+        let result = if rule.gui {
+            rule.run(path)
+        } else {
+            self.suspended(|| rule.run(path))
+        };
+        if let Err(source) = result {
+            self.show_error(BrowrsError::Command {
+                command: rule.expand(path),
+                source,
+            });
+        }
+    }
+
+    /// Lists what can be done with the selected entry.
+    fn open_actions(&mut self) {
+        let Some(path) = self.selected_path() else {
+            return;
+        };
+        let actions = file_actions::for_path(&path, &self.config.actions);
+        let items = actions.iter().map(FileAction::label).collect();
+        self.picker = Some(Picker::new(
+            PickerKind::Actions(path.clone(), actions),
+            format!(
+                "Actions for {}",
+                path.file_name().unwrap_or_default().to_string_lossy()
+            ),
+            "Enter run, Esc cancel",
+            items,
+        ));
+    }
+
+    fn run_file_action(&mut self, action: FileAction, path: PathBuf) {
+        log::info!("{} on {}", action.label(), path.display());
+        match action {
+            FileAction::Open => self.open_selected(),
+            FileAction::Extract => self.prompt_extract(),
+            FileAction::OpenDefault => self.open_default(),
+            FileAction::Run => {
+                if let Ok(rule) = opener::Rule::parse("*", "{file}") {
+                    self.open_with_rule(&rule, &path);
+                    self.refresh();
+                }
+            }
+            FileAction::Custom(association) => {
+                if let Some(path) = self.local_copy(path) {
+                    self.open_with_rule(&association.rule, &path);
+                    self.refresh();
+                }
+            }
+        }
+    }
+
+    /// Opens the selected entry with the default application, offering
+    /// other programs if that fails.
+    fn open_default(&mut self) {
+        let Some(path) = self.selected_path().and_then(|path| self.local_copy(path)) else {
+            return;
+        };
+        let opener = self.config.opener.clone();
+        let status = self.suspended(|| desktop::open_default(&path, opener.as_deref()));
+
+        let reason = match status {
+            Ok(status) if status.success() => return,
+            Ok(status) => format!("exited with {}", status),
+            Err(e) => e.to_string(),
+        };
+        log::error!("couldn't open {}: {}", path.display(), reason);
+        let mut items = desktop::open_with_candidates();
+        items.push("Other…".to_string());
+        self.picker = Some(Picker::new(
+            PickerKind::OpenWith(path.clone(), items.clone()),
+            format!(
+                "Open {} with ({})",
+                path.file_name().unwrap_or_default().to_string_lossy(),
+                reason
+            ),
+            "Enter open, Esc cancel",
+            items,
+        ));
+    }
+
+    /// Gives the terminal back for the length of `run`, for a program that
+    /// uses it. Without a terminal, as in tests, it just runs.
+    fn suspended<T>(&mut self, run: impl FnOnce() -> T) -> T {
+        use std::io::IsTerminal;
+        if !std::io::stdout().is_terminal() {
+            return run();
+        }
+        restore_terminal();
+        let result = run();
+        init_terminal();
+        self.redraw = true;
+        result
+    }
+
+    /// Runs `command` on `file_path` in the terminal, suspending the app
+    /// until it exits.
+    fn run_on_file(&mut self, command: String, file_path: &PathBuf) {
+        let status = self.suspended(|| editor::open(&command, file_path));
+
+        match status {
+            Ok(status) if !status.success() => {
+                self.show_error(BrowrsError::Command {
+                    command,
+                    source: std::io::Error::other(format!("exited with {}", status)),
+                });
+            }
+            Err(source) => self.show_error(BrowrsError::Command { command, source }),
+            _ => {}
+        }
+    }
+
+    fn update_preview(&mut self) {
+        let _timer = log::timer(|| "preview".to_string());
+        self.preview = self.pane().entries.get(self.pane().selected).map(|entry| {
+            if entry.is_parent() {
+                Text::from("← Parent Directory").into()
+            } else {
+                let path = self.pane().current_dir.join(&entry.name);
+                let mut preview = self.read_preview(path.clone());
+                if let Some(note) = self.annotations.get(&path) {
+                    preview.annotate(note);
+                }
+                preview
+            }
+        });
+        self.preview_id += 1;
+        self.preview_scroll = 0;
+        self.focus = Focus::List;
+    }
+
+    /// Reads the preview on a thread of its own, so that a read which
+    /// hangs, like on a stale NFS handle or a misbehaving FUSE mount, leaves
+    /// that thread stuck instead of the interface.
+    fn read_preview(&self, path: PathBuf) -> Preview {
+        if self.timed_out.borrow().contains(&path) {
+            return self.timed_out_preview(&path);
+        }
+        if let Some(preview) = self.previews.borrow().get(&path) {
+            return preview;
+        }
+        let (sender, receiver) = mpsc::channel();
+        let (show_hidden, max_size) = (self.show_hidden, self.config.preview_max_size);
+        let reading = path.clone();
+        let theme = self.config.theme;
+        let previewers = self.previewers();
+        std::thread::spawn(move || {
+            theme::set_current(theme);
+            let _ = sender.send(preview::preview(
+                &reading,
+                show_hidden,
+                max_size,
+                &previewers,
+            ));
+        });
+        let timeout = Duration::from_millis(self.config.preview_timeout_ms);
+        match receiver.recv_timeout(timeout) {
+            Ok(preview) => {
+                self.previews.borrow_mut().store(&path, &preview);
+                preview
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                log::error!("preview of {} timed out", path.display());
+                let preview = self.timed_out_preview(&path);
+                self.timed_out.borrow_mut().insert(path);
+                preview
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => Text::from("Unable to preview").into(),
+        }
+    }
+
+    /// The `[previewers]` commands, each given as long as a preview.
+    fn previewers(&self) -> Previewers {
+        Previewers {
+            rules: self.config.previewers.clone(),
+            timeout: Duration::from_millis(self.config.preview_timeout_ms),
+        }
+    }
+
+    fn timed_out_preview(&self, path: &Path) -> Preview {
+        let keys = self
+            .config
+            .keymap
+            .keys_for(Action::RetryPreview)
+            .unwrap_or_default()
+            .iter()
+            .map(|chord| chord.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        Text::from(vec![
+            Line::from(format!("⏳ {} | preview timed out", name)),
+            Line::from("─".repeat(40)),
+            Line::from(format!(
+                "Reading it took longer than {} ms",
+                self.config.preview_timeout_ms
+            )),
+            Line::default(),
+            Line::from(format!("Press {} to try again", keys).fg(self.config.theme.dim)),
+        ])
+        .into()
+    }
+
+    fn show_error(&mut self, error: BrowrsError) {
+        log::error!("{}", error);
+        self.show_message(error.message());
+    }
+
+    fn show_message(&mut self, message: String) {
+        let first = message.lines().find(|line| !line.trim().is_empty());
+        self.status = first.map(|line| (line.trim().to_string(), Instant::now()));
+        self.show_text(Text::from(message));
+    }
+
+    fn show_text(&mut self, text: Text<'static>) {
+        self.preview = Some(text.into());
+        self.preview_id += 1;
+    }
+
+    /// Draws the preview image with the terminal's graphics protocol after
+    /// ratatui has drawn everything else. Overlays hide it, since the image
+    /// would cover them.
+    fn draw_image(&mut self, terminal: &mut DefaultTerminal) -> std::io::Result<()> {
+        if self.graphics == Protocol::HalfBlocks {
+            return Ok(());
+        }
+        let overlay = self.prompt.is_some()
+            || self.picker.is_some()
+            || self.server_panel
+            || self.jobs_panel
+            || self.quick_edit.is_some()
+            || self.hex_view.is_some()
+            || self.qr_view.is_some()
+            || self.permissions.is_some();
+        let wanted = self
+            .image_area
+            .get()
+            .filter(|_| !overlay)
+            .map(|area| (self.preview_id, area));
+        if wanted == self.shown_image {
+            return Ok(());
+        }
+
+        if self.shown_image.take().is_some() {
+            match graphics::clear(self.graphics) {
+                Some(sequence) => terminal.backend_mut().write_all(sequence.as_bytes())?,
+                None => {
+                    terminal.clear()?;
+                    terminal.draw(|frame| self.draw(frame))?;
+                }
+            }
+        }
+        if let (Some((_, area)), Some(image)) =
+            (wanted, self.preview.as_ref().and_then(|p| p.image.as_ref()))
+        {
+            let sequence = graphics::encode(self.graphics, image, area);
+            terminal.backend_mut().write_all(sequence.as_bytes())?;
+        }
+        terminal.backend_mut().flush()?;
+        self.shown_image = wanted;
+        Ok(())
+    }
+
+    fn update_scroll(&mut self) {
+        // what to do here?
+    }
+
+    /// Whether an entry was modified within the configured recent window.
+    fn is_recent(&self, entry: &Entry) -> bool {
+        if self.config.recent_minutes == 0 || entry.is_parent() {
+            return false;
+        }
+        entry
+            .modified
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_some_and(|age| age.as_secs() < self.config.recent_minutes * 60)
+    }
+
+    /// Whether an entry hasn't been modified for the configured stale days.
+    fn is_stale(&self, entry: &Entry) -> bool {
+        if self.config.stale_days == 0 || entry.is_parent() {
+            return false;
+        }
+        entry
+            .modified
+            .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+            .is_some_and(|age| age.as_secs() >= self.config.stale_days * 86_400)
+    }
+
+    fn exit(&mut self) {
+        self.exit = true;
+    }
+
+    pub fn is_exiting(&self) -> bool {
+        self.exit
+    }
+
+    /// Whether any background job is still running.
+    pub fn is_busy(&self) -> bool {
+        !self.jobs.is_empty()
+    }
+
+    pub fn draw(&self, frame: &mut Frame) {
+        frame.render_widget(self, frame.area());
+    }
+}
+
+impl App {
+    fn render_tab_bar(&self, area: Rect, buf: &mut Buffer) {
+        let mut spans = Vec::new();
+        for (i, tab) in self.tabs.iter().enumerate() {
+            let label = format!(" {}:{} ", i + 1, tab.title());
+            let accent = self.config.theme.accent;
+            spans.push(if i == self.active {
+                label.fg(accent).reversed().bold()
+            } else {
+                label.fg(accent)
+            });
+            spans.push(" ".into());
+        }
+        Paragraph::new(Line::from(spans)).render(area, buf);
+    }
+
+    /// A project the current directory belongs to, with the keys for
+    /// what can be run in it.
+    fn render_project_bar(
+        &self,
+        title: String,
+        version: Option<&str>,
+        dir: &Path,
+        keys: &[(&str, Action)],
+        area: Rect,
+        buf: &mut Buffer,
+    ) {
+        let accent = self.config.theme.accent;
+        let mut spans = vec![title.fg(accent).bold()];
+        if let Some(version) = version {
+            spans.push(format!(" {}", version).fg(accent));
+        }
+        spans.push(format!("  {}", dir.display()).fg(self.config.theme.dim));
+        for (label, action) in keys {
+            let chords = self.config.keymap.keys_for(*action);
+            if let Some(chords) = chords.filter(|k| !k.is_empty()) {
+                let chords: Vec<String> = chords.iter().map(|chord| chord.to_string()).collect();
+                spans.push(format!("  {} ", label).into());
+                spans.push(format!("<{}>", chords.join(" ")).fg(accent).bold());
+            }
+        }
+        Paragraph::new(Line::from(spans)).render(area, buf);
+    }
+
+    /// Draws the listing of pane `index` of the current tab. The focused
+    /// pane also carries the search and the status of background work.
+    fn render_pane(&self, index: usize, area: Rect, buf: &mut Buffer) {
+        let pane = &self.tab().panes[index];
+        let focused = index == self.tab().focus;
+        let mut list_rect = area;
+
+        if let Some(note) = &pane.note {
+            let banner_height = (note.lines.len() as u16 + 2).min(list_rect.height / 3);
+            let [banner_rect, rest] = ratatui::layout::Layout::vertical([
+                ratatui::layout::Constraint::Length(banner_height),
+                ratatui::layout::Constraint::Min(0),
+            ])
+            .areas(list_rect);
+            list_rect = rest;
+
+            let banner_lines: Vec<Line> = note
+                .lines
+                .iter()
+                .map(|line| Line::from(line.clone().italic()))
+                .collect();
+            Paragraph::new(Text::from(banner_lines))
+                .block(
+                    Block::bordered()
+                        .title(format!(" 📌 {} ", note.file_name).fg(self.config.theme.highlight))
+                        .border_style(self.config.theme.border)
+                        .border_set(border::PLAIN),
+                )
+                .render(banner_rect, buf);
+        }
+
+        // The table of details has a row of column names
+        let header = u16::from(self.detailed);
+        let max_visible = list_rect.height.saturating_sub(2 + header) as usize;
+
+        let scroll = pane.scroll_for_height(max_visible);
+        if self.search.is_none() && self.grep.is_none() {
+            let mut rect = list_rect.inner(Margin::new(1, 1));
+            rect.y += header.min(rect.height);
+            rect.height = rect.height.saturating_sub(header);
+            self.list_areas.borrow_mut().push(ListArea {
+                pane: index,
+                rect,
+                first: scroll,
+            });
+        }
+
+        let total = pane.entries.len();
+        let start = scroll;
+        let end = (start + max_visible).min(total);
+        let numbered = if focused && pane.filter.is_some() {
+            self.first_visible.set(start);
+            pane.numbered(start)
+        } else {
+            Vec::new()
+        };
+
+        let git = self.git_status(&pane.current_dir);
+        let theme = &self.config.theme;
+        let ls_colors = self.config.ls_colors.as_ref();
+        let file_lines: Vec<(Line, Style)> = pane.entries[start..end]
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| {
+                let absolute_index = start + i;
+                let name = entry.display_name();
+                let size_color = (!entry.is_dir)
+                    .then(|| self.config.size_color(entry.size))
+                    .flatten();
+                let mut line = if entry.marked {
+                    Line::from(format!("* {}", name).fg(theme.marked).bold())
+                } else if let Some(style) = ls_colors.and_then(|colors| colors.style(entry)) {
+                    Line::from(Span::styled(name, style))
+                } else if let Some(special) = entry.special() {
+                    // Like the pi, so, bd and cd colors of ls
+                    Line::from(match special {
+                        Special::Fifo => name.fg(theme.fifo),
+                        Special::Socket => name.fg(theme.socket).bold(),
+                        Special::BlockDevice | Special::CharDevice => name.fg(theme.device).bold(),
+                    })
+                } else if let Some(link) = &entry.link {
+                    // Like the ln and or colors of ls
+                    Line::from(if link.broken {
+                        name.fg(theme.broken_link)
+                    } else {
+                        name.fg(theme.link)
+                    })
+                } else if self.is_stale(entry) {
+                    Line::from(name.fg(theme.stale))
+                } else if let Some(color) = size_color {
+                    Line::from(name.fg(color))
+                } else if let Some(style) = file_colors::style(entry, theme) {
+                    Line::from(Span::styled(name, style))
+                } else {
+                    Line::from(name)
+                };
+                if pane.filter.is_some() && focused {
+                    let number = match numbered.iter().position(|&n| n == absolute_index) {
+                        Some(position) => format!("{} ", position + 1),
+                        None => "  ".to_string(),
+                    };
+                    line.spans.insert(0, number.fg(theme.dim));
+                }
+                if let Some(link) = &entry.link {
+                    let target = format!(" -> {}", link.target.display());
+                    line.push_span(if link.broken {
+                        target.fg(theme.broken_link).crossed_out()
+                    } else {
+                        target.fg(theme.dim)
+                    });
+                }
+                if let Some(color) = size_color
+                    && !self.detailed
+                {
+                    line.push_span(format!(" {}", jobs::format_size(entry.size)).fg(color));
+                }
+                if self.is_recent(entry) {
+                    line.push_span(" ●".fg(theme.highlight).bold());
+                }
+                // Right-aligned, as columns
+                let mut columns = Vec::new();
+                if entry.rating > 0 {
+                    columns.push(
+                        format!(" {}", "★".repeat(entry.rating as usize)).fg(theme.highlight),
+                    );
+                }
+                if let Some(git) = &git
+                    && !entry.is_parent()
+                    && let Some(mark) = git.mark(&pane.current_dir.join(&entry.name))
+                {
+                    let symbol = format!(" {}", mark.symbol());
+                    columns.push(match mark {
+                        git::Mark::Modified => symbol.fg(theme.highlight).bold(),
+                        git::Mark::Staged => symbol.fg(theme.success).bold(),
+                        git::Mark::Untracked => symbol.fg(theme.error),
+                        git::Mark::Ignored => symbol.fg(theme.dim),
+                    });
+                }
+                if self.config.line_counts
+                    && let Some(count) = self.line_counts.get(&pane.current_dir, entry)
+                {
+                    let count = match count {
+                        1 => " 1 line".to_string(),
+                        count => format!(" {} lines", count),
+                    };
+                    columns.push(count.fg(theme.dim));
+                }
+                if self.detailed {
+                    line.spans.extend(columns);
+                } else if !columns.is_empty() {
+                    let inner = list_rect.width.saturating_sub(2) as usize;
+                    let width: usize = columns.iter().map(|span| span.width()).sum();
+                    let padding = inner.saturating_sub(line.width() + width);
+                    line.push_span(" ".repeat(padding));
+                    line.spans.extend(columns);
+                }
+                let style = if absolute_index == pane.selected && focused {
+                    Style::default()
+                        .bg(theme.selection)
+                        .fg(theme.selection_text)
+                        .add_modifier(Modifier::BOLD)
+                } else if absolute_index == pane.selected {
+                    Style::default().bg(theme.cursor)
+                } else {
+                    Style::default()
+                };
+                (line, style)
+            })
+            .collect();
+
+        let narrowed: Vec<String> = pane
+            .filter
+            .iter()
+            .cloned()
+            .chain((!pane.kinds.is_empty()).then(|| pane.kinds.label()))
+            .chain((pane.min_rating > 0).then(|| format!("★{}+", pane.min_rating)))
+            .collect();
+        let sort = format!(
+            "{}{}",
+            self.sort.label(),
+            if self.dirs_first { ", dirs first" } else { "" }
+        );
+        let branch = git
+            .as_ref()
+            .and_then(|git| git.branch.as_ref())
+            .map(|branch| format!(" ⎇ {} ·", branch))
+            .unwrap_or_default();
+        let (title, sort) = match &self.config.pane_title {
+            // The template has the sort and filter where it wants them
+            Some(template) => {
+                let title = template
+                    .replace("{path}", &pane.current_dir.display().to_string())
+                    .replace("{shortpath}", &pane::short_path(&pane.current_dir))
+                    .replace("{count}", &(pane.entries.len() - 1).to_string())
+                    .replace("{total}", &pane.total.to_string())
+                    .replace("{filter}", &narrowed.join(", "))
+                    .replace("{sort}", &sort);
+                (format!(" {} ", title.trim()), String::new())
+            }
+            None => {
+                let filter = if narrowed.is_empty() {
+                    String::new()
+                } else {
+                    format!(
+                        " {}: {} of {} entries ·",
+                        narrowed.join(", "),
+                        pane.entries.len() - 1,
+                        pane.total
+                    )
+                };
+                let sort = format!("{} sort: {} ", filter, sort);
+                // Keep the end of a long path, leaving room for the branch
+                // and the sort mode
+                let room = (list_rect.width as usize)
+                    .saturating_sub(branch.chars().count() + sort.chars().count() + 4);
+                let dir = pane.current_dir.display().to_string();
+                let label = " Directory: ".len();
+                let title = if label + dir.chars().count() > room {
+                    let keep = room.saturating_sub(label + 1);
+                    let tail: String = dir.chars().skip(dir.chars().count() - keep).collect();
+                    format!(" Directory: …{}", tail)
+                } else {
+                    format!(" Directory: {}", dir)
+                };
+                (title, sort)
+            }
+        };
+        let branch = match branch.strip_suffix(" ·") {
+            Some(name) if sort.is_empty() => format!("{} ", name),
+            _ => branch,
+        };
+        let theme = &self.config.theme;
+        let mut list_block = Block::bordered()
+            .title(if focused && self.focus == Focus::List {
+                title.fg(theme.accent)
+            } else {
+                title.fg(theme.dim)
+            })
+            .title(Line::from(vec![branch.fg(theme.path), sort.fg(theme.dim)]).right_aligned())
+            .border_style(theme.border)
+            .border_set(border::PLAIN);
+        // The table has a column for them
+        if focused
+            && !self.detailed
+            && let Some(meta) = pane.entries.get(pane.selected).and_then(|e| e.meta)
+        {
+            let label = format!(" {} {} ", meta.permissions(), self.user_name(meta.uid));
+            list_block = list_block.title_bottom(Line::from(label.fg(theme.dim)));
+        }
+        if let Some(root) = &pane.sync_root {
+            let label = match self.tab().synced_dir(index) {
+                Some(dir) if dir != pane.current_dir => {
+                    let missing = dir.strip_prefix(root).unwrap_or(&dir);
+                    format!(" ⇄ no {} here ", missing.display()).fg(theme.error)
+                }
+                _ => " ⇄ synced ".fg(theme.success),
+            };
+            list_block = list_block.title_bottom(Line::from(label));
+        }
+        if pane.ignored > 0 {
+            let label = format!(" {} ignored hidden ", pane.ignored);
+            list_block = list_block.title_bottom(Line::from(label.fg(theme.dim)).right_aligned());
+        }
+        let marked = pane.marked_count();
+        if marked > 0 {
+            let label = if pane.visual.is_some() {
+                format!(" VISUAL {} marked ", marked)
+            } else {
+                format!(" {} marked ", marked)
+            };
+            list_block =
+                list_block.title_bottom(Line::from(label.fg(theme.marked)).right_aligned());
+        }
+        if !focused {
+            self.render_list(
+                &pane.entries[start..end],
+                file_lines,
+                list_block,
+                list_rect,
+                buf,
+            );
+            return;
+        }
+        if self.server.is_some() && !self.server_panel {
+            list_block =
+                list_block.title_bottom(Line::from(" ⇅ sharing, H to show ".fg(theme.success)));
+        }
+        if let Some(job) = self.jobs.running().first() {
+            let more = match self.jobs.running().len() {
+                1 => String::new(),
+                n => format!(" (+{})", n - 1),
+            };
+            let hint = if self.jobs_panel { "" } else { ", J to show" };
+            list_block = list_block.title_bottom(Line::from(
+                format!(" {}{}{} ", job.label(), more, hint).fg(theme.info),
+            ));
+        }
+        if let Some(search) = &self.search {
+            search.render(list_rect, buf);
+        } else if let Some(grep) = &self.grep {
+            grep.render(list_rect, buf);
+        } else {
+            self.render_list(
+                &pane.entries[start..end],
+                file_lines,
+                list_block,
+                list_rect,
+                buf,
+            );
+        }
+    }
+
+    /// The visible entries, given their styled names: one per line, or as a
+    /// table of details. Columns that don't fit next to the names are left
+    /// out, last first.
+    fn render_list(
+        &self,
+        entries: &[Entry],
+        names: Vec<(Line, Style)>,
+        block: Block,
+        area: Rect,
+        buf: &mut Buffer,
+    ) {
+        if !self.detailed {
+            let lines = names.into_iter().map(|(line, style)| line.style(style));
+            Paragraph::new(Text::from_iter(lines))
+                .block(block)
+                .render(area, buf);
+            return;
+        }
+        let owners: Vec<Option<String>> = entries
+            .iter()
+            .map(|entry| entry.meta.map(|meta| self.user_name(meta.uid)))
+            .collect();
+        let owner_width = owners
+            .iter()
+            .flatten()
+            .map(|owner| owner.chars().count())
+            .max()
+            .unwrap_or(0)
+            .clamp(5, 12) as u16;
+        let column_widths = [9, 16, 11, owner_width];
+        // Room left for names at the least
+        let mut room = area.width.saturating_sub(2 + 16);
+        let shown = column_widths
+            .iter()
+            .take_while(|&&width| {
+                let fits = room >= width;
+                room = room.saturating_sub(width + 1);
+                fits
+            })
+            .count();
+
+        let dim = self.config.theme.dim;
+        let rows = entries
+            .iter()
+            .zip(names)
+            .zip(&owners)
+            .map(|((entry, (name, style)), owner)| {
+                let size = if entry.is_dir {
+                    Line::default()
+                } else {
+                    let size = jobs::format_size(entry.size);
+                    match self.config.size_color(entry.size) {
+                        Some(color) => Line::from(size.fg(color)),
+                        None => Line::from(size),
+                    }
+                };
+                let modified = entry.modified.map(format_time).unwrap_or_default();
+                let permissions = entry
+                    .meta
+                    .map(|meta| meta.permissions())
+                    .unwrap_or_default();
+                let owner = owner.clone().unwrap_or_default();
+                let cells = [
+                    widgets::Cell::from(name),
+                    widgets::Cell::from(size.right_aligned()),
+                    widgets::Cell::from(modified.fg(dim)),
+                    widgets::Cell::from(permissions.fg(dim)),
+                    widgets::Cell::from(owner.fg(dim)),
+                ];
+                Row::new(cells.into_iter().take(1 + shown)).style(style)
+            });
+        let header = ["Name", "Size", "Modified", "Permissions", "Owner"];
+        let header = Row::new(
+            header
+                .into_iter()
+                .take(1 + shown)
+                .enumerate()
+                .map(|(i, title)| match i {
+                    1 => widgets::Cell::from(Line::from(title).right_aligned()),
+                    _ => widgets::Cell::from(title),
+                }),
+        )
+        .style(Style::new().fg(dim).bold());
+        let widths = std::iter::once(Constraint::Fill(1)).chain(
+            column_widths[..shown]
+                .iter()
+                .map(|&width| Constraint::Length(width)),
+        );
+        Widget::render(
+            Table::new(rows, widths).header(header).block(block),
+            area,
+            buf,
+        );
+    }
+
+    /// The name of the user with `uid`, or the number when it has none.
+    /// The bottom line: the last message while it is fresh, or what the
+    /// selected entry is, then the cursor's position and the free space.
+    fn render_status_bar(&self, quit: Line, area: Rect, buf: &mut Buffer) {
+        let pane = self.pane();
+        let parent = pane.entries.first().is_some_and(Entry::is_parent) as usize;
+        let mut right = vec![
+            format!(
+                "{}/{}",
+                (pane.selected + 1).saturating_sub(parent),
+                pane.entries.len() - parent
+            )
+            .into(),
+        ];
+        if let Some(free) = self.free_space(&pane.current_dir) {
+            right.push(format!("  {} free", jobs::format_size(free)).fg(self.config.theme.dim));
+        }
+        right.extend(quit.spans);
+        let right = Line::from(right);
+
+        let left = match &self.status {
+            Some((message, at)) if at.elapsed() < STATUS_TIME => {
+                Line::from(format!(" {}", message).fg(self.config.theme.accent))
+            }
+            _ => Line::from(
+                pane.entries
+                    .get(pane.selected)
+                    .filter(|entry| !entry.is_parent())
+                    .map(|entry| format!(" {}", self.describe(entry)))
+                    .unwrap_or_default()
+                    .fg(self.config.theme.status),
+            ),
+        };
+        let width = (right.width() as u16).min(area.width);
+        left.render(
+            Rect {
+                width: area.width - width,
+                ..area
+            },
+            buf,
+        );
+        right.right_aligned().render(area, buf);
+    }
+
+    /// What the status bar says about an entry: its type, size,
+    /// modification time and permissions.
+    fn describe(&self, entry: &Entry) -> String {
+        let kind = match (&entry.link, entry.special()) {
+            (Some(link), _) if link.broken => {
+                format!("broken link → {}", link.target.display())
+            }
+            (Some(link), _) => format!("link → {}", link.target.display()),
+            (None, Some(special)) => special.name().to_string(),
+            (None, None) if entry.is_dir => "directory".to_string(),
+            (None, None) => "file".to_string(),
+        };
+        let mut parts = vec![kind];
+        if !entry.is_dir && entry.special().is_none() {
+            parts.push(jobs::format_size(entry.size));
+        }
+        parts.extend(entry.modified.map(format_time));
+        parts.extend(entry.meta.map(|meta| meta.permissions()));
+        parts.join("  ")
+    }
+
+    /// Free space on the filesystem holding `dir`, looked up again every
+    /// few seconds.
+    fn free_space(&self, dir: &Path) -> Option<u64> {
+        let mut cached = self.free_space.borrow_mut();
+        match &*cached {
+            Some((cached_dir, at, free)) if cached_dir == dir && at.elapsed() < FREE_SPACE_AGE => {
+                *free
+            }
+            _ => {
+                #[cfg(unix)]
+                let free = attrs::free_space(dir);
+                #[cfg(not(unix))]
+                let free = None;
+                *cached = Some((dir.to_path_buf(), Instant::now(), free));
+                free
+            }
+        }
+    }
+
+    fn user_name(&self, uid: u32) -> String {
+        self.users
+            .borrow_mut()
+            .entry(uid)
+            .or_insert_with(|| {
+                #[cfg(unix)]
+                return attrs::user_name(uid).unwrap_or_else(|| uid.to_string());
+                #[cfg(not(unix))]
+                uid.to_string()
+            })
+            .clone()
+    }
+
+    fn render_preview(&self, area: Rect, buf: &mut Buffer) {
+        self.preview_area.set(Some(area));
+        let mut preview_block = Block::bordered()
+            .title(
+                " Preview "
+                    .fg(self.config.theme.accent)
+                    .bold()
+                    .into_right_aligned_line(),
+            )
+            .border_style(self.config.theme.border)
+            .border_set(border::PLAIN);
+        if self.focus == Focus::Preview {
+            preview_block = preview_block.border_style(self.config.theme.accent);
+        }
+
+        if let Some(preview) = &self.preview {
+            let inner = preview_block.inner(area);
+            if let Some(image) = &preview.image {
+                let header = (preview.text.lines.len() as u16).min(inner.height);
+                let area = Rect {
+                    y: inner.y + header,
+                    height: inner.height - header,
+                    ..inner
+                };
+                if self.graphics == Protocol::HalfBlocks {
+                    graphics::render_half_blocks(image, area, buf);
+                } else if !area.is_empty() {
+                    self.image_area.set(Some(area));
+                }
+            }
+            let preview_paragraph = Paragraph::new(preview.text.clone())
+                .block(preview_block)
+                .wrap(Wrap { trim: false })
+                .scroll((self.preview_scroll, 0));
+            preview_paragraph.render(area, buf);
+        } else {
+            preview_block.render(area, buf);
+        }
+    }
+}
+
+impl Widget for &App {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        theme::set_current(self.config.theme);
+        let title = Line::from("< Browrs >".fg(self.config.theme.title).bold());
+        let key = |action| {
+            let keys = self.config.keymap.keys_for(action).unwrap_or_default();
+            keys.iter()
+                .map(|chord| match chord.code {
+                    KeyCode::Up => "↑".to_string(),
+                    KeyCode::Down => "↓".to_string(),
+                    KeyCode::Enter => "↵".to_string(),
+                    _ => chord.to_string().to_uppercase(),
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+        let quit = Line::from(vec![
+            "  Quit ".into(),
+            format!("<{}>", key(Action::Quit))
+                .fg(self.config.theme.error)
+                .bold(),
+        ]);
+
+        let outer = Block::bordered()
+            .title(title.centered())
+            .border_set(border::EMPTY);
+
+        let mut inner = outer.inner(area);
+        outer.render(area, buf);
+        if area.height > 2 {
+            let bar = Rect {
+                y: area.y + area.height - 1,
+                height: 1,
+                ..inner
+            };
+            self.render_status_bar(quit, bar, buf);
+        }
+
+        if !inner.is_empty() {
+            self.render_tab_bar(Rect { height: 1, ..inner }, buf);
+            inner.y += 1;
+            inner.height -= 1;
+        }
+
+        let projects = self.projects();
+        if inner.height > 2
+            && let Some(package) = &projects.npm
+        {
+            inner.height -= 1;
+            let bar = Rect {
+                y: inner.y + inner.height,
+                height: 1,
+                ..inner
+            };
+            let title = format!(" 📦 {}", package.name);
+            let keys = [("scripts", Action::Scripts)];
+            let version = package.version.as_deref();
+            self.render_project_bar(title, version, &package.dir, &keys, bar, buf);
+        }
+        if inner.height > 2
+            && let Some(project) = &projects.cargo
+        {
+            inner.height -= 1;
+            let bar = Rect {
+                y: inner.y + inner.height,
+                height: 1,
+                ..inner
+            };
+            let title = format!(" 🦀 {}", project.name);
+            let keys = [
+                ("build", Action::Cargo(Task::Build)),
+                ("test", Action::Cargo(Task::Test)),
+                ("clean", Action::Cargo(Task::Clean)),
+            ];
+            let version = project.version.as_deref();
+            self.render_project_bar(title, version, &project.dir, &keys, bar, buf);
+        }
+
+        let chunks = ratatui::layout::Layout::default()
+            .direction(ratatui::layout::Direction::Horizontal)
+            .constraints([
+                ratatui::layout::Constraint::Percentage(50),
+                ratatui::layout::Constraint::Percentage(50),
+            ])
+            .split(inner);
+
+        self.image_area.set(None);
+        self.preview_area.set(None);
+        self.list_areas.borrow_mut().clear();
+        if self.dual_pane || !self.show_preview {
+            if self.dual_pane {
+                for (index, area) in self.tab().layout.areas(inner) {
+                    self.render_pane(index, area, buf);
+                }
+            } else {
+                self.render_pane(self.tab().focus, inner, buf);
+            }
+            if self.preview_overlay {
+                let overlay = Rect {
+                    x: inner.x + inner.width / 10,
+                    y: inner.y + inner.height / 10,
+                    width: inner.width - inner.width / 5,
+                    height: inner.height - inner.height / 5,
+                };
+                Clear.render(overlay, buf);
+                self.render_preview(overlay, buf);
+            }
+        } else {
+            self.render_pane(self.tab().focus, chunks[0], buf);
+            self.render_preview(chunks[1], buf);
+        }
+
+        if let Some(picker) = &self.picker {
+            picker.render(area, buf);
+        }
+
+        if self.server_panel
+            && let Some(server) = &self.server
+        {
+            server.render(area, buf);
+        }
+
+        if self.jobs_panel && !self.jobs.is_empty() {
+            self.jobs.render(area, buf);
+        }
+
+        if let Some(edit) = &self.quick_edit {
+            edit.render(area, buf);
+        }
+
+        if let Some(view) = &self.hex_view {
+            view.render(area, buf);
+        }
+
+        if let Some(view) = &self.qr_view {
+            view.render(area, buf);
+        }
+
+        if let Some(editor) = &self.permissions {
+            editor.render(area, buf);
+        }
+
+        if let Some(prompt) = &self.prompt {
+            prompt.render(area, buf);
+        }
+    }
+}
@@ -0,0 +1,385 @@
+//! Reading zip and tar archives through the `unzip` and `tar` commands, for
+//! browsing them like directories.
+
+use std::{
+    io::{self, BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{jobs::Progress, log, touch};
+
+/// Archive file names browsed as directories.
+const TAR_SUFFIXES: [&str; 10] = [
+    ".tar", ".tar.gz", ".tgz", ".tar.bz2", ".tbz2", ".tar.xz", ".txz", ".tar.zst", ".tzst",
+    ".tar.lz",
+];
+const ZIP_SUFFIXES: [&str; 4] = [".zip", ".jar", ".war", ".apk"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Zip,
+    /// Any tar, compressed or not; `tar` works out the compression.
+    Tar,
+}
+
+impl Format {
+    pub fn detect(path: &Path) -> Option<Format> {
+        let name = path.file_name()?.to_string_lossy().to_lowercase();
+        if ZIP_SUFFIXES.iter().any(|suffix| name.ends_with(suffix)) {
+            Some(Format::Zip)
+        } else if TAR_SUFFIXES.iter().any(|suffix| name.ends_with(suffix)) {
+            Some(Format::Tar)
+        } else {
+            None
+        }
+    }
+}
+
+/// `archive`'s name without its archive suffix, like `data` for
+/// `data.tar.gz`.
+pub fn stem(archive: &Path) -> String {
+    let name = archive.file_name().unwrap_or_default().to_string_lossy();
+    let lower = name.to_lowercase();
+    ZIP_SUFFIXES
+        .iter()
+        .chain(&TAR_SUFFIXES)
+        .filter(|suffix| lower.ends_with(*suffix))
+        .map(|suffix| name[..name.len() - suffix.len()].to_string())
+        .min_by_key(String::len)
+        .filter(|stem| !stem.is_empty())
+        .unwrap_or_else(|| name.into_owned())
+}
+
+/// A file or directory stored in an archive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+    /// The name as stored, which is what the tools want back.
+    pub raw: String,
+    /// The name without a leading `./` or trailing `/`.
+    pub path: PathBuf,
+    pub is_dir: bool,
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+}
+
+/// Everything stored in `archive`.
+pub fn members(archive: &Path) -> io::Result<Vec<Member>> {
+    let _timer = log::timer(|| format!("list {}", archive.display()));
+    match Format::detect(archive) {
+        Some(Format::Zip) => {
+            let listing = output(Command::new("unzip").arg("-Zl").arg("-T").arg(archive))?;
+            Ok(listing.lines().filter_map(parse_zip_line).collect())
+        }
+        Some(Format::Tar) => {
+            let listing = output(
+                Command::new("tar")
+                    .args(["--quoting-style=literal", "-tvf"])
+                    .arg(archive),
+            )?;
+            Ok(listing.lines().filter_map(parse_tar_line).collect())
+        }
+        None => Err(not_an_archive(archive)),
+    }
+}
+
+/// Up to `limit` bytes of the member stored as `raw`.
+pub fn read(archive: &Path, raw: &str, limit: u64) -> io::Result<Vec<u8>> {
+    let mut command = match Format::detect(archive) {
+        Some(Format::Zip) => {
+            let mut command = Command::new("unzip");
+            command.arg("-p").arg(archive).arg(zip_pattern(raw));
+            command
+        }
+        Some(Format::Tar) => {
+            let mut command = Command::new("tar");
+            command.arg("-xOf").arg(archive).arg("--").arg(raw);
+            command
+        }
+        None => return Err(not_an_archive(archive)),
+    };
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+    let mut bytes = Vec::new();
+    if let Some(stdout) = child.stdout.take() {
+        stdout.take(limit).read_to_end(&mut bytes)?;
+    }
+    // Stop it early if there was more than wanted
+    let _ = child.kill();
+    let _ = child.wait();
+    Ok(bytes)
+}
+
+/// Extracts the members stored as `raws` under `into`, keeping their paths.
+pub fn extract(archive: &Path, raws: &[String], into: &Path) -> io::Result<()> {
+    log::info!(
+        "extracting {} members of {} into {}",
+        raws.len(),
+        archive.display(),
+        into.display()
+    );
+    let mut command = match Format::detect(archive) {
+        Some(Format::Zip) => {
+            let mut command = Command::new("unzip");
+            command.args(["-qq", "-o"]).arg(archive);
+            command.args(raws.iter().map(|raw| zip_pattern(raw)));
+            command.arg("-d").arg(into);
+            command
+        }
+        Some(Format::Tar) => {
+            let mut command = Command::new("tar");
+            command.args(["--no-recursion", "-xf"]).arg(archive);
+            command.arg("-C").arg(into).arg("--").args(raws);
+            command
+        }
+        None => return Err(not_an_archive(archive)),
+    };
+    output(&mut command).map(|_| ())
+}
+
+/// Extracts all of `archive` into `into`; returns how many members it had.
+/// Nothing already in `into` is overwritten: a member landing on an existing
+/// entry stops it before anything is extracted.
+pub fn extract_everything(archive: &Path, into: &Path, progress: &Progress) -> io::Result<usize> {
+    let members = members(archive)?;
+    if let Some(taken) = members.iter().find_map(|member| {
+        let target = into.join(&member.path);
+        let both_dirs = member.is_dir && target.is_dir();
+        (target.symlink_metadata().is_ok() && !both_dirs).then_some(target)
+    }) {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", taken.display()),
+        ));
+    }
+    log::info!("extracting {} into {}", archive.display(), into.display());
+    let mut command = match Format::detect(archive) {
+        Some(Format::Zip) => {
+            let mut command = Command::new("unzip");
+            command.arg("-n").arg(archive).arg("-d").arg(into);
+            command
+        }
+        Some(Format::Tar) => {
+            let mut command = Command::new("tar");
+            command.args(["--keep-old-files", "-xvf"]).arg(archive);
+            command.arg("-C").arg(into);
+            command
+        }
+        None => return Err(not_an_archive(archive)),
+    };
+    counting(&mut command, members.len(), progress)?;
+    Ok(members.len())
+}
+
+/// Packs `sources`, which share a directory, into a new archive at `dest`
+/// in the format its name asks for.
+pub fn create(dest: &Path, sources: &[PathBuf], progress: &Progress) -> io::Result<()> {
+    let format = Format::detect(dest).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "name it .zip, .tar, .tar.gz, .tar.xz or .tar.zst",
+        )
+    })?;
+    if dest.symlink_metadata().is_ok() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", dest.display()),
+        ));
+    }
+    let Some(dir) = sources.first().and_then(|source| source.parent()) else {
+        return Ok(());
+    };
+    if sources.iter().any(|source| source.parent() != Some(dir)) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "only entries from one directory can be packed together",
+        ));
+    }
+    let names: Vec<_> = sources
+        .iter()
+        .filter_map(|source| source.file_name())
+        .collect();
+    let total = sources.iter().map(|source| count(source)).sum();
+    log::info!("packing {} entries into {}", sources.len(), dest.display());
+    let mut command = match format {
+        Format::Zip => {
+            let mut command = Command::new("zip");
+            // As ./name, which zip stores as name, so that a name starting
+            // with - isn't read as an option
+            command.arg("-r").arg(dest);
+            command.args(names.iter().map(|name| Path::new(".").join(name)));
+            command
+        }
+        Format::Tar => {
+            let mut command = Command::new("tar");
+            command.arg("-cvaf").arg(dest).arg("--").args(&names);
+            command
+        }
+    };
+    let result = counting(command.current_dir(dir), total, progress);
+    if result.is_err() {
+        let _ = std::fs::remove_file(dest);
+    }
+    result
+}
+
+/// `raw` as an `unzip` pattern matching only itself. Names are taken as
+/// wildcards, and one starting with `-` as an option, which could send
+/// files elsewhere.
+fn zip_pattern(raw: &str) -> String {
+    let mut pattern = String::with_capacity(raw.len());
+    for (i, c) in raw.chars().enumerate() {
+        if matches!(c, '*' | '?' | '[') || (i == 0 && c == '-') {
+            pattern.push('[');
+            pattern.push(c);
+            pattern.push(']');
+        } else {
+            pattern.push(c);
+        }
+    }
+    pattern
+}
+
+/// Entries at and under `path`.
+fn count(path: &Path) -> usize {
+    let below = match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.is_dir() => std::fs::read_dir(path)
+            .map(|entries| entries.flatten().map(|entry| count(&entry.path())).sum())
+            .unwrap_or(0),
+        _ => 0,
+    };
+    1 + below
+}
+
+/// Runs `command`, which prints a line per member, as progress towards
+/// `total`. Stops it if the job is cancelled.
+fn counting(command: &mut Command, total: usize, progress: &Progress) -> io::Result<()> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    let mut done = 0;
+    if let Some(stdout) = child.stdout.take() {
+        for _ in BufReader::new(stdout).lines() {
+            if progress.is_cancelled() {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(io::Error::new(io::ErrorKind::Interrupted, "cancelled"));
+            }
+            done += 1;
+            progress.count(done.min(total), total);
+        }
+    }
+    let mut stderr = String::new();
+    if let Some(mut pipe) = child.stderr.take() {
+        let _ = pipe.read_to_string(&mut stderr);
+    }
+    let status = child.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(
+            stderr
+                .lines()
+                .next()
+                .map(str::to_string)
+                .unwrap_or_else(|| format!("exited with {}", status)),
+        ))
+    }
+}
+
+/// Runs `command`, failing with what it printed to stderr.
+fn output(command: &mut Command) -> io::Result<String> {
+    let output = command.stdin(Stdio::null()).output()?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(io::Error::other(
+            stderr
+                .lines()
+                .next()
+                .unwrap_or("failed to read the archive")
+                .to_string(),
+        ))
+    }
+}
+
+fn not_an_archive(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("{} is not an archive", path.display()),
+    )
+}
+
+/// The text after the first `count` whitespace-separated fields, and those
+/// fields.
+fn split_fields(line: &str, count: usize) -> Option<(Vec<&str>, &str)> {
+    let mut fields = Vec::with_capacity(count);
+    let mut rest = line;
+    for _ in 0..count {
+        rest = rest.trim_start();
+        let end = rest.find(char::is_whitespace)?;
+        fields.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+    let rest = rest.trim_start_matches(' ');
+    (!rest.is_empty()).then_some((fields, rest))
+}
+
+/// `-rw-r--r--  3.0 unx  3 tx  3 stor 20261015.133932 name`
+fn parse_zip_line(line: &str) -> Option<Member> {
+    let (fields, name) = split_fields(line, 8)?;
+    // Only member lines start with permissions and the zip version
+    if !fields[0].starts_with(['-', 'd', 'l']) || fields[1].parse::<f32>().is_err() {
+        return None;
+    }
+    let size = fields[3].parse().ok()?;
+    let modified = fields[7].split_once('.').and_then(|(date, time)| {
+        let (date, time) = (date.as_bytes(), time.as_bytes());
+        if date.len() != 8 || time.len() < 4 {
+            return None;
+        }
+        let text = |bytes: &[u8]| String::from_utf8_lossy(bytes).into_owned();
+        let stamp = format!(
+            "{}-{}-{} {}:{}",
+            text(&date[..4]),
+            text(&date[4..6]),
+            text(&date[6..]),
+            text(&time[..2]),
+            text(&time[2..4])
+        );
+        touch::parse_time(&stamp, UNIX_EPOCH).ok()
+    });
+    member(name, fields[0].starts_with('d'), size, modified)
+}
+
+/// `-rw-r--r-- root/root  3 2026-10-15 13:39 name`
+fn parse_tar_line(line: &str) -> Option<Member> {
+    let (fields, name) = split_fields(line, 5)?;
+    let kind = fields[0].chars().next()?;
+    let name = match kind {
+        'l' => name.split(" -> ").next()?,
+        'h' => name.split(" link to ").next()?,
+        _ => name,
+    };
+    let size = fields[2].parse().unwrap_or(0);
+    let modified = touch::parse_time(&format!("{} {}", fields[3], fields[4]), UNIX_EPOCH).ok();
+    member(name, kind == 'd', size, modified)
+}
+
+fn member(raw: &str, is_dir: bool, size: u64, modified: Option<SystemTime>) -> Option<Member> {
+    let path = raw.trim_start_matches("./").trim_end_matches('/');
+    (!path.is_empty()).then(|| Member {
+        raw: raw.to_string(),
+        path: PathBuf::from(path),
+        is_dir: is_dir || raw.ends_with('/'),
+        size,
+        modified,
+    })
+}
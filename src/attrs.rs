@@ -0,0 +1,297 @@
+use std::{
+    ffi::{CStr, CString},
+    os::unix::fs::{MetadataExt, PermissionsExt},
+    path::{Path, PathBuf},
+};
+
+use crate::ops::BatchReport;
+
+/// One `[ugoa]*[+-=][rwx]*` clause of a symbolic mode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolicClause {
+    who: u32,
+    op: char,
+    perms: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModeSpec {
+    Octal(u32),
+    Symbolic(Vec<SymbolicClause>),
+}
+
+impl ModeSpec {
+    /// Computes the new permission bits from the current ones.
+    pub fn apply(&self, current: u32) -> u32 {
+        match self {
+            ModeSpec::Octal(mode) => *mode,
+            ModeSpec::Symbolic(clauses) => clauses.iter().fold(current, |mode, clause| {
+                let bits = clause.perms & clause.who;
+                match clause.op {
+                    '+' => mode | bits,
+                    '-' => mode & !bits,
+                    _ => (mode & !clause.who) | bits,
+                }
+            }),
+        }
+    }
+}
+
+/// A parsed chmod/chown request such as `-R 644 alice:staff`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AttrChange {
+    pub recursive: bool,
+    pub mode: Option<ModeSpec>,
+    pub owner: Option<u32>,
+    pub group: Option<u32>,
+}
+
+impl AttrChange {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let mut change = Self::default();
+        for token in spec.split_whitespace() {
+            if token == "-R" || token == "--recursive" {
+                change.recursive = true;
+            } else if let Some(mode) = parse_mode(token) {
+                change.mode = Some(mode);
+            } else {
+                change.set_owner(token)?;
+            }
+        }
+        if change.mode.is_none() && change.owner.is_none() && change.group.is_none() {
+            return Err("Nothing to change, expected a mode and/or owner[:group]".into());
+        }
+        Ok(change)
+    }
+
+    /// A chown to `owner[:group]`, either of which may be left out.
+    pub fn owner(spec: &str) -> Result<Self, String> {
+        let mut change = Self::default();
+        change.set_owner(spec.trim())?;
+        if change.owner.is_none() && change.group.is_none() {
+            return Err("Nothing to change, expected owner[:group]".into());
+        }
+        Ok(change)
+    }
+
+    fn set_owner(&mut self, spec: &str) -> Result<(), String> {
+        let (user, group) = spec.split_once(':').unwrap_or((spec, ""));
+        if !user.is_empty() {
+            self.owner = Some(lookup_user(user).ok_or(format!("Unknown user: {user}"))?);
+        }
+        if !group.is_empty() {
+            self.group = Some(lookup_group(group).ok_or(format!("Unknown group: {group}"))?);
+        }
+        Ok(())
+    }
+}
+
+/// Completes the user of an `owner[:group]` spec, or the group after the
+/// `:`, as far as the names that fit agree; those names come back too.
+pub fn complete_owner(spec: &str) -> (String, Vec<String>) {
+    let (head, partial, names) = match spec.split_once(':') {
+        Some((user, group)) => (format!("{}:", user), group, group_names()),
+        None => (String::new(), spec, user_names()),
+    };
+    let mut fits: Vec<String> = names
+        .into_iter()
+        .filter(|name| name.starts_with(partial))
+        .collect();
+    fits.sort();
+    fits.dedup();
+    let first = fits.first().map_or(partial, String::as_str);
+    let common = fits.iter().fold(first, |common, name| {
+        let len = common
+            .chars()
+            .zip(name.chars())
+            .take_while(|(a, b)| a == b)
+            .map(|(a, _)| a.len_utf8())
+            .sum();
+        &common[..len]
+    });
+    (format!("{}{}", head, common), fits)
+}
+
+/// Every user name, from the user database.
+fn user_names() -> Vec<String> {
+    let mut names = Vec::new();
+    // SAFETY: getpwent returns null or a pointer to a static passwd
+    // record, read before the next call
+    unsafe {
+        libc::setpwent();
+        loop {
+            let passwd = libc::getpwent();
+            if passwd.is_null() {
+                break;
+            }
+            names.push(
+                CStr::from_ptr((*passwd).pw_name)
+                    .to_string_lossy()
+                    .into_owned(),
+            );
+        }
+        libc::endpwent();
+    }
+    names
+}
+
+/// Every group name, from the group database.
+fn group_names() -> Vec<String> {
+    let mut names = Vec::new();
+    // SAFETY: as for getpwent
+    unsafe {
+        libc::setgrent();
+        loop {
+            let group = libc::getgrent();
+            if group.is_null() {
+                break;
+            }
+            names.push(
+                CStr::from_ptr((*group).gr_name)
+                    .to_string_lossy()
+                    .into_owned(),
+            );
+        }
+        libc::endgrent();
+    }
+    names
+}
+
+fn parse_mode(token: &str) -> Option<ModeSpec> {
+    if !token.is_empty() && token.len() <= 4 && token.chars().all(|c| c.is_digit(8)) {
+        return u32::from_str_radix(token, 8).ok().map(ModeSpec::Octal);
+    }
+
+    let mut clauses = vec![];
+    for clause in token.split(',') {
+        let op_index = clause.find(['+', '-', '='])?;
+        let (who_part, rest) = clause.split_at(op_index);
+        let mut who = 0;
+        for c in who_part.chars() {
+            who |= match c {
+                'u' => 0o700,
+                'g' => 0o070,
+                'o' => 0o007,
+                'a' => 0o777,
+                _ => return None,
+            };
+        }
+        let mut chars = rest.chars();
+        let op = chars.next()?;
+        let mut perms = 0;
+        for c in chars {
+            perms |= match c {
+                'r' => 0o444,
+                'w' => 0o222,
+                'x' => 0o111,
+                _ => return None,
+            };
+        }
+        clauses.push(SymbolicClause {
+            who: if who == 0 { 0o777 } else { who },
+            op,
+            perms,
+        });
+    }
+    Some(ModeSpec::Symbolic(clauses))
+}
+
+fn lookup_user(name: &str) -> Option<u32> {
+    if let Ok(uid) = name.parse() {
+        return Some(uid);
+    }
+    let name = CString::new(name).ok()?;
+    // SAFETY: getpwnam returns null or a pointer to a static passwd record
+    let passwd = unsafe { libc::getpwnam(name.as_ptr()) };
+    (!passwd.is_null()).then(|| unsafe { (*passwd).pw_uid })
+}
+
+/// The name of the user with `uid`, if it has one.
+pub fn user_name(uid: u32) -> Option<String> {
+    // SAFETY: getpwuid returns null or a pointer to a static passwd record,
+    // read before anything else can overwrite it
+    let passwd = unsafe { libc::getpwuid(uid) };
+    if passwd.is_null() {
+        return None;
+    }
+    let name = unsafe { CStr::from_ptr((*passwd).pw_name) };
+    Some(name.to_string_lossy().into_owned())
+}
+
+/// The name of the group with `gid`, if it has one.
+pub fn group_name(gid: u32) -> Option<String> {
+    // SAFETY: getgrgid returns null or a pointer to a static group record,
+    // read before anything else can overwrite it
+    let group = unsafe { libc::getgrgid(gid) };
+    if group.is_null() {
+        return None;
+    }
+    let name = unsafe { CStr::from_ptr((*group).gr_name) };
+    Some(name.to_string_lossy().into_owned())
+}
+
+fn lookup_group(name: &str) -> Option<u32> {
+    if let Ok(gid) = name.parse() {
+        return Some(gid);
+    }
+    let name = CString::new(name).ok()?;
+    // SAFETY: getgrnam returns null or a pointer to a static group record
+    let group = unsafe { libc::getgrnam(name.as_ptr()) };
+    (!group.is_null()).then(|| unsafe { (*group).gr_gid })
+}
+
+/// Every path a change would touch, descending into directories when
+/// recursive. Symlinks are never followed.
+pub fn targets(roots: &[PathBuf], recursive: bool) -> Vec<PathBuf> {
+    let mut targets = vec![];
+    let mut stack: Vec<PathBuf> = roots.iter().rev().cloned().collect();
+    while let Some(path) = stack.pop() {
+        let is_dir = std::fs::symlink_metadata(&path).is_ok_and(|m| m.is_dir());
+        if recursive
+            && is_dir
+            && let Ok(entries) = std::fs::read_dir(&path)
+        {
+            stack.extend(entries.flatten().map(|e| e.path()));
+        }
+        targets.push(path);
+    }
+    targets
+}
+
+pub fn apply(change: &AttrChange, targets: &[PathBuf]) -> BatchReport {
+    let mut report = BatchReport::new("change attributes of");
+    for path in targets {
+        report.record(path, apply_one(change, path));
+    }
+    report
+}
+
+fn apply_one(change: &AttrChange, path: &Path) -> std::io::Result<()> {
+    let metadata = std::fs::symlink_metadata(path)?;
+    if change.owner.is_some() || change.group.is_some() {
+        std::os::unix::fs::lchown(path, change.owner, change.group)?;
+    }
+    // Permissions of a symlink itself are meaningless on most systems
+    if let Some(mode) = &change.mode
+        && !metadata.file_type().is_symlink()
+    {
+        let current = metadata.mode() & 0o7777;
+        let new_mode = match mode {
+            ModeSpec::Octal(mode) => *mode,
+            ModeSpec::Symbolic(_) => mode.apply(current & 0o777) | (current & 0o7000),
+        };
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(new_mode))?;
+    }
+    Ok(())
+}
+
+/// Bytes free for unprivileged users on the filesystem holding `path`.
+pub fn free_space(path: &Path) -> Option<u64> {
+    let path = CString::new(path.as_os_str().as_encoded_bytes()).ok()?;
+    // SAFETY: statvfs is plain data, filled in by the call
+    let mut stats: libc::statvfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statvfs(path.as_ptr(), &mut stats) } != 0 {
+        return None;
+    }
+    Some(stats.f_bavail as u64 * stats.f_frsize as u64)
+}
@@ -0,0 +1,73 @@
+//! Favorite directories, one path per line in `~/.config/browrs/bookmarks`.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct Bookmarks {
+    /// Where the list is saved; `None` keeps it in memory only.
+    file: Option<PathBuf>,
+    pub dirs: Vec<PathBuf>,
+}
+
+impl Bookmarks {
+    pub fn default_file() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("browrs").join("bookmarks"))
+    }
+
+    /// Reads the list kept in `file`, starting empty if it doesn't exist.
+    pub fn load(file: PathBuf) -> Self {
+        let dirs = std::fs::read_to_string(&file)
+            .unwrap_or_default()
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(PathBuf::from)
+            .collect();
+        Self {
+            file: Some(file),
+            dirs,
+        }
+    }
+
+    pub fn file(&self) -> Option<&Path> {
+        self.file.as_deref()
+    }
+
+    pub fn contains(&self, dir: &Path) -> bool {
+        self.dirs.iter().any(|d| d == dir)
+    }
+
+    /// Adds `dir` at the end and saves; returns false if it was already there.
+    pub fn add(&mut self, dir: PathBuf) -> io::Result<bool> {
+        if self.contains(&dir) {
+            return Ok(false);
+        }
+        self.dirs.push(dir);
+        self.save().map(|()| true)
+    }
+
+    pub fn remove(&mut self, index: usize) -> io::Result<()> {
+        if index < self.dirs.len() {
+            self.dirs.remove(index);
+        }
+        self.save()
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let Some(file) = &self.file else {
+            return Ok(());
+        };
+        if let Some(parent) = file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut contents = String::new();
+        for dir in &self.dirs {
+            contents.push_str(&dir.to_string_lossy());
+            contents.push('\n');
+        }
+        std::fs::write(file, contents)
+    }
+}
@@ -0,0 +1,92 @@
+//! Rust projects: the package a directory belongs to, read from the
+//! nearest `Cargo.toml`, and the cargo commands run on it.
+
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+pub const MANIFEST: &str = "Cargo.toml";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Project {
+    /// Where the manifest is, and cargo runs.
+    pub dir: PathBuf,
+    pub name: String,
+    /// `None` for a workspace without a package, or an inherited version.
+    pub version: Option<String>,
+}
+
+impl Project {
+    /// The package or workspace with its manifest in `dir` or the nearest
+    /// directory above it.
+    pub fn find(dir: &Path) -> Option<Self> {
+        dir.ancestors().find_map(|dir| {
+            let manifest = std::fs::read_to_string(dir.join(MANIFEST)).ok()?;
+            Some(parse(dir, &manifest))
+        })
+    }
+}
+
+/// The name and version in the `[package]` table, or the directory's name
+/// for a virtual workspace.
+fn parse(dir: &Path, manifest: &str) -> Project {
+    let mut section = "";
+    let mut name = None;
+    let mut version = None;
+    for line in manifest.lines() {
+        let line = line.trim();
+        if let Some(header) = line.strip_prefix('[') {
+            section = header.trim_end_matches(']').trim();
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        // `version.workspace = true` has no quotes and stays unknown
+        let Some(value) = value.strip_prefix('"').and_then(|v| v.split('"').next()) else {
+            continue;
+        };
+        match (section, key.trim()) {
+            ("package", "name") => name = Some(value.to_string()),
+            ("package", "version") => version = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    Project {
+        dir: dir.to_path_buf(),
+        name: name.unwrap_or_else(|| {
+            let dir = dir.file_name().unwrap_or_default().to_string_lossy();
+            format!("{} (workspace)", dir)
+        }),
+        version,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Task {
+    Build,
+    Test,
+    Clean,
+}
+
+impl Task {
+    pub fn name(self) -> &'static str {
+        match self {
+            Task::Build => "build",
+            Task::Test => "test",
+            Task::Clean => "clean",
+        }
+    }
+
+    /// `cargo <task>` in `dir`.
+    pub fn command(self, dir: &Path) -> Command {
+        let mut command = Command::new("cargo");
+        command
+            .arg(self.name())
+            .current_dir(dir)
+            .env("CARGO_TERM_COLOR", "never");
+        command
+    }
+}
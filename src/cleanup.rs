@@ -0,0 +1,127 @@
+//! Finds directories that only hold caches, build output and stale
+//! temporary files, which can be deleted to free space and will be
+//! recreated when needed.
+
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::walk::Policy;
+
+/// Files in the temp directory untouched for this long are suggested.
+const STALE_TEMP: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+/// A directory or file that can go, with what it is and its size.
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub path: PathBuf,
+    pub what: String,
+    pub size: u64,
+}
+
+/// The system's cache and temp directories, whose entries are all
+/// suggested when they are under the scanned tree.
+#[derive(Debug, Clone, Default)]
+pub struct Places {
+    pub cache_dir: Option<PathBuf>,
+    pub temp_dir: Option<PathBuf>,
+}
+
+impl Places {
+    pub fn system() -> Self {
+        Self {
+            cache_dir: dirs::cache_dir(),
+            temp_dir: Some(std::env::temp_dir()),
+        }
+    }
+}
+
+/// Every candidate under `root`, biggest first. Stops early, with what it
+/// found so far, once `cancelled` returns true.
+pub fn scan(
+    root: &Path,
+    places: &Places,
+    policy: &Policy,
+    cancelled: &dyn Fn() -> bool,
+) -> Vec<Candidate> {
+    let mut found = Vec::new();
+    let mut walk = policy.walk(root, true);
+    while let Some(entry) = walk.next() {
+        if cancelled() {
+            break;
+        }
+        let (path, metadata) = (entry.path, entry.metadata);
+        let dir = path.parent();
+        let in_cache = places.cache_dir.is_some() && places.cache_dir.as_deref() == dir;
+        let in_temp = places.temp_dir.is_some() && places.temp_dir.as_deref() == dir;
+        let what = if in_cache {
+            Some("cache".to_string())
+        } else if in_temp {
+            let stale = metadata
+                .modified()
+                .ok()
+                .and_then(|m| SystemTime::now().duration_since(m).ok())
+                .is_some_and(|age| age >= STALE_TEMP);
+            stale.then(|| "old temporary file".to_string())
+        } else if metadata.is_dir() {
+            known(&path)
+        } else {
+            None
+        };
+        if let Some(what) = what {
+            // Sized as a whole, not gone through entry by entry
+            walk.prune(&path);
+            found.push(Candidate {
+                size: size(&path, policy, cancelled),
+                path,
+                what,
+            });
+        }
+    }
+    found.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+    found
+}
+
+/// What `dir` is, if it is a well-known kind of cache or build output.
+fn known(dir: &Path) -> Option<String> {
+    let name = dir.file_name()?.to_str()?;
+    let sibling = |file: &str| dir.parent().is_some_and(|p| p.join(file).exists());
+    let what = match name {
+        "node_modules" => "npm packages",
+        "target" if sibling("Cargo.toml") => "Cargo build output",
+        "__pycache__" => "Python bytecode",
+        ".pytest_cache" | ".mypy_cache" | ".ruff_cache" | ".tox" => "Python tool cache",
+        ".gradle" => "Gradle cache",
+        ".next" | ".nuxt" | ".parcel-cache" if sibling("package.json") => "JavaScript build cache",
+        "zig-cache" | ".zig-cache" => "Zig cache",
+        // The standard marker for cache directories, see bford.info/cachedir
+        _ if dir.join("CACHEDIR.TAG").is_file() => "cache",
+        _ => return None,
+    };
+    Some(what.to_string())
+}
+
+/// Bytes in `path` and everything under it, as far as `policy` goes.
+pub fn size(path: &Path, policy: &Policy, cancelled: &dyn Fn() -> bool) -> u64 {
+    let Ok(metadata) = path.symlink_metadata() else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return if metadata.is_file() {
+            metadata.len()
+        } else {
+            0
+        };
+    }
+    let mut total = 0;
+    for found in policy.walk(path, true) {
+        if cancelled() {
+            break;
+        }
+        if found.metadata.is_file() {
+            total += found.metadata.len();
+        }
+    }
+    total
+}
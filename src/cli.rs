@@ -0,0 +1,146 @@
+//! Command-line arguments.
+
+use std::path::{Path, PathBuf};
+
+use crate::log;
+
+pub const USAGE: &str = "\
+usage: browrs [OPTIONS] [PATH]
+
+Opens PATH, or the start directory from the config. When PATH is a file,
+browrs opens the directory it is in with the file selected.
+
+Options:
+  -a, --show-hidden     show hidden files
+      --no-preview      leave out the preview pane
+      --select FILE     start on FILE, in the directory it is in
+      --picker          print the path of the file opened with Enter and
+                        exit, instead of opening it in the editor; also
+                        --choose-file
+      --chooser-file=PATH
+                        like --picker, but write the path to PATH
+      --cwd-file=PATH   when quitting with Q, write the current directory
+                        to PATH
+      --init SHELL      print a brcd function for bash, zsh or fish that
+                        cds to where browrs was left with Q, and exit
+  -v, -vv, -vvv         log more: info, debug or trace
+      --verbose=LEVEL   log at LEVEL: error, warn, info, debug or trace
+      --log-file=PATH   log to PATH
+  -h, --help            print this and exit
+  -V, --version         print the version and exit";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Args {
+    pub path: Option<PathBuf>,
+    pub show_hidden: bool,
+    pub no_preview: bool,
+    pub select: Option<PathBuf>,
+    /// Enter on a file picks it instead of opening it.
+    pub choose_file: bool,
+    /// Where the picked path goes instead of stdout.
+    pub chooser_file: Option<PathBuf>,
+    /// Where `Q` leaves the directory it quit in.
+    pub cwd_file: Option<PathBuf>,
+    /// Shell to print the init script for.
+    pub init: Option<String>,
+    pub level: log::Level,
+    pub log_file: Option<PathBuf>,
+    pub help: bool,
+    pub version: bool,
+}
+
+impl Args {
+    /// Reads the arguments after the program name. Options taking a value
+    /// accept it as the next argument or after `=`.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+        let mut parsed = Args {
+            path: None,
+            show_hidden: false,
+            no_preview: false,
+            select: None,
+            choose_file: false,
+            chooser_file: None,
+            cwd_file: None,
+            init: None,
+            level: log::Level::Warn,
+            log_file: None,
+            help: false,
+            version: false,
+        };
+        let mut verbosity = 0;
+        let mut level = None;
+        let mut options_done = false;
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            if options_done || !arg.starts_with('-') || arg == "-" {
+                if parsed.path.is_some() {
+                    return Err(format!("unexpected argument: {}", arg));
+                }
+                parsed.path = Some(PathBuf::from(arg));
+                continue;
+            }
+            let (name, inline) = match arg.split_once('=') {
+                Some((name, value)) if name.starts_with("--") => (name, Some(value.to_string())),
+                _ => (arg.as_str(), None),
+            };
+            let mut value = |name: &str| {
+                inline
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or(format!("{} needs a value", name))
+            };
+            match name {
+                "--" => options_done = true,
+                "-a" | "--show-hidden" => parsed.show_hidden = true,
+                "--no-preview" => parsed.no_preview = true,
+                "--select" => parsed.select = Some(PathBuf::from(value(name)?)),
+                "--choose-file" | "--picker" => parsed.choose_file = true,
+                "--chooser-file" => {
+                    parsed.chooser_file = Some(PathBuf::from(value(name)?));
+                    parsed.choose_file = true;
+                }
+                "--verbose" if inline.is_none() => verbosity += 1,
+                "--verbose" => {
+                    let name = value(name)?;
+                    level = Some(
+                        log::Level::parse(&name).ok_or(format!("unknown log level: {}", name))?,
+                    );
+                }
+                "--cwd-file" => parsed.cwd_file = Some(PathBuf::from(value(name)?)),
+                "--init" => parsed.init = Some(value(name)?),
+                "--log-file" => parsed.log_file = Some(PathBuf::from(value(name)?)),
+                "-h" | "--help" => parsed.help = true,
+                "-V" | "--version" => parsed.version = true,
+                flags if flags[1..].chars().all(|c| c == 'v') => verbosity += flags.len() - 1,
+                _ => return Err(format!("unknown option: {}", arg)),
+            }
+        }
+        parsed.level = level.unwrap_or(log::Level::from_verbosity(verbosity));
+        Ok(parsed)
+    }
+
+    /// The directory to start in and the entry to select there, from PATH
+    /// and `--select`. `None` leaves the start directory to the config.
+    pub fn start(&self) -> Option<(PathBuf, Option<String>)> {
+        let target = match (&self.path, &self.select) {
+            (Some(dir), Some(file)) => dir.join(file),
+            (None, Some(file)) => file.clone(),
+            (Some(path), None) if !path.is_dir() => path.clone(),
+            (Some(dir), None) => return Some((full_dir(dir), None)),
+            (None, None) => return None,
+        };
+        let name = target.file_name()?.to_string_lossy().to_string();
+        let dir = match target.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        Some((full_dir(dir), Some(name)))
+    }
+}
+
+/// `dir` without `.` and `..` in it, so the parent entry leads up.
+fn full_dir(dir: &Path) -> PathBuf {
+    dir.canonicalize()
+        .or_else(|_| std::path::absolute(dir))
+        .unwrap_or_else(|_| dir.to_path_buf())
+}
@@ -0,0 +1,459 @@
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
+
+use ratatui::style::Color;
+
+use crate::{
+    error::BrowrsError,
+    file_actions::Association,
+    file_colors::LsColors,
+    jobs,
+    keymap::Keymap,
+    opener,
+    previewer::Previewer,
+    sort::{Custom, Sort},
+    theme::{self, Theme},
+    walk,
+};
+
+/// User settings read from `~/.config/browrs/config.toml`.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// Directory opened at startup instead of the home directory.
+    pub start_dir: Option<PathBuf>,
+    pub show_hidden: bool,
+    pub sort: Sort,
+    pub dirs_first: bool,
+    /// Leaves out what git ignores inside a repository.
+    pub hide_ignored: bool,
+    /// Lists entries as a table with their size, modification time,
+    /// permissions and owner.
+    pub detailed: bool,
+    /// Up at the top of the listing goes to the bottom, and Down at the
+    /// bottom to the top.
+    pub wrap_around: bool,
+    /// How quickly a held Up or Down speeds up on a listing longer than a
+    /// page, in entries per ten repeats; 0 moves one entry at a time.
+    pub scroll_acceleration: u64,
+    /// What the listing's title says instead of its directory and sort,
+    /// with `{path}`, `{shortpath}`, `{count}`, `{total}`, `{filter}` and
+    /// `{sort}` filled in.
+    pub pane_title: Option<String>,
+    /// The terminal window's title while browrs runs, with `{path}`,
+    /// `{shortpath}` and `{name}` of the current directory filled in;
+    /// `None` leaves it alone.
+    pub terminal_title: Option<String>,
+    /// How often the directories shown are looked at for changes made
+    /// outside browrs, in milliseconds; 0 only re-reads them on request.
+    pub watch_interval_ms: u64,
+    /// Text files larger than this many bytes aren't previewed.
+    pub preview_max_size: u64,
+    /// How long a preview may take to read, in milliseconds, before it is
+    /// given up on.
+    pub preview_timeout_ms: u64,
+    /// Editor command used for every file, e.g. `editor = "nvim"`.
+    pub editor: Option<String>,
+    /// Command that opens files with their default application, instead of
+    /// xdg-open, open or start.
+    pub opener: Option<String>,
+    /// Programs for opening files by name from the `[open]` table, tried
+    /// in file order before the editor.
+    pub open_rules: Vec<opener::Rule>,
+    /// Commands whose output previews files matching a pattern, from the
+    /// `[previewers]` table, tried in file order before the built-in
+    /// previews.
+    pub previewers: Vec<Previewer>,
+    /// Commands for the actions menu from the `[actions]` table.
+    pub actions: Vec<Association>,
+    /// Per-extension editor commands from the `[editors]` table.
+    pub editors: HashMap<String, String>,
+    /// Entries modified within this many minutes get a badge, 0 disables it.
+    pub recent_minutes: u64,
+    /// A column with the line count of small text files.
+    pub line_counts: bool,
+    /// Entries not modified for this many days are dimmed, 0 disables it.
+    pub stale_days: u64,
+    /// Files are colored and badged by which of `size_tiers` they reach.
+    pub size_colors: bool,
+    /// Sizes in bytes, smallest first, where the medium, large and huge
+    /// colors start.
+    pub size_tiers: [u64; 3],
+    /// "Send to" commands from the `[send_to]` table, in file order.
+    pub send_to: Vec<(String, String)>,
+    /// "Send to" copy destinations from the `[send_to_folders]` table.
+    pub send_to_folders: Vec<(String, PathBuf)>,
+    /// Image preview protocol: kitty, iterm2, sixel or halfblocks. Detected
+    /// from the terminal when unset.
+    pub image_protocol: Option<String>,
+    /// Copies hash every file against its source.
+    pub verify_copies: bool,
+    /// Start in the commander-style layout with two directory panes.
+    pub dual_pane: bool,
+    /// Copies of a file kept in browrs's history before it is overwritten or
+    /// deleted, 0 disables the history.
+    pub keep_versions: usize,
+    /// Earlier commands, searches, filters and paths gone to kept for Up to bring back,
+    /// per prompt; 0 keeps none.
+    pub history_size: usize,
+    /// Whether that history is saved for the next session. Off, it is
+    /// forgotten on exit.
+    pub save_history: bool,
+    /// Whether the most visited directories are suggested with the
+    /// bookmarks. Off, visits aren't counted either.
+    pub suggest_places: bool,
+    /// Jobs that run at least this many seconds send a desktop notification
+    /// when they end, unless the terminal is known to have focus; 0 never.
+    pub notify_after: u64,
+    /// Per kind of job from the `[notify]` table, like `copy = 30`, in
+    /// place of `notify_after`.
+    pub notify: HashMap<String, u64>,
+    /// Sort orders of their own from the `[sorts]` table, cycled through
+    /// after the built-in ones.
+    pub sorts: Vec<Sort>,
+    /// Command run with a title and a message to notify, instead of
+    /// notify-send or osascript.
+    pub notifier: Option<String>,
+    /// Command printing the clipboard's text, instead of pbpaste, wl-paste,
+    /// xclip or xsel.
+    pub paste_command: Option<String>,
+    /// How far search, grep, disk usage, duplicates and the cleanup scan
+    /// go into a tree: `max_depth`, `max_entries`, `follow_symlinks` and
+    /// `exclude`, a comma-separated list of patterns.
+    pub walk: walk::Policy,
+    /// Entries colored as `$LS_COLORS` has them, where it says how, rather
+    /// than by the theme.
+    pub ls_colors: Option<LsColors>,
+    /// One of the named color schemes, with `[colors]` applied.
+    pub theme: Theme,
+    /// Colors from the `[colors]` table, by name, kept to apply again when
+    /// the theme changes. Values are color names, `#rrggbb` or 256-color
+    /// indices.
+    pub colors: Vec<(String, Color)>,
+    /// Built-in key bindings with the `[keys]` table applied, e.g.
+    /// `j = "move_down"` or `"g g" = "top"`.
+    pub keymap: Keymap,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            start_dir: None,
+            show_hidden: false,
+            sort: Sort::Name,
+            dirs_first: false,
+            hide_ignored: false,
+            detailed: false,
+            wrap_around: false,
+            scroll_acceleration: 0,
+            pane_title: None,
+            terminal_title: Some("browrs: {shortpath}".to_string()),
+            watch_interval_ms: 1000,
+            preview_max_size: 1024 * 1024,
+            preview_timeout_ms: 2000,
+            editor: None,
+            opener: None,
+            open_rules: vec![],
+            previewers: vec![],
+            actions: vec![],
+            editors: HashMap::new(),
+            recent_minutes: 10,
+            line_counts: false,
+            stale_days: 0,
+            size_colors: false,
+            size_tiers: [1 << 20, 100 << 20, 1 << 30],
+            send_to: vec![],
+            send_to_folders: vec![],
+            image_protocol: None,
+            verify_copies: false,
+            dual_pane: false,
+            keep_versions: 0,
+            history_size: 100,
+            save_history: true,
+            suggest_places: true,
+            notify_after: 0,
+            notify: HashMap::new(),
+            notifier: None,
+            paste_command: None,
+            sorts: vec![],
+            walk: walk::Policy::default(),
+            ls_colors: None,
+            theme: Theme::default(),
+            colors: vec![],
+            keymap: Keymap::default(),
+        }
+    }
+}
+
+impl Config {
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("browrs").join("config.toml"))
+    }
+
+    /// Loads the config file, falling back to defaults when it is absent.
+    /// Lines that can't be applied are skipped and returned.
+    pub fn load() -> (Self, Vec<BrowrsError>) {
+        Self::path()
+            .and_then(|path| std::fs::read_to_string(path).ok())
+            .map(|content| Self::parse_checked(&content))
+            .unwrap_or_default()
+    }
+
+    /// Parses the config, skipping lines that can't be applied.
+    pub fn parse(content: &str) -> Self {
+        Self::parse_checked(content).0
+    }
+
+    /// Parses the config, returning what was wrong with the lines that
+    /// couldn't be applied along with it.
+    pub fn parse_checked(content: &str) -> (Self, Vec<BrowrsError>) {
+        let mut config = Self::default();
+        let mut errors = Vec::new();
+        let mut section = String::new();
+        // A sort of its own is defined further down than where it's picked
+        let mut sort = None;
+
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fail = move |reason: String| BrowrsError::Config {
+                line: number + 1,
+                reason,
+            };
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                section = name.trim().to_string();
+                if !SECTIONS.contains(&section.as_str()) {
+                    errors.push(fail(format!("unknown section [{}]", section)));
+                }
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                errors.push(fail(format!("expected key = value, not {}", line)));
+                continue;
+            };
+            let key = unquote(key.trim());
+            let value = unquote(value.trim());
+            if section.is_empty() && key == "sort" {
+                sort = Some((value, fail));
+                continue;
+            }
+            if let Err(reason) = config.apply(&section, &key, value) {
+                errors.push(fail(reason));
+            }
+        }
+        if let Some((name, fail)) = sort
+            && let Err(reason) = config.apply("", "sort", name)
+        {
+            errors.push(fail(reason));
+        }
+        (config, errors)
+    }
+
+    /// Sets one `key = value` entry of `section`.
+    pub(crate) fn apply(&mut self, section: &str, key: &str, value: String) -> Result<(), String> {
+        match (section, key) {
+            ("", "start_dir") => self.start_dir = Some(expand_tilde(&value)),
+            ("", "show_hidden") => self.show_hidden = parse_bool(&value)?,
+            ("", "sort") => {
+                self.sort =
+                    Sort::named(&value, &self.sorts).ok_or(format!("unknown sort {}", value))?
+            }
+            ("", "dirs_first") => self.dirs_first = parse_bool(&value)?,
+            ("", "hide_ignored") => self.hide_ignored = parse_bool(&value)?,
+            ("", "preview_max_size") => {
+                self.preview_max_size =
+                    parse_size(&value).ok_or(format!("not a size: {}", value))?
+            }
+            ("", "editor") => self.editor = Some(value),
+            ("", "opener") => self.opener = Some(value),
+            ("", "image_protocol") => self.image_protocol = Some(value),
+            ("", "verify_copies") => self.verify_copies = parse_bool(&value)?,
+            ("", "detailed") => self.detailed = parse_bool(&value)?,
+            ("", "wrap_around") => self.wrap_around = parse_bool(&value)?,
+            ("", "scroll_acceleration") => self.scroll_acceleration = parse_number(&value)?,
+            ("", "pane_title") => self.pane_title = Some(value).filter(|v| !v.is_empty()),
+            ("", "terminal_title") => self.terminal_title = Some(value).filter(|v| !v.is_empty()),
+            ("", "dual_pane") => self.dual_pane = parse_bool(&value)?,
+            ("", "keep_versions") => self.keep_versions = parse_number(&value)?,
+            ("", "history_size") => self.history_size = parse_number(&value)?,
+            ("", "save_history") => self.save_history = parse_bool(&value)?,
+            ("", "suggest_places") => self.suggest_places = parse_bool(&value)?,
+            ("", "watch_interval_ms") => self.watch_interval_ms = parse_number(&value)?,
+            ("", "preview_timeout_ms") => self.preview_timeout_ms = parse_number(&value)?,
+            ("", "notify_after") => self.notify_after = parse_number(&value)?,
+            ("", "notifier") => self.notifier = Some(value).filter(|v| !v.is_empty()),
+            ("", "paste_command") => self.paste_command = Some(value).filter(|v| !v.is_empty()),
+            ("", "recent_minutes") => self.recent_minutes = parse_number(&value)?,
+            ("", "line_counts") => self.line_counts = parse_bool(&value)?,
+            ("", "stale_days") => self.stale_days = parse_number(&value)?,
+            ("", "size_colors") => self.size_colors = parse_bool(&value)?,
+            ("", "size_tiers") => self.size_tiers = parse_tiers(&value)?,
+            ("", "max_depth") => self.walk.max_depth = parse_number(&value)?,
+            ("", "max_entries") => self.walk.max_entries = parse_number(&value)?,
+            ("", "follow_symlinks") => self.walk.follow_symlinks = parse_bool(&value)?,
+            ("", "exclude") => {
+                self.walk.exclude = value
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|pattern| !pattern.is_empty())
+                    .map(str::to_string)
+                    .collect();
+            }
+            ("", "ls_colors") => {
+                self.ls_colors = parse_bool(&value)?.then(LsColors::from_env);
+            }
+            ("", "theme") => {
+                let mut theme = Theme::named(&value).ok_or(format!(
+                    "unknown theme {}, expected one of {}",
+                    value,
+                    theme::NAMES.join(", ")
+                ))?;
+                for (name, color) in &self.colors {
+                    if let Some(slot) = theme.color_mut(name) {
+                        *slot = *color;
+                    }
+                }
+                self.theme = theme;
+            }
+            ("", key) => return Err(format!("unknown option {}", key)),
+            ("send_to", name) => self.send_to.push((name.to_string(), value)),
+            ("send_to_folders", name) => {
+                self.send_to_folders
+                    .push((name.to_string(), expand_tilde(&value)));
+            }
+            ("colors", name) => {
+                let color = value
+                    .parse()
+                    .map_err(|_| format!("not a color: {}", value))?;
+                *self
+                    .theme
+                    .color_mut(name)
+                    .ok_or(format!("unknown color {}", name))? = color;
+                self.colors.retain(|(other, _)| other != name);
+                self.colors.push((name.to_string(), color));
+            }
+            ("notify", kind) => {
+                if jobs::Kind::from_name(kind).is_none() {
+                    return Err(format!("unknown kind of job {}", kind));
+                }
+                self.notify.insert(kind.to_string(), parse_number(&value)?);
+            }
+            ("sorts", name) => {
+                if Sort::parse(name).is_some() {
+                    return Err(format!("{} is a built-in sort", name));
+                }
+                let sort = Sort::Custom(Arc::new(Custom::parse(name, &value)?));
+                self.sorts.retain(|other| other.label() != name);
+                self.sorts.push(sort);
+            }
+            ("keys", keys) => self.keymap.bind(keys, &value).map_err(|e| e.to_string())?,
+            ("open", pattern) => self.open_rules.push(opener::Rule::parse(pattern, &value)?),
+            ("previewers", pattern) => self.previewers.push(Previewer::parse(pattern, &value)?),
+            ("actions", key) => self.actions.extend(Association::parse(key, &value)?),
+            ("editors", ext) => {
+                self.editors
+                    .insert(ext.trim_start_matches('.').to_lowercase(), value);
+            }
+            // Reported once, at the section header
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Color for a file of `size` bytes, `None` below the smallest tier or
+    /// with size colors off.
+    pub fn size_color(&self, size: u64) -> Option<Color> {
+        if !self.size_colors {
+            return None;
+        }
+        let colors = [
+            self.theme.size_medium,
+            self.theme.size_large,
+            self.theme.size_huge,
+        ];
+        self.size_tiers
+            .iter()
+            .zip(colors)
+            .rev()
+            .find(|(tier, _)| size >= **tier)
+            .map(|(_, color)| color)
+    }
+}
+
+/// Tables the config file can have.
+const SECTIONS: &[&str] = &[
+    "send_to",
+    "send_to_folders",
+    "colors",
+    "keys",
+    "editors",
+    "open",
+    "actions",
+    "notify",
+    "sorts",
+];
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    match value {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        _ => Err(format!("expected true or false, not {}", value)),
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("not a number: {}", value))
+}
+
+/// Parses a byte count with an optional K, M or G suffix.
+fn parse_size(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let (number, unit) = match value.char_indices().last()? {
+        (i, 'k' | 'K') => (&value[..i], 1024),
+        (i, 'm' | 'M') => (&value[..i], 1024 * 1024),
+        (i, 'g' | 'G') => (&value[..i], 1024 * 1024 * 1024),
+        _ => (value, 1),
+    };
+    number.trim().parse::<u64>().ok()?.checked_mul(unit)
+}
+
+/// Parses three ascending sizes like `1M 100M 1G`.
+fn parse_tiers(value: &str) -> Result<[u64; 3], String> {
+    let sizes = value
+        .split([' ', ','])
+        .filter(|part| !part.is_empty())
+        .map(|part| parse_size(part).ok_or(format!("not a size: {}", part)))
+        .collect::<Result<Vec<_>, _>>()?;
+    match sizes[..] {
+        [medium, large, huge] if medium < large && large < huge => Ok([medium, large, huge]),
+        _ => Err(format!("expected three ascending sizes, not {}", value)),
+    }
+}
+
+/// Expands a leading `~` to the home directory.
+pub fn expand_tilde(path: &str) -> PathBuf {
+    match (path.strip_prefix('~'), dirs::home_dir()) {
+        (Some(rest), Some(home)) if rest.is_empty() || rest.starts_with('/') => {
+            home.join(rest.trim_start_matches('/'))
+        }
+        _ => PathBuf::from(path),
+    }
+}
+
+fn unquote(value: &str) -> String {
+    let value = value.trim();
+    for quote in ['"', '\''] {
+        if let Some(rest) = value.strip_prefix(quote) {
+            // Anything after the closing quote is a comment
+            return rest.split(quote).next().unwrap_or_default().to_string();
+        }
+    }
+    value
+        .split('#')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_string()
+}
@@ -0,0 +1,113 @@
+//! Crash reports. When browrs panics it writes what it knows to
+//! `$XDG_STATE_HOME/browrs/crash-<time>.txt` and prints the path once the
+//! terminal is back to normal, so there is something to attach to a bug
+//! report.
+
+use std::{
+    backtrace::Backtrace,
+    fmt::Write as _,
+    io,
+    panic::PanicHookInfo,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::{datetime, log};
+
+fn default_dir() -> Option<PathBuf> {
+    dirs::state_dir()
+        .or_else(dirs::cache_dir)
+        .map(|dir| dir.join("browrs"))
+}
+
+/// Writes a report on every panic, to `dir` or the default directory. Call
+/// it after the terminal is set up so the terminal is restored before the
+/// path is printed.
+pub fn install(dir: Option<PathBuf>) {
+    let dir = dir.or_else(default_dir);
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let message = panic_message(info);
+        log::error!("panic: {}", message);
+        let report = report(&message, &Backtrace::force_capture());
+        let written = dir.as_deref().map(|dir| write(dir, &report));
+        previous(info);
+        match written {
+            Some(Ok(path)) => {
+                eprintln!("browrs crashed; a report was written to {}", path.display())
+            }
+            Some(Err(e)) => eprintln!("browrs crashed and the report couldn't be written: {}", e),
+            None => eprintln!("browrs crashed and there is nowhere to write a report"),
+        }
+    }));
+}
+
+fn panic_message(info: &PanicHookInfo) -> String {
+    let payload = info.payload();
+    let message = payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "(no message)".to_string());
+    match info.location() {
+        Some(at) => format!("{} at {}:{}:{}", message, at.file(), at.line(), at.column()),
+        None => message,
+    }
+}
+
+/// The text of a report: the panic, version and OS, the recent log and the
+/// backtrace.
+pub fn report(message: &str, backtrace: &Backtrace) -> String {
+    let mut report = String::new();
+    let _ = writeln!(report, "browrs {} crashed", env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(report, "Time: {}", datetime::local(SystemTime::now()).iso());
+    let _ = writeln!(report, "OS: {}", os());
+    let _ = writeln!(report, "Panic: {}", message);
+    let _ = writeln!(report, "\nRecent log:");
+    let recent = log::recent();
+    if recent.is_empty() {
+        let _ = writeln!(report, "(empty)");
+    }
+    for line in recent {
+        let _ = writeln!(report, "{}", line);
+    }
+    let _ = writeln!(report, "\nBacktrace:\n{}", backtrace);
+    report
+}
+
+/// Saves `report` in `dir` under a name with the current time.
+pub fn write(dir: &Path, report: &str) -> io::Result<PathBuf> {
+    std::fs::create_dir_all(dir)?;
+    let now = datetime::local(SystemTime::now());
+    let path = dir.join(format!("crash-{}.txt", now.iso().replace(':', "")));
+    std::fs::write(&path, report)?;
+    Ok(path)
+}
+
+#[cfg(unix)]
+fn os() -> String {
+    // SAFETY: utsname is plain data that uname fills in
+    let mut name: libc::utsname = unsafe { std::mem::zeroed() };
+    if unsafe { libc::uname(&mut name) } != 0 {
+        return format!("{} {}", std::env::consts::OS, std::env::consts::ARCH);
+    }
+    let field = |chars: &[libc::c_char]| {
+        let bytes: Vec<u8> = chars
+            .iter()
+            .take_while(|&&c| c != 0)
+            .map(|&c| c as u8)
+            .collect();
+        String::from_utf8_lossy(&bytes).into_owned()
+    };
+    format!(
+        "{} {} {}",
+        field(&name.sysname),
+        field(&name.release),
+        field(&name.machine)
+    )
+}
+
+#[cfg(not(unix))]
+fn os() -> String {
+    format!("{} {}", std::env::consts::OS, std::env::consts::ARCH)
+}
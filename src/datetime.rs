@@ -0,0 +1,104 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Broken-down wall-clock time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateTime {
+    pub year: i64,
+    pub month: i64,
+    pub day: i64,
+    pub hour: i64,
+    pub minute: i64,
+    pub second: i64,
+}
+
+impl DateTime {
+    /// `YYYY-MM-DDTHH:MM:SS`, as used by trash info files.
+    pub fn iso(&self) -> String {
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
+
+/// Seconds since the epoch for a wall-clock time in the local timezone.
+#[cfg(unix)]
+pub fn local_to_epoch(time: DateTime) -> Option<i64> {
+    // SAFETY: tm is plain data and mktime only reads/normalizes it
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    tm.tm_year = (time.year - 1900) as i32;
+    tm.tm_mon = (time.month - 1) as i32;
+    tm.tm_mday = time.day as i32;
+    tm.tm_hour = time.hour as i32;
+    tm.tm_min = time.minute as i32;
+    tm.tm_sec = time.second as i32;
+    tm.tm_isdst = -1;
+    let epoch = unsafe { libc::mktime(&mut tm) };
+    (epoch != -1).then_some(epoch as i64)
+}
+
+/// Seconds since the epoch, treating the wall-clock time as UTC.
+#[cfg(not(unix))]
+pub fn local_to_epoch(time: DateTime) -> Option<i64> {
+    // Days from civil, see http://howardhinnant.github.io/date_algorithms.html
+    let y = if time.month <= 2 {
+        time.year - 1
+    } else {
+        time.year
+    };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (time.month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + time.day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+    Some(days * 86_400 + time.hour * 3600 + time.minute * 60 + time.second)
+}
+
+/// Local wall-clock time of `time`.
+#[cfg(unix)]
+pub fn local(time: SystemTime) -> DateTime {
+    let epoch = epoch_seconds(time) as libc::time_t;
+    // SAFETY: localtime_r only writes into the provided tm
+    let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+    unsafe { libc::localtime_r(&epoch, &mut tm) };
+    DateTime {
+        year: tm.tm_year as i64 + 1900,
+        month: tm.tm_mon as i64 + 1,
+        day: tm.tm_mday as i64,
+        hour: tm.tm_hour as i64,
+        minute: tm.tm_min as i64,
+        second: tm.tm_sec as i64,
+    }
+}
+
+/// UTC wall-clock time of `time`.
+#[cfg(not(unix))]
+pub fn local(time: SystemTime) -> DateTime {
+    let epoch = epoch_seconds(time);
+    let (days, secs) = (epoch.div_euclid(86_400), epoch.rem_euclid(86_400));
+    // Civil from days, the inverse of local_to_epoch
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    DateTime {
+        year: yoe + era * 400 + i64::from(month <= 2),
+        month,
+        day,
+        hour: secs / 3600,
+        minute: secs % 3600 / 60,
+        second: secs % 60,
+    }
+}
+
+fn epoch_seconds(time: SystemTime) -> i64 {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(since) => since.as_secs() as i64,
+        Err(before) => -(before.duration().as_secs() as i64),
+    }
+}
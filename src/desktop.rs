@@ -0,0 +1,212 @@
+use std::{
+    io,
+    path::Path,
+    process::{Command, ExitStatus, Stdio},
+};
+
+use crate::log;
+
+/// Starts a GUI program without tying its output or lifetime to the TUI.
+fn spawn_detached(program: &str, args: &[&std::ffi::OsStr]) -> io::Result<()> {
+    log::info!("starting {} {:?}", program, args);
+    Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map(|_| ())
+}
+
+/// Sends a desktop notification with `notifier`, given the title and
+/// message as its last two arguments, or the platform's way.
+pub fn notify(title: &str, message: &str, notifier: Option<&str>) -> io::Result<()> {
+    if let Some(notifier) = notifier {
+        let mut parts = notifier.split_whitespace();
+        let program = parts.next().unwrap_or_default();
+        let mut args: Vec<&std::ffi::OsStr> = parts.map(|part| part.as_ref()).collect();
+        args.extend([std::ffi::OsStr::new(title), std::ffi::OsStr::new(message)]);
+        return spawn_detached(program, &args);
+    }
+    if cfg!(target_os = "macos") {
+        let quote = |text: &str| text.replace('\\', "\\\\").replace('"', "\\\"");
+        let script = format!(
+            "display notification \"{}\" with title \"{}\"",
+            quote(message),
+            quote(title)
+        );
+        spawn_detached("osascript", &["-e".as_ref(), script.as_ref()])
+    } else if cfg!(target_os = "windows") {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "no notifier on Windows, set one in the config",
+        ))
+    } else {
+        spawn_detached(
+            "notify-send",
+            &[
+                "--app-name=browrs".as_ref(),
+                title.as_ref(),
+                message.as_ref(),
+            ],
+        )
+    }
+}
+
+/// The text on the system clipboard, as printed by `paste_command` or the
+/// platform's tool for it.
+pub fn clipboard_text(paste_command: Option<&str>) -> io::Result<String> {
+    let parts: Vec<&str> = match paste_command {
+        Some(command) => command.split_whitespace().collect(),
+        None if cfg!(target_os = "macos") => vec!["pbpaste"],
+        None if cfg!(target_os = "windows") => {
+            vec!["powershell", "-NoProfile", "-Command", "Get-Clipboard -Raw"]
+        }
+        None => {
+            let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+            let tools: [&[&str]; 3] = [
+                &["wl-paste", "--no-newline"],
+                &["xclip", "-selection", "clipboard", "-o"],
+                &["xsel", "--clipboard", "--output"],
+            ];
+            let found = tools
+                .into_iter()
+                .filter(|tool| wayland || tool[0] != "wl-paste")
+                .find(|tool| crate::editor::is_available(tool[0]));
+            match found {
+                Some(tool) => tool.to_vec(),
+                None => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::NotFound,
+                        "no wl-paste, xclip or xsel to read the clipboard with, set paste_command",
+                    ));
+                }
+            }
+        }
+    };
+    let Some((program, args)) = parts.split_first() else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "empty paste_command",
+        ));
+    };
+    log::info!("reading the clipboard with {}", parts.join(" "));
+    let output = Command::new(program)
+        .args(args)
+        .stdin(Stdio::null())
+        .output()?;
+    if !output.status.success() {
+        let error = String::from_utf8_lossy(&output.stderr);
+        let error = error
+            .lines()
+            .next()
+            .unwrap_or("the clipboard couldn't be read");
+        return Err(io::Error::other(format!("{}: {}", program, error)));
+    }
+    String::from_utf8(output.stdout).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            "the clipboard doesn't hold text",
+        )
+    })
+}
+
+/// Programs offered for opening a file the default application couldn't,
+/// when they are installed.
+const OPEN_WITH: &[&str] = &[
+    "less",
+    "vim",
+    "nano",
+    "mpv",
+    "vlc",
+    "feh",
+    "imv",
+    "zathura",
+    "evince",
+    "firefox",
+    "libreoffice",
+];
+
+/// The platform's command for opening a file with its default
+/// application; `start` takes a window title before the file.
+const DEFAULT_OPENER: &[&str] = if cfg!(target_os = "macos") {
+    &["open"]
+} else if cfg!(target_os = "windows") {
+    &["cmd", "/C", "start", ""]
+} else {
+    &["xdg-open"]
+};
+
+/// Opens `path` with the `opener` command, or the platform's, waiting for
+/// it to finish since it may use the terminal.
+pub fn open_default(path: &Path, opener: Option<&str>) -> io::Result<ExitStatus> {
+    let parts: Vec<&str> = match opener {
+        Some(opener) => opener.split_whitespace().collect(),
+        None => DEFAULT_OPENER.to_vec(),
+    };
+    let Some((program, args)) = parts.split_first() else {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "empty opener"));
+    };
+    log::info!("opening {} with {}", path.display(), parts.join(" "));
+    Command::new(program).args(args).arg(path).status()
+}
+
+/// Installed programs to offer when the default application fails.
+pub fn open_with_candidates() -> Vec<String> {
+    OPEN_WITH
+        .iter()
+        .filter(|program| crate::editor::is_available(program))
+        .map(|program| program.to_string())
+        .collect()
+}
+
+/// Shows `path` selected in the system file manager, returning the name of
+/// the tool that handled it.
+#[cfg(target_os = "macos")]
+pub fn reveal(path: &Path) -> io::Result<&'static str> {
+    spawn_detached("open", &["-R".as_ref(), path.as_os_str()]).map(|_| "Finder")
+}
+
+#[cfg(target_os = "windows")]
+pub fn reveal(path: &Path) -> io::Result<&'static str> {
+    let mut select = std::ffi::OsString::from("/select,");
+    select.push(path.as_os_str());
+    spawn_detached("explorer", &[select.as_os_str()]).map(|_| "Explorer")
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+pub fn reveal(path: &Path) -> io::Result<&'static str> {
+    let path = path.canonicalize()?;
+
+    // The freedesktop FileManager1 interface works for most desktop environments
+    let uri = format!("array:string:file://{}", path.display());
+    let shown = Command::new("dbus-send")
+        .args([
+            "--session",
+            "--print-reply",
+            "--dest=org.freedesktop.FileManager1",
+            "--type=method_call",
+            "/org/freedesktop/FileManager1",
+            "org.freedesktop.FileManager1.ShowItems",
+            &uri,
+            "string:",
+        ])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success());
+    if shown {
+        return Ok("file manager");
+    }
+
+    for manager in ["nautilus", "dolphin"] {
+        if crate::editor::is_available(manager) {
+            return spawn_detached(manager, &["--select".as_ref(), path.as_os_str()])
+                .map(|_| manager);
+        }
+    }
+
+    // No selection support, at least open the containing folder
+    let parent = path.parent().unwrap_or(&path);
+    spawn_detached("xdg-open", &[parent.as_os_str()]).map(|_| "xdg-open")
+}
@@ -0,0 +1,107 @@
+//! Line diffs between two versions of a text file.
+
+use ratatui::{
+    style::Stylize,
+    text::{Line, Text},
+};
+
+use crate::theme;
+
+/// Size of the comparison table above which we don't try to diff.
+const MAX_CELLS: usize = 4_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// Diffs `old` against `new` line by line via their longest common
+/// subsequence. Returns `None` when the files are too large for that.
+pub fn lines<'a>(old: &'a str, new: &'a str) -> Option<Vec<Change<'a>>> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // Only the middle that differs needs the table
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let (a, b) = (
+        &old[prefix..old.len() - suffix],
+        &new[prefix..new.len() - suffix],
+    );
+    if (a.len() + 1).saturating_mul(b.len() + 1) > MAX_CELLS {
+        return None;
+    }
+
+    // lengths[i][j]: LCS length of a[i..] and b[j..]
+    let width = b.len() + 1;
+    let mut lengths = vec![0u32; (a.len() + 1) * width];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lengths[i * width + j] = if a[i] == b[j] {
+                lengths[(i + 1) * width + j + 1] + 1
+            } else {
+                lengths[(i + 1) * width + j].max(lengths[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut changes: Vec<Change> = old[..prefix].iter().map(|l| Change::Same(l)).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            changes.push(Change::Same(a[i]));
+            i += 1;
+            j += 1;
+        } else if i < a.len()
+            && (j == b.len() || lengths[(i + 1) * width + j] >= lengths[i * width + j + 1])
+        {
+            changes.push(Change::Removed(a[i]));
+            i += 1;
+        } else {
+            changes.push(Change::Added(b[j]));
+            j += 1;
+        }
+    }
+    changes.extend(old[old.len() - suffix..].iter().map(|l| Change::Same(l)));
+    Some(changes)
+}
+
+/// Colored unified-style listing of the changes, keeping `context` unchanged
+/// lines around each one.
+pub fn render(changes: &[Change], context: usize) -> Text<'static> {
+    let theme = theme::current();
+    let near_change = |i: usize| {
+        let start = i.saturating_sub(context);
+        let end = (i + context + 1).min(changes.len());
+        changes[start..end]
+            .iter()
+            .any(|c| !matches!(c, Change::Same(_)))
+    };
+    let mut lines = Vec::new();
+    let mut skipped = false;
+    for (i, change) in changes.iter().enumerate() {
+        match change {
+            Change::Same(line) if near_change(i) => {
+                lines.push(Line::from(format!(" {}", line)));
+            }
+            Change::Same(_) => {
+                if !skipped {
+                    lines.push(Line::from("⋯".fg(theme.dim)));
+                }
+                skipped = true;
+                continue;
+            }
+            Change::Removed(line) => lines.push(Line::from(format!("-{}", line).fg(theme.error))),
+            Change::Added(line) => lines.push(Line::from(format!("+{}", line).fg(theme.success))),
+        }
+        skipped = false;
+    }
+    Text::from(lines)
+}
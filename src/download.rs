@@ -0,0 +1,219 @@
+//! Downloading files with curl into a directory, resuming from a `.part`
+//! file left by an earlier attempt.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    jobs::{Progress, format_size},
+    log, url,
+};
+
+/// How often the partial file is checked while curl runs.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+/// curl's exit code when the server can't resume a transfer.
+const RANGE_ERROR: i32 = 33;
+
+/// Whether pasted or typed text is a single http(s) URL.
+pub fn is_url(text: &str) -> bool {
+    let text = text.trim();
+    (text.starts_with("http://") || text.starts_with("https://"))
+        && !text.contains(char::is_whitespace)
+}
+
+/// Name to show for a download before the server has told us anything.
+pub fn title(url: &str) -> String {
+    format!(
+        "⬇ {}",
+        name_from_url(url).unwrap_or_else(|| url.to_string())
+    )
+}
+
+/// Fetches `url` into `dir`, returning a message for the finished job.
+pub fn download(url: &str, dir: &Path, progress: &Progress) -> io::Result<String> {
+    let head = probe(url);
+    let name = head
+        .file_name
+        .or_else(|| name_from_url(&head.url))
+        .map(|name| sanitize(&name))
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "download".to_string());
+    let (dest, partial) = destination(dir, &name);
+
+    let mut status = fetch(url, &partial, head.size, true, progress)?;
+    if status == Some(RANGE_ERROR) {
+        std::fs::remove_file(&partial)?;
+        status = fetch(url, &partial, head.size, false, progress)?;
+    }
+    match status {
+        Some(0) => {}
+        None => return Err(io::Error::new(io::ErrorKind::Interrupted, "cancelled")),
+        Some(code) => {
+            return Err(io::Error::other(format!(
+                "curl exited with status {}, partial file kept as {}",
+                code,
+                partial.display()
+            )));
+        }
+    }
+
+    std::fs::rename(&partial, &dest)?;
+    let size = std::fs::metadata(&dest).map(|m| m.len()).unwrap_or(0);
+    Ok(format!(
+        "⬇ Downloaded {} ({})\n{}",
+        dest.file_name().unwrap_or_default().to_string_lossy(),
+        format_size(size),
+        url
+    ))
+}
+
+/// What a HEAD request told us about the download.
+struct Head {
+    /// URL after redirects.
+    url: String,
+    file_name: Option<String>,
+    size: Option<u64>,
+}
+
+fn probe(url: &str) -> Head {
+    let mut head = Head {
+        url: url.to_string(),
+        file_name: None,
+        size: None,
+    };
+    log::debug!("probing {}", url);
+    let _timer = log::timer(|| format!("probe {}", url));
+    let output = Command::new("curl")
+        .args(["-sSIL", "--max-time", "30", "-w", "\n%{url_effective}", url])
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output();
+    let Ok(output) = output else {
+        return head;
+    };
+    let text = String::from_utf8_lossy(&output.stdout);
+    let mut lines: Vec<&str> = text.lines().collect();
+    if let Some(effective) = lines.pop().filter(|l| !l.is_empty()) {
+        head.url = effective.to_string();
+    }
+    // Each redirect adds a block of headers, only the last one counts
+    for line in lines {
+        if line.starts_with("HTTP/") {
+            head.file_name = None;
+            head.size = None;
+        } else if let Some((key, value)) = line.split_once(':') {
+            match key.trim().to_ascii_lowercase().as_str() {
+                "content-length" => head.size = value.trim().parse().ok(),
+                "content-disposition" => head.file_name = disposition_name(value),
+                _ => {}
+            }
+        }
+    }
+    head
+}
+
+/// The filename from a Content-Disposition header, preferring the
+/// RFC 5987 `filename*` form.
+fn disposition_name(value: &str) -> Option<String> {
+    let mut plain = None;
+    for param in value.split(';') {
+        let Some((key, value)) = param.trim().split_once('=') else {
+            continue;
+        };
+        match key.trim().to_ascii_lowercase().as_str() {
+            "filename*" => {
+                let encoded = value.trim().splitn(3, '\'').last()?;
+                return Some(url::decode(encoded));
+            }
+            "filename" => plain = Some(value.trim().trim_matches('"').to_string()),
+            _ => {}
+        }
+    }
+    plain
+}
+
+fn name_from_url(url: &str) -> Option<String> {
+    let path = url.split(['?', '#']).next()?;
+    let path = path.split_once("://").map_or(path, |(_, rest)| rest);
+    let (_, path) = path.split_once('/')?;
+    let name = path.rsplit('/').next().filter(|n| !n.is_empty())?;
+    Some(url::decode(name))
+}
+
+/// Keeps a server-provided name from escaping the download directory.
+fn sanitize(name: &str) -> String {
+    let name = name.rsplit(['/', '\\']).next().unwrap_or_default();
+    name.trim_start_matches('.')
+        .chars()
+        .filter(|c| !c.is_control())
+        .collect()
+}
+
+/// Where to save `name` in `dir` and the partial file to download into.
+/// A leftover partial file is resumed; otherwise existing files get a
+/// numbered name rather than being overwritten.
+fn destination(dir: &Path, name: &str) -> (PathBuf, PathBuf) {
+    let path = Path::new(name);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    let mut candidate = name.to_string();
+    for n in 1.. {
+        let dest = dir.join(&candidate);
+        let partial = dir.join(format!("{}.part", candidate));
+        if partial.exists() || !dest.exists() {
+            return (dest, partial);
+        }
+        candidate = format!("{} ({}){}", stem, n, extension);
+    }
+    unreachable!()
+}
+
+/// Runs curl into `partial`, reporting its growth. Returns curl's exit code,
+/// or `None` if the job was cancelled.
+fn fetch(
+    url: &str,
+    partial: &Path,
+    size: Option<u64>,
+    resume: bool,
+    progress: &Progress,
+) -> io::Result<Option<i32>> {
+    log::info!(
+        "fetching {} to {} (resume: {})",
+        url,
+        partial.display(),
+        resume
+    );
+    let mut command = Command::new("curl");
+    command.args(["-fsSL", "--retry", "2", "-o"]).arg(partial);
+    if resume {
+        command.args(["-C", "-"]);
+    }
+    let mut child = command
+        .arg(url)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| io::Error::new(e.kind(), format!("unable to run curl: {}", e)))?;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status.code().unwrap_or(-1)));
+        }
+        if progress.is_cancelled() {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Ok(None);
+        }
+        let done = std::fs::metadata(partial).map(|m| m.len()).unwrap_or(0);
+        progress.set(done, size);
+        thread::sleep(POLL_INTERVAL);
+    }
+}
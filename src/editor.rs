@@ -0,0 +1,91 @@
+use std::{
+    collections::HashMap,
+    env,
+    path::{Path, PathBuf},
+    process::{Command, ExitStatus},
+};
+
+use crate::{config::Config, log};
+
+/// Editors tried after every configured choice when nothing else is usable.
+const FALLBACK_EDITORS: [&str; 3] = ["vim", "vi", "nano"];
+
+/// Lowercased extension used to key per-file-type editor choices.
+pub fn file_type(path: &Path) -> String {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default()
+}
+
+/// Editor commands for `path` in priority order: the in-app choice for its
+/// file type, the config file, `$VISUAL`, `$EDITOR`, then the fallbacks.
+pub fn candidates(path: &Path, choices: &HashMap<String, String>, config: &Config) -> Vec<String> {
+    let file_type = file_type(path);
+    let mut candidates = vec![];
+
+    candidates.extend(choices.get(&file_type).cloned());
+    candidates.extend(config.editors.get(&file_type).cloned());
+    candidates.extend(config.editor.clone());
+    for var in ["VISUAL", "EDITOR"] {
+        candidates.extend(env::var(var).ok().filter(|v| !v.trim().is_empty()));
+    }
+    candidates.extend(FALLBACK_EDITORS.iter().map(|e| e.to_string()));
+    candidates
+}
+
+/// Picks the first candidate whose program can actually be found.
+pub fn resolve(path: &Path, choices: &HashMap<String, String>, config: &Config) -> Option<String> {
+    candidates(path, choices, config)
+        .into_iter()
+        .find(|command| is_available(command))
+}
+
+/// Whether the program of an editor command exists on disk or in `$PATH`.
+pub fn is_available(command: &str) -> bool {
+    let Some(program) = command.split_whitespace().next() else {
+        return false;
+    };
+    if program.contains(std::path::MAIN_SEPARATOR) {
+        return Path::new(program).is_file();
+    }
+    env::var_os("PATH")
+        .map(|paths| env::split_paths(&paths).any(|dir| dir.join(program).is_file()))
+        .unwrap_or(false)
+}
+
+/// Runs `command` with the file appended as its last argument.
+pub fn open(command: &str, file_path: &PathBuf) -> std::io::Result<ExitStatus> {
+    open_all(command, std::slice::from_ref(file_path))
+}
+
+/// Runs `command` on the file with the cursor at `line`: `+LINE` before it,
+/// which vi, nano, emacs and most others read, or `-g FILE:LINE` for VS Code.
+pub fn open_at(command: &str, file_path: &Path, line: usize) -> std::io::Result<ExitStatus> {
+    let mut parts = command.split_whitespace();
+    let program = parts.next().unwrap_or("vim");
+    log::info!(
+        "running {} on {} at line {}",
+        command,
+        file_path.display(),
+        line
+    );
+    let mut run = Command::new(program);
+    run.args(parts);
+    let name = Path::new(program).file_name().unwrap_or_default();
+    if name == "code" || name == "codium" {
+        run.arg("-g")
+            .arg(format!("{}:{}", file_path.display(), line));
+    } else {
+        run.arg(format!("+{}", line)).arg(file_path);
+    }
+    run.status()
+}
+
+/// Runs `command` once with all of `files` appended.
+pub fn open_all(command: &str, files: &[PathBuf]) -> std::io::Result<ExitStatus> {
+    let mut parts = command.split_whitespace();
+    let program = parts.next().unwrap_or("vim");
+    log::info!("running {} on {} files", command, files.len());
+    Command::new(program).args(parts).args(files).status()
+}
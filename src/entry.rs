@@ -0,0 +1,173 @@
+use std::{path::PathBuf, time::SystemTime};
+
+/// A row of the file list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub name: String,
+    pub is_dir: bool,
+    pub marked: bool,
+    /// Size in bytes, as read when the directory was listed.
+    pub size: u64,
+    pub modified: Option<SystemTime>,
+    /// Permissions and ownership, for entries on disk.
+    pub meta: Option<Meta>,
+    /// Where the entry points, if it is a symlink. `is_dir`, `size` and
+    /// `modified` are those of the target.
+    pub link: Option<Link>,
+    /// Stars the file was given, 0 for none.
+    pub rating: u8,
+}
+
+impl Entry {
+    pub fn new(name: impl Into<String>, is_dir: bool) -> Self {
+        Self {
+            name: name.into(),
+            is_dir,
+            marked: false,
+            size: 0,
+            modified: None,
+            meta: None,
+            link: None,
+            rating: 0,
+        }
+    }
+
+    pub fn parent() -> Self {
+        Self::new("..", true)
+    }
+
+    pub fn is_parent(&self) -> bool {
+        self.name == ".."
+    }
+
+    /// Name as listed, with a trailing `/` on directories, and `|` on
+    /// named pipes and `=` on sockets as `ls -F` marks them.
+    pub fn display_name(&self) -> String {
+        if self.is_dir && !self.is_parent() {
+            return format!("{}/", self.name);
+        }
+        match self.special() {
+            Some(Special::Fifo) => format!("{}|", self.name),
+            Some(Special::Socket) => format!("{}=", self.name),
+            _ => self.name.clone(),
+        }
+    }
+
+    /// Whether it is a regular file anyone may run.
+    pub fn is_executable(&self) -> bool {
+        self.meta
+            .is_some_and(|meta| meta.mode & 0o170000 == 0o100000 && meta.mode & 0o111 != 0)
+    }
+
+    /// What kind of special file it is, if it is one.
+    pub fn special(&self) -> Option<Special> {
+        self.meta.and_then(|meta| meta.special())
+    }
+}
+
+/// The target of a symlink.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Link {
+    /// The target as written in the link, often relative to its directory.
+    pub target: PathBuf,
+    /// Whether nothing exists at the target.
+    pub broken: bool,
+}
+
+/// Files that are neither regular files, directories nor links. Reading
+/// one can wait forever, or never end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Special {
+    Fifo,
+    Socket,
+    BlockDevice,
+    CharDevice,
+}
+
+impl Special {
+    /// From the file type bits of a mode.
+    pub fn of(mode: u32) -> Option<Self> {
+        match mode & 0o170000 {
+            0o010000 => Some(Special::Fifo),
+            0o140000 => Some(Special::Socket),
+            0o060000 => Some(Special::BlockDevice),
+            0o020000 => Some(Special::CharDevice),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Special::Fifo => "named pipe",
+            Special::Socket => "socket",
+            Special::BlockDevice => "block device",
+            Special::CharDevice => "character device",
+        }
+    }
+
+    pub fn is_device(self) -> bool {
+        matches!(self, Special::BlockDevice | Special::CharDevice)
+    }
+}
+
+/// Unix permissions and ownership of an entry.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Meta {
+    /// File type and permission bits, as in `st_mode`.
+    pub mode: u32,
+    pub uid: u32,
+    pub gid: u32,
+    /// The device a device node stands for, as in `st_rdev`.
+    pub rdev: u64,
+}
+
+impl Meta {
+    #[cfg(unix)]
+    pub fn of(metadata: &std::fs::Metadata) -> Self {
+        use std::os::unix::fs::MetadataExt;
+        Self {
+            mode: metadata.mode(),
+            uid: metadata.uid(),
+            gid: metadata.gid(),
+            rdev: metadata.rdev(),
+        }
+    }
+
+    pub fn special(&self) -> Option<Special> {
+        Special::of(self.mode)
+    }
+
+    /// The major and minor numbers of a device node's device, e.g. `1:3`.
+    #[cfg(unix)]
+    pub fn device(&self) -> String {
+        let rdev = self.rdev as libc::dev_t;
+        format!("{}:{}", libc::major(rdev), libc::minor(rdev))
+    }
+
+    /// Like `ls -l`, e.g. `drwxr-xr-x`.
+    pub fn permissions(&self) -> String {
+        let kind = match self.mode & 0o170000 {
+            0o040000 => 'd',
+            0o120000 => 'l',
+            0o010000 => 'p',
+            0o140000 => 's',
+            0o020000 => 'c',
+            0o060000 => 'b',
+            _ => '-',
+        };
+        let mut text = String::from(kind);
+        // Owner, group and others, each with its setuid/setgid/sticky bit
+        for (shift, special, letter) in [(6, 0o4000, 's'), (3, 0o2000, 's'), (0, 0o1000, 't')] {
+            let bits = self.mode >> shift;
+            text.push(if bits & 4 != 0 { 'r' } else { '-' });
+            text.push(if bits & 2 != 0 { 'w' } else { '-' });
+            text.push(match (bits & 1 != 0, self.mode & special != 0) {
+                (true, true) => letter,
+                (false, true) => letter.to_ascii_uppercase(),
+                (true, false) => 'x',
+                (false, false) => '-',
+            });
+        }
+        text
+    }
+}
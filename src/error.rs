@@ -0,0 +1,87 @@
+//! The crate-wide error type. Every failure the user sees goes through
+//! [`BrowrsError`], so it reads the same wherever it is shown.
+
+use std::{fmt, io, path::Path, path::PathBuf};
+
+#[derive(Debug)]
+pub enum BrowrsError {
+    /// A filesystem call failed; `op` says what we were doing, like "copy".
+    Io {
+        op: &'static str,
+        path: PathBuf,
+        source: io::Error,
+    },
+    /// An external program couldn't be started, or failed.
+    Command { command: String, source: io::Error },
+    /// Input that doesn't parse, e.g. typed into a prompt.
+    Invalid { input: String, reason: String },
+    /// A line of the config file that can't be applied.
+    Config { line: usize, reason: String },
+    /// A background job failed.
+    Job { title: String, source: io::Error },
+    /// Drawing or reading input failed.
+    Terminal(io::Error),
+}
+
+pub type Result<T> = std::result::Result<T, BrowrsError>;
+
+impl BrowrsError {
+    pub fn io(op: &'static str, path: &Path, source: io::Error) -> Self {
+        BrowrsError::Io {
+            op,
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+
+    pub fn invalid(input: impl Into<String>, reason: impl Into<String>) -> Self {
+        BrowrsError::Invalid {
+            input: input.into(),
+            reason: reason.into(),
+        }
+    }
+
+    /// The error as a one-line notice.
+    pub fn message(&self) -> String {
+        format!("❌ {}", self)
+    }
+}
+
+impl fmt::Display for BrowrsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BrowrsError::Io { op, path, source } => {
+                write!(f, "Unable to {} {}: {}", op, path.display(), source)
+            }
+            BrowrsError::Command { command, source } => write!(f, "{}: {}", command, source),
+            BrowrsError::Invalid { input, reason } if input.is_empty() => write!(f, "{}", reason),
+            BrowrsError::Invalid { input, reason } => write!(f, "{}: {}", reason, input),
+            BrowrsError::Config { line, reason } => write!(f, "Config line {}: {}", line, reason),
+            BrowrsError::Job { title, source } => write!(f, "{} failed: {}", title, source),
+            BrowrsError::Terminal(source) => write!(f, "Terminal error: {}", source),
+        }
+    }
+}
+
+impl std::error::Error for BrowrsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            BrowrsError::Io { source, .. }
+            | BrowrsError::Command { source, .. }
+            | BrowrsError::Job { source, .. }
+            | BrowrsError::Terminal(source) => Some(source),
+            BrowrsError::Invalid { .. } | BrowrsError::Config { .. } => None,
+        }
+    }
+}
+
+/// Attaches what we were doing, and to which path, to an I/O error.
+pub trait Context<T> {
+    fn context(self, op: &'static str, path: &Path) -> Result<T>;
+}
+
+impl<T> Context<T> for io::Result<T> {
+    fn context(self, op: &'static str, path: &Path) -> Result<T> {
+        self.map_err(|source| BrowrsError::io(op, path, source))
+    }
+}
@@ -0,0 +1,385 @@
+//! Listings and reports written out as CSV, JSON or Markdown, to be shared
+//! or processed by other tools.
+
+use std::{
+    collections::HashMap,
+    ffi::OsString,
+    fs::File,
+    hash::{DefaultHasher, Hasher},
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{datetime, entry::Entry, walk::Policy};
+
+/// What can be exported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Report {
+    /// The entries listed in the pane, as filtered.
+    Listing,
+    /// The size of everything in the directory, biggest first.
+    DiskUsage,
+    /// Files under the directory with the same contents.
+    Duplicates,
+}
+
+impl Report {
+    /// Parses the name given to `:export`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "listing" | "ls" => Some(Report::Listing),
+            "usage" | "du" => Some(Report::DiskUsage),
+            "duplicates" | "dupes" => Some(Report::Duplicates),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Report::Listing => "listing",
+            Report::DiskUsage => "usage",
+            Report::Duplicates => "duplicates",
+        }
+    }
+}
+
+/// The file format, told by the extension of the path written to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Csv,
+    Json,
+    Markdown,
+}
+
+impl Format {
+    pub fn of(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_string_lossy().to_ascii_lowercase();
+        match extension.as_str() {
+            "csv" => Some(Format::Csv),
+            "json" => Some(Format::Json),
+            "md" | "markdown" => Some(Format::Markdown),
+            _ => None,
+        }
+    }
+}
+
+/// A cell of a report.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Value {
+    Text(String),
+    /// Left unquoted in JSON.
+    Number(u64),
+}
+
+impl Value {
+    fn text(&self) -> String {
+        match self {
+            Value::Text(text) => text.clone(),
+            Value::Number(number) => number.to_string(),
+        }
+    }
+}
+
+/// Rows of values under named columns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Table {
+    pub columns: &'static [&'static str],
+    pub rows: Vec<Vec<Value>>,
+}
+
+impl Table {
+    /// Writes the table to `path` in the format its extension names.
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        let format = Format::of(path).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "end the name in .csv, .json or .md",
+            )
+        })?;
+        let mut file = io::BufWriter::new(File::create(path)?);
+        file.write_all(self.render(format).as_bytes())?;
+        file.flush()
+    }
+
+    pub fn render(&self, format: Format) -> String {
+        match format {
+            Format::Csv => self.csv(),
+            Format::Json => self.json(),
+            Format::Markdown => self.markdown(),
+        }
+    }
+
+    fn csv(&self) -> String {
+        let line = |cells: Vec<String>| {
+            let cells: Vec<String> = cells
+                .into_iter()
+                .map(|cell| {
+                    if cell.contains([',', '"', '\n', '\r']) {
+                        format!("\"{}\"", cell.replace('"', "\"\""))
+                    } else {
+                        cell
+                    }
+                })
+                .collect();
+            cells.join(",") + "\n"
+        };
+        let mut out = line(self.columns.iter().map(|c| c.to_string()).collect());
+        for row in &self.rows {
+            out += &line(row.iter().map(Value::text).collect());
+        }
+        out
+    }
+
+    fn json(&self) -> String {
+        let rows: Vec<String> = self
+            .rows
+            .iter()
+            .map(|row| {
+                let fields: Vec<String> = self
+                    .columns
+                    .iter()
+                    .zip(row)
+                    .map(|(column, value)| {
+                        let value = match value {
+                            Value::Text(text) => json_string(text),
+                            Value::Number(number) => number.to_string(),
+                        };
+                        format!("{}: {}", json_string(column), value)
+                    })
+                    .collect();
+                format!("  {{{}}}", fields.join(", "))
+            })
+            .collect();
+        if rows.is_empty() {
+            "[]\n".to_string()
+        } else {
+            format!("[\n{}\n]\n", rows.join(",\n"))
+        }
+    }
+
+    fn markdown(&self) -> String {
+        let line = |cells: Vec<String>| format!("| {} |\n", cells.join(" | "));
+        let mut out = line(self.columns.iter().map(|c| c.to_string()).collect());
+        out += &line(self.columns.iter().map(|_| "---".to_string()).collect());
+        for row in &self.rows {
+            out += &line(
+                row.iter()
+                    .map(|value| {
+                        value
+                            .text()
+                            .replace('\\', "\\\\")
+                            .replace('|', "\\|")
+                            .replace('\n', " ")
+                    })
+                    .collect(),
+            );
+        }
+        out
+    }
+}
+
+fn json_string(text: &str) -> String {
+    let mut out = String::from('"');
+    for c in text.chars() {
+        match c {
+            '"' => out += "\\\"",
+            '\\' => out += "\\\\",
+            '\n' => out += "\\n",
+            '\r' => out += "\\r",
+            '\t' => out += "\\t",
+            c if (c as u32) < 0x20 => out += &format!("\\u{:04x}", c as u32),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn kind(entry: &Entry) -> &'static str {
+    match (&entry.link, entry.special()) {
+        (Some(_), _) => "link",
+        (None, Some(special)) => special.name(),
+        (None, None) if entry.is_dir => "directory",
+        (None, None) => "file",
+    }
+}
+
+/// The entries as listed, without `..`.
+pub fn listing(entries: &[Entry]) -> Table {
+    let rows = entries
+        .iter()
+        .filter(|entry| !entry.is_parent())
+        .map(|entry| {
+            vec![
+                Value::Text(entry.name.clone()),
+                Value::Text(kind(entry).to_string()),
+                Value::Number(entry.size),
+                Value::Text(
+                    entry
+                        .modified
+                        .map(|time| datetime::local(time).iso())
+                        .unwrap_or_default(),
+                ),
+                Value::Text(
+                    entry
+                        .meta
+                        .map(|meta| meta.permissions())
+                        .unwrap_or_default(),
+                ),
+            ]
+        })
+        .collect();
+    Table {
+        columns: &["name", "type", "size", "modified", "permissions"],
+        rows,
+    }
+}
+
+/// Each entry of `dir` with the bytes in it and under it, biggest first.
+pub fn disk_usage(
+    dir: &Path,
+    show_hidden: bool,
+    policy: &Policy,
+    cancelled: &dyn Fn() -> bool,
+) -> Table {
+    let mut sizes: Vec<(String, bool, u64)> = vec![];
+    let mut rows_by_name: HashMap<OsString, usize> = HashMap::new();
+    // Hidden files inside a shown directory count towards its size
+    for found in policy.walk(dir, true) {
+        if cancelled() {
+            break;
+        }
+        let Some(top) = found
+            .path
+            .strip_prefix(dir)
+            .ok()
+            .and_then(|relative| relative.components().next())
+        else {
+            continue;
+        };
+        let name = top.as_os_str();
+        if found.depth == 1 {
+            if !show_hidden && name.to_string_lossy().starts_with('.') {
+                continue;
+            }
+            rows_by_name.insert(name.to_os_string(), sizes.len());
+            sizes.push((
+                name.to_string_lossy().into_owned(),
+                found.metadata.is_dir(),
+                0,
+            ));
+        }
+        if found.metadata.is_file()
+            && let Some(&row) = rows_by_name.get(name)
+        {
+            sizes[row].2 += found.metadata.len();
+        }
+    }
+    sizes.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+    let rows = sizes
+        .into_iter()
+        .map(|(name, is_dir, size)| {
+            vec![
+                Value::Text(name),
+                Value::Text(if is_dir { "directory" } else { "file" }.to_string()),
+                Value::Number(size),
+            ]
+        })
+        .collect();
+    Table {
+        columns: &["name", "type", "size"],
+        rows,
+    }
+}
+
+/// Files under `root` with the same contents, in groups numbered from 1;
+/// the groups wasting the most space come first. Paths are relative to
+/// `root`.
+pub fn duplicates(
+    root: &Path,
+    show_hidden: bool,
+    policy: &Policy,
+    progress: &dyn Fn(usize, usize),
+    cancelled: &dyn Fn() -> bool,
+) -> Table {
+    // Only files of the same size can be the same
+    let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    for found in policy.walk(root, show_hidden) {
+        if cancelled() {
+            break;
+        }
+        if found.metadata.is_file() && found.metadata.len() > 0 {
+            by_size
+                .entry(found.metadata.len())
+                .or_default()
+                .push(found.path);
+        }
+    }
+
+    let candidates: Vec<(u64, Vec<PathBuf>)> = by_size
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .collect();
+    let total = candidates.iter().map(|(_, paths)| paths.len()).sum();
+    let mut done = 0;
+    let mut groups: Vec<(u64, Vec<PathBuf>)> = vec![];
+    for (size, paths) in candidates {
+        let mut by_hash: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+        for path in paths {
+            if cancelled() {
+                break;
+            }
+            if let Ok(hash) = hash(&path) {
+                by_hash.entry(hash).or_default().push(path);
+            }
+            done += 1;
+            progress(done, total);
+        }
+        groups.extend(
+            by_hash
+                .into_values()
+                .filter(|paths| paths.len() > 1)
+                .map(|mut paths| {
+                    paths.sort();
+                    (size, paths)
+                }),
+        );
+    }
+    groups.sort_by(|a, b| {
+        let wasted = |(size, paths): &(u64, Vec<PathBuf>)| size * (paths.len() as u64 - 1);
+        wasted(b).cmp(&wasted(a)).then_with(|| a.1.cmp(&b.1))
+    });
+
+    let rows = groups
+        .into_iter()
+        .enumerate()
+        .flat_map(|(index, (size, paths))| {
+            paths.into_iter().map(move |path| {
+                let relative = path.strip_prefix(root).unwrap_or(&path);
+                vec![
+                    Value::Number(index as u64 + 1),
+                    Value::Number(size),
+                    Value::Text(relative.to_string_lossy().into_owned()),
+                ]
+            })
+        })
+        .collect();
+    Table {
+        columns: &["group", "size", "path"],
+        rows,
+    }
+}
+
+/// A hash of the file's contents.
+fn hash(path: &Path) -> io::Result<u64> {
+    let mut file = File::open(path)?;
+    let mut hasher = DefaultHasher::new();
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            return Ok(hasher.finish());
+        }
+        hasher.write(&buffer[..read]);
+    }
+}
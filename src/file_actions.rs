@@ -0,0 +1,119 @@
+//! The actions menu: what can be done with the selected entry, by its type.
+//! Archives can be extracted and executables run; everything else comes
+//! from the `[actions]` config table, a label after the patterns it is
+//! for:
+//!
+//! ```toml
+//! [actions]
+//! "*.gpg: Decrypt" = "gpg --decrypt-files {file}"
+//! "*.mp3, *.flac: Play" = "mpv --no-video {file}"
+//! ```
+//!
+//! Commands work as in the `[open]` table. Media files get Play and `.gpg`
+//! files Decrypt when mpv and gpg are installed, unless the config names
+//! an action of the same label for them.
+
+use std::path::Path;
+
+use crate::{archive::Format, editor, kinds, opener::Rule, vfs};
+
+/// Actions offered without any config, when their program is installed.
+const DEFAULTS: &[(&str, &str, &str)] = &[
+    (
+        "*.mp3, *.flac, *.ogg, *.opus, *.wav, *.m4a, *.mp4, *.mkv, *.webm, *.avi, *.mov",
+        "Play",
+        "mpv {file}",
+    ),
+    ("*.gpg, *.pgp", "Decrypt", "gpg --decrypt-files {file}"),
+];
+
+/// A labelled command for files matching one pattern.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Association {
+    pub label: String,
+    pub rule: Rule,
+}
+
+impl Association {
+    /// Reads one `"patterns: label" = command` entry, one association for
+    /// each comma-separated pattern.
+    pub fn parse(key: &str, command: &str) -> Result<Vec<Association>, String> {
+        let (patterns, label) = key
+            .rsplit_once(':')
+            .ok_or(format!("expected \"patterns: label\", not {}", key))?;
+        let label = label.trim();
+        if label.is_empty() {
+            return Err(format!("no label for the action on {}", patterns));
+        }
+        patterns
+            .split(',')
+            .map(|pattern| {
+                Ok(Association {
+                    label: label.to_string(),
+                    rule: Rule::parse(pattern, command)?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// An entry of the actions menu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileAction {
+    /// What `Enter` does.
+    Open,
+    Extract,
+    Run,
+    Custom(Association),
+    OpenDefault,
+}
+
+impl FileAction {
+    pub fn label(&self) -> String {
+        match self {
+            FileAction::Open => "Open".to_string(),
+            FileAction::Extract => "Extract…".to_string(),
+            FileAction::Run => "Run".to_string(),
+            FileAction::Custom(association) => association.label.clone(),
+            FileAction::OpenDefault => "Open with default application".to_string(),
+        }
+    }
+}
+
+/// What the menu offers for `path`, given the configured associations.
+pub fn for_path(path: &Path, associations: &[Association]) -> Vec<FileAction> {
+    let mut actions = vec![FileAction::Open];
+    // An archive itself counts as in one, so look at where it is
+    let on_disk = path.parent().is_none_or(|dir| !vfs::in_archive(dir));
+    if on_disk && path.is_file() && Format::detect(path).is_some() {
+        actions.push(FileAction::Extract);
+    }
+    if on_disk && kinds::is_executable(path) {
+        actions.push(FileAction::Run);
+    }
+    // The config's own come first, so they win over a default of the
+    // same label
+    let defaults = defaults();
+    let configured = associations
+        .iter()
+        .chain(defaults.iter())
+        .filter(|association| association.rule.matches(path));
+    for association in configured {
+        let action = FileAction::Custom(association.clone());
+        if !actions.iter().any(|a| a.label() == action.label()) {
+            actions.push(action);
+        }
+    }
+    actions.push(FileAction::OpenDefault);
+    actions
+}
+
+fn defaults() -> Vec<Association> {
+    DEFAULTS
+        .iter()
+        .filter(|(_, _, command)| editor::is_available(command))
+        .flat_map(|(patterns, label, command)| {
+            Association::parse(&format!("{}: {}", patterns, label), command).unwrap_or_default()
+        })
+        .collect()
+}
@@ -0,0 +1,121 @@
+//! Colors of entries by type: directories, executables, archives, images
+//! and media in the theme's colors, or as `$LS_COLORS` has them for
+//! parity with `ls`.
+
+use std::{collections::HashMap, path::Path};
+
+use ratatui::style::{Modifier, Style};
+
+use crate::{
+    ansi, archive,
+    entry::{Entry, Special},
+    media,
+    preview::IMAGE_EXTENSIONS,
+    theme::Theme,
+};
+
+/// The style of `entry` by its type, `None` for plain files.
+pub fn style(entry: &Entry, theme: &Theme) -> Option<Style> {
+    if entry.is_dir {
+        return Some(
+            Style::new()
+                .fg(theme.directory)
+                .add_modifier(Modifier::BOLD),
+        );
+    }
+    if entry.is_executable() {
+        return Some(
+            Style::new()
+                .fg(theme.executable)
+                .add_modifier(Modifier::BOLD),
+        );
+    }
+    if archive::Format::detect(Path::new(&entry.name)).is_some() {
+        return Some(Style::new().fg(theme.archive));
+    }
+    let extension = Path::new(&entry.name)
+        .extension()?
+        .to_string_lossy()
+        .to_lowercase();
+    if IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+        Some(Style::new().fg(theme.image))
+    } else if media::EXTENSIONS.contains(&extension.as_str()) {
+        Some(Style::new().fg(theme.media))
+    } else {
+        None
+    }
+}
+
+/// Styles read from a `$LS_COLORS` value like `di=01;34:*.tar=01;31`.
+#[derive(Debug, Clone, Default)]
+pub struct LsColors {
+    /// By two-letter type, like `di` or `ex`.
+    types: HashMap<String, Style>,
+    /// By the end of the name, lowercased, like `.tar`.
+    suffixes: Vec<(String, Style)>,
+    /// `ln=target`: links are colored as what they point to.
+    link_as_target: bool,
+}
+
+impl LsColors {
+    /// Reads `$LS_COLORS`, empty when it isn't set.
+    pub fn from_env() -> Self {
+        std::env::var("LS_COLORS")
+            .map(|spec| Self::parse(&spec))
+            .unwrap_or_default()
+    }
+
+    pub fn parse(spec: &str) -> Self {
+        let mut colors = Self::default();
+        for (key, codes) in spec.split(':').filter_map(|part| part.split_once('=')) {
+            if key == "ln" && codes == "target" {
+                colors.link_as_target = true;
+            } else if let Some(suffix) = key.strip_prefix('*') {
+                colors
+                    .suffixes
+                    .push((suffix.to_lowercase(), ansi::style(codes)));
+            } else {
+                colors.types.insert(key.to_string(), ansi::style(codes));
+            }
+        }
+        colors
+    }
+
+    /// The style `ls` would give `entry`, `None` when nothing in the value
+    /// applies to it.
+    pub fn style(&self, entry: &Entry) -> Option<Style> {
+        let get = |key: &str| self.types.get(key).copied();
+        if let Some(link) = &entry.link {
+            if link.broken {
+                return get("or").or_else(|| get("ln"));
+            }
+            if !self.link_as_target {
+                return get("ln");
+            }
+        }
+        let key = match entry.special() {
+            Some(Special::Fifo) => "pi",
+            Some(Special::Socket) => "so",
+            Some(Special::BlockDevice) => "bd",
+            Some(Special::CharDevice) => "cd",
+            None if entry.is_dir => "di",
+            None if entry.is_executable() => "ex",
+            None => "fi",
+        };
+        if key != "fi" {
+            return get(key);
+        }
+        // The longest suffix is the most specific. Links go by the name of
+        // their target, as with ls.
+        let name = match &entry.link {
+            Some(link) => link.target.to_string_lossy().to_lowercase(),
+            None => entry.name.to_lowercase(),
+        };
+        self.suffixes
+            .iter()
+            .filter(|(suffix, _)| name.ends_with(suffix.as_str()))
+            .max_by_key(|(suffix, _)| suffix.len())
+            .map(|(_, style)| *style)
+            .or_else(|| get("fi"))
+    }
+}
@@ -0,0 +1,107 @@
+//! Name patterns for the `f` filter. A pattern with `*`, `?` or `[...]` is
+//! a glob that has to match the whole name; anything else matches names
+//! containing it. Both ignore case unless the pattern has capitals.
+
+/// Whether `name` matches `pattern`.
+pub fn matches(pattern: &str, name: &str) -> bool {
+    let name = if pattern.chars().any(char::is_uppercase) {
+        name.to_string()
+    } else {
+        name.to_lowercase()
+    };
+    if is_glob(pattern) {
+        let pattern: Vec<char> = pattern.chars().collect();
+        let name: Vec<char> = name.chars().collect();
+        glob(&pattern, &name)
+    } else {
+        name.contains(pattern)
+    }
+}
+
+/// Whether `name` matches the glob `pattern` as a whole, case and all, as
+/// in ignore files.
+pub fn glob_matches(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    glob(&pattern, &name)
+}
+
+fn is_glob(pattern: &str) -> bool {
+    pattern.contains(['*', '?', '['])
+}
+
+fn glob(pattern: &[char], name: &[char]) -> bool {
+    let (mut p, mut n) = (0, 0);
+    // Where the last `*` was, and how much of the name it has taken
+    let mut star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                star = Some((p, n));
+                p += 1;
+                continue;
+            }
+            Some('?') => {
+                p += 1;
+                n += 1;
+                continue;
+            }
+            Some('[') => match class(&pattern[p..], name[n]) {
+                Some((true, len)) => {
+                    p += len;
+                    n += 1;
+                    continue;
+                }
+                // An unclosed `[` is just a character
+                None if name[n] == '[' => {
+                    p += 1;
+                    n += 1;
+                    continue;
+                }
+                _ => {}
+            },
+            Some(&c) if c == name[n] => {
+                p += 1;
+                n += 1;
+                continue;
+            }
+            _ => {}
+        }
+        // Let the last `*` take one more character and try again
+        let Some((star_p, star_n)) = star else {
+            return false;
+        };
+        star = Some((star_p, star_n + 1));
+        p = star_p + 1;
+        n = star_n + 1;
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Matches `c` against the class at the start of `pattern`, like `[a-z]` or
+/// `[!0-9]`, returning whether it matched and the class's length, or `None`
+/// if the class isn't closed.
+fn class(pattern: &[char], c: char) -> Option<(bool, usize)> {
+    let mut i = 1;
+    let negated = matches!(pattern.get(i), Some('!' | '^'));
+    if negated {
+        i += 1;
+    }
+    let mut matched = false;
+    let mut first = true;
+    loop {
+        let &start = pattern.get(i)?;
+        if start == ']' && !first {
+            return Some((matched != negated, i + 1));
+        }
+        first = false;
+        if pattern.get(i + 1) == Some(&'-') && pattern.get(i + 2).is_some_and(|&e| e != ']') {
+            let end = pattern[i + 2];
+            matched |= start <= c && c <= end;
+            i += 3;
+        } else {
+            matched |= start == c;
+            i += 1;
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//! Git status of the repository around a directory, from
+//! `git status --porcelain`, for marking entries and naming the branch.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::mpsc,
+    thread,
+    time::Duration,
+};
+
+/// How long `git status` may take, in a big repository, before the listing
+/// goes on without it.
+const STATUS_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How an entry differs from what is committed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Mark {
+    Ignored,
+    Untracked,
+    /// Changed, with every change staged.
+    Staged,
+    /// Changed in the working tree.
+    Modified,
+}
+
+impl Mark {
+    pub fn symbol(self) -> &'static str {
+        match self {
+            Mark::Ignored => "!",
+            Mark::Untracked => "?",
+            Mark::Staged => "+",
+            Mark::Modified => "M",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Status {
+    /// The working tree's top directory.
+    pub root: PathBuf,
+    /// `None` with a detached HEAD.
+    pub branch: Option<String>,
+    /// Paths relative to the root; directories that are untracked or
+    /// ignored as a whole are listed once, not by their files.
+    marks: HashMap<PathBuf, Mark>,
+}
+
+impl Status {
+    /// The status of the repository `dir` is in, `None` outside of one or
+    /// if git isn't installed or takes too long.
+    pub fn of(dir: &Path) -> Option<Self> {
+        let root = dir.ancestors().find(|d| d.join(".git").exists())?;
+        let mut command = Command::new("git");
+        command
+            .arg("-C")
+            .arg(root)
+            .args(["status", "--porcelain=v1", "-z", "--branch", "--ignored"])
+            .stdin(Stdio::null())
+            .stderr(Stdio::null());
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            let _ = sender.send(command.output());
+        });
+        let output = receiver.recv_timeout(STATUS_TIMEOUT).ok()?.ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        Some(parse(root, &String::from_utf8_lossy(&output.stdout)))
+    }
+
+    /// The mark for `path`: its own, that of an untracked or ignored
+    /// directory it is in, or for a directory the most pressing one of
+    /// what changed inside.
+    pub fn mark(&self, path: &Path) -> Option<Mark> {
+        let relative = path.strip_prefix(&self.root).ok()?;
+        if relative.as_os_str().is_empty() {
+            return None;
+        }
+        if let Some(mark) = relative.ancestors().find_map(|p| self.marks.get(p)) {
+            return Some(*mark);
+        }
+        self.marks
+            .iter()
+            .filter(|(changed, mark)| **mark != Mark::Ignored && changed.starts_with(relative))
+            .map(|(_, mark)| *mark)
+            .max()
+    }
+}
+
+/// Reads the NUL-separated output of `git status --porcelain -z --branch`.
+pub fn parse(root: &Path, output: &str) -> Status {
+    let mut status = Status {
+        root: root.to_path_buf(),
+        branch: None,
+        marks: HashMap::new(),
+    };
+    let mut records = output.split('\0');
+    while let Some(record) = records.next() {
+        if let Some(branch) = record.strip_prefix("## ") {
+            status.branch = parse_branch(branch);
+            continue;
+        }
+        let (Some(code), Some(path)) = (record.get(..2), record.get(3..)) else {
+            continue;
+        };
+        let mut code = code.chars();
+        let (index, tree) = (code.next().unwrap_or(' '), code.next().unwrap_or(' '));
+        // Renames and copies are followed by where they came from
+        if matches!(index, 'R' | 'C') {
+            records.next();
+        }
+        let mark = match (index, tree) {
+            ('?', '?') => Mark::Untracked,
+            ('!', '!') => Mark::Ignored,
+            (_, ' ') => Mark::Staged,
+            _ => Mark::Modified,
+        };
+        let path = PathBuf::from(path.trim_end_matches('/'));
+        status.marks.insert(path, mark);
+    }
+    status
+}
+
+/// The branch from a line like `main...origin/main [ahead 1]`.
+fn parse_branch(line: &str) -> Option<String> {
+    let line = line.strip_prefix("No commits yet on ").unwrap_or(line);
+    let branch = line.split("...").next()?.split(' ').next()?;
+    (branch != "HEAD" && !branch.is_empty()).then(|| branch.to_string())
+}
@@ -0,0 +1,227 @@
+//! Drawing images in the terminal, with a graphics protocol when the
+//! terminal speaks one and unicode half blocks otherwise.
+
+use std::fmt::Write;
+
+use ratatui::{buffer::Buffer, layout::Rect, style::Color};
+
+use crate::image::Image;
+
+/// Cell size assumed when the terminal doesn't report its pixel size.
+const DEFAULT_CELL: (u16, u16) = (8, 16);
+/// Base64 bytes per kitty graphics escape.
+const KITTY_CHUNK: usize = 4096;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+    Kitty,
+    Iterm2,
+    Sixel,
+    HalfBlocks,
+}
+
+impl Protocol {
+    pub fn parse(name: &str) -> Option<Protocol> {
+        match name {
+            "kitty" => Some(Protocol::Kitty),
+            "iterm2" => Some(Protocol::Iterm2),
+            "sixel" => Some(Protocol::Sixel),
+            "halfblocks" => Some(Protocol::HalfBlocks),
+            _ => None,
+        }
+    }
+
+    /// Uses the configured protocol, or guesses from the environment.
+    /// Multiplexers don't pass graphics through, so they get half blocks.
+    pub fn detect(configured: Option<&str>) -> Protocol {
+        if let Some(protocol) = configured.and_then(Protocol::parse) {
+            return protocol;
+        }
+        let var = |name: &str| std::env::var(name).unwrap_or_default();
+        let (term, program) = (var("TERM"), var("TERM_PROGRAM"));
+        if !var("TMUX").is_empty() || term.starts_with("screen") {
+            Protocol::HalfBlocks
+        } else if !var("KITTY_WINDOW_ID").is_empty()
+            || term == "xterm-kitty"
+            || term == "xterm-ghostty"
+        {
+            Protocol::Kitty
+        } else if program == "iTerm.app" || program == "WezTerm" || var("LC_TERMINAL") == "iTerm2" {
+            Protocol::Iterm2
+        } else if term.contains("sixel") || term == "foot" || term.starts_with("mlterm") {
+            Protocol::Sixel
+        } else {
+            Protocol::HalfBlocks
+        }
+    }
+}
+
+/// Draws the image with `▀` cells, two pixels per cell, into `area`.
+pub fn render_half_blocks(image: &Image, area: Rect, buf: &mut Buffer) {
+    let image = image.fit(area.width as usize, area.height as usize * 2);
+    let color = |x: usize, y: usize| {
+        if y >= image.height {
+            return Color::Reset;
+        }
+        match image.get(x, y) {
+            [_, _, _, alpha] if alpha < 128 => Color::Reset,
+            [r, g, b, _] => Color::Rgb(r, g, b),
+        }
+    };
+    for row in 0..image.height.div_ceil(2) {
+        for x in 0..image.width {
+            if let Some(cell) = buf.cell_mut((area.x + x as u16, area.y + row as u16)) {
+                cell.set_symbol("▀")
+                    .set_fg(color(x, row * 2))
+                    .set_bg(color(x, row * 2 + 1));
+            }
+        }
+    }
+}
+
+/// Escape sequence drawing the image at `area` with a graphics protocol.
+pub fn encode(protocol: Protocol, image: &Image, area: Rect) -> String {
+    let (cell_width, cell_height) = cell_size();
+    let image = image.fit(
+        area.width as usize * cell_width as usize,
+        area.height as usize * cell_height as usize,
+    );
+    let columns = image.width.div_ceil(cell_width as usize);
+    let rows = image.height.div_ceil(cell_height as usize);
+
+    let mut out = format!("\x1b7\x1b[{};{}H", area.y + 1, area.x + 1);
+    match protocol {
+        Protocol::Kitty => {
+            let rgba: Vec<u8> = image.pixels().iter().flatten().copied().collect();
+            let data = base64(&rgba);
+            let chunks: Vec<&[u8]> = data.as_bytes().chunks(KITTY_CHUNK).collect();
+            for (i, chunk) in chunks.iter().enumerate() {
+                let more = u8::from(i + 1 < chunks.len());
+                if i == 0 {
+                    let _ = write!(
+                        out,
+                        "\x1b_Ga=T,f=32,s={},v={},c={},r={},C=1,q=2,m={};",
+                        image.width, image.height, columns, rows, more
+                    );
+                } else {
+                    let _ = write!(out, "\x1b_Gm={};", more);
+                }
+                out.push_str(std::str::from_utf8(chunk).unwrap_or_default());
+                out.push_str("\x1b\\");
+            }
+        }
+        Protocol::Iterm2 => {
+            let png = image.to_png();
+            let _ = write!(
+                out,
+                "\x1b]1337;File=inline=1;size={};width={};height={};preserveAspectRatio=1:{}\x07",
+                png.len(),
+                columns,
+                rows,
+                base64(&png)
+            );
+        }
+        Protocol::Sixel => out.push_str(&sixel(&image)),
+        Protocol::HalfBlocks => {}
+    }
+    out.push_str("\x1b8");
+    out
+}
+
+/// Escape sequence removing every image we drew, if the protocol has one.
+/// Without it the screen has to be repainted.
+pub fn clear(protocol: Protocol) -> Option<&'static str> {
+    (protocol == Protocol::Kitty).then_some("\x1b_Ga=d,q=2\x1b\\")
+}
+
+fn cell_size() -> (u16, u16) {
+    match crossterm::terminal::window_size() {
+        Ok(size) if size.width > 0 && size.columns > 0 && size.rows > 0 => (
+            (size.width / size.columns).max(1),
+            (size.height / size.rows).max(1),
+        ),
+        _ => DEFAULT_CELL,
+    }
+}
+
+/// Sixel data using a fixed 6×6×6 color cube; translucent pixels are left
+/// unpainted.
+fn sixel(image: &Image) -> String {
+    let level = |channel: u8| (u32::from(channel) * 5 + 127) / 255;
+    let index = |[r, g, b, a]: [u8; 4]| (a >= 128).then(|| level(r) * 36 + level(g) * 6 + level(b));
+
+    let mut out = format!("\x1bP0;1;0q\"1;1;{};{}", image.width, image.height);
+    for i in 0..216 {
+        let _ = write!(
+            out,
+            "#{};2;{};{};{}",
+            i,
+            i / 36 * 20,
+            i / 6 % 6 * 20,
+            i % 6 * 20
+        );
+    }
+    for band in (0..image.height).step_by(6) {
+        let rows = (image.height - band).min(6);
+        let mut used = [false; 216];
+        for y in band..band + rows {
+            for x in 0..image.width {
+                if let Some(i) = index(image.get(x, y)) {
+                    used[i as usize] = true;
+                }
+            }
+        }
+        for color in (0..216).filter(|&c| used[c as usize]) {
+            let _ = write!(out, "#{}", color);
+            let mut run: Option<(u8, usize)> = None;
+            for x in 0..image.width {
+                let bits = (0..rows)
+                    .filter(|&dy| index(image.get(x, band + dy)) == Some(color))
+                    .fold(0u8, |bits, dy| bits | 1 << dy);
+                let symbol = 63 + bits;
+                run = match run {
+                    Some((previous, count)) if previous == symbol => Some((symbol, count + 1)),
+                    Some(previous) => {
+                        push_run(&mut out, previous);
+                        Some((symbol, 1))
+                    }
+                    None => Some((symbol, 1)),
+                };
+            }
+            if let Some(run) = run {
+                push_run(&mut out, run);
+            }
+            out.push('$');
+        }
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+    out
+}
+
+fn push_run(out: &mut String, (symbol, count): (u8, usize)) {
+    if count > 3 {
+        let _ = write!(out, "!{}{}", count, symbol as char);
+    } else {
+        out.extend(std::iter::repeat_n(symbol as char, count));
+    }
+}
+
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | u32::from(b) << (16 - 8 * i));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
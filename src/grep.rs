@@ -0,0 +1,456 @@
+//! Content search: the files under a directory grepped for a string on
+//! background threads, with matching lines streamed in as they are found.
+
+use std::{
+    collections::{BTreeSet, HashSet},
+    fs::File,
+    io::{BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender, TryRecvError},
+    },
+    thread,
+};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style, Stylize},
+    symbols::border,
+    text::{Line, Span, Text},
+    widgets::{Block, Paragraph, Widget},
+};
+
+use crate::{
+    theme,
+    walk::{Policy, Walk},
+};
+
+/// Matches kept; the search stops once it has found this many.
+const MAX_MATCHES: usize = 1000;
+/// Files bigger than this are skipped, they are rarely worth grepping.
+const MAX_FILE_SIZE: u64 = 16 * 1024 * 1024;
+/// Bytes looked at for a NUL to tell binary files from text.
+const SNIFF_SIZE: usize = 8192;
+/// Threads reading files at most.
+const MAX_WORKERS: usize = 4;
+/// Characters of a matching line kept for showing.
+const SNIPPET_LENGTH: usize = 240;
+
+/// A matching line; `path` is relative to the search root.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrepMatch {
+    pub path: PathBuf,
+    /// Counted from 1, as editors do.
+    pub line: usize,
+    pub text: String,
+}
+
+/// A search that is under way, cancelled when dropped.
+#[derive(Debug)]
+struct Scan {
+    receiver: Receiver<GrepMatch>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl Drop for Scan {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Grep of a tree: a query being typed, then the lines matching it as the
+/// workers find them.
+#[derive(Debug)]
+pub struct Grep {
+    pub root: PathBuf,
+    pub query: String,
+    pub matches: Vec<GrepMatch>,
+    pub selected: usize,
+    show_hidden: bool,
+    policy: Policy,
+    /// Files marked for a batch operation, kept while the query changes.
+    marked: BTreeSet<PathBuf>,
+    /// The query the matches are for, `None` before the first search.
+    searched: Option<String>,
+    scan: Option<Scan>,
+}
+
+impl Grep {
+    pub fn new(root: PathBuf, show_hidden: bool, policy: Policy) -> Self {
+        Self {
+            root,
+            query: String::new(),
+            matches: vec![],
+            selected: 0,
+            show_hidden,
+            policy,
+            marked: BTreeSet::new(),
+            searched: None,
+            scan: None,
+        }
+    }
+
+    /// Whether the matches are still coming in.
+    pub fn is_running(&self) -> bool {
+        self.scan.is_some()
+    }
+
+    /// Whether the matches shown are for the query as typed.
+    pub fn is_current(&self) -> bool {
+        self.searched.as_deref() == Some(self.query.as_str())
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.query.push(c);
+        self.scan = None;
+    }
+
+    pub fn pop(&mut self) {
+        self.query.pop();
+        self.scan = None;
+    }
+
+    pub fn selected_match(&self) -> Option<&GrepMatch> {
+        self.matches.get(self.selected)
+    }
+
+    /// Marks or unmarks the selected match's file and moves on to the next
+    /// match.
+    pub fn toggle_mark(&mut self) {
+        let Some(path) = self.selected_match().map(|m| m.path.clone()) else {
+            return;
+        };
+        if !self.marked.remove(&path) {
+            self.marked.insert(path);
+        }
+        if self.selected + 1 < self.matches.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// Marks every file with a match, or unmarks them all if they already
+    /// are.
+    pub fn toggle_mark_all(&mut self) {
+        if self.matches.iter().all(|m| self.marked.contains(&m.path)) {
+            for m in &self.matches {
+                self.marked.remove(&m.path);
+            }
+        } else {
+            self.marked
+                .extend(self.matches.iter().map(|m| m.path.clone()));
+        }
+    }
+
+    /// Full paths of the marked files, or of the selected match's file if
+    /// none are.
+    pub fn targets(&self) -> Vec<PathBuf> {
+        if self.marked.is_empty() {
+            self.selected_match()
+                .map(|m| vec![self.root.join(&m.path)])
+                .unwrap_or_default()
+        } else {
+            self.marked
+                .iter()
+                .map(|path| self.root.join(path))
+                .collect()
+        }
+    }
+
+    /// Searches for the query, dropping the matches of the one before.
+    pub fn start(&mut self) {
+        self.matches.clear();
+        self.selected = 0;
+        self.searched = Some(self.query.clone());
+        self.scan = None;
+        if self.query.is_empty() {
+            return;
+        }
+        let (sender, receiver) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+        spawn(
+            self.root.clone(),
+            Matcher::new(&self.query),
+            self.policy.walk(&self.root, self.show_hidden),
+            sender,
+            Arc::clone(&cancel),
+        );
+        self.scan = Some(Scan { receiver, cancel });
+    }
+
+    /// Pulls in what the workers found; returns whether anything arrived.
+    pub fn poll(&mut self) -> bool {
+        let Some(scan) = &self.scan else {
+            return false;
+        };
+        let mut changed = false;
+        loop {
+            match scan.receiver.try_recv() {
+                Ok(found) if self.matches.len() < MAX_MATCHES => {
+                    self.matches.push(found);
+                    changed = true;
+                }
+                Ok(_) => {
+                    self.scan = None;
+                    return true;
+                }
+                Err(TryRecvError::Empty) => return changed,
+                Err(TryRecvError::Disconnected) => {
+                    self.scan = None;
+                    return true;
+                }
+            }
+        }
+    }
+}
+
+/// Case-insensitive unless the query has capitals in it.
+#[derive(Debug, Clone)]
+struct Matcher {
+    query: String,
+    ignore_case: bool,
+}
+
+impl Matcher {
+    fn new(query: &str) -> Self {
+        let ignore_case = !query.chars().any(char::is_uppercase);
+        Self {
+            query: if ignore_case {
+                query.to_lowercase()
+            } else {
+                query.to_string()
+            },
+            ignore_case,
+        }
+    }
+
+    fn is_match(&self, line: &str) -> bool {
+        if self.ignore_case {
+            line.to_lowercase().contains(&self.query)
+        } else {
+            line.contains(&self.query)
+        }
+    }
+}
+
+/// Starts a walker that hands files to a few workers, which send back the
+/// lines matching.
+fn spawn(
+    root: PathBuf,
+    matcher: Matcher,
+    walk: Walk,
+    sender: Sender<GrepMatch>,
+    cancel: Arc<AtomicBool>,
+) {
+    let (files, queue) = mpsc::channel::<PathBuf>();
+    let queue = Arc::new(Mutex::new(queue));
+    let workers = thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .clamp(1, MAX_WORKERS);
+    for _ in 0..workers {
+        let queue = Arc::clone(&queue);
+        let (root, matcher, sender, cancel) = (
+            root.clone(),
+            matcher.clone(),
+            sender.clone(),
+            Arc::clone(&cancel),
+        );
+        thread::spawn(move || {
+            loop {
+                let next = queue.lock().ok().and_then(|queue| queue.recv().ok());
+                let Some(path) = next else {
+                    return;
+                };
+                if cancel.load(Ordering::Relaxed)
+                    || !grep_file(&root, &path, &matcher, &sender, &cancel)
+                {
+                    return;
+                }
+            }
+        });
+    }
+
+    // Files by name, so matches come in roughly sorted. Only regular
+    // files, not devices.
+    thread::spawn(move || {
+        for found in walk {
+            if cancel.load(Ordering::Relaxed) {
+                return;
+            }
+            if found.metadata.is_file() && files.send(found.path).is_err() {
+                return;
+            }
+        }
+    });
+}
+
+/// Sends the lines of `path` that match; returns whether to go on.
+fn grep_file(
+    root: &Path,
+    path: &Path,
+    matcher: &Matcher,
+    sender: &Sender<GrepMatch>,
+    cancel: &AtomicBool,
+) -> bool {
+    let Ok(file) = File::open(path) else {
+        return true;
+    };
+    if !file.metadata().is_ok_and(|m| m.len() <= MAX_FILE_SIZE) {
+        return true;
+    }
+    let mut reader = BufReader::new(file);
+    let mut head = Vec::with_capacity(SNIFF_SIZE);
+    if (&mut reader)
+        .take(SNIFF_SIZE as u64)
+        .read_to_end(&mut head)
+        .is_err()
+        || head.contains(&0)
+    {
+        return true;
+    }
+    let relative = path.strip_prefix(root).unwrap_or(path);
+    let mut lines = head.as_slice().chain(reader).split(b'\n');
+    let mut number = 0;
+    while let Some(Ok(line)) = lines.next() {
+        number += 1;
+        let line = String::from_utf8_lossy(&line);
+        if !matcher.is_match(&line) {
+            continue;
+        }
+        if cancel.load(Ordering::Relaxed) {
+            return false;
+        }
+        let found = GrepMatch {
+            path: relative.to_path_buf(),
+            line: number,
+            text: snippet(&line),
+        };
+        if sender.send(found).is_err() {
+            return false;
+        }
+    }
+    true
+}
+
+/// The line without its indentation or line ending, cut short.
+fn snippet(line: &str) -> String {
+    line.trim_start()
+        .trim_end_matches('\r')
+        .chars()
+        .map(|c| if c == '\t' { ' ' } else { c })
+        .take(SNIPPET_LENGTH)
+        .collect()
+}
+
+/// `text` with every occurrence of the query picked out.
+fn highlighted(text: &str, matcher: &Matcher) -> Vec<Span<'static>> {
+    let theme = theme::current();
+    let haystack = if matcher.ignore_case {
+        text.to_lowercase()
+    } else {
+        text.to_string()
+    };
+    // Lowercasing can change lengths; don't highlight then
+    if haystack.len() != text.len() || matcher.query.is_empty() {
+        return vec![text.to_string().into()];
+    }
+    let mut spans = vec![];
+    let mut rest = 0;
+    for (start, found) in haystack.match_indices(&matcher.query) {
+        spans.push(text[rest..start].to_string().into());
+        spans.push(
+            text[start..start + found.len()]
+                .to_string()
+                .fg(theme.highlight)
+                .bold(),
+        );
+        rest = start + found.len();
+    }
+    spans.push(text[rest..].to_string().into());
+    spans
+}
+
+impl Widget for &Grep {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let theme = theme::current();
+        let max_visible = area.height.saturating_sub(2) as usize;
+        let start = (self.selected + 1).saturating_sub(max_visible);
+        let matcher = Matcher::new(self.searched.as_deref().unwrap_or_default());
+
+        let lines: Vec<Line> = self
+            .matches
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(max_visible)
+            .map(|(i, found)| {
+                let path = found.path.to_string_lossy().to_string();
+                let path = if self.marked.contains(&found.path) {
+                    format!("* {}", path).fg(theme.marked).bold()
+                } else {
+                    path.fg(theme.path)
+                };
+                let mut spans = vec![
+                    path,
+                    ":".fg(theme.dim),
+                    found.line.to_string().fg(theme.success),
+                    ": ".fg(theme.dim),
+                ];
+                spans.extend(highlighted(&found.text, &matcher));
+                let line = Line::from(spans);
+                if i == self.selected {
+                    line.style(
+                        Style::default()
+                            .bg(theme.selection)
+                            .fg(theme.selection_text)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                } else {
+                    line
+                }
+            })
+            .collect();
+
+        let files = self
+            .matches
+            .iter()
+            .map(|m| &m.path)
+            .collect::<HashSet<_>>()
+            .len();
+        let status = match &self.searched {
+            None => " Enter to search ".to_string(),
+            Some(_) if self.is_running() => format!(
+                " {} matches in {} files, searching… ",
+                self.matches.len(),
+                files
+            ),
+            Some(_) if self.matches.len() >= MAX_MATCHES => {
+                format!(" first {} matches in {} files ", MAX_MATCHES, files)
+            }
+            Some(_) => format!(" {} matches in {} files ", self.matches.len(), files),
+        };
+        let status = if self.searched.is_some() && !self.is_current() {
+            format!(" Enter to search again,{}", status)
+        } else {
+            status
+        };
+        let status = if self.marked.is_empty() {
+            status
+        } else {
+            format!(" {} marked,{}", self.marked.len(), status)
+        };
+
+        Paragraph::new(Text::from(lines))
+            .block(
+                Block::bordered()
+                    .title(format!(" Grep: {}▏", self.query).fg(theme.highlight).bold())
+                    .title_bottom(Line::from(status).right_aligned())
+                    .border_style(theme.border)
+                    .border_set(border::PLAIN),
+            )
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,154 @@
+//! Hex dumps of binary files: a few lines at the top of the preview, and a
+//! full-screen view that reads only the part on screen.
+
+use std::{
+    cell::Cell,
+    fs::File,
+    io::{self, Read, Seek, SeekFrom},
+    path::PathBuf,
+};
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Style, Stylize},
+    symbols::border,
+    text::{Line, Span, Text},
+    widgets::{Block, Clear, Paragraph, Widget},
+};
+
+use crate::theme;
+
+/// Bytes per line in the full-screen view; the preview pane fits half.
+pub const WIDE: usize = 16;
+pub const NARROW: usize = 8;
+
+/// One line of a dump: offset, bytes in hex, and the printable ones.
+pub fn line(offset: u64, bytes: &[u8], width: usize) -> Line<'static> {
+    let theme = theme::current();
+    let mut spans = vec![format!("{:08x}  ", offset).fg(theme.dim)];
+    for i in 0..width {
+        if i == width / 2 {
+            spans.push(Span::raw(" "));
+        }
+        spans.push(match bytes.get(i) {
+            Some(&byte) => Span::styled(format!("{:02x} ", byte), byte_style(byte)),
+            None => Span::raw("   "),
+        });
+    }
+    let ascii: String = bytes
+        .iter()
+        .map(|&b| {
+            if b.is_ascii_graphic() || b == b' ' {
+                b as char
+            } else {
+                '.'
+            }
+        })
+        .collect();
+    spans.push(format!("│{:<1$}│", ascii, width).fg(theme.dim));
+    Line::from(spans)
+}
+
+/// Zero bytes fade out and text stands out from the rest.
+fn byte_style(byte: u8) -> Style {
+    let theme = theme::current();
+    match byte {
+        0 => Style::new().fg(theme.dim),
+        b if b.is_ascii_graphic() || b == b' ' => Style::new().fg(theme.info),
+        _ => Style::new(),
+    }
+}
+
+/// Full-screen hex view of a file.
+#[derive(Debug)]
+pub struct HexView {
+    pub path: PathBuf,
+    len: u64,
+    /// First line on screen.
+    top: u64,
+    /// Lines on screen at the last render, for paging.
+    height: Cell<u64>,
+}
+
+impl HexView {
+    pub fn open(path: PathBuf) -> io::Result<Self> {
+        let len = std::fs::metadata(&path)?.len();
+        Ok(Self {
+            path,
+            len,
+            top: 0,
+            height: Cell::new(20),
+        })
+    }
+
+    /// Handles a key; returns whether it closes the view.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let page = self.height.get().max(1);
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Char('x') => return true,
+            KeyCode::Up | KeyCode::Char('k') => self.top = self.top.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.scroll_to(self.top + 1),
+            KeyCode::PageUp => self.top = self.top.saturating_sub(page),
+            KeyCode::PageDown | KeyCode::Char(' ') => self.scroll_to(self.top + page),
+            KeyCode::Home | KeyCode::Char('g') => self.top = 0,
+            KeyCode::End | KeyCode::Char('G') => self.scroll_to(u64::MAX),
+            _ => {}
+        }
+        false
+    }
+
+    /// Offset of the first byte on screen.
+    pub fn offset(&self) -> u64 {
+        self.top * WIDE as u64
+    }
+
+    fn scroll_to(&mut self, top: u64) {
+        let lines = self.len.div_ceil(WIDE as u64);
+        let last = lines.saturating_sub(self.height.get());
+        self.top = top.min(last);
+    }
+
+    fn read(&self, lines: u64) -> io::Result<Vec<u8>> {
+        let mut file = File::open(&self.path)?;
+        file.seek(SeekFrom::Start(self.offset()))?;
+        let mut bytes = Vec::new();
+        file.take(lines * WIDE as u64).read_to_end(&mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+impl Widget for &HexView {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let theme = theme::current();
+        let name = self.path.file_name().unwrap_or_default().to_string_lossy();
+        let position = match self.len {
+            0 => "empty".to_string(),
+            len => format!("{:x} of {:x}", self.offset().min(len - 1), len),
+        };
+        let block = Block::bordered()
+            .title(format!(" {} ", name).fg(theme.highlight).bold())
+            .title(Line::from(format!(" {} ", position)).right_aligned())
+            .title_bottom(Line::from(" ↑↓ PgUp PgDn g G scroll, x or Esc close ").right_aligned())
+            .border_style(theme.border)
+            .border_set(border::ROUNDED);
+        let inner = block.inner(area);
+        Clear.render(area, buf);
+        block.render(area, buf);
+
+        let height = inner.height as u64;
+        self.height.set(height);
+        let text = match self.read(height) {
+            Ok(bytes) => Text::from(
+                bytes
+                    .chunks(WIDE)
+                    .enumerate()
+                    .map(|(i, chunk)| line(self.offset() + (i * WIDE) as u64, chunk, WIDE))
+                    .collect::<Vec<_>>(),
+            ),
+            Err(e) => Text::from(format!("Unable to read {}: {}", name, e).fg(theme.error)),
+        };
+        Paragraph::new(text).render(inner, buf);
+    }
+}
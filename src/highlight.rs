@@ -0,0 +1,542 @@
+use std::path::Path;
+
+use ratatui::{
+    style::{Modifier, Style},
+    text::{Line, Span},
+};
+
+use crate::theme;
+
+/// Lexical rules for one language.
+#[derive(Debug)]
+pub struct Syntax {
+    pub name: &'static str,
+    extensions: &'static [&'static str],
+    /// Interpreters recognised on a `#!` line.
+    interpreters: &'static [&'static str],
+    keywords: &'static [&'static str],
+    line_comments: &'static [&'static str],
+    block_comment: Option<(&'static str, &'static str)>,
+    quotes: &'static [char],
+    /// Highlight capitalized identifiers as types.
+    types: bool,
+}
+
+const RUST: Syntax = Syntax {
+    name: "Rust",
+    extensions: &["rs"],
+    interpreters: &[],
+    keywords: &[
+        "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum",
+        "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+        "mut", "pub", "ref", "return", "self", "Self", "static", "struct", "super", "trait",
+        "true", "type", "unsafe", "use", "where", "while",
+    ],
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"'],
+    types: true,
+};
+
+const PYTHON: Syntax = Syntax {
+    name: "Python",
+    extensions: &["py", "pyw", "pyi"],
+    interpreters: &["python", "python3", "python2"],
+    keywords: &[
+        "and", "as", "assert", "async", "await", "break", "class", "continue", "def", "del",
+        "elif", "else", "except", "False", "finally", "for", "from", "global", "if", "import",
+        "in", "is", "lambda", "None", "nonlocal", "not", "or", "pass", "raise", "return", "True",
+        "try", "while", "with", "yield", "self",
+    ],
+    line_comments: &["#"],
+    block_comment: None,
+    quotes: &['"', '\''],
+    types: true,
+};
+
+const JAVASCRIPT: Syntax = Syntax {
+    name: "JavaScript",
+    extensions: &["js", "jsx", "mjs", "cjs", "ts", "tsx"],
+    interpreters: &["node", "deno", "bun"],
+    keywords: &[
+        "async",
+        "await",
+        "break",
+        "case",
+        "catch",
+        "class",
+        "const",
+        "continue",
+        "default",
+        "delete",
+        "do",
+        "else",
+        "export",
+        "extends",
+        "false",
+        "finally",
+        "for",
+        "from",
+        "function",
+        "if",
+        "import",
+        "in",
+        "instanceof",
+        "interface",
+        "let",
+        "new",
+        "null",
+        "return",
+        "static",
+        "super",
+        "switch",
+        "this",
+        "throw",
+        "true",
+        "try",
+        "type",
+        "typeof",
+        "undefined",
+        "var",
+        "void",
+        "while",
+        "yield",
+    ],
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"', '\'', '`'],
+    types: true,
+};
+
+const C_LIKE: Syntax = Syntax {
+    name: "C",
+    extensions: &[
+        "c", "h", "cc", "cpp", "cxx", "hpp", "hh", "java", "cs", "go", "kt", "swift", "zig",
+    ],
+    interpreters: &[],
+    keywords: &[
+        "auto",
+        "break",
+        "case",
+        "catch",
+        "class",
+        "const",
+        "continue",
+        "default",
+        "defer",
+        "delete",
+        "do",
+        "else",
+        "enum",
+        "extern",
+        "false",
+        "final",
+        "fn",
+        "for",
+        "func",
+        "fun",
+        "go",
+        "if",
+        "import",
+        "interface",
+        "let",
+        "namespace",
+        "new",
+        "nil",
+        "null",
+        "nullptr",
+        "package",
+        "private",
+        "protected",
+        "public",
+        "return",
+        "sizeof",
+        "static",
+        "struct",
+        "switch",
+        "template",
+        "this",
+        "throw",
+        "true",
+        "try",
+        "typedef",
+        "union",
+        "using",
+        "val",
+        "var",
+        "void",
+        "volatile",
+        "while",
+        "#include",
+        "#define",
+        "#ifdef",
+        "#ifndef",
+        "#endif",
+    ],
+    line_comments: &["//"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['"', '\''],
+    types: true,
+};
+
+const SHELL: Syntax = Syntax {
+    name: "Shell",
+    extensions: &["sh", "bash", "zsh", "fish", "ksh"],
+    interpreters: &["sh", "bash", "zsh", "fish", "ksh", "dash"],
+    keywords: &[
+        "case", "do", "done", "elif", "else", "esac", "export", "fi", "for", "function", "if",
+        "in", "local", "return", "set", "then", "until", "while", "echo", "source", "exit",
+    ],
+    line_comments: &["#"],
+    block_comment: None,
+    quotes: &['"', '\''],
+    types: false,
+};
+
+const CONFIG: Syntax = Syntax {
+    name: "Config",
+    extensions: &[
+        "toml",
+        "ini",
+        "cfg",
+        "conf",
+        "yaml",
+        "yml",
+        "properties",
+        "env",
+    ],
+    interpreters: &[],
+    keywords: &["true", "false", "yes", "no", "on", "off", "null"],
+    line_comments: &["#", ";"],
+    block_comment: None,
+    quotes: &['"', '\''],
+    types: false,
+};
+
+const JSON: Syntax = Syntax {
+    name: "JSON",
+    extensions: &["json", "jsonc", "json5"],
+    interpreters: &[],
+    keywords: &["true", "false", "null"],
+    line_comments: &["//"],
+    block_comment: None,
+    quotes: &['"'],
+    types: false,
+};
+
+const MARKUP: Syntax = Syntax {
+    name: "Markup",
+    extensions: &["html", "htm", "xml", "svg", "vue"],
+    interpreters: &[],
+    keywords: &[],
+    line_comments: &[],
+    block_comment: Some(("<!--", "-->")),
+    quotes: &['"', '\''],
+    types: false,
+};
+
+const LUA: Syntax = Syntax {
+    name: "Lua",
+    extensions: &["lua"],
+    interpreters: &["lua"],
+    keywords: &[
+        "and", "break", "do", "else", "elseif", "end", "false", "for", "function", "if", "in",
+        "local", "nil", "not", "or", "repeat", "return", "then", "true", "until", "while",
+    ],
+    line_comments: &["--"],
+    block_comment: None,
+    quotes: &['"', '\''],
+    types: false,
+};
+
+const SQL: Syntax = Syntax {
+    name: "SQL",
+    extensions: &["sql"],
+    interpreters: &[],
+    keywords: &[
+        "SELECT", "FROM", "WHERE", "INSERT", "INTO", "VALUES", "UPDATE", "SET", "DELETE", "CREATE",
+        "TABLE", "JOIN", "LEFT", "ON", "AND", "OR", "NOT", "NULL", "ORDER", "BY", "GROUP", "LIMIT",
+        "AS", "select", "from", "where", "insert", "into", "values", "update", "set", "delete",
+        "create", "table", "join", "on", "and", "or", "not", "null", "order", "by", "group",
+        "limit", "as",
+    ],
+    line_comments: &["--"],
+    block_comment: Some(("/*", "*/")),
+    quotes: &['\''],
+    types: false,
+};
+
+const RUBY: Syntax = Syntax {
+    name: "Ruby",
+    extensions: &["rb", "rake", "pl", "pm", "r"],
+    interpreters: &["ruby", "perl"],
+    keywords: &[
+        "begin", "class", "def", "do", "else", "elsif", "end", "ensure", "false", "for", "if",
+        "module", "my", "nil", "require", "rescue", "return", "self", "sub", "true", "unless",
+        "use", "while", "yield",
+    ],
+    line_comments: &["#"],
+    block_comment: None,
+    quotes: &['"', '\''],
+    types: true,
+};
+
+const SYNTAXES: [&Syntax; 11] = [
+    &RUST,
+    &PYTHON,
+    &JAVASCRIPT,
+    &C_LIKE,
+    &SHELL,
+    &CONFIG,
+    &JSON,
+    &MARKUP,
+    &LUA,
+    &SQL,
+    &RUBY,
+];
+
+/// Markdown is line oriented and handled separately.
+const MARKDOWN_EXTENSIONS: [&str; 3] = ["md", "markdown", "mdx"];
+
+/// How a file's content should be highlighted.
+#[derive(Debug, Clone, Copy)]
+pub enum Highlighter {
+    Code(&'static Syntax),
+    Markdown,
+}
+
+impl Highlighter {
+    /// Picks a highlighter from the extension, file name or `#!` line.
+    pub fn detect(path: &Path, first_line: &str) -> Option<Self> {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+            .unwrap_or_default();
+        let file_name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        if MARKDOWN_EXTENSIONS.contains(&extension.as_str()) {
+            return Some(Highlighter::Markdown);
+        }
+        if let Some(syntax) = SYNTAXES
+            .iter()
+            .find(|s| s.extensions.contains(&extension.as_str()))
+        {
+            return Some(Highlighter::Code(syntax));
+        }
+        if matches!(
+            file_name.as_str(),
+            "Makefile" | "Dockerfile" | ".bashrc" | ".zshrc" | ".profile"
+        ) {
+            return Some(Highlighter::Code(&SHELL));
+        }
+
+        let interpreter = first_line.strip_prefix("#!").and_then(|shebang| {
+            let mut parts = shebang.split_whitespace();
+            let program = parts.next()?.rsplit('/').next()?;
+            if program == "env" {
+                parts.find(|p| !p.starts_with('-'))
+            } else {
+                Some(program)
+            }
+        })?;
+        SYNTAXES
+            .iter()
+            .find(|s| s.interpreters.contains(&interpreter))
+            .map(|syntax| Highlighter::Code(syntax))
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Highlighter::Code(syntax) => syntax.name,
+            Highlighter::Markdown => "Markdown",
+        }
+    }
+
+    /// Highlights `lines`, which continue a block comment or code fence
+    /// when `open` is set, as left by the previous lines.
+    pub fn highlight<'a>(
+        &self,
+        lines: impl Iterator<Item = &'a str>,
+        open: &mut bool,
+    ) -> Vec<Line<'static>> {
+        match self {
+            Highlighter::Code(syntax) => lines
+                .map(|line| highlight_code(line, syntax, open))
+                .collect(),
+            Highlighter::Markdown => lines.map(|line| highlight_markdown(line, open)).collect(),
+        }
+    }
+}
+
+fn keyword_style() -> Style {
+    Style::default()
+        .fg(theme::current().keyword)
+        .add_modifier(Modifier::BOLD)
+}
+
+fn string_style() -> Style {
+    Style::default().fg(theme::current().string)
+}
+
+fn comment_style() -> Style {
+    Style::default()
+        .fg(theme::current().comment)
+        .add_modifier(Modifier::ITALIC)
+}
+
+fn number_style() -> Style {
+    Style::default().fg(theme::current().number)
+}
+
+fn type_style() -> Style {
+    Style::default().fg(theme::current().type_name)
+}
+
+/// Splits one line into styled spans, tracking block comments across lines.
+fn highlight_code(line: &str, syntax: &Syntax, in_block_comment: &mut bool) -> Line<'static> {
+    let mut spans: Vec<Span<'static>> = vec![];
+    let mut plain = String::new();
+    let flush = |plain: &mut String, spans: &mut Vec<Span<'static>>| {
+        if !plain.is_empty() {
+            spans.push(Span::raw(std::mem::take(plain)));
+        }
+    };
+
+    let mut i = 0;
+    while i < line.len() {
+        let rest = &line[i..];
+
+        if *in_block_comment {
+            let end_token = syntax.block_comment.map_or("", |(_, end)| end);
+            let end = match rest.find(end_token) {
+                Some(pos) => {
+                    *in_block_comment = false;
+                    pos + end_token.len()
+                }
+                None => rest.len(),
+            };
+            spans.push(Span::styled(rest[..end].to_string(), comment_style()));
+            i += end;
+            continue;
+        }
+
+        if syntax.line_comments.iter().any(|c| rest.starts_with(c)) {
+            flush(&mut plain, &mut spans);
+            spans.push(Span::styled(rest.to_string(), comment_style()));
+            break;
+        }
+
+        if let Some((start, _)) = syntax.block_comment
+            && rest.starts_with(start)
+        {
+            flush(&mut plain, &mut spans);
+            *in_block_comment = true;
+            spans.push(Span::styled(start.to_string(), comment_style()));
+            i += start.len();
+            continue;
+        }
+
+        let c = rest.chars().next().unwrap_or_default();
+
+        if syntax.quotes.contains(&c) {
+            flush(&mut plain, &mut spans);
+            let open = c.len_utf8();
+            let mut end = rest.len();
+            let mut escaped = false;
+            for (offset, ch) in rest[open..].char_indices() {
+                if escaped {
+                    escaped = false;
+                } else if ch == '\\' {
+                    escaped = true;
+                } else if ch == c {
+                    end = open + offset + ch.len_utf8();
+                    break;
+                }
+            }
+            spans.push(Span::styled(rest[..end].to_string(), string_style()));
+            i += end;
+            continue;
+        }
+
+        let word_len = rest
+            .char_indices()
+            .find(|(_, ch)| !(ch.is_alphanumeric() || *ch == '_' || *ch == '#'))
+            .map(|(pos, _)| pos)
+            .unwrap_or(rest.len());
+        if word_len > 0 && (c.is_alphanumeric() || c == '_' || c == '#') {
+            let word = &rest[..word_len];
+            let style = if c.is_ascii_digit() {
+                Some(number_style())
+            } else if syntax.keywords.contains(&word) {
+                Some(keyword_style())
+            } else if syntax.types && c.is_uppercase() {
+                Some(type_style())
+            } else {
+                None
+            };
+            match style {
+                Some(style) => {
+                    flush(&mut plain, &mut spans);
+                    spans.push(Span::styled(word.to_string(), style));
+                }
+                None => plain.push_str(word),
+            }
+            i += word_len;
+            continue;
+        }
+
+        plain.push(c);
+        i += c.len_utf8();
+    }
+
+    flush(&mut plain, &mut spans);
+    Line::from(spans)
+}
+
+fn highlight_markdown(line: &str, in_fence: &mut bool) -> Line<'static> {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+        *in_fence = !*in_fence;
+        return Line::styled(line.to_string(), comment_style());
+    }
+    if *in_fence {
+        return Line::styled(line.to_string(), string_style());
+    }
+    if trimmed.starts_with('#') {
+        return Line::styled(
+            line.to_string(),
+            Style::default()
+                .fg(theme::current().heading)
+                .add_modifier(Modifier::BOLD),
+        );
+    }
+    if trimmed.starts_with('>') {
+        return Line::styled(line.to_string(), comment_style());
+    }
+
+    // Inline `code` spans and list markers
+    let mut spans = vec![];
+    let marker_len = ["- ", "* ", "+ "]
+        .iter()
+        .find(|m| trimmed.starts_with(**m))
+        .map(|_| line.len() - trimmed.len() + 2)
+        .unwrap_or(0);
+    if marker_len > 0 {
+        spans.push(Span::styled(
+            line[..marker_len].to_string(),
+            keyword_style(),
+        ));
+    }
+    for (i, part) in line[marker_len..].split('`').enumerate() {
+        if i % 2 == 1 {
+            spans.push(Span::styled(format!("`{}`", part), string_style()));
+        } else if !part.is_empty() {
+            spans.push(Span::raw(part.to_string()));
+        }
+    }
+    Line::from(spans)
+}
@@ -0,0 +1,178 @@
+//! Copies of files that browrs kept before overwriting or deleting them,
+//! for when the filesystem has no snapshots. Each file gets a directory
+//! under `$XDG_CACHE_HOME/browrs/history`, named after a hash of its path,
+//! holding that path in `path` and one copy per save.
+
+use std::{
+    fs::File,
+    io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::{checksum, snapshots::Version};
+
+/// Files larger than this aren't kept.
+const MAX_SIZE: u64 = 16 * 1024 * 1024;
+/// Most files and bytes one save copies, so that deleting a big tree
+/// doesn't copy all of it first.
+const MAX_FILES: usize = 1000;
+const MAX_TOTAL: u64 = 256 * 1024 * 1024;
+
+/// Label of history copies in the versions list.
+const LABEL: &str = "browrs history";
+
+fn root() -> io::Result<PathBuf> {
+    dirs::cache_dir()
+        .map(|dir| dir.join("browrs").join("history"))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no cache directory"))
+}
+
+fn slots_dir(path: &Path) -> io::Result<PathBuf> {
+    let digest = checksum::bytes(path.to_string_lossy().as_bytes());
+    Ok(root()?.join(&checksum::hex(&digest)[..32]))
+}
+
+/// What one save copied.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Saved {
+    pub files: usize,
+    pub bytes: u64,
+    /// Whether it stopped at [`MAX_FILES`] or [`MAX_TOTAL`], leaving the
+    /// rest of the tree without copies.
+    pub partial: bool,
+}
+
+/// Keeps a copy of `path`, or of every file under it for a directory,
+/// leaving at most `keep` copies per file. Does nothing when `keep` is 0.
+pub fn save(path: &Path, keep: usize) -> io::Result<Saved> {
+    let mut saved = Saved::default();
+    if keep > 0 {
+        save_tree(path, keep, &mut saved)?;
+    }
+    Ok(saved)
+}
+
+fn save_tree(path: &Path, keep: usize, saved: &mut Saved) -> io::Result<()> {
+    let metadata = path.symlink_metadata()?;
+    if metadata.is_dir() {
+        for entry in std::fs::read_dir(path)? {
+            save_tree(&entry?.path(), keep, saved)?;
+            if saved.partial {
+                break;
+            }
+        }
+        return Ok(());
+    }
+    if !metadata.is_file() || metadata.len() > MAX_SIZE {
+        return Ok(());
+    }
+    if saved.files >= MAX_FILES || saved.bytes + metadata.len() > MAX_TOTAL {
+        saved.partial = true;
+        return Ok(());
+    }
+    save_file(path, &metadata, keep)?;
+    saved.files += 1;
+    saved.bytes += metadata.len();
+    Ok(())
+}
+
+fn save_file(path: &Path, metadata: &std::fs::Metadata, keep: usize) -> io::Result<()> {
+    let path = std::path::absolute(path)?;
+    let dir = slots_dir(&path)?;
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(dir.join("path"), path.to_string_lossy().as_bytes())?;
+
+    let mut slots = slots(&dir);
+    let modified = metadata.modified().ok();
+    // Saving twice in a row shouldn't push out an older copy
+    let unchanged = slots
+        .last()
+        .and_then(|slot| slot.metadata().ok())
+        .is_some_and(|m| m.len() == metadata.len() && m.modified().ok() == modified);
+    if !unchanged {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        let slot = dir.join(format!("{:012}.{:09}", now.as_secs(), now.subsec_nanos()));
+        std::fs::copy(&path, &slot)?;
+        // Keep the original's time so the copy can be told apart
+        if let Some(modified) = modified {
+            File::options()
+                .write(true)
+                .open(&slot)?
+                .set_modified(modified)?;
+        }
+        slots.push(slot);
+    }
+    for old in &slots[..slots.len().saturating_sub(keep)] {
+        std::fs::remove_file(old)?;
+    }
+    Ok(())
+}
+
+/// Saved copies in a file's history directory, oldest first.
+fn slots(dir: &Path) -> Vec<PathBuf> {
+    let mut slots: Vec<PathBuf> = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.file_name().is_some_and(|name| name != "path"))
+        .collect();
+    slots.sort();
+    slots
+}
+
+fn version(slot: PathBuf) -> Option<Version> {
+    let metadata = std::fs::metadata(&slot).ok()?;
+    Some(Version {
+        snapshot: LABEL.to_string(),
+        modified: metadata.modified().ok(),
+        size: metadata.len(),
+        path: slot,
+    })
+}
+
+/// Kept copies of `path`, newest first.
+pub fn versions(path: &Path) -> Vec<Version> {
+    let Ok(dir) = std::path::absolute(path).and_then(|path| slots_dir(&path)) else {
+        return Vec::new();
+    };
+    slots(&dir).into_iter().rev().filter_map(version).collect()
+}
+
+/// Files under `dir` that no longer exist but have kept copies, each with
+/// its newest copy, most recently deleted first.
+pub fn deleted(dir: &Path) -> Vec<(PathBuf, Version)> {
+    let Ok(root) = root() else {
+        return Vec::new();
+    };
+    let mut deleted: Vec<(PathBuf, PathBuf)> = std::fs::read_dir(root)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let original = PathBuf::from(std::fs::read_to_string(entry.path().join("path")).ok()?);
+            if !original.starts_with(dir) || original.symlink_metadata().is_ok() {
+                return None;
+            }
+            Some((original, slots(&entry.path()).pop()?))
+        })
+        .collect();
+    // Slots are named after the time they were saved
+    deleted.sort_by(|(_, a), (_, b)| b.file_name().cmp(&a.file_name()));
+    deleted
+        .into_iter()
+        .filter_map(|(original, slot)| Some((original, version(slot)?)))
+        .collect()
+}
+
+/// Puts a kept copy back at `dest`, recreating its directory if that was
+/// deleted too.
+pub fn restore(version: &Path, dest: &Path) -> io::Result<()> {
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    crate::snapshots::restore(version, dest)
+}
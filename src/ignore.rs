@@ -0,0 +1,159 @@
+//! Ignore files in gitignore syntax: which entries a repository's
+//! `.gitignore` files leave out, and which `.browrsignore` files hide
+//! wherever they are.
+//!
+//! Each line is a glob, matched against the name anywhere below the file's
+//! directory, or against the path from there when it has a `/` before its
+//! end. A trailing `/` only matches directories, `**` matches any number of
+//! directories, and a leading `!` lets back in what an earlier line left
+//! out. Later lines, and files further down, win.
+
+use std::path::{Component, Path, PathBuf};
+
+use crate::filter;
+
+/// Hides entries below its directory like a hidden file, git or not.
+pub const BROWRS_FILE: &str = ".browrsignore";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Rule {
+    /// Where the file the rule came from is.
+    base: PathBuf,
+    /// The pattern's parts between slashes.
+    parts: Vec<String>,
+    /// Matched against the path from `base` rather than just the name.
+    anchored: bool,
+    dir_only: bool,
+    negated: bool,
+}
+
+impl Rule {
+    fn parse(base: &Path, line: &str) -> Option<Self> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            // Escaped, for names that start with them
+            None => (false, line.strip_prefix('\\').unwrap_or(line)),
+        };
+        let (dir_only, line) = match line.strip_suffix('/') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let anchored = line.contains('/');
+        let parts: Vec<String> = line
+            .split('/')
+            .filter(|part| !part.is_empty())
+            .map(str::to_string)
+            .collect();
+        (!parts.is_empty()).then(|| Self {
+            base: base.to_path_buf(),
+            parts,
+            anchored,
+            dir_only,
+            negated,
+        })
+    }
+
+    fn matches(&self, path: &Path, is_dir: bool) -> bool {
+        if self.dir_only && !is_dir {
+            return false;
+        }
+        let Ok(relative) = path.strip_prefix(&self.base) else {
+            return false;
+        };
+        let names: Vec<String> = relative
+            .components()
+            .filter_map(|part| match part {
+                Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+                _ => None,
+            })
+            .collect();
+        if self.anchored {
+            matches_parts(&self.parts, &names)
+        } else {
+            names
+                .last()
+                .is_some_and(|name| filter::glob_matches(&self.parts[0], name))
+        }
+    }
+}
+
+/// Whether the pattern's parts match the path's names one for one, with
+/// `**` standing for any number of them.
+fn matches_parts(parts: &[String], names: &[String]) -> bool {
+    match parts.split_first() {
+        None => names.is_empty(),
+        Some((part, rest)) if part == "**" => {
+            (0..=names.len()).any(|skip| matches_parts(rest, &names[skip..]))
+        }
+        Some((part, rest)) => names.split_first().is_some_and(|(name, names)| {
+            filter::glob_matches(part, name) && matches_parts(rest, names)
+        }),
+    }
+}
+
+/// The rules that apply in a directory, in the order they are read.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Rules {
+    rules: Vec<Rule>,
+}
+
+impl Rules {
+    /// The rules of the git repository `dir` is in: its `info/exclude` and
+    /// every `.gitignore` from the top of the working tree down to `dir`.
+    /// `None` outside of a repository.
+    pub fn git(dir: &Path) -> Option<Self> {
+        let root = dir.ancestors().find(|d| d.join(".git").exists())?;
+        let mut rules = Self::default();
+        rules.read(root, &root.join(".git/info/exclude"));
+        let below: Vec<&Path> = dir
+            .ancestors()
+            .take_while(|d| d.starts_with(root))
+            .collect();
+        for dir in below.into_iter().rev() {
+            rules.read(dir, &dir.join(".gitignore"));
+        }
+        Some(rules)
+    }
+
+    /// The rules of every `.browrsignore` from the root down to `dir`.
+    pub fn browrs(dir: &Path) -> Self {
+        let mut rules = Self::default();
+        let above: Vec<&Path> = dir.ancestors().collect();
+        for dir in above.into_iter().rev() {
+            rules.read(dir, &dir.join(BROWRS_FILE));
+        }
+        rules
+    }
+
+    /// Rules from lines given some other way, which apply below `base`.
+    pub fn patterns(base: &Path, lines: &[String]) -> Self {
+        Self {
+            rules: lines
+                .iter()
+                .filter_map(|line| Rule::parse(base, line))
+                .collect(),
+        }
+    }
+
+    /// Adds the rules in `file`, which apply below `base`. A missing file
+    /// has none.
+    pub fn read(&mut self, base: &Path, file: &Path) {
+        if let Ok(text) = std::fs::read_to_string(file) {
+            self.rules
+                .extend(text.lines().filter_map(|line| Rule::parse(base, line)));
+        }
+    }
+
+    /// Whether the last rule matching `path` leaves it out.
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| rule.matches(path, is_dir))
+            .is_some_and(|rule| !rule.negated)
+    }
+}
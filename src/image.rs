@@ -0,0 +1,193 @@
+//! Decoding of the common image formats for previews.
+
+mod gif;
+mod inflate;
+mod jpeg;
+mod png;
+
+use std::{io, path::Path};
+
+/// Files larger than this are not decoded.
+const MAX_FILE_SIZE: u64 = 64 * 1024 * 1024;
+/// Images with more pixels than this are refused before allocating.
+const MAX_PIXELS: usize = 64 * 1024 * 1024;
+
+/// An RGBA raster.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    /// Dimensions stored in the file; decoders may produce a smaller raster.
+    pub original: (usize, usize),
+    pixels: Vec<[u8; 4]>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            original: (width, height),
+            pixels: vec![[0, 0, 0, 0]; width * height],
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> [u8; 4] {
+        self.pixels[y * self.width + x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, pixel: [u8; 4]) {
+        self.pixels[y * self.width + x] = pixel;
+    }
+
+    pub fn pixels(&self) -> &[[u8; 4]] {
+        &self.pixels
+    }
+
+    pub fn to_png(&self) -> Vec<u8> {
+        png::encode(self)
+    }
+
+    /// Shrinks the image to fit within the bounds, keeping its aspect
+    /// ratio. Each target pixel averages the source pixels it covers.
+    pub fn fit(&self, max_width: usize, max_height: usize) -> Image {
+        let scale = (max_width as f64 / self.width as f64)
+            .min(max_height as f64 / self.height as f64)
+            .min(1.0);
+        let width = ((self.width as f64 * scale).round() as usize).max(1);
+        let height = ((self.height as f64 * scale).round() as usize).max(1);
+        if (width, height) == (self.width, self.height) {
+            return self.clone();
+        }
+
+        let mut out = Image::new(width, height);
+        out.original = self.original;
+        for y in 0..height {
+            let (y0, y1) = span(y, height, self.height);
+            for x in 0..width {
+                let (x0, x1) = span(x, width, self.width);
+                let mut sum = [0u32; 4];
+                for sy in y0..y1 {
+                    for sx in x0..x1 {
+                        for (total, channel) in sum.iter_mut().zip(self.get(sx, sy)) {
+                            *total += u32::from(channel);
+                        }
+                    }
+                }
+                let count = ((x1 - x0) * (y1 - y0)) as u32;
+                out.set(x, y, sum.map(|total| (total / count) as u8));
+            }
+        }
+        out
+    }
+}
+
+/// Source range covered by target index `i` when scaling `from` to `to`.
+fn span(i: usize, from: usize, to: usize) -> (usize, usize) {
+    let start = i * to / from;
+    let end = ((i + 1) * to / from).max(start + 1).min(to);
+    (start, end)
+}
+
+/// Name of the format if `data` looks like an image we can decode.
+pub fn format(data: &[u8]) -> Option<&'static str> {
+    if png::is_png(data) {
+        Some("PNG")
+    } else if jpeg::is_jpeg(data) {
+        Some("JPEG")
+    } else if gif::is_gif(data) {
+        Some("GIF")
+    } else if data.starts_with(b"BM") {
+        Some("BMP")
+    } else {
+        None
+    }
+}
+
+/// Reads and decodes the image at `path`, returning it with its format.
+pub fn load(path: &Path) -> io::Result<(Image, &'static str)> {
+    if std::fs::metadata(path)?.len() > MAX_FILE_SIZE {
+        return Err(invalid("file too large to decode"));
+    }
+    let data = std::fs::read(path)?;
+    let format = format(&data).ok_or_else(|| invalid("unsupported image format"))?;
+    let image = match format {
+        "PNG" => png::decode(&data)?,
+        "JPEG" => jpeg::decode(&data)?,
+        "GIF" => gif::decode(&data)?,
+        _ => decode_bmp(&data)?,
+    };
+    Ok((image, format))
+}
+
+fn decode_bmp(data: &[u8]) -> io::Result<Image> {
+    let truncated = || invalid("truncated BMP");
+    let u32_at = |pos: usize| -> io::Result<u32> {
+        let bytes = data.get(pos..pos + 4).ok_or_else(truncated)?;
+        Ok(u32::from_le_bytes(bytes.try_into().unwrap()))
+    };
+    let offset = u32_at(10)? as usize;
+    let header_size = u32_at(14)? as usize;
+    let width = u32_at(18)? as i32;
+    let height = u32_at(22)? as i32;
+    let bpp = u16::from_le_bytes(data.get(28..30).ok_or_else(truncated)?.try_into().unwrap());
+    let compression = u32_at(30)?;
+    if width <= 0 || compression != 0 && !(compression == 3 && bpp == 32) {
+        return Err(invalid("unsupported BMP format"));
+    }
+    let (width, bottom_up) = (width as usize, height > 0);
+    let height = height.unsigned_abs() as usize;
+    check_size(width, height)?;
+
+    let palette = if bpp <= 8 {
+        let count = match u32_at(46)? {
+            0 => 1 << bpp,
+            n => n as usize,
+        };
+        let start = 14 + header_size;
+        data.get(start..start + count * 4)
+            .ok_or_else(truncated)?
+            .chunks_exact(4)
+            .map(|c| [c[2], c[1], c[0], 255])
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let stride = (width * bpp as usize).div_ceil(32) * 4;
+    let mut image = Image::new(width, height);
+    for y in 0..height {
+        let row = if bottom_up { height - 1 - y } else { y };
+        let start = offset + row * stride;
+        let line = data.get(start..start + stride).ok_or_else(truncated)?;
+        for x in 0..width {
+            let pixel = match bpp {
+                32 => [line[x * 4 + 2], line[x * 4 + 1], line[x * 4], 255],
+                24 => [line[x * 3 + 2], line[x * 3 + 1], line[x * 3], 255],
+                1 | 4 | 8 => {
+                    let bit = x * bpp as usize;
+                    let index =
+                        (line[bit / 8] >> (8 - bpp as usize - bit % 8)) & ((1 << bpp) - 1) as u8;
+                    palette
+                        .get(index as usize)
+                        .copied()
+                        .unwrap_or([0, 0, 0, 255])
+                }
+                _ => return Err(invalid("unsupported BMP bit depth")),
+            };
+            image.set(x, y, pixel);
+        }
+    }
+    Ok(image)
+}
+
+fn check_size(width: usize, height: usize) -> io::Result<()> {
+    if width == 0 || height == 0 || width.saturating_mul(height) > MAX_PIXELS {
+        return Err(invalid("unsupported image dimensions"));
+    }
+    Ok(())
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
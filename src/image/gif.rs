@@ -0,0 +1,189 @@
+//! GIF decoder for the first frame of a file.
+
+use std::io;
+
+use super::{Image, invalid};
+
+pub fn is_gif(data: &[u8]) -> bool {
+    data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a")
+}
+
+pub fn decode(data: &[u8]) -> io::Result<Image> {
+    let truncated = || invalid("truncated GIF");
+    let header = data.get(..13).ok_or_else(truncated)?;
+    let width = u16::from_le_bytes([header[6], header[7]]) as usize;
+    let height = u16::from_le_bytes([header[8], header[9]]) as usize;
+    super::check_size(width, height)?;
+
+    let mut pos = 13;
+    let mut global_palette = Vec::new();
+    if header[10] & 0x80 != 0 {
+        let size = 3 << ((header[10] & 7) + 1);
+        global_palette = data.get(pos..pos + size).ok_or_else(truncated)?.to_vec();
+        pos += size;
+    }
+
+    let mut transparent = None;
+    loop {
+        match *data.get(pos).ok_or_else(truncated)? {
+            // Extension: only the graphic control block matters, for transparency
+            0x21 => {
+                let label = *data.get(pos + 1).ok_or_else(truncated)?;
+                pos += 2;
+                let (blocks, next) = sub_blocks(data, pos)?;
+                if label == 0xF9 && blocks.len() >= 4 && blocks[0] & 1 != 0 {
+                    transparent = Some(blocks[3]);
+                }
+                pos = next;
+            }
+            0x2C => break,
+            _ => return Err(invalid("GIF has no image")),
+        }
+    }
+
+    let descriptor = data.get(pos..pos + 10).ok_or_else(truncated)?;
+    let left = u16::from_le_bytes([descriptor[1], descriptor[2]]) as usize;
+    let top = u16::from_le_bytes([descriptor[3], descriptor[4]]) as usize;
+    let frame_width = u16::from_le_bytes([descriptor[5], descriptor[6]]) as usize;
+    let frame_height = u16::from_le_bytes([descriptor[7], descriptor[8]]) as usize;
+    let flags = descriptor[9];
+    pos += 10;
+
+    let mut image = Image::new(width, height);
+    if frame_width == 0 || frame_height == 0 {
+        return Ok(image);
+    }
+
+    let palette = if flags & 0x80 != 0 {
+        let size = 3 << ((flags & 7) + 1);
+        let local = data.get(pos..pos + size).ok_or_else(truncated)?;
+        pos += size;
+        local
+    } else {
+        &global_palette[..]
+    };
+
+    let min_code_size = *data.get(pos).ok_or_else(truncated)?;
+    let (compressed, _) = sub_blocks(data, pos + 1)?;
+    let indices = lzw_decode(&compressed, min_code_size, frame_width * frame_height)?;
+
+    let rows: Vec<usize> = if flags & 0x40 != 0 {
+        // Interlaced rows come in four passes
+        [(0, 8), (4, 8), (2, 4), (1, 2)]
+            .iter()
+            .flat_map(|&(start, step)| (start..frame_height).step_by(step))
+            .collect()
+    } else {
+        (0..frame_height).collect()
+    };
+
+    for (i, &index) in indices.iter().enumerate() {
+        let (x, y) = (left + i % frame_width, top + rows[i / frame_width]);
+        if x >= width || y >= height || Some(index) == transparent {
+            continue;
+        }
+        let color = palette.get(index as usize * 3..index as usize * 3 + 3);
+        if let Some(color) = color {
+            image.set(x, y, [color[0], color[1], color[2], 255]);
+        }
+    }
+    Ok(image)
+}
+
+/// Concatenates length-prefixed data sub-blocks, returning them and the
+/// position after the terminator.
+fn sub_blocks(data: &[u8], mut pos: usize) -> io::Result<(Vec<u8>, usize)> {
+    let mut out = Vec::new();
+    loop {
+        let len = *data.get(pos).ok_or_else(|| invalid("truncated GIF"))? as usize;
+        pos += 1;
+        if len == 0 {
+            return Ok((out, pos));
+        }
+        out.extend_from_slice(
+            data.get(pos..pos + len)
+                .ok_or_else(|| invalid("truncated GIF"))?,
+        );
+        pos += len;
+    }
+}
+
+fn lzw_decode(data: &[u8], min_code_size: u8, max_len: usize) -> io::Result<Vec<u8>> {
+    if !(1..=11).contains(&min_code_size) {
+        return Err(invalid("bad GIF code size"));
+    }
+    let clear = 1usize << min_code_size;
+    let end = clear + 1;
+    // Each code is a prefix code plus a final byte
+    let mut prefixes: Vec<(usize, u8)> = (0..clear).map(|i| (usize::MAX, i as u8)).collect();
+    prefixes.extend([(usize::MAX, 0), (usize::MAX, 0)]);
+    let mut code_size = min_code_size as u32 + 1;
+    let mut previous: Option<usize> = None;
+
+    let mut out = Vec::with_capacity(max_len);
+    let mut scratch = Vec::new();
+    let (mut acc, mut bits, mut pos) = (0u32, 0u32, 0);
+    while out.len() < max_len {
+        while bits < code_size {
+            let Some(&byte) = data.get(pos) else {
+                return Ok(out);
+            };
+            acc |= u32::from(byte) << bits;
+            bits += 8;
+            pos += 1;
+        }
+        let code = (acc & ((1 << code_size) - 1)) as usize;
+        acc >>= code_size;
+        bits -= code_size;
+
+        if code == clear {
+            prefixes.truncate(end + 1);
+            code_size = min_code_size as u32 + 1;
+            previous = None;
+            continue;
+        }
+        if code == end {
+            break;
+        }
+
+        let known = code < prefixes.len();
+        let Some(prev) = previous else {
+            if !known {
+                return Err(invalid("bad GIF code"));
+            }
+            expand(&prefixes, code, &mut scratch);
+            out.extend_from_slice(&scratch);
+            previous = Some(code);
+            continue;
+        };
+        if known {
+            expand(&prefixes, code, &mut scratch);
+        } else if code == prefixes.len() {
+            expand(&prefixes, prev, &mut scratch);
+            scratch.push(scratch[0]);
+        } else {
+            return Err(invalid("bad GIF code"));
+        }
+        out.extend_from_slice(&scratch);
+        if prefixes.len() < 4096 {
+            prefixes.push((prev, scratch[0]));
+            if prefixes.len() == 1 << code_size && code_size < 12 {
+                code_size += 1;
+            }
+        }
+        previous = Some(code);
+    }
+    out.truncate(max_len);
+    Ok(out)
+}
+
+/// Writes the bytes a code stands for into `out`.
+fn expand(prefixes: &[(usize, u8)], mut code: usize, out: &mut Vec<u8>) {
+    out.clear();
+    while code != usize::MAX {
+        let (prefix, byte) = prefixes[code];
+        out.push(byte);
+        code = prefix;
+    }
+    out.reverse();
+}
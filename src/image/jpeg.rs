@@ -0,0 +1,445 @@
+//! Baseline JPEG decoder. Large images are decoded from the DC coefficients
+//! only, giving an eighth-size thumbnail without running the IDCT.
+
+use std::{f32::consts::PI, io};
+
+use super::{Image, invalid};
+
+/// Natural block index of each zigzag position.
+const ZIGZAG: [usize; 64] = [
+    0, 1, 8, 16, 9, 2, 3, 10, 17, 24, 32, 25, 18, 11, 4, 5, 12, 19, 26, 33, 40, 48, 41, 34, 27, 20,
+    13, 6, 7, 14, 21, 28, 35, 42, 49, 56, 57, 50, 43, 36, 29, 22, 15, 23, 30, 37, 44, 51, 58, 59,
+    52, 45, 38, 31, 39, 46, 53, 60, 61, 54, 47, 55, 62, 63,
+];
+/// Above this many pixels only the DC coefficients are used.
+const FULL_DECODE_PIXELS: usize = 1 << 20;
+
+pub fn is_jpeg(data: &[u8]) -> bool {
+    data.starts_with(&[0xFF, 0xD8])
+}
+
+#[derive(Default, Clone)]
+struct Huffman {
+    counts: [u16; 17],
+    symbols: Vec<u8>,
+}
+
+struct Component {
+    id: u8,
+    h: usize,
+    v: usize,
+    quant: usize,
+    dc_table: usize,
+    ac_table: usize,
+    predictor: i32,
+    plane: Vec<u8>,
+    stride: usize,
+}
+
+struct Frame {
+    width: usize,
+    height: usize,
+    components: Vec<Component>,
+}
+
+pub fn decode(data: &[u8]) -> io::Result<Image> {
+    let mut quant = [[0u16; 64]; 4];
+    let mut dc_tables = vec![Huffman::default(); 4];
+    let mut ac_tables = vec![Huffman::default(); 4];
+    let mut frame: Option<Frame> = None;
+    let mut restart_interval = 0;
+
+    let mut pos = 2;
+    while pos + 4 <= data.len() {
+        if data[pos] != 0xFF {
+            return Err(invalid("bad JPEG marker"));
+        }
+        let marker = data[pos + 1];
+        if marker == 0xFF {
+            // Fill byte
+            pos += 1;
+            continue;
+        }
+        let len = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+        let body = data
+            .get(pos + 4..pos + 2 + len)
+            .ok_or_else(|| invalid("truncated JPEG segment"))?;
+        pos += 2 + len;
+
+        match marker {
+            0xDB => read_quant_tables(body, &mut quant)?,
+            0xC4 => read_huffman_tables(body, &mut dc_tables, &mut ac_tables)?,
+            0xC0 | 0xC1 => frame = Some(read_frame(body)?),
+            0xC2 | 0xC3 | 0xC5..=0xC7 | 0xC9..=0xCB | 0xCD..=0xCF => {
+                return Err(invalid("only baseline JPEG is supported"));
+            }
+            0xDD if body.len() >= 2 => {
+                restart_interval = u16::from_be_bytes([body[0], body[1]]) as usize
+            }
+            0xDA => {
+                let mut frame = frame.ok_or_else(|| invalid("JPEG scan before frame"))?;
+                read_scan_header(body, &mut frame)?;
+                let tables = Tables {
+                    quant: &quant,
+                    dc: &dc_tables,
+                    ac: &ac_tables,
+                };
+                return decode_scan(&data[pos..], &mut frame, &tables, restart_interval);
+            }
+            0xD9 => break,
+            _ => {}
+        }
+    }
+    Err(invalid("JPEG has no image data"))
+}
+
+fn read_quant_tables(mut body: &[u8], quant: &mut [[u16; 64]; 4]) -> io::Result<()> {
+    while let Some((&info, rest)) = body.split_first() {
+        let (wide, id) = (info >> 4 != 0, (info & 3) as usize);
+        let size = if wide { 128 } else { 64 };
+        let values = rest.get(..size).ok_or_else(|| invalid("truncated DQT"))?;
+        for (i, q) in quant[id].iter_mut().enumerate() {
+            *q = if wide {
+                u16::from_be_bytes([values[i * 2], values[i * 2 + 1]])
+            } else {
+                u16::from(values[i])
+            };
+        }
+        body = &rest[size..];
+    }
+    Ok(())
+}
+
+fn read_huffman_tables(mut body: &[u8], dc: &mut [Huffman], ac: &mut [Huffman]) -> io::Result<()> {
+    while body.len() >= 17 {
+        let (class, id) = (body[0] >> 4, (body[0] & 3) as usize);
+        let mut table = Huffman::default();
+        for (count, &byte) in table.counts[1..].iter_mut().zip(&body[1..17]) {
+            *count = u16::from(byte);
+        }
+        let total: usize = table.counts.iter().map(|&c| c as usize).sum();
+        table.symbols = body
+            .get(17..17 + total)
+            .ok_or_else(|| invalid("truncated DHT"))?
+            .to_vec();
+        if class == 0 {
+            dc[id] = table;
+        } else {
+            ac[id] = table;
+        }
+        body = &body[17 + total..];
+    }
+    Ok(())
+}
+
+fn read_frame(body: &[u8]) -> io::Result<Frame> {
+    if body.len() < 6 || body[0] != 8 {
+        return Err(invalid("unsupported JPEG precision"));
+    }
+    let height = u16::from_be_bytes([body[1], body[2]]) as usize;
+    let width = u16::from_be_bytes([body[3], body[4]]) as usize;
+    let count = body[5] as usize;
+    if count != 1 && count != 3 {
+        return Err(invalid("unsupported JPEG color space"));
+    }
+    super::check_size(width, height)?;
+    let components = body[6..]
+        .chunks_exact(3)
+        .take(count)
+        .map(|c| Component {
+            id: c[0],
+            h: (c[1] >> 4).clamp(1, 4) as usize,
+            v: (c[1] & 15).clamp(1, 4) as usize,
+            quant: (c[2] & 3) as usize,
+            dc_table: 0,
+            ac_table: 0,
+            predictor: 0,
+            plane: Vec::new(),
+            stride: 0,
+        })
+        .collect::<Vec<_>>();
+    if components.len() != count {
+        return Err(invalid("truncated JPEG frame"));
+    }
+    Ok(Frame {
+        width,
+        height,
+        components,
+    })
+}
+
+fn read_scan_header(body: &[u8], frame: &mut Frame) -> io::Result<()> {
+    let count = *body.first().ok_or_else(|| invalid("empty JPEG scan"))? as usize;
+    if count != frame.components.len() {
+        return Err(invalid("non-interleaved JPEG scans are not supported"));
+    }
+    for selector in body[1..].chunks_exact(2).take(count) {
+        let component = frame
+            .components
+            .iter_mut()
+            .find(|c| c.id == selector[0])
+            .ok_or_else(|| invalid("unknown JPEG component"))?;
+        component.dc_table = (selector[1] >> 4 & 3) as usize;
+        component.ac_table = (selector[1] & 3) as usize;
+    }
+    Ok(())
+}
+
+struct Tables<'a> {
+    quant: &'a [[u16; 64]; 4],
+    dc: &'a [Huffman],
+    ac: &'a [Huffman],
+}
+
+fn decode_scan(
+    data: &[u8],
+    frame: &mut Frame,
+    tables: &Tables,
+    restart_interval: usize,
+) -> io::Result<Image> {
+    // A single component is never subsampled, whatever its factors say
+    if let [component] = &mut frame.components[..] {
+        component.h = 1;
+        component.v = 1;
+    }
+    let h_max = frame.components.iter().map(|c| c.h).max().unwrap_or(1);
+    let v_max = frame.components.iter().map(|c| c.v).max().unwrap_or(1);
+    let mcus_x = frame.width.div_ceil(8 * h_max);
+    let mcus_y = frame.height.div_ceil(8 * v_max);
+
+    let dc_only = frame.width * frame.height > FULL_DECODE_PIXELS;
+    let block_size = if dc_only { 1 } else { 8 };
+    for component in &mut frame.components {
+        component.stride = mcus_x * component.h * block_size;
+        component.plane = vec![0; component.stride * mcus_y * component.v * block_size];
+    }
+
+    let cosines = cosine_table();
+    let mut bits = Bits {
+        data,
+        pos: 0,
+        acc: 0,
+        count: 0,
+    };
+    let mut coefficients = [0i32; 64];
+    for mcu in 0..mcus_x * mcus_y {
+        if restart_interval > 0 && mcu > 0 && mcu % restart_interval == 0 {
+            bits.restart();
+            for component in &mut frame.components {
+                component.predictor = 0;
+            }
+        }
+        let (mx, my) = (mcu % mcus_x, mcu / mcus_x);
+        for component in &mut frame.components {
+            for by in 0..component.v {
+                for bx in 0..component.h {
+                    decode_block(&mut bits, component, tables, &mut coefficients, dc_only)?;
+                    let x = (mx * component.h + bx) * block_size;
+                    let y = (my * component.v + by) * block_size;
+                    if dc_only {
+                        // The DC term is eight times the block's mean
+                        let value = coefficients[0] / 8 + 128;
+                        component.plane[y * component.stride + x] = value.clamp(0, 255) as u8;
+                    } else {
+                        idct(&coefficients, &cosines, component, x, y);
+                    }
+                }
+            }
+        }
+    }
+
+    let (width, height) = if dc_only {
+        (frame.width.div_ceil(8), frame.height.div_ceil(8))
+    } else {
+        (frame.width, frame.height)
+    };
+    let mut image = Image::new(width, height);
+    image.original = (frame.width, frame.height);
+    for y in 0..height {
+        for x in 0..width {
+            let sample =
+                |c: &Component| c.plane[(y * c.v / v_max) * c.stride + x * c.h / h_max] as f32;
+            let pixel = if let [luma] = &frame.components[..] {
+                let v = sample(luma) as u8;
+                [v, v, v, 255]
+            } else {
+                let (l, cb, cr) = (
+                    sample(&frame.components[0]),
+                    sample(&frame.components[1]) - 128.0,
+                    sample(&frame.components[2]) - 128.0,
+                );
+                [
+                    (l + 1.402 * cr).clamp(0.0, 255.0) as u8,
+                    (l - 0.344_136 * cb - 0.714_136 * cr).clamp(0.0, 255.0) as u8,
+                    (l + 1.772 * cb).clamp(0.0, 255.0) as u8,
+                    255,
+                ]
+            };
+            image.set(x, y, pixel);
+        }
+    }
+    Ok(image)
+}
+
+fn decode_block(
+    bits: &mut Bits,
+    component: &mut Component,
+    tables: &Tables,
+    coefficients: &mut [i32; 64],
+    dc_only: bool,
+) -> io::Result<()> {
+    let quant = &tables.quant[component.quant];
+    coefficients.fill(0);
+
+    let size = bits.decode(&tables.dc[component.dc_table])?;
+    // DC differences of 8-bit samples take at most 11 bits
+    if size > 11 {
+        return Err(invalid("bad JPEG DC size"));
+    }
+    // A corrupt file can run the predictor anywhere; the pixels are clamped
+    component.predictor = component.predictor.wrapping_add(bits.receive_extend(size)?);
+    coefficients[0] = component.predictor.wrapping_mul(i32::from(quant[0]));
+
+    let mut k = 1;
+    while k < 64 {
+        let symbol = bits.decode(&tables.ac[component.ac_table])?;
+        let (run, size) = ((symbol >> 4) as usize, symbol & 15);
+        if size == 0 {
+            if run == 15 {
+                k += 16;
+                continue;
+            }
+            break;
+        }
+        k += run;
+        if k > 63 {
+            break;
+        }
+        let value = bits.receive_extend(size)?;
+        if !dc_only {
+            coefficients[ZIGZAG[k]] = value * i32::from(quant[k]);
+        }
+        k += 1;
+    }
+    Ok(())
+}
+
+/// `table[x * 8 + u]` is the scaled cosine basis of frequency `u` at `x`.
+fn cosine_table() -> [f32; 64] {
+    let mut table = [0.0; 64];
+    for x in 0..8 {
+        for u in 0..8 {
+            let scale = if u == 0 { 1.0 / 2f32.sqrt() } else { 1.0 };
+            table[x * 8 + u] = scale / 2.0 * ((2 * x + 1) as f32 * u as f32 * PI / 16.0).cos();
+        }
+    }
+    table
+}
+
+fn idct(
+    coefficients: &[i32; 64],
+    cosines: &[f32; 64],
+    component: &mut Component,
+    x: usize,
+    y: usize,
+) {
+    // Rows first, then columns
+    let mut rows = [0f32; 64];
+    for v in 0..8 {
+        for px in 0..8 {
+            rows[v * 8 + px] = (0..8)
+                .map(|u| cosines[px * 8 + u] * coefficients[v * 8 + u] as f32)
+                .sum();
+        }
+    }
+    for py in 0..8 {
+        for px in 0..8 {
+            let value: f32 = (0..8).map(|v| cosines[py * 8 + v] * rows[v * 8 + px]).sum();
+            component.plane[(y + py) * component.stride + x + px] =
+                (value + 128.0).round().clamp(0.0, 255.0) as u8;
+        }
+    }
+}
+
+/// Entropy-coded data reader that removes byte stuffing.
+struct Bits<'a> {
+    data: &'a [u8],
+    pos: usize,
+    acc: u32,
+    count: u32,
+}
+
+impl Bits<'_> {
+    fn next_byte(&mut self) -> u32 {
+        match self.data.get(self.pos) {
+            Some(0xFF) if self.data.get(self.pos + 1) == Some(&0) => {
+                self.pos += 2;
+                0xFF
+            }
+            // A marker; pad with zeros instead of reading into it
+            Some(0xFF) | None => 0,
+            Some(&byte) => {
+                self.pos += 1;
+                u32::from(byte)
+            }
+        }
+    }
+
+    fn bit(&mut self) -> u32 {
+        if self.count == 0 {
+            self.acc = self.next_byte();
+            self.count = 8;
+        }
+        self.count -= 1;
+        (self.acc >> self.count) & 1
+    }
+
+    fn decode(&mut self, huffman: &Huffman) -> io::Result<u8> {
+        let (mut code, mut first, mut index) = (0i32, 0i32, 0i32);
+        for len in 1..=16 {
+            code |= self.bit() as i32;
+            let count = i32::from(huffman.counts[len]);
+            if code - count < first {
+                return huffman
+                    .symbols
+                    .get((index + code - first) as usize)
+                    .copied()
+                    .ok_or_else(|| invalid("bad JPEG huffman code"));
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+        Err(invalid("bad JPEG huffman code"))
+    }
+
+    /// Reads a `size`-bit magnitude and sign-extends it.
+    fn receive_extend(&mut self, size: u8) -> io::Result<i32> {
+        if size == 0 {
+            return Ok(0);
+        }
+        if size > 16 {
+            return Err(invalid("bad JPEG coefficient size"));
+        }
+        let mut value = 0i32;
+        for _ in 0..size {
+            value = value << 1 | self.bit() as i32;
+        }
+        Ok(if value < 1 << (size - 1) {
+            value - (1 << size) + 1
+        } else {
+            value
+        })
+    }
+
+    /// Skips past the next RST marker and starts on a byte boundary.
+    fn restart(&mut self) {
+        self.count = 0;
+        while self.pos + 1 < self.data.len()
+            && !(self.data[self.pos] == 0xFF && (0xD0..=0xD7).contains(&self.data[self.pos + 1]))
+        {
+            self.pos += 1;
+        }
+        self.pos += 2;
+    }
+}
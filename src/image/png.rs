@@ -0,0 +1,274 @@
+use std::io;
+
+use super::{Image, inflate, invalid};
+
+const SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+/// Adam7 passes as (x offset, y offset, x step, y step).
+const ADAM7: [(usize, usize, usize, usize); 7] = [
+    (0, 0, 8, 8),
+    (4, 0, 8, 8),
+    (0, 4, 4, 8),
+    (2, 0, 4, 4),
+    (0, 2, 2, 4),
+    (1, 0, 2, 2),
+    (0, 1, 1, 2),
+];
+
+pub fn is_png(data: &[u8]) -> bool {
+    data.starts_with(SIGNATURE)
+}
+
+struct Header {
+    width: usize,
+    height: usize,
+    depth: u8,
+    color: u8,
+    interlaced: bool,
+}
+
+impl Header {
+    fn channels(&self) -> usize {
+        match self.color {
+            2 => 3,
+            4 => 2,
+            6 => 4,
+            _ => 1,
+        }
+    }
+
+    fn bits_per_pixel(&self) -> usize {
+        self.channels() * self.depth as usize
+    }
+}
+
+pub fn decode(data: &[u8]) -> io::Result<Image> {
+    let mut header = None;
+    let mut palette: Vec<[u8; 4]> = Vec::new();
+    let mut compressed = Vec::new();
+
+    let mut pos = SIGNATURE.len();
+    while pos + 8 <= data.len() {
+        let len = u32::from_be_bytes(data[pos..pos + 4].try_into().unwrap()) as usize;
+        let kind = &data[pos + 4..pos + 8];
+        let body = data
+            .get(pos + 8..pos + 8 + len)
+            .ok_or_else(|| invalid("truncated PNG chunk"))?;
+        pos += 12 + len;
+        match kind {
+            b"IHDR" if body.len() >= 13 => {
+                header = Some(Header {
+                    width: u32::from_be_bytes(body[0..4].try_into().unwrap()) as usize,
+                    height: u32::from_be_bytes(body[4..8].try_into().unwrap()) as usize,
+                    depth: body[8],
+                    color: body[9],
+                    interlaced: body[12] == 1,
+                })
+            }
+            b"PLTE" => {
+                palette = body
+                    .chunks_exact(3)
+                    .map(|c| [c[0], c[1], c[2], 255])
+                    .collect()
+            }
+            b"tRNS" => {
+                for (entry, &alpha) in palette.iter_mut().zip(body) {
+                    entry[3] = alpha;
+                }
+            }
+            b"IDAT" => compressed.extend_from_slice(body),
+            b"IEND" => break,
+            _ => {}
+        }
+    }
+
+    let header = header.ok_or_else(|| invalid("missing PNG header"))?;
+    if !matches!(header.depth, 1 | 2 | 4 | 8 | 16) || !matches!(header.color, 0 | 2 | 3 | 4 | 6) {
+        return Err(invalid("unsupported PNG format"));
+    }
+    super::check_size(header.width, header.height)?;
+    let raw = inflate::zlib_decompress(&compressed)?;
+
+    let mut image = Image::new(header.width, header.height);
+    let passes: &[(usize, usize, usize, usize)] = if header.interlaced {
+        &ADAM7
+    } else {
+        &[(0, 0, 1, 1)]
+    };
+    let mut offset = 0;
+    for &(x0, y0, dx, dy) in passes {
+        let pass_width = (header.width + dx - 1 - x0) / dx;
+        let pass_height = (header.height + dy - 1 - y0) / dy;
+        if pass_width == 0 || pass_height == 0 {
+            continue;
+        }
+        let stride = (pass_width * header.bits_per_pixel()).div_ceil(8);
+        let end = offset + (stride + 1) * pass_height;
+        let scanlines = raw
+            .get(offset..end)
+            .ok_or_else(|| invalid("truncated PNG data"))?;
+        offset = end;
+
+        let rows = unfilter(scanlines, stride, header.bits_per_pixel().div_ceil(8))?;
+        for (row, line) in rows.chunks(stride).enumerate() {
+            for column in 0..pass_width {
+                let pixel = read_pixel(&header, &palette, line, column);
+                image.set(x0 + column * dx, y0 + row * dy, pixel);
+            }
+        }
+    }
+    Ok(image)
+}
+
+/// Reverses the per-scanline filters, returning the bare rows back to back.
+fn unfilter(data: &[u8], stride: usize, bpp: usize) -> io::Result<Vec<u8>> {
+    let mut out = vec![0u8; data.len() / (stride + 1) * stride];
+    for (row, line) in data.chunks(stride + 1).enumerate() {
+        let (filter, line) = (line[0], &line[1..]);
+        let (done, current) = out.split_at_mut(row * stride);
+        let previous = if row == 0 {
+            None
+        } else {
+            Some(&done[(row - 1) * stride..])
+        };
+        let current = &mut current[..stride];
+        for i in 0..stride {
+            let a = if i >= bpp { current[i - bpp] } else { 0 };
+            let b = previous.map_or(0, |p| p[i]);
+            let c = if i >= bpp {
+                previous.map_or(0, |p| p[i - bpp])
+            } else {
+                0
+            };
+            current[i] = line[i].wrapping_add(match filter {
+                0 => 0,
+                1 => a,
+                2 => b,
+                3 => ((u16::from(a) + u16::from(b)) / 2) as u8,
+                4 => paeth(a, b, c),
+                _ => return Err(invalid("bad PNG filter")),
+            });
+        }
+    }
+    Ok(out)
+}
+
+fn paeth(a: u8, b: u8, c: u8) -> u8 {
+    let p = i16::from(a) + i16::from(b) - i16::from(c);
+    let (pa, pb, pc) = (
+        (p - i16::from(a)).abs(),
+        (p - i16::from(b)).abs(),
+        (p - i16::from(c)).abs(),
+    );
+    if pa <= pb && pa <= pc {
+        a
+    } else if pb <= pc {
+        b
+    } else {
+        c
+    }
+}
+
+fn read_pixel(header: &Header, palette: &[[u8; 4]], line: &[u8], column: usize) -> [u8; 4] {
+    let depth = header.depth as usize;
+    // Sample `n` of this pixel, scaled to 8 bits
+    let sample = |n: usize| -> u8 {
+        let index = column * header.channels() + n;
+        match depth {
+            16 => line[index * 2],
+            8 => line[index],
+            _ => {
+                let bit = index * depth;
+                let value = (line[bit / 8] >> (8 - depth - bit % 8)) & ((1 << depth) - 1);
+                if header.color == 3 {
+                    value
+                } else {
+                    (value as usize * 255 / ((1 << depth) - 1)) as u8
+                }
+            }
+        }
+    };
+    match header.color {
+        0 => {
+            let v = sample(0);
+            [v, v, v, 255]
+        }
+        2 => [sample(0), sample(1), sample(2), 255],
+        3 => palette
+            .get(sample(0) as usize)
+            .copied()
+            .unwrap_or([0, 0, 0, 255]),
+        4 => {
+            let v = sample(0);
+            [v, v, v, sample(1)]
+        }
+        _ => [sample(0), sample(1), sample(2), sample(3)],
+    }
+}
+
+/// Encodes the image as an RGBA PNG. The data is stored uncompressed, which
+/// is fine for handing a thumbnail to the terminal.
+pub fn encode(image: &Image) -> Vec<u8> {
+    let mut raw = Vec::with_capacity((image.width * 4 + 1) * image.height);
+    for row in image.pixels().chunks(image.width) {
+        raw.push(0);
+        raw.extend(row.iter().flatten());
+    }
+
+    let mut zlib = vec![0x78, 0x01];
+    let mut blocks = raw.chunks(0xFFFF).peekable();
+    while let Some(block) = blocks.next() {
+        let len = block.len() as u16;
+        zlib.push(u8::from(blocks.peek().is_none()));
+        zlib.extend(len.to_le_bytes());
+        zlib.extend((!len).to_le_bytes());
+        zlib.extend(block);
+    }
+    if raw.is_empty() {
+        zlib.extend([1, 0, 0, 0xFF, 0xFF]);
+    }
+    zlib.extend(adler32(&raw).to_be_bytes());
+
+    let mut header = Vec::with_capacity(13);
+    header.extend((image.width as u32).to_be_bytes());
+    header.extend((image.height as u32).to_be_bytes());
+    header.extend([8, 6, 0, 0, 0]);
+
+    let mut out = SIGNATURE.to_vec();
+    write_chunk(&mut out, b"IHDR", &header);
+    write_chunk(&mut out, b"IDAT", &zlib);
+    write_chunk(&mut out, b"IEND", &[]);
+    out
+}
+
+fn write_chunk(out: &mut Vec<u8>, kind: &[u8; 4], body: &[u8]) {
+    out.extend((body.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend(kind);
+    out.extend(body);
+    let crc = crc32(&out[start..]);
+    out.extend(crc.to_be_bytes());
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in data {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xEDB8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + u32::from(byte)) % 65_521;
+        b = (b + a) % 65_521;
+    }
+    b << 16 | a
+}
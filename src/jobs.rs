@@ -0,0 +1,427 @@
+//! Long-running work done on background threads, reporting progress back to
+//! the UI as it goes.
+
+use std::{
+    collections::VecDeque,
+    io::{self, BufRead, BufReader},
+    process::{Command, Stdio},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender},
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    symbols::border,
+    text::{Line, Text},
+    widgets::{Block, Clear, Paragraph, Widget},
+};
+
+use crate::{
+    error::{self, BrowrsError},
+    log, theme,
+};
+
+/// Lines of output kept from a command; the earlier ones are dropped.
+const MAX_OUTPUT_LINES: usize = 5000;
+
+/// What a job does, for settings that differ between them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Copy,
+    Move,
+    Trash,
+    Delete,
+    Download,
+    /// Extracting or compressing an archive.
+    Archive,
+    /// A build, script or task run with its output kept.
+    Command,
+    /// A look through a tree, like for caches to clean up.
+    Scan,
+}
+
+impl Kind {
+    pub const ALL: [Kind; 8] = [
+        Kind::Copy,
+        Kind::Move,
+        Kind::Trash,
+        Kind::Delete,
+        Kind::Download,
+        Kind::Archive,
+        Kind::Command,
+        Kind::Scan,
+    ];
+
+    /// The name it goes by in the config file.
+    pub fn name(self) -> &'static str {
+        match self {
+            Kind::Copy => "copy",
+            Kind::Move => "move",
+            Kind::Trash => "trash",
+            Kind::Delete => "delete",
+            Kind::Download => "download",
+            Kind::Archive => "archive",
+            Kind::Command => "command",
+            Kind::Scan => "scan",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+}
+
+/// A job still running, as last reported by its worker.
+#[derive(Debug)]
+pub struct Job {
+    pub id: usize,
+    pub kind: Kind,
+    pub title: String,
+    started: Instant,
+    pub done: u64,
+    pub total: Option<u64>,
+    /// Whether progress is counted in bytes rather than entries.
+    pub bytes: bool,
+    cancel: Arc<AtomicBool>,
+}
+
+impl Job {
+    /// How far along it is, when the total is known.
+    pub fn ratio(&self) -> Option<f64> {
+        self.total
+            .filter(|&total| total > 0)
+            .map(|total| (self.done as f64 / total as f64).min(1.0))
+    }
+
+    /// The progress so far in its own units, like `3.0 MB of 12.0 MB`.
+    pub fn detail(&self) -> String {
+        let amount = |n: u64| {
+            if self.bytes {
+                format_size(n)
+            } else {
+                n.to_string()
+            }
+        };
+        match self.total {
+            Some(total) if self.bytes => format!("{} of {}", amount(self.done), amount(total)),
+            Some(total) => format!("{} of {} entries", self.done, total),
+            None => amount(self.done),
+        }
+    }
+
+    /// Asks the worker to stop; it ends once it next checks.
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+
+    /// Title with the progress so far, as a percentage when the total is known.
+    pub fn label(&self) -> String {
+        match self.total {
+            Some(total) if total > 0 => {
+                format!("{} {}%", self.title, self.done * 100 / total)
+            }
+            _ if self.done > 0 => format!("{} {}", self.title, format_size(self.done)),
+            _ => self.title.clone(),
+        }
+    }
+}
+
+/// Handed to a worker so it can report progress and notice cancellation.
+pub struct Progress {
+    id: usize,
+    sender: Sender<Update>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl Progress {
+    /// Progress for work run in the foreground: reported nowhere and never
+    /// cancelled.
+    pub fn silent() -> Self {
+        Self {
+            id: 0,
+            sender: mpsc::channel().0,
+            cancel: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    /// Reports `done` bytes out of `total`.
+    pub fn set(&self, done: u64, total: Option<u64>) {
+        self.send(done, total, true);
+    }
+
+    /// Reports `done` entries out of `total`.
+    pub fn count(&self, done: usize, total: usize) {
+        self.send(done as u64, Some(total as u64), false);
+    }
+
+    fn send(&self, done: u64, total: Option<u64>, bytes: bool) {
+        let _ = self.sender.send(Update::Progress {
+            id: self.id,
+            done,
+            total,
+            bytes,
+        });
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+}
+
+/// A job that has ended, with the message its worker produced.
+pub struct Finished {
+    pub kind: Kind,
+    pub title: String,
+    /// How long it ran for.
+    pub elapsed: Duration,
+    pub result: error::Result<String>,
+}
+
+#[derive(Debug)]
+enum Update {
+    Progress {
+        id: usize,
+        done: u64,
+        total: Option<u64>,
+        bytes: bool,
+    },
+    Finished {
+        id: usize,
+        result: io::Result<String>,
+    },
+}
+
+#[derive(Debug)]
+pub struct Jobs {
+    running: Vec<Job>,
+    next_id: usize,
+    sender: Sender<Update>,
+    receiver: Receiver<Update>,
+}
+
+impl Jobs {
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::channel();
+        Self {
+            running: Vec::new(),
+            next_id: 0,
+            sender,
+            receiver,
+        }
+    }
+
+    /// Runs `work` on its own thread. Whatever it returns is reported by
+    /// [`Jobs::poll`] once it is done.
+    pub fn spawn<F>(&mut self, kind: Kind, title: impl Into<String>, work: F)
+    where
+        F: FnOnce(&Progress) -> io::Result<String> + Send + 'static,
+    {
+        let title = title.into();
+        log::info!("job started: {}", title);
+        let id = self.next_id;
+        self.next_id += 1;
+        let cancel = Arc::new(AtomicBool::new(false));
+        let progress = Progress {
+            id,
+            sender: self.sender.clone(),
+            cancel: Arc::clone(&cancel),
+        };
+        thread::spawn(move || {
+            let result = work(&progress);
+            let _ = progress.sender.send(Update::Finished { id, result });
+        });
+        self.running.push(Job {
+            id,
+            kind,
+            title,
+            started: Instant::now(),
+            done: 0,
+            total: None,
+            bytes: true,
+            cancel,
+        });
+    }
+
+    pub fn running(&self) -> &[Job] {
+        &self.running
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.running.is_empty()
+    }
+
+    /// Asks every running job to stop.
+    pub fn cancel_all(&self) {
+        for job in &self.running {
+            job.cancel();
+        }
+    }
+
+    /// Applies progress sent by the workers and returns the jobs that ended.
+    pub fn poll(&mut self) -> Vec<Finished> {
+        let mut finished = Vec::new();
+        while let Ok(update) = self.receiver.try_recv() {
+            match update {
+                Update::Progress {
+                    id,
+                    done,
+                    total,
+                    bytes,
+                } => {
+                    if let Some(job) = self.running.iter_mut().find(|j| j.id == id) {
+                        job.done = done;
+                        job.total = total;
+                        job.bytes = bytes;
+                    }
+                }
+                Update::Finished { id, result } => {
+                    if let Some(index) = self.running.iter().position(|j| j.id == id) {
+                        let job = self.running.remove(index);
+                        match &result {
+                            Ok(message) => log::info!("job finished: {}: {}", job.title, message),
+                            Err(e) => log::error!("job failed: {}: {}", job.title, e),
+                        }
+                        let result = result.map_err(|source| BrowrsError::Job {
+                            title: job.title.clone(),
+                            source,
+                        });
+                        finished.push(Finished {
+                            kind: job.kind,
+                            title: job.title,
+                            elapsed: job.started.elapsed(),
+                            result,
+                        });
+                    }
+                }
+            }
+        }
+        finished
+    }
+}
+
+impl Drop for Jobs {
+    fn drop(&mut self) {
+        self.cancel_all();
+    }
+}
+
+/// The panel listing running jobs with a progress bar each.
+impl Widget for &Jobs {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let theme = theme::current();
+        let width = (area.width * 3 / 5).max(40).min(area.width);
+        let height = (self.running.len() as u16 * 3 + 1).min(area.height);
+        let rect = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+        let block = Block::bordered()
+            .title(" Progress ".fg(theme.info).bold())
+            .title_bottom(Line::from(" Esc cancel, Enter hide ").right_aligned())
+            .border_style(theme.border)
+            .border_set(border::ROUNDED);
+        let inner = block.inner(rect);
+        Clear.render(rect, buf);
+        block.render(rect, buf);
+
+        let mut lines = Vec::new();
+        for job in &self.running {
+            if !lines.is_empty() {
+                lines.push(Line::from(""));
+            }
+            lines.push(if job.is_cancelled() {
+                Line::from(format!("{} (cancelling)", job.title).fg(theme.dim))
+            } else {
+                Line::from(job.title.clone().bold())
+            });
+            let detail = job.detail();
+            let room = (inner.width as usize).saturating_sub(detail.chars().count() + 7);
+            let bar = match job.ratio() {
+                Some(ratio) => {
+                    let filled = (ratio * room as f64).round() as usize;
+                    format!(
+                        "{}{} {:>3}%  ",
+                        "█".repeat(filled),
+                        "░".repeat(room - filled),
+                        (ratio * 100.0) as u64
+                    )
+                }
+                None => String::new(),
+            };
+            lines.push(Line::from(vec![bar.fg(theme.info), detail.fg(theme.dim)]));
+        }
+        Paragraph::new(Text::from(lines)).render(inner, buf);
+    }
+}
+
+/// What a command run by a job printed, stdout and stderr interleaved.
+#[derive(Debug, Clone)]
+pub struct Output {
+    /// The command as shown to the user, e.g. `cargo test`.
+    pub command: String,
+    pub success: bool,
+    /// How it ended, e.g. `exit status: 101`.
+    pub status: String,
+    pub lines: Vec<String>,
+}
+
+/// Runs `command`, keeping what it prints. Stops it if the job is
+/// cancelled.
+pub fn capture(label: String, command: &mut Command, progress: &Progress) -> io::Result<Output> {
+    let (reader, writer) = io::pipe()?;
+    let program = command.get_program().to_string_lossy().to_string();
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(writer.try_clone()?)
+        .stderr(writer)
+        .spawn()
+        .map_err(|e| io::Error::new(e.kind(), format!("unable to run {}: {}", program, e)))?;
+    // The write ends held by the command would keep the pipe open
+    command.stdout(Stdio::null()).stderr(Stdio::null());
+    let mut lines = VecDeque::new();
+    for line in BufReader::new(reader).lines() {
+        if progress.is_cancelled() {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(io::Error::new(io::ErrorKind::Interrupted, "cancelled"));
+        }
+        lines.push_back(line?);
+        if lines.len() > MAX_OUTPUT_LINES {
+            lines.pop_front();
+        }
+    }
+    let status = child.wait()?;
+    Ok(Output {
+        command: label,
+        success: status.success(),
+        status: status.to_string(),
+        lines: lines.into(),
+    })
+}
+
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < UNITS.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
@@ -0,0 +1,498 @@
+//! What each key does, from the built-in bindings and the `[keys]` config
+//! table. Bindings are sequences of chords, so `g g` can mean something
+//! different from `g` followed by a key of its own.
+
+use crate::{
+    cargo::Task,
+    error::{self, BrowrsError},
+    keys::Chord,
+};
+
+/// Everything a key can be bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    /// Quits, leaving the current directory for the shell to change to.
+    QuitCd,
+    Search,
+    /// Searches the contents of the files in the current tree.
+    Grep,
+    ToggleHidden,
+    /// Hides or shows what git ignores.
+    ToggleIgnored,
+    /// Narrows the listing to names matching a pattern.
+    Filter,
+    /// Cycles through the sort modes.
+    Sort,
+    DirsFirst,
+    /// Switches the listing between names and a table of details.
+    Detailed,
+    Editor,
+    /// Edits a small text file in place, without leaving browrs.
+    QuickEdit,
+    /// Edits the permission bits of the targeted entries.
+    Permissions,
+    /// Changes the owner and group of the targeted entries.
+    Owner,
+    /// Changes the mode and owner of the targeted entries as typed, like
+    /// chmod and chown.
+    Attributes,
+    MoveUp,
+    MoveDown,
+    PageUp,
+    PageDown,
+    /// Moves the keys between the listing and the preview.
+    FocusPreview,
+    /// Reads the preview again after it timed out.
+    RetryPreview,
+    Top,
+    Bottom,
+    Open,
+    /// Opens the file with the system's default application.
+    OpenDefault,
+    /// Lists what can be done with the selected entry, by its type.
+    Actions,
+    /// The directory visited before this one.
+    Back,
+    Forward,
+    /// The place jumped from last, with the cursor on the same entry.
+    JumpBack,
+    JumpForward,
+    Mark,
+    Visual,
+    MarkAll,
+    ClearMarks,
+    /// Adds the targeted entries to the scratch, or takes them out when
+    /// they are all in it already.
+    Collect,
+    /// Lists the scratch, to go to an entry or act on them all at once.
+    Scratch,
+    /// Reads the current directory again, keeping the cursor, marks and
+    /// filter.
+    Refresh,
+    Copy,
+    Move,
+    /// Creates symlinks to the targeted entries in another directory.
+    Symlink,
+    /// Creates hard links to the targeted files in another directory.
+    Hardlink,
+    Rename,
+    Trash,
+    Delete,
+    OpenTrash,
+    /// Looks for caches and build output under the current directory.
+    Cleanup,
+    /// Runs the cargo command on the Rust project around the current
+    /// directory.
+    Cargo(Task),
+    /// Lists the scripts of the JavaScript package around the current
+    /// directory, to run one.
+    Scripts,
+    /// Lists the targets of the Makefile and justfile in the current
+    /// directory, to run one.
+    Tasks,
+    SendTo,
+    Touch,
+    /// Creates a file, or a directory when the name ends in `/`.
+    NewFile,
+    /// Creates a file holding the text on the clipboard.
+    PasteFile,
+    NewDir,
+    /// Unpacks the selected archive.
+    Extract,
+    /// Packs the targeted entries into a new archive.
+    Compress,
+    Reveal,
+    /// Shows the selected path, or a small text file's contents, as a QR
+    /// code.
+    Qr,
+    Versions,
+    Share,
+    /// Shows the progress of running jobs.
+    Jobs,
+    /// Attaches a short note to the selected entry.
+    Annotate,
+    NewTab,
+    CloseTab,
+    NextTab,
+    PrevTab,
+    /// The next pane in dual-pane mode, the next tab otherwise.
+    SwitchPane,
+    DualPane,
+    /// Splits the focused pane into two side by side.
+    SplitSide,
+    /// Splits the focused pane into two, one above the other.
+    SplitBelow,
+    CloseSplit,
+    /// Diffs the selected file against the same name in the next pane.
+    DiffPanes,
+    /// Makes the other panes follow the focused one to the same path
+    /// under where each was.
+    SyncPanes,
+    FocusLeft,
+    FocusRight,
+    FocusUp,
+    FocusDown,
+    PreviewOverlay,
+    Command,
+    /// Bookmarks the current directory.
+    Bookmark,
+    /// Lists the bookmarks and smart folders to jump to one.
+    Bookmarks,
+    /// Asks for a path to go to, completing directories with Tab.
+    Goto,
+    /// Saves the pane's filter as a smart folder.
+    SaveFilter,
+    /// Toggles listing only directories.
+    OnlyDirs,
+    OnlyFiles,
+    OnlyExecutables,
+    OnlyImages,
+    OnlyArchives,
+    /// Turns every kind filter off, and the rating filter.
+    AllKinds,
+    /// Gives the targeted entries this many stars; 0 takes them away.
+    Rate(u8),
+    /// Lists only files with at least this many stars; 0 lists them all.
+    MinRating(u8),
+    /// Opens the settings screen.
+    Settings,
+    /// Shows the recent log.
+    Log,
+}
+
+impl Action {
+    const ALL: [(&'static str, Action); 105] = [
+        ("quit", Action::Quit),
+        ("quit_cd", Action::QuitCd),
+        ("search", Action::Search),
+        ("grep", Action::Grep),
+        ("toggle_hidden", Action::ToggleHidden),
+        ("toggle_ignored", Action::ToggleIgnored),
+        ("filter", Action::Filter),
+        ("sort", Action::Sort),
+        ("dirs_first", Action::DirsFirst),
+        ("detailed", Action::Detailed),
+        ("editor", Action::Editor),
+        ("quick_edit", Action::QuickEdit),
+        ("permissions", Action::Permissions),
+        ("owner", Action::Owner),
+        ("attributes", Action::Attributes),
+        ("move_up", Action::MoveUp),
+        ("move_down", Action::MoveDown),
+        ("page_up", Action::PageUp),
+        ("page_down", Action::PageDown),
+        ("focus_preview", Action::FocusPreview),
+        ("retry_preview", Action::RetryPreview),
+        ("top", Action::Top),
+        ("bottom", Action::Bottom),
+        ("open", Action::Open),
+        ("open_default", Action::OpenDefault),
+        ("actions", Action::Actions),
+        ("back", Action::Back),
+        ("forward", Action::Forward),
+        ("jump_back", Action::JumpBack),
+        ("jump_forward", Action::JumpForward),
+        ("mark", Action::Mark),
+        ("visual", Action::Visual),
+        ("mark_all", Action::MarkAll),
+        ("clear_marks", Action::ClearMarks),
+        ("collect", Action::Collect),
+        ("scratch", Action::Scratch),
+        ("refresh", Action::Refresh),
+        ("copy", Action::Copy),
+        ("move", Action::Move),
+        ("symlink", Action::Symlink),
+        ("hardlink", Action::Hardlink),
+        ("rename", Action::Rename),
+        ("trash", Action::Trash),
+        ("delete", Action::Delete),
+        ("open_trash", Action::OpenTrash),
+        ("cleanup", Action::Cleanup),
+        ("cargo_build", Action::Cargo(Task::Build)),
+        ("cargo_test", Action::Cargo(Task::Test)),
+        ("cargo_clean", Action::Cargo(Task::Clean)),
+        ("scripts", Action::Scripts),
+        ("tasks", Action::Tasks),
+        ("send_to", Action::SendTo),
+        ("touch", Action::Touch),
+        ("new_file", Action::NewFile),
+        ("paste_file", Action::PasteFile),
+        ("new_dir", Action::NewDir),
+        ("extract", Action::Extract),
+        ("compress", Action::Compress),
+        ("reveal", Action::Reveal),
+        ("qr", Action::Qr),
+        ("versions", Action::Versions),
+        ("share", Action::Share),
+        ("jobs", Action::Jobs),
+        ("annotate", Action::Annotate),
+        ("new_tab", Action::NewTab),
+        ("close_tab", Action::CloseTab),
+        ("next_tab", Action::NextTab),
+        ("prev_tab", Action::PrevTab),
+        ("switch_pane", Action::SwitchPane),
+        ("dual_pane", Action::DualPane),
+        ("split_side", Action::SplitSide),
+        ("split_below", Action::SplitBelow),
+        ("close_split", Action::CloseSplit),
+        ("sync_panes", Action::SyncPanes),
+        ("diff_panes", Action::DiffPanes),
+        ("focus_left", Action::FocusLeft),
+        ("focus_right", Action::FocusRight),
+        ("focus_up", Action::FocusUp),
+        ("focus_down", Action::FocusDown),
+        ("preview_overlay", Action::PreviewOverlay),
+        ("command", Action::Command),
+        ("bookmark", Action::Bookmark),
+        ("bookmarks", Action::Bookmarks),
+        ("goto", Action::Goto),
+        ("save_filter", Action::SaveFilter),
+        ("only_dirs", Action::OnlyDirs),
+        ("only_files", Action::OnlyFiles),
+        ("only_executables", Action::OnlyExecutables),
+        ("only_images", Action::OnlyImages),
+        ("only_archives", Action::OnlyArchives),
+        ("all_kinds", Action::AllKinds),
+        ("rate_0", Action::Rate(0)),
+        ("rate_1", Action::Rate(1)),
+        ("rate_2", Action::Rate(2)),
+        ("rate_3", Action::Rate(3)),
+        ("rate_4", Action::Rate(4)),
+        ("rate_5", Action::Rate(5)),
+        ("min_rating_0", Action::MinRating(0)),
+        ("min_rating_1", Action::MinRating(1)),
+        ("min_rating_2", Action::MinRating(2)),
+        ("min_rating_3", Action::MinRating(3)),
+        ("min_rating_4", Action::MinRating(4)),
+        ("min_rating_5", Action::MinRating(5)),
+        ("settings", Action::Settings),
+        ("log", Action::Log),
+    ];
+
+    /// Whether the action changes the files in the current directory, which
+    /// can't be done inside an archive.
+    pub fn changes_files(self) -> bool {
+        matches!(
+            self,
+            Action::Move
+                | Action::Rename
+                | Action::Trash
+                | Action::Delete
+                | Action::Touch
+                | Action::NewFile
+                | Action::PasteFile
+                | Action::NewDir
+                | Action::Extract
+                | Action::Compress
+                | Action::Attributes
+                | Action::QuickEdit
+                | Action::Cleanup
+        )
+    }
+
+    /// Parses the snake_case name used in the config, e.g. `move_down`.
+    pub fn parse(name: &str) -> Option<Action> {
+        let name = name.trim().to_ascii_lowercase().replace('-', "_");
+        Action::ALL
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, action)| *action)
+    }
+}
+
+/// The built-in bindings, as `[keys]` entries would write them.
+const DEFAULTS: &[(&str, Action)] = &[
+    ("q", Action::Quit),
+    ("Q", Action::QuitCd),
+    ("/", Action::Search),
+    ("ctrl+f", Action::Grep),
+    (".", Action::ToggleHidden),
+    ("alt+.", Action::ToggleHidden),
+    ("alt+g", Action::ToggleIgnored),
+    ("f", Action::Filter),
+    ("s", Action::Sort),
+    ("F", Action::DirsFirst),
+    ("i", Action::Detailed),
+    ("e", Action::Editor),
+    ("E", Action::QuickEdit),
+    ("P", Action::Permissions),
+    ("alt+o", Action::Owner),
+    ("alt+p", Action::Attributes),
+    ("up", Action::MoveUp),
+    ("down", Action::MoveDown),
+    ("pageup", Action::PageUp),
+    ("pagedown", Action::PageDown),
+    ("l", Action::FocusPreview),
+    ("ctrl+r", Action::RetryPreview),
+    ("g g", Action::Top),
+    ("home", Action::Top),
+    ("G", Action::Bottom),
+    ("end", Action::Bottom),
+    ("enter", Action::Open),
+    ("o", Action::OpenDefault),
+    ("a", Action::Actions),
+    ("backspace", Action::Back),
+    ("alt+left", Action::Back),
+    ("alt+right", Action::Forward),
+    ("ctrl+o", Action::JumpBack),
+    ("ctrl+n", Action::JumpForward),
+    ("space", Action::Mark),
+    ("V", Action::Visual),
+    ("A", Action::MarkAll),
+    ("y", Action::Collect),
+    ("Y", Action::Scratch),
+    ("esc", Action::ClearMarks),
+    ("R", Action::Refresh),
+    ("c", Action::Copy),
+    ("m", Action::Move),
+    ("alt+s", Action::Symlink),
+    ("alt+h", Action::Hardlink),
+    ("r", Action::Rename),
+    ("d", Action::Trash),
+    ("D", Action::Delete),
+    ("U", Action::OpenTrash),
+    ("C", Action::Cleanup),
+    ("K b", Action::Cargo(Task::Build)),
+    ("K t", Action::Cargo(Task::Test)),
+    ("K c", Action::Cargo(Task::Clean)),
+    ("K r", Action::Scripts),
+    ("K m", Action::Tasks),
+    ("S", Action::SendTo),
+    ("T", Action::Touch),
+    ("n", Action::NewFile),
+    ("ctrl+v", Action::PasteFile),
+    ("N", Action::NewDir),
+    ("x", Action::Extract),
+    ("z", Action::Compress),
+    ("O", Action::Reveal),
+    ("alt+q", Action::Qr),
+    ("v", Action::Versions),
+    ("H", Action::Share),
+    ("J", Action::Jobs),
+    ("#", Action::Annotate),
+    ("t", Action::NewTab),
+    ("ctrl+w", Action::CloseTab),
+    ("tab", Action::SwitchPane),
+    ("backtab", Action::PrevTab),
+    ("|", Action::DualPane),
+    ("ctrl+x v", Action::SplitSide),
+    ("ctrl+x s", Action::SplitBelow),
+    ("ctrl+x x", Action::CloseSplit),
+    ("=", Action::SyncPanes),
+    ("ctrl+d", Action::DiffPanes),
+    ("ctrl+x h", Action::FocusLeft),
+    ("ctrl+x left", Action::FocusLeft),
+    ("ctrl+x l", Action::FocusRight),
+    ("ctrl+x right", Action::FocusRight),
+    ("ctrl+x k", Action::FocusUp),
+    ("ctrl+x up", Action::FocusUp),
+    ("ctrl+x j", Action::FocusDown),
+    ("ctrl+x down", Action::FocusDown),
+    ("p", Action::PreviewOverlay),
+    (":", Action::Command),
+    ("b", Action::Bookmark),
+    ("'", Action::Bookmarks),
+    ("g p", Action::Goto),
+    ("B", Action::SaveFilter),
+    ("alt+d", Action::OnlyDirs),
+    ("alt+f", Action::OnlyFiles),
+    ("alt+x", Action::OnlyExecutables),
+    ("alt+i", Action::OnlyImages),
+    ("alt+a", Action::OnlyArchives),
+    ("alt+0", Action::AllKinds),
+    ("* 0", Action::Rate(0)),
+    ("* 1", Action::Rate(1)),
+    ("* 2", Action::Rate(2)),
+    ("* 3", Action::Rate(3)),
+    ("* 4", Action::Rate(4)),
+    ("* 5", Action::Rate(5)),
+    ("alt+1", Action::MinRating(1)),
+    ("alt+2", Action::MinRating(2)),
+    ("alt+3", Action::MinRating(3)),
+    ("alt+4", Action::MinRating(4)),
+    ("alt+5", Action::MinRating(5)),
+    (",", Action::Settings),
+    ("L", Action::Log),
+];
+
+/// Parses a key sequence: chords separated by spaces (`g g`, `ctrl+x d`),
+/// or a run of plain characters written together (`gg`).
+pub fn parse_sequence(spec: &str) -> Option<Vec<Chord>> {
+    let mut sequence = Vec::new();
+    for part in spec.split_whitespace() {
+        if let Some(chord) = Chord::parse(part) {
+            sequence.push(chord);
+        } else if part.chars().all(|c| c.is_ascii_alphanumeric()) {
+            sequence.extend(part.chars().map(Chord::from_char));
+        } else {
+            return None;
+        }
+    }
+    (!sequence.is_empty()).then_some(sequence)
+}
+
+/// Outcome of feeding a key to the keymap.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lookup {
+    Action(Action),
+    /// The keys so far start a longer binding.
+    Pending,
+    None,
+}
+
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: Vec<(Vec<Chord>, Action)>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let bindings = DEFAULTS
+            .iter()
+            .map(|(spec, action)| (parse_sequence(spec).unwrap(), *action))
+            .collect();
+        Self { bindings }
+    }
+}
+
+impl Keymap {
+    /// Applies a `[keys]` entry. The value is an action name, `none` to
+    /// unbind the keys, or another key whose current action they take on
+    /// (`j = "down"`). Entries that don't parse leave the keymap as it was.
+    pub fn bind(&mut self, keys: &str, value: &str) -> error::Result<()> {
+        let sequence =
+            parse_sequence(keys).ok_or_else(|| BrowrsError::invalid(keys, "Not a key sequence"))?;
+        let action = match Action::parse(value) {
+            Some(action) => Some(action),
+            None if value.trim() == "none" => None,
+            None => match parse_sequence(value).map(|target| self.lookup(&target)) {
+                Some(Lookup::Action(action)) => Some(action),
+                _ => return Err(BrowrsError::invalid(value, "No action or bound key named")),
+            },
+        };
+        self.bindings.retain(|(keys, _)| *keys != sequence);
+        if let Some(action) = action {
+            self.bindings.push((sequence, action));
+        }
+        Ok(())
+    }
+
+    pub fn lookup(&self, keys: &[Chord]) -> Lookup {
+        if let Some((_, action)) = self.bindings.iter().find(|(k, _)| k == keys) {
+            return Lookup::Action(*action);
+        }
+        if self.bindings.iter().any(|(k, _)| k.starts_with(keys)) {
+            return Lookup::Pending;
+        }
+        Lookup::None
+    }
+
+    /// The first sequence bound to `action`, for hints.
+    pub fn keys_for(&self, action: Action) -> Option<&[Chord]> {
+        self.bindings
+            .iter()
+            .find(|(_, a)| *a == action)
+            .map(|(keys, _)| keys.as_slice())
+    }
+}
@@ -0,0 +1,90 @@
+//! Quick filters by kind of entry, toggled with a key each and layered
+//! over the pattern filter. With several on, entries of any of them stay.
+
+use std::path::Path;
+
+use crate::{archive::Format, entry::Entry, preview::IMAGE_EXTENSIONS};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Dirs,
+    Files,
+    Executables,
+    Images,
+    Archives,
+}
+
+impl Kind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Kind::Dirs => "dirs",
+            Kind::Files => "files",
+            Kind::Executables => "executables",
+            Kind::Images => "images",
+            Kind::Archives => "archives",
+        }
+    }
+
+    /// Whether `entry`, listed in `dir`, is of this kind.
+    pub fn matches(self, dir: &Path, entry: &Entry) -> bool {
+        let extension = Path::new(&entry.name)
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase());
+        match self {
+            Kind::Dirs => entry.is_dir,
+            Kind::Files => !entry.is_dir,
+            Kind::Executables => !entry.is_dir && is_executable(&dir.join(&entry.name)),
+            Kind::Images => {
+                !entry.is_dir && extension.is_some_and(|ext| IMAGE_EXTENSIONS.contains(&&*ext))
+            }
+            Kind::Archives => !entry.is_dir && Format::detect(Path::new(&entry.name)).is_some(),
+        }
+    }
+}
+
+/// The kinds toggled on, in the order they were.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Kinds(Vec<Kind>);
+
+impl Kinds {
+    pub fn toggle(&mut self, kind: Kind) {
+        match self.0.iter().position(|&k| k == kind) {
+            Some(index) => {
+                self.0.remove(index);
+            }
+            None => self.0.push(kind),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Whether `entry` stays listed: `..` always does.
+    pub fn keeps(&self, dir: &Path, entry: &Entry) -> bool {
+        self.0.is_empty() || entry.is_parent() || self.0.iter().any(|kind| kind.matches(dir, entry))
+    }
+
+    /// For the pane's title, like `only dirs+images`.
+    pub fn label(&self) -> String {
+        let names: Vec<&str> = self.0.iter().map(|kind| kind.label()).collect();
+        format!("only {}", names.join("+"))
+    }
+}
+
+#[cfg(unix)]
+pub fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path).is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+pub fn is_executable(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| matches!(ext.as_str(), "exe" | "bat" | "cmd" | "com" | "ps1"))
+}
@@ -0,0 +1,120 @@
+//! browrs, a terminal file browser. The binary only sets up the terminal
+//! and runs [`App`]; everything else lives here so integration tests can
+//! drive the app directly.
+
+pub mod annotations;
+mod ansi;
+mod app;
+mod archive;
+#[cfg(unix)]
+mod attrs;
+pub mod bookmarks;
+pub mod cargo;
+mod checksum;
+pub mod cleanup;
+pub mod cli;
+pub mod config;
+pub mod crash;
+mod datetime;
+mod desktop;
+mod diff;
+mod download;
+mod editor;
+pub mod entry;
+pub mod error;
+mod export;
+mod file_actions;
+pub mod file_colors;
+mod filter;
+mod git;
+mod graphics;
+mod grep;
+mod hex;
+mod highlight;
+mod history;
+mod ignore;
+mod image;
+mod jobs;
+pub mod keymap;
+pub mod keys;
+mod kinds;
+mod line_count;
+pub mod log;
+mod media;
+mod notes;
+mod npm;
+pub mod opener;
+mod ops;
+pub mod pane;
+mod permissions;
+mod picker;
+mod prefetch;
+mod preview;
+pub mod previewer;
+mod prompt;
+mod pseudo;
+mod qr;
+mod quick_edit;
+pub mod recall;
+mod search;
+mod sendto;
+mod serve;
+pub mod settings;
+pub mod shell;
+pub mod smart;
+mod snapshots;
+pub mod sort;
+mod systemd;
+pub mod tab;
+mod tasks;
+mod template;
+pub mod theme;
+mod touch;
+mod trash;
+mod url;
+mod vfs;
+pub mod visits;
+pub mod walk;
+mod watch;
+
+use crossterm::{
+    event::{
+        DisableBracketedPaste, DisableFocusChange, DisableMouseCapture, EnableBracketedPaste,
+        EnableFocusChange, EnableMouseCapture,
+    },
+    style::Print,
+};
+use ratatui::DefaultTerminal;
+
+pub use app::App;
+pub use config::Config;
+
+/// Asks the terminal to save its window title, to be put back by
+/// [`POP_TITLE`] after browrs has changed it.
+const PUSH_TITLE: &str = "\x1b[22;0t";
+const POP_TITLE: &str = "\x1b[23;0t";
+
+/// Sets up the terminal, with bracketed paste so pasted text arrives whole
+/// and the mouse and focus changes reported as events.
+pub fn init_terminal() -> DefaultTerminal {
+    let terminal = ratatui::init();
+    let _ = crossterm::execute!(
+        std::io::stdout(),
+        EnableBracketedPaste,
+        EnableMouseCapture,
+        EnableFocusChange,
+        Print(PUSH_TITLE)
+    );
+    terminal
+}
+
+pub fn restore_terminal() {
+    let _ = crossterm::execute!(
+        std::io::stdout(),
+        DisableMouseCapture,
+        DisableBracketedPaste,
+        DisableFocusChange,
+        Print(POP_TITLE)
+    );
+    ratatui::restore();
+}
@@ -0,0 +1,54 @@
+//! Line counts of small text files for the listing's optional column,
+//! counted when first shown and kept until the file changes.
+
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::entry::Entry;
+
+/// Files bigger than this aren't counted.
+const MAX_SIZE: u64 = 256 * 1024;
+
+/// A file's modification time when it was counted, and its count; `None`
+/// for files that aren't text.
+type Counted = (Option<SystemTime>, Option<usize>);
+
+#[derive(Debug, Default)]
+pub struct LineCounts {
+    cache: RefCell<HashMap<PathBuf, Counted>>,
+}
+
+impl LineCounts {
+    /// Lines in `entry` of `dir`, if it is a small text file.
+    pub fn get(&self, dir: &Path, entry: &Entry) -> Option<usize> {
+        // Reading a named pipe or a device may never end
+        if entry.is_dir || entry.special().is_some() || entry.size > MAX_SIZE {
+            return None;
+        }
+        let path = dir.join(&entry.name);
+        if let Some((modified, count)) = self.cache.borrow().get(&path)
+            && *modified == entry.modified
+        {
+            return *count;
+        }
+        let count = count_lines(&path);
+        self.cache
+            .borrow_mut()
+            .insert(path, (entry.modified, count));
+        count
+    }
+}
+
+fn count_lines(path: &Path) -> Option<usize> {
+    let bytes = std::fs::read(path).ok()?;
+    if bytes.contains(&0) {
+        return None;
+    }
+    let newlines = bytes.iter().filter(|&&b| b == b'\n').count();
+    // A last line without a newline still counts
+    Some(newlines + usize::from(bytes.last().is_some_and(|&b| b != b'\n')))
+}
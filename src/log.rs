@@ -0,0 +1,246 @@
+//! A small leveled log, written to `$XDG_STATE_HOME/browrs/browrs.log` and
+//! kept in memory for the log popup. The file is rolled over once it gets
+//! large, keeping a few old ones as `browrs.log.1`, `browrs.log.2`, ...
+//!
+//! The memory keeps info lines even when the file doesn't, so the popup
+//! and crash reports can say what led up to a problem. Nothing is logged
+//! until [`init`] is called, so the library stays quiet in tests.
+
+use std::{
+    collections::VecDeque,
+    fmt,
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{
+        Mutex,
+        atomic::{AtomicU8, Ordering},
+    },
+    time::{Instant, SystemTime},
+};
+
+use crate::datetime;
+
+/// The file is rolled over past this size.
+const MAX_SIZE: u64 = 1024 * 1024;
+
+/// Rolled-over files kept next to the current one.
+const KEEP: usize = 3;
+
+/// Lines kept in memory for the log popup.
+const RECENT: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error = 1,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl Level {
+    /// The level for `-v` given `count` times; warnings and errors only
+    /// without it.
+    pub fn from_verbosity(count: usize) -> Level {
+        match count {
+            0 => Level::Warn,
+            1 => Level::Info,
+            2 => Level::Debug,
+            _ => Level::Trace,
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Level> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "error" => Some(Level::Error),
+            "warn" | "warning" => Some(Level::Warn),
+            "info" => Some(Level::Info),
+            "debug" => Some(Level::Debug),
+            "trace" => Some(Level::Trace),
+            _ => None,
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            Level::Error => "ERROR",
+            Level::Warn => "WARN",
+            Level::Info => "INFO",
+            Level::Debug => "DEBUG",
+            Level::Trace => "TRACE",
+        }
+    }
+}
+
+struct Logger {
+    /// The most verbose level written to the file.
+    level: Level,
+    path: Option<PathBuf>,
+    file: Option<File>,
+    written: u64,
+    recent: VecDeque<String>,
+}
+
+/// The most verbose level logged anywhere, 0 before [`init`].
+static LEVEL: AtomicU8 = AtomicU8::new(0);
+
+static LOGGER: Mutex<Option<Logger>> = Mutex::new(None);
+
+fn default_path() -> Option<PathBuf> {
+    dirs::state_dir()
+        .or_else(dirs::cache_dir)
+        .map(|dir| dir.join("browrs").join("browrs.log"))
+}
+
+/// Starts logging at `level` to `path`, or to the default file. Returns the
+/// file in use; lines are still kept in memory when it can't be opened.
+pub fn init(level: Level, path: Option<PathBuf>) -> Option<PathBuf> {
+    let path = path.or_else(default_path);
+    let file = path.as_deref().and_then(|path| {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).ok()?;
+        }
+        File::options().create(true).append(true).open(path).ok()
+    });
+    let written = file
+        .as_ref()
+        .and_then(|f| f.metadata().ok())
+        .map_or(0, |m| m.len());
+    let path = file.is_some().then_some(path).flatten();
+    *LOGGER.lock().unwrap_or_else(|e| e.into_inner()) = Some(Logger {
+        level,
+        path: path.clone(),
+        file,
+        written,
+        recent: VecDeque::new(),
+    });
+    LEVEL.store(level.max(Level::Info) as u8, Ordering::Relaxed);
+    path
+}
+
+pub fn enabled(level: Level) -> bool {
+    level as u8 <= LEVEL.load(Ordering::Relaxed)
+}
+
+/// The log file, if logging started and it could be opened.
+pub fn path() -> Option<PathBuf> {
+    let logger = LOGGER.lock().unwrap_or_else(|e| e.into_inner());
+    logger.as_ref().and_then(|logger| logger.path.clone())
+}
+
+/// The most recent lines, oldest first.
+pub fn recent() -> Vec<String> {
+    let logger = LOGGER.lock().unwrap_or_else(|e| e.into_inner());
+    logger
+        .as_ref()
+        .map(|logger| logger.recent.iter().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Writes one line; use the `error!`, `info!`, ... macros instead,
+/// which skip formatting when the level is off.
+pub fn write(level: Level, args: fmt::Arguments) {
+    let now = SystemTime::now();
+    let millis = now
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |d| d.subsec_millis());
+    let line = format!(
+        "{}.{:03} {:<5} {}",
+        datetime::local(now).iso(),
+        millis,
+        level.label(),
+        args
+    );
+
+    // A panic while logging shouldn't take the log down with it
+    let mut guard = LOGGER.lock().unwrap_or_else(|e| e.into_inner());
+    let Some(logger) = guard.as_mut() else {
+        return;
+    };
+    if logger.recent.len() == RECENT {
+        logger.recent.pop_front();
+    }
+    logger.recent.push_back(line.clone());
+    if level > logger.level {
+        return;
+    }
+
+    if logger.written + line.len() as u64 + 1 > MAX_SIZE
+        && let Some(path) = logger.path.clone()
+    {
+        logger.file = None;
+        roll(&path);
+        logger.file = File::create(&path).ok();
+        logger.written = 0;
+    }
+    if let Some(file) = logger.file.as_mut()
+        && writeln!(file, "{}", line).is_ok()
+    {
+        logger.written += line.len() as u64 + 1;
+    }
+}
+
+/// Shifts `browrs.log` to `browrs.log.1`, `.1` to `.2` and so on, dropping
+/// the oldest.
+fn roll(path: &Path) {
+    let numbered = |n: usize| {
+        let mut name = path.as_os_str().to_owned();
+        name.push(format!(".{}", n));
+        PathBuf::from(name)
+    };
+    let _ = std::fs::remove_file(numbered(KEEP));
+    for n in (1..KEEP).rev() {
+        let _ = std::fs::rename(numbered(n), numbered(n + 1));
+    }
+    let _ = std::fs::rename(path, numbered(1));
+}
+
+/// Logs how long something took when dropped, at debug level.
+pub struct Timer {
+    what: String,
+    start: Instant,
+}
+
+impl Drop for Timer {
+    fn drop(&mut self) {
+        write(
+            Level::Debug,
+            format_args!("{} took {:.1?}", self.what, self.start.elapsed()),
+        );
+    }
+}
+
+/// Times the rest of the scope, if debug logging is on.
+pub fn timer(what: impl FnOnce() -> String) -> Option<Timer> {
+    enabled(Level::Debug).then(|| Timer {
+        what: what(),
+        start: Instant::now(),
+    })
+}
+
+macro_rules! event {
+    ($level:expr, $($arg:tt)*) => {
+        if $crate::log::enabled($level) {
+            $crate::log::write($level, format_args!($($arg)*));
+        }
+    };
+}
+
+macro_rules! error {
+    ($($arg:tt)*) => { $crate::log::event!($crate::log::Level::Error, $($arg)*) };
+}
+
+macro_rules! info {
+    ($($arg:tt)*) => { $crate::log::event!($crate::log::Level::Info, $($arg)*) };
+}
+
+macro_rules! debug {
+    ($($arg:tt)*) => { $crate::log::event!($crate::log::Level::Debug, $($arg)*) };
+}
+
+macro_rules! trace {
+    ($($arg:tt)*) => { $crate::log::event!($crate::log::Level::Trace, $($arg)*) };
+}
+
+pub(crate) use {debug, error, event, info, trace};
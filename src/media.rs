@@ -0,0 +1,780 @@
+//! Duration, streams and tags of audio and video files, read from their
+//! container headers: ID3 and MPEG frame headers for MP3, the metadata
+//! blocks of FLAC, the `moov` box of MP4 and the header elements of
+//! Matroska and WebM.
+
+use std::{
+    fs::File,
+    io::{self, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+use ratatui::text::{Line, Text};
+
+use crate::jobs;
+
+pub const EXTENSIONS: [&str; 9] = [
+    "mp3", "flac", "mp4", "m4a", "m4v", "mov", "mkv", "mka", "webm",
+];
+
+/// Header bytes read from the start of MP3 and Matroska files.
+const HEAD_SIZE: u64 = 1024 * 1024;
+/// A `moov` box bigger than this isn't read.
+const MAX_MOOV: u64 = 64 * 1024 * 1024;
+
+/// What was found in a file's headers.
+#[derive(Debug, Default)]
+pub struct Info {
+    pub format: String,
+    /// In seconds.
+    pub duration: Option<f64>,
+    /// Overall, in bits per second.
+    pub bitrate: Option<u64>,
+    pub video: Option<Video>,
+    pub audio: Option<Audio>,
+    /// Title, artist, album and the like, in the order found.
+    pub tags: Vec<(String, String)>,
+}
+
+#[derive(Debug, Default)]
+pub struct Video {
+    pub codec: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Default)]
+pub struct Audio {
+    pub codec: String,
+    pub sample_rate: Option<u32>,
+    pub channels: Option<u16>,
+}
+
+/// Reads what it can of `path`'s metadata, by its extension.
+pub fn probe(path: &Path) -> io::Result<Info> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_lowercase)
+        .unwrap_or_default();
+    let mut file = File::open(path)?;
+    let size = file.metadata()?.len();
+    let mut info = match extension.as_str() {
+        "mp3" => mp3(&read_head(&mut file)?, size),
+        "flac" => flac(&mut file),
+        "mkv" | "mka" | "webm" => matroska(&read_head(&mut file)?),
+        _ => mp4(&mut file, size),
+    }
+    .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "unrecognised header"))?;
+    if info.bitrate.is_none()
+        && let Some(duration) = info.duration.filter(|d| *d > 0.0)
+    {
+        info.bitrate = Some((size as f64 * 8.0 / duration) as u64);
+    }
+    Ok(info)
+}
+
+/// The preview pane text for a media file.
+pub fn preview(name: &str, size: u64, info: &Info) -> Text<'static> {
+    let icon = if info.video.is_some() { "🎬" } else { "🎵" };
+    let mut lines = vec![
+        Line::from(format!(
+            "{} {} | {} | {}",
+            icon,
+            name,
+            jobs::format_size(size),
+            info.format
+        )),
+        Line::from("─".repeat(40)),
+    ];
+    let mut field = |label: &str, value: String| {
+        lines.push(Line::from(format!(
+            "{:<9} {}",
+            format!("{}:", label),
+            value
+        )));
+    };
+    if let Some(duration) = info.duration {
+        field("Duration", format_duration(duration));
+    }
+    if let Some(bitrate) = info.bitrate.filter(|b| *b >= 1000) {
+        field("Bitrate", format!("{} kb/s", bitrate / 1000));
+    }
+    if let Some(video) = &info.video {
+        let size = if video.width > 0 {
+            format!(", {}×{}", video.width, video.height)
+        } else {
+            String::new()
+        };
+        field("Video", format!("{}{}", video.codec, size));
+    }
+    if let Some(audio) = &info.audio {
+        let mut parts = vec![audio.codec.clone()];
+        if let Some(rate) = audio.sample_rate {
+            parts.push(format!("{} kHz", rate as f64 / 1000.0));
+        }
+        match audio.channels {
+            Some(1) => parts.push("mono".to_string()),
+            Some(2) => parts.push("stereo".to_string()),
+            Some(n) => parts.push(format!("{} channels", n)),
+            None => {}
+        }
+        field("Audio", parts.join(", "));
+    }
+    if !info.tags.is_empty() {
+        lines.push(Line::from(""));
+        for (key, value) in &info.tags {
+            lines.push(Line::from(format!("{:<9} {}", format!("{}:", key), value)));
+        }
+    }
+    Text::from(lines)
+}
+
+/// `1:02:03` or `2:03`.
+fn format_duration(seconds: f64) -> String {
+    let total = seconds.round() as u64;
+    let (hours, minutes, seconds) = (total / 3600, total / 60 % 60, total % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+fn read_head(file: &mut File) -> io::Result<Vec<u8>> {
+    let mut head = Vec::new();
+    file.take(HEAD_SIZE).read_to_end(&mut head)?;
+    Ok(head)
+}
+
+fn be(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0, |n, &b| n << 8 | b as u64)
+}
+
+fn le(bytes: &[u8]) -> u64 {
+    bytes.iter().rev().fold(0, |n, &b| n << 8 | b as u64)
+}
+
+/// Adds a tag under a friendly name, if it is one worth showing.
+fn push_tag(tags: &mut Vec<(String, String)>, key: &str, value: String) {
+    let label = match key.to_ascii_uppercase().as_str() {
+        "TITLE" | "TIT2" | "TT2" | "©NAM" => "Title",
+        "ARTIST" | "TPE1" | "TP1" | "©ART" => "Artist",
+        "ALBUM" | "TALB" | "TAL" | "©ALB" => "Album",
+        "DATE" | "YEAR" | "TYER" | "TDRC" | "TYE" | "©DAY" => "Year",
+        "GENRE" | "TCON" | "TCO" | "©GEN" => "Genre",
+        "TRACKNUMBER" | "TRCK" | "TRK" => "Track",
+        _ => return,
+    };
+    let value = value.trim_matches(char::from(0)).trim().to_string();
+    if !value.is_empty() && !tags.iter().any(|(k, _)| k == label) {
+        tags.push((label.to_string(), value));
+    }
+}
+
+// MP3
+
+fn mp3(head: &[u8], size: u64) -> Option<Info> {
+    let mut info = Info {
+        format: "MP3".to_string(),
+        ..Info::default()
+    };
+    let mut start = 0;
+    if head.starts_with(b"ID3") && head.len() >= 10 {
+        let tag_size = synchsafe(&head[6..10]) as usize;
+        let end = (10 + tag_size).min(head.len());
+        id3(head[3], &head[10..end], &mut info.tags);
+        start = 10 + tag_size;
+    }
+    // The first frame header after the tag
+    let offset = (start..head.len().saturating_sub(4))
+        .find(|&i| head[i] == 0xff && head[i + 1] & 0xe0 == 0xe0 && frame(&head[i..]).is_some())?;
+    let frame = frame(&head[offset..])?;
+    info.audio = Some(Audio {
+        codec: frame.codec(),
+        sample_rate: Some(frame.sample_rate),
+        channels: Some(if frame.mono { 1 } else { 2 }),
+    });
+    let audio_bytes = size.saturating_sub(offset as u64);
+    // A Xing or Info header counts the frames of a variable bitrate file
+    let xing = offset + 4 + frame.side_info();
+    let frames = match head.get(xing..xing + 12) {
+        Some(x) if (&x[..4] == b"Xing" || &x[..4] == b"Info") && x[7] & 1 == 1 => {
+            Some(be(&x[8..12]))
+        }
+        _ => None,
+    };
+    info.duration = match frames {
+        Some(frames) => Some(frames as f64 * frame.samples() as f64 / frame.sample_rate as f64),
+        None if frame.bitrate > 0 => Some(audio_bytes as f64 * 8.0 / frame.bitrate as f64),
+        None => None,
+    };
+    info.bitrate = match (frames, info.duration) {
+        (Some(_), Some(duration)) if duration > 0.0 => {
+            Some((audio_bytes as f64 * 8.0 / duration) as u64)
+        }
+        _ => Some(frame.bitrate as u64),
+    };
+    Some(info)
+}
+
+fn synchsafe(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0, |n, &b| n << 7 | (b & 0x7f) as u64)
+}
+
+/// Text frames of an ID3v2.2, 2.3 or 2.4 tag.
+fn id3(version: u8, mut frames: &[u8], tags: &mut Vec<(String, String)>) {
+    let (id_len, header_len) = if version == 2 { (3, 6) } else { (4, 10) };
+    while frames.len() > header_len && frames[0] != 0 {
+        let id = String::from_utf8_lossy(&frames[..id_len]).to_string();
+        let size = match version {
+            2 => be(&frames[3..6]),
+            3 => be(&frames[4..8]),
+            _ => synchsafe(&frames[4..8]),
+        } as usize;
+        let Some(body) = frames.get(header_len..header_len + size) else {
+            return;
+        };
+        if id.starts_with('T') && !body.is_empty() {
+            push_tag(tags, &id, decode_text(body[0], &body[1..]));
+        }
+        frames = &frames[header_len + size..];
+    }
+}
+
+/// An ID3 text in the encoding named by its first byte.
+fn decode_text(encoding: u8, bytes: &[u8]) -> String {
+    match encoding {
+        1 | 2 => {
+            let mut units: Vec<u16> = bytes
+                .chunks_exact(2)
+                .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                .collect();
+            // Without a byte order mark, version 2 is big-endian
+            match units.first() {
+                Some(0xfffe) => {
+                    units = units.iter().map(|u| u.swap_bytes()).skip(1).collect();
+                }
+                Some(0xfeff) => {
+                    units.remove(0);
+                }
+                _ => {}
+            }
+            String::from_utf16_lossy(&units)
+        }
+        3 => String::from_utf8_lossy(bytes).to_string(),
+        _ => bytes.iter().map(|&b| b as char).collect(),
+    }
+}
+
+struct Frame {
+    /// 1 for MPEG-1, 2 for MPEG-2, 25 for MPEG-2.5.
+    version: u8,
+    layer: u8,
+    bitrate: u32,
+    sample_rate: u32,
+    mono: bool,
+}
+
+impl Frame {
+    fn codec(&self) -> String {
+        let version = match self.version {
+            25 => "2.5".to_string(),
+            v => v.to_string(),
+        };
+        let layer = ["I", "II", "III"][self.layer as usize - 1];
+        format!("MPEG-{} Layer {}", version, layer)
+    }
+
+    fn samples(&self) -> u32 {
+        match (self.layer, self.version) {
+            (1, _) => 384,
+            (3, 2 | 25) => 576,
+            _ => 1152,
+        }
+    }
+
+    /// Bytes between the header and where a Xing header would be.
+    fn side_info(&self) -> usize {
+        match (self.version, self.mono) {
+            (1, false) => 32,
+            (1, true) | (_, false) => 17,
+            (_, true) => 9,
+        }
+    }
+}
+
+fn frame(bytes: &[u8]) -> Option<Frame> {
+    let header = be(bytes.get(..4)?) as u32;
+    if header >> 21 != 0x7ff {
+        return None;
+    }
+    let version = match header >> 19 & 3 {
+        0 => 25,
+        2 => 2,
+        3 => 1,
+        _ => return None,
+    };
+    let layer = match header >> 17 & 3 {
+        1 => 3,
+        2 => 2,
+        3 => 1,
+        _ => return None,
+    };
+    const V1: [[u32; 15]; 3] = [
+        [
+            0, 32, 64, 96, 128, 160, 192, 224, 256, 288, 320, 352, 384, 416, 448,
+        ],
+        [
+            0, 32, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384,
+        ],
+        [
+            0, 32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320,
+        ],
+    ];
+    const V2: [[u32; 15]; 2] = [
+        [
+            0, 32, 48, 56, 64, 80, 96, 112, 128, 144, 160, 176, 192, 224, 256,
+        ],
+        [0, 8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160],
+    ];
+    let index = (header >> 12 & 0xf) as usize;
+    let rates = match version {
+        1 => [44100, 48000, 32000],
+        2 => [22050, 24000, 16000],
+        _ => [11025, 12000, 8000],
+    };
+    let sample_rate = *rates.get((header >> 10 & 3) as usize)?;
+    let kbps = match version {
+        1 => V1[layer as usize - 1].get(index)?,
+        _ => V2[(layer as usize).min(2) - 1].get(index)?,
+    };
+    Some(Frame {
+        version,
+        layer,
+        bitrate: kbps * 1000,
+        sample_rate,
+        mono: header >> 6 & 3 == 3,
+    })
+}
+
+// FLAC
+
+fn flac(file: &mut File) -> Option<Info> {
+    let mut magic = [0; 4];
+    file.read_exact(&mut magic).ok()?;
+    if &magic != b"fLaC" {
+        return None;
+    }
+    let mut info = Info {
+        format: "FLAC".to_string(),
+        ..Info::default()
+    };
+    loop {
+        let mut header = [0; 4];
+        file.read_exact(&mut header).ok()?;
+        let (last, kind, length) = (header[0] & 0x80 != 0, header[0] & 0x7f, be(&header[1..]));
+        match kind {
+            // STREAMINFO and VORBIS_COMMENT
+            0 | 4 => {
+                let mut block = vec![0; length as usize];
+                file.read_exact(&mut block).ok()?;
+                if kind == 0 {
+                    let bits = be(block.get(10..18)?);
+                    let sample_rate = (bits >> 44) as u32;
+                    let samples = bits & 0xf_ffff_ffff;
+                    info.audio = Some(Audio {
+                        codec: format!("FLAC {} bit", (bits >> 36 & 0x1f) + 1),
+                        sample_rate: Some(sample_rate),
+                        channels: Some((bits >> 41 & 7) as u16 + 1),
+                    });
+                    if sample_rate > 0 && samples > 0 {
+                        info.duration = Some(samples as f64 / sample_rate as f64);
+                    }
+                } else {
+                    vorbis_comments(&block, &mut info.tags);
+                }
+            }
+            _ => {
+                file.seek(SeekFrom::Current(length as i64)).ok()?;
+            }
+        }
+        if last {
+            break;
+        }
+    }
+    Some(info)
+}
+
+/// `KEY=value` comments after the vendor string, little-endian lengths.
+fn vorbis_comments(block: &[u8], tags: &mut Vec<(String, String)>) {
+    let read = |at: usize| block.get(at..at + 4).map(le);
+    let Some(vendor) = read(0) else {
+        return;
+    };
+    let mut at = 4 + vendor as usize;
+    let Some(count) = read(at) else {
+        return;
+    };
+    at += 4;
+    for _ in 0..count {
+        let Some(length) = read(at) else {
+            return;
+        };
+        let Some(comment) = block.get(at + 4..at + 4 + length as usize) else {
+            return;
+        };
+        if let Some((key, value)) = String::from_utf8_lossy(comment).split_once('=') {
+            push_tag(tags, key, value.to_string());
+        }
+        at += 4 + length as usize;
+    }
+}
+
+// MP4
+
+/// The boxes directly inside `data`, as type and contents.
+fn boxes(mut data: &[u8]) -> Vec<([u8; 4], &[u8])> {
+    let mut found = Vec::new();
+    while data.len() >= 8 {
+        let (size, kind) = (
+            be(&data[..4]) as usize,
+            [data[4], data[5], data[6], data[7]],
+        );
+        let (start, size) = match size {
+            0 => (8, data.len()),
+            1 if data.len() >= 16 => (16, be(&data[8..16]) as usize),
+            _ => (8, size),
+        };
+        if size < start || size > data.len() {
+            break;
+        }
+        found.push((kind, &data[start..size]));
+        data = &data[size..];
+    }
+    found
+}
+
+fn child<'a>(data: &'a [u8], kind: &[u8; 4]) -> Option<&'a [u8]> {
+    boxes(data)
+        .into_iter()
+        .find(|(k, _)| k == kind)
+        .map(|(_, d)| d)
+}
+
+fn mp4(file: &mut File, size: u64) -> Option<Info> {
+    let mut info = Info::default();
+    let mut moov = None;
+    let mut at = 0;
+    // Only the top-level headers are read, skipping over the media data
+    while at + 8 <= size && moov.is_none() {
+        file.seek(SeekFrom::Start(at)).ok()?;
+        let mut header = [0; 16];
+        file.read_exact(&mut header[..8]).ok()?;
+        let (mut length, kind) = (
+            be(&header[..4]),
+            [header[4], header[5], header[6], header[7]],
+        );
+        if length == 1 {
+            file.read_exact(&mut header[8..]).ok()?;
+            length = be(&header[8..]);
+        } else if length == 0 {
+            length = size - at;
+        }
+        if length < 8 {
+            return None;
+        }
+        match &kind {
+            b"ftyp" => {
+                let mut brand = [0; 4];
+                file.read_exact(&mut brand).ok()?;
+                info.format = match &brand {
+                    b"qt  " => "QuickTime".to_string(),
+                    b"M4A " | b"M4B " => "MPEG-4 audio".to_string(),
+                    _ => "MPEG-4".to_string(),
+                };
+            }
+            b"moov" if length <= MAX_MOOV => {
+                file.seek(SeekFrom::Start(at + 8)).ok()?;
+                let mut data = vec![0; length as usize - 8];
+                file.read_exact(&mut data).ok()?;
+                moov = Some(data);
+            }
+            _ => {}
+        }
+        at += length;
+    }
+    if info.format.is_empty() {
+        return None;
+    }
+    let moov = moov?;
+    if let Some(mvhd) = child(&moov, b"mvhd") {
+        let (scale, duration) = match mvhd.first()? {
+            1 => (be(mvhd.get(20..24)?), be(mvhd.get(24..32)?)),
+            _ => (be(mvhd.get(12..16)?), be(mvhd.get(16..20)?)),
+        };
+        if scale > 0 {
+            info.duration = Some(duration as f64 / scale as f64);
+        }
+    }
+    for (kind, trak) in boxes(&moov) {
+        if &kind == b"trak" {
+            track(trak, &mut info);
+        }
+    }
+    if let Some(ilst) = child(&moov, b"udta")
+        .and_then(|udta| child(udta, b"meta"))
+        .and_then(|meta| child(meta, b"ilst").or_else(|| child(meta.get(4..)?, b"ilst")))
+    {
+        for (kind, item) in boxes(ilst) {
+            if let Some(data) = child(item, b"data").and_then(|d| d.get(8..)) {
+                let key: String = kind.iter().map(|&b| b as char).collect();
+                push_tag(
+                    &mut info.tags,
+                    &key,
+                    String::from_utf8_lossy(data).to_string(),
+                );
+            }
+        }
+    }
+    Some(info)
+}
+
+/// The codec and its details from a track's first sample description.
+fn track(trak: &[u8], info: &mut Info) {
+    let Some(mdia) = child(trak, b"mdia") else {
+        return;
+    };
+    let handler = child(mdia, b"hdlr").and_then(|hdlr| hdlr.get(8..12));
+    let Some(entry) = child(mdia, b"minf")
+        .and_then(|minf| child(minf, b"stbl"))
+        .and_then(|stbl| child(stbl, b"stsd"))
+        .and_then(|stsd| stsd.get(8..))
+        .and_then(|entries| boxes(entries).into_iter().next())
+    else {
+        return;
+    };
+    let (format, body) = entry;
+    match handler {
+        Some(b"vide") if info.video.is_none() => {
+            info.video = Some(Video {
+                codec: codec_name(&format),
+                width: body.get(24..26).map_or(0, be) as u32,
+                height: body.get(26..28).map_or(0, be) as u32,
+            });
+        }
+        Some(b"soun") if info.audio.is_none() => {
+            info.audio = Some(Audio {
+                codec: codec_name(&format),
+                channels: body.get(16..18).map(|b| be(b) as u16),
+                sample_rate: body.get(24..26).map(|b| be(b) as u32),
+            });
+        }
+        _ => {}
+    }
+}
+
+fn codec_name(format: &[u8; 4]) -> String {
+    match format {
+        b"avc1" | b"avc3" => "H.264",
+        b"hvc1" | b"hev1" => "H.265",
+        b"av01" => "AV1",
+        b"vp09" => "VP9",
+        b"mp4v" => "MPEG-4 Visual",
+        b"mp4a" => "AAC",
+        b"Opus" => "Opus",
+        b"alac" => "ALAC",
+        b"ac-3" => "AC-3",
+        b"ec-3" => "E-AC-3",
+        b"fLaC" => "FLAC",
+        _ => return String::from_utf8_lossy(format).trim().to_string(),
+    }
+    .to_string()
+}
+
+// Matroska
+
+const EBML: u64 = 0x1a45dfa3;
+const DOC_TYPE: u64 = 0x4282;
+const SEGMENT: u64 = 0x18538067;
+const INFO: u64 = 0x1549a966;
+const TIMESTAMP_SCALE: u64 = 0x2ad7b1;
+const DURATION: u64 = 0x4489;
+const TITLE: u64 = 0x7ba9;
+const TRACKS: u64 = 0x1654ae6b;
+const TRACK_ENTRY: u64 = 0xae;
+const CODEC_ID: u64 = 0x86;
+const VIDEO: u64 = 0xe0;
+const PIXEL_WIDTH: u64 = 0xb0;
+const PIXEL_HEIGHT: u64 = 0xba;
+const AUDIO: u64 = 0xe1;
+const SAMPLING_FREQUENCY: u64 = 0xb5;
+const CHANNELS: u64 = 0x9f;
+const TAGS: u64 = 0x1254c367;
+const TAG: u64 = 0x7373;
+const SIMPLE_TAG: u64 = 0x67c8;
+const TAG_NAME: u64 = 0x45a3;
+const TAG_STRING: u64 = 0x4487;
+
+/// A variable-length integer; IDs keep their length marker, sizes don't.
+fn vint(data: &[u8], keep_marker: bool) -> Option<(u64, usize)> {
+    let first = *data.first()?;
+    let length = first.leading_zeros() as usize + 1;
+    if length > 8 {
+        return None;
+    }
+    let bytes = data.get(..length)?;
+    let value = be(bytes);
+    if keep_marker {
+        return Some((value, length));
+    }
+    let value = value & (u64::MAX >> (64 - 7 * length));
+    // All ones means the size is unknown: up to the end of the parent
+    let unknown = value == u64::MAX >> (64 - 7 * length);
+    Some((if unknown { u64::MAX } else { value }, length))
+}
+
+/// The elements directly inside `data`, as ID and contents.
+fn elements(mut data: &[u8]) -> Vec<(u64, &[u8])> {
+    let mut found = Vec::new();
+    while let Some((id, id_len)) = vint(data, true) {
+        let Some((size, size_len)) = vint(&data[id_len..], false) else {
+            break;
+        };
+        let start = id_len + size_len;
+        let end = start
+            .saturating_add(size.min(usize::MAX as u64) as usize)
+            .min(data.len());
+        found.push((id, &data[start..end]));
+        data = &data[end..];
+    }
+    found
+}
+
+fn float(data: &[u8]) -> Option<f64> {
+    match data.len() {
+        4 => Some(f32::from_bits(be(data) as u32) as f64),
+        8 => Some(f64::from_bits(be(data))),
+        _ => None,
+    }
+}
+
+fn matroska(head: &[u8]) -> Option<Info> {
+    let top = elements(head);
+    let (_, header) = top.iter().find(|(id, _)| *id == EBML)?;
+    let doc_type = elements(header)
+        .into_iter()
+        .find(|(id, _)| *id == DOC_TYPE)
+        .map(|(_, d)| String::from_utf8_lossy(d).to_string());
+    let mut info = Info {
+        format: match doc_type.as_deref() {
+            Some("webm") => "WebM".to_string(),
+            _ => "Matroska".to_string(),
+        },
+        ..Info::default()
+    };
+    let (_, segment) = top.into_iter().find(|(id, _)| *id == SEGMENT)?;
+    for (id, data) in elements(segment) {
+        match id {
+            INFO => {
+                let mut scale = 1_000_000.0;
+                let mut duration = None;
+                for (id, data) in elements(data) {
+                    match id {
+                        TIMESTAMP_SCALE => scale = be(data) as f64,
+                        DURATION => duration = float(data),
+                        TITLE => push_tag(
+                            &mut info.tags,
+                            "TITLE",
+                            String::from_utf8_lossy(data).to_string(),
+                        ),
+                        _ => {}
+                    }
+                }
+                info.duration = duration.map(|d| d * scale / 1e9);
+            }
+            TRACKS => {
+                for (_, entry) in elements(data)
+                    .into_iter()
+                    .filter(|(id, _)| *id == TRACK_ENTRY)
+                {
+                    matroska_track(entry, &mut info);
+                }
+            }
+            TAGS => {
+                for (_, tag) in elements(data).into_iter().filter(|(id, _)| *id == TAG) {
+                    for (_, simple) in elements(tag)
+                        .into_iter()
+                        .filter(|(id, _)| *id == SIMPLE_TAG)
+                    {
+                        let fields = elements(simple);
+                        let text = |wanted| {
+                            fields
+                                .iter()
+                                .find(|(id, _)| *id == wanted)
+                                .map(|(_, d)| String::from_utf8_lossy(d).to_string())
+                        };
+                        if let (Some(name), Some(value)) = (text(TAG_NAME), text(TAG_STRING)) {
+                            push_tag(&mut info.tags, &name, value);
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    Some(info)
+}
+
+fn matroska_track(entry: &[u8], info: &mut Info) {
+    let fields = elements(entry);
+    let find = |wanted| fields.iter().find(|(id, _)| *id == wanted).map(|(_, d)| *d);
+    let codec = find(CODEC_ID)
+        .map(|d| matroska_codec(&String::from_utf8_lossy(d)))
+        .unwrap_or_default();
+    if let Some(video) = find(VIDEO)
+        && info.video.is_none()
+    {
+        let fields = elements(video);
+        let number = |wanted| {
+            fields
+                .iter()
+                .find(|(id, _)| *id == wanted)
+                .map_or(0, |(_, d)| be(d))
+        };
+        info.video = Some(Video {
+            codec,
+            width: number(PIXEL_WIDTH) as u32,
+            height: number(PIXEL_HEIGHT) as u32,
+        });
+    } else if let Some(audio) = find(AUDIO)
+        && info.audio.is_none()
+    {
+        let fields = elements(audio);
+        let find = |wanted| fields.iter().find(|(id, _)| *id == wanted).map(|(_, d)| *d);
+        info.audio = Some(Audio {
+            codec,
+            sample_rate: find(SAMPLING_FREQUENCY).and_then(float).map(|f| f as u32),
+            channels: find(CHANNELS).map(|d| be(d) as u16),
+        });
+    }
+}
+
+fn matroska_codec(id: &str) -> String {
+    match id {
+        "V_MPEG4/ISO/AVC" => "H.264",
+        "V_MPEGH/ISO/HEVC" => "H.265",
+        "V_AV1" => "AV1",
+        "V_VP8" => "VP8",
+        "V_VP9" => "VP9",
+        "A_OPUS" => "Opus",
+        "A_VORBIS" => "Vorbis",
+        "A_FLAC" => "FLAC",
+        "A_AC3" => "AC-3",
+        "A_EAC3" => "E-AC-3",
+        "A_MPEG/L3" => "MP3",
+        id if id.starts_with("A_AAC") => "AAC",
+        id => return id.to_string(),
+    }
+    .to_string()
+}
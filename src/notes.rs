@@ -0,0 +1,42 @@
+use std::path::Path;
+
+/// Files that describe a directory, in order of preference.
+const NOTE_FILES: [&str; 5] = [
+    ".browrs-note",
+    "README.md",
+    "README",
+    "README.txt",
+    "readme.md",
+];
+
+/// A snippet of the note pinned to a directory.
+#[derive(Debug, Clone)]
+pub struct Note {
+    pub file_name: String,
+    pub lines: Vec<String>,
+}
+
+/// Returns the first `max_lines` non-blank lines of the directory's note file.
+pub fn find(dir: &Path, max_lines: usize) -> Option<Note> {
+    NOTE_FILES.iter().find_map(|name| {
+        let path = dir.join(name);
+        if !path.is_file() {
+            return None;
+        }
+        let content = std::fs::read_to_string(&path).ok()?;
+        let lines: Vec<String> = content
+            .lines()
+            .map(str::trim_end)
+            .filter(|line| !line.trim().is_empty())
+            .take(max_lines)
+            .map(str::to_string)
+            .collect();
+        if lines.is_empty() {
+            return None;
+        }
+        Some(Note {
+            file_name: name.to_string(),
+            lines,
+        })
+    })
+}
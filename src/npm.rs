@@ -0,0 +1,206 @@
+//! JavaScript projects: the package a directory belongs to, read from the
+//! nearest `package.json`, and its scripts run with the package manager
+//! its lock file belongs to.
+
+use std::{
+    collections::BTreeMap,
+    iter::Peekable,
+    path::{Path, PathBuf},
+    process::Command,
+    str::Chars,
+};
+
+pub const MANIFEST: &str = "package.json";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Package {
+    /// Where `package.json` is, and the scripts run.
+    pub dir: PathBuf,
+    pub name: String,
+    pub version: Option<String>,
+    /// Script names and their commands, in the order listed.
+    pub scripts: Vec<(String, String)>,
+    /// `npm`, `yarn`, `pnpm` or `bun`.
+    pub manager: &'static str,
+}
+
+impl Package {
+    /// The package with its manifest in `dir` or the nearest directory
+    /// above it; `None` if that manifest isn't valid JSON.
+    pub fn find(dir: &Path) -> Option<Self> {
+        let (dir, manifest) = dir.ancestors().find_map(|dir| {
+            let manifest = std::fs::read_to_string(dir.join(MANIFEST)).ok()?;
+            Some((dir, manifest))
+        })?;
+        let Value::Object(fields) = parse(&manifest)? else {
+            return None;
+        };
+        let string = |key: &str| match fields.iter().find(|(k, _)| k == key) {
+            Some((_, Value::String(value))) => Some(value.clone()),
+            _ => None,
+        };
+        let scripts = match fields.iter().find(|(k, _)| k == "scripts") {
+            Some((_, Value::Object(scripts))) => scripts
+                .iter()
+                .filter_map(|(name, command)| match command {
+                    Value::String(command) => Some((name.clone(), command.clone())),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+        Some(Self {
+            dir: dir.to_path_buf(),
+            name: string("name").unwrap_or_else(|| {
+                dir.file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string()
+            }),
+            version: string("version"),
+            scripts,
+            manager: manager(dir),
+        })
+    }
+
+    /// Runs `script` with the package's manager, e.g. `yarn run lint`.
+    pub fn command(&self, script: &str) -> Command {
+        let mut command = Command::new(self.manager);
+        command.args(["run", script]).current_dir(&self.dir);
+        command
+    }
+}
+
+/// The package manager whose lock file is in `dir`, npm without one.
+fn manager(dir: &Path) -> &'static str {
+    const LOCK_FILES: [(&str, &str); 4] = [
+        ("yarn.lock", "yarn"),
+        ("pnpm-lock.yaml", "pnpm"),
+        ("bun.lockb", "bun"),
+        ("bun.lock", "bun"),
+    ];
+    LOCK_FILES
+        .iter()
+        .find(|(file, _)| dir.join(file).exists())
+        .map_or("npm", |(_, manager)| manager)
+}
+
+/// Just enough JSON for a manifest.
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    String(String),
+    /// Keys in the order written.
+    Object(Vec<(String, Value)>),
+    /// Numbers, booleans, null and arrays, checked but not kept.
+    Other,
+}
+
+fn parse(text: &str) -> Option<Value> {
+    let mut chars = text.chars().peekable();
+    let value = value(&mut chars, 0)?;
+    skip_space(&mut chars);
+    chars.peek().is_none().then_some(value)
+}
+
+/// Nesting deeper than this is refused rather than overflowing the stack.
+const MAX_DEPTH: usize = 64;
+
+fn skip_space(chars: &mut Peekable<Chars>) {
+    while chars.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+fn value(chars: &mut Peekable<Chars>, depth: usize) -> Option<Value> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+    skip_space(chars);
+    match chars.peek()? {
+        '{' => {
+            chars.next();
+            let mut fields = Vec::new();
+            // Later duplicates win, as in JavaScript
+            let mut seen = BTreeMap::new();
+            skip_space(chars);
+            if chars.next_if_eq(&'}').is_some() {
+                return Some(Value::Object(fields));
+            }
+            loop {
+                skip_space(chars);
+                let Value::String(key) = value(chars, depth + 1)? else {
+                    return None;
+                };
+                skip_space(chars);
+                chars.next_if_eq(&':')?;
+                let value = value(chars, depth + 1)?;
+                match seen.get(&key) {
+                    Some(&index) => fields[index] = (key, value),
+                    None => {
+                        seen.insert(key.clone(), fields.len());
+                        fields.push((key, value));
+                    }
+                }
+                skip_space(chars);
+                match chars.next()? {
+                    ',' => continue,
+                    '}' => return Some(Value::Object(fields)),
+                    _ => return None,
+                }
+            }
+        }
+        '[' => {
+            chars.next();
+            skip_space(chars);
+            if chars.next_if_eq(&']').is_some() {
+                return Some(Value::Other);
+            }
+            loop {
+                value(chars, depth + 1)?;
+                skip_space(chars);
+                match chars.next()? {
+                    ',' => continue,
+                    ']' => return Some(Value::Other),
+                    _ => return None,
+                }
+            }
+        }
+        '"' => {
+            chars.next();
+            let mut string = String::new();
+            loop {
+                match chars.next()? {
+                    '"' => return Some(Value::String(string)),
+                    '\\' => string.push(match chars.next()? {
+                        'n' => '\n',
+                        't' => '\t',
+                        'r' => '\r',
+                        'b' => '\u{8}',
+                        'f' => '\u{c}',
+                        'u' => {
+                            let hex: String = chars.by_ref().take(4).collect();
+                            let unit = u32::from_str_radix(&hex, 16).ok()?;
+                            // Surrogate pairs aren't needed for a manifest
+                            char::from_u32(unit).unwrap_or(char::REPLACEMENT_CHARACTER)
+                        }
+                        c => c,
+                    }),
+                    c => string.push(c),
+                }
+            }
+        }
+        c if *c == '-' || c.is_ascii_digit() => {
+            while chars
+                .next_if(|c| "+-.eE".contains(*c) || c.is_ascii_digit())
+                .is_some()
+            {}
+            Some(Value::Other)
+        }
+        _ => {
+            let word: String =
+                std::iter::from_fn(|| chars.next_if(char::is_ascii_alphabetic)).collect();
+            match word.as_str() {
+                "true" | "false" | "null" => Some(Value::Other),
+                _ => None,
+            }
+        }
+    }
+}
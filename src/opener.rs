@@ -0,0 +1,80 @@
+//! Rules from the `[open]` config table saying which program opens files
+//! matching a pattern, tried before the editor when a file is opened:
+//!
+//! ```toml
+//! [open]
+//! "*.pdf" = "zathura {file} &"
+//! "*.md" = "glow -p"
+//! ```
+//!
+//! `{file}` is the file and `{dir}` its directory; a command with neither
+//! gets the file appended. A trailing `&` starts a GUI program in the
+//! background, anything else runs in the terminal while browrs waits.
+
+use std::{
+    io,
+    path::Path,
+    process::{Command, Stdio},
+};
+
+use crate::{log, template::Template};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rule {
+    pub template: Template,
+    /// Whether it is started in the background rather than in the terminal.
+    pub gui: bool,
+}
+
+impl Rule {
+    /// Reads one `pattern = command` entry. A bare extension such as `pdf`
+    /// stands for `*.pdf`.
+    pub fn parse(pattern: &str, command: &str) -> Result<Rule, String> {
+        let command = command.trim();
+        let (command, gui) = match command.strip_suffix('&') {
+            Some(command) => (command, true),
+            None => (command, false),
+        };
+        Ok(Rule {
+            template: Template::parse(pattern, command, "open")?,
+            gui,
+        })
+    }
+
+    pub fn matches(&self, path: &Path) -> bool {
+        self.template.matches(path)
+    }
+
+    /// The command for `path`, with the placeholders filled in.
+    pub fn expand(&self, path: &Path) -> String {
+        self.template.expand(path)
+    }
+
+    /// Opens `path`. A terminal command has the terminal to itself until it
+    /// exits; a GUI one is left running.
+    pub fn run(&self, path: &Path) -> io::Result<()> {
+        let command = self.expand(path);
+        log::info!("opening {} with sh -c {}", path.display(), command);
+        let mut sh = Command::new("sh");
+        sh.arg("-c").arg(&command);
+        if self.gui {
+            sh.stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .map(|_| ())
+        } else {
+            let status = sh.status()?;
+            if status.success() {
+                Ok(())
+            } else {
+                Err(io::Error::other(format!("exited with {}", status)))
+            }
+        }
+    }
+}
+
+/// The first rule matching `path`.
+pub fn find<'a>(rules: &'a [Rule], path: &Path) -> Option<&'a Rule> {
+    rules.iter().find(|rule| rule.matches(path))
+}
@@ -0,0 +1,426 @@
+use std::{
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{checksum, error::BrowrsError, history, jobs::Progress, log, vfs};
+
+/// Outcome of applying an operation to many files.
+#[derive(Debug)]
+pub struct BatchReport {
+    /// What is being done to the files, like "copy", for error messages.
+    pub op: &'static str,
+    pub changed: usize,
+    /// Files whose copy was read back and matched the source.
+    pub verified: usize,
+    pub errors: Vec<BrowrsError>,
+    /// Whether the job was cancelled before it got through every source.
+    pub cancelled: bool,
+    /// Entries removed without a copy of every file in the history.
+    pub unkept: usize,
+}
+
+impl BatchReport {
+    pub fn new(op: &'static str) -> Self {
+        Self {
+            op,
+            changed: 0,
+            verified: 0,
+            errors: Vec::new(),
+            cancelled: false,
+            unkept: 0,
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        let mut summary = format!("✅ Updated {} entries", self.changed);
+        if self.verified > 0 {
+            summary.push_str(&format!("\n🔒 Verified {} files", self.verified));
+        }
+        if self.cancelled {
+            summary.push_str("\n⏹ Cancelled, the rest was left alone");
+        }
+        if self.unkept > 0 {
+            summary.push_str(&format!(
+                "\n⚠ {} entries went without a copy of every file in the history",
+                self.unkept
+            ));
+        }
+        if !self.errors.is_empty() {
+            summary.push_str(&format!("\n❌ {} errors:\n", self.errors.len()));
+            for error in &self.errors {
+                summary.push_str(&format!("{}\n", error));
+            }
+        }
+        summary
+    }
+
+    pub fn record(&mut self, path: &Path, result: io::Result<()>) {
+        match result {
+            Ok(()) => self.changed += 1,
+            Err(e) => self.errors.push(BrowrsError::io(self.op, path, e)),
+        }
+    }
+
+    pub fn log(&self) {
+        log::info!(
+            "{}: {} changed, {} failed{}",
+            self.op,
+            self.changed,
+            self.errors.len(),
+            if self.cancelled { ", cancelled" } else { "" }
+        );
+        for error in &self.errors {
+            log::error!("{}", error);
+        }
+    }
+}
+
+/// Applies `op` to each source in turn, counting them off as progress and
+/// stopping early if the job is cancelled.
+pub fn each(
+    op: &'static str,
+    sources: &[PathBuf],
+    progress: &Progress,
+    mut apply: impl FnMut(&Path) -> io::Result<()>,
+) -> BatchReport {
+    let mut report = BatchReport::new(op);
+    for (i, source) in sources.iter().enumerate() {
+        if progress.is_cancelled() {
+            report.cancelled = true;
+            break;
+        }
+        progress.count(i, sources.len());
+        report.record(source, apply(source));
+    }
+    report
+}
+
+/// Copies every source to `dest`, recursing into directories, and reports
+/// the bytes copied. Sources in an archive are extracted. A copy cut short
+/// by cancelling is removed again.
+pub fn copy_all(sources: &[PathBuf], dest: &Path, progress: &Progress) -> BatchReport {
+    copy_each(sources, dest, progress, false)
+}
+
+/// Copies like [`copy_all`], then hashes every copied file and its source,
+/// reporting any that differ.
+pub fn copy_verified(sources: &[PathBuf], dest: &Path, progress: &Progress) -> BatchReport {
+    copy_each(sources, dest, progress, true)
+}
+
+fn copy_each(sources: &[PathBuf], dest: &Path, progress: &Progress, verified: bool) -> BatchReport {
+    let mut report = BatchReport::new("copy");
+    let total = sources.iter().map(|source| tree_size(source)).sum();
+    let mut done = 0;
+    let mut copied = |bytes| {
+        done += bytes;
+        progress.set(done, Some(total));
+        if progress.is_cancelled() {
+            Err(io::Error::new(io::ErrorKind::Interrupted, "cancelled"))
+        } else {
+            Ok(())
+        }
+    };
+    for source in sources {
+        if progress.is_cancelled() {
+            report.cancelled = true;
+            break;
+        }
+        let result = destination(source, dest, sources.len() == 1).and_then(|dest| {
+            let result = if vfs::is_archive_member(source) {
+                vfs::extract(source, &dest)
+            } else {
+                copy_tree(source, &dest, &mut copied)
+            };
+            // Only what was cut short; a copy that got to the end stays
+            if let Err(e) = &result
+                && e.kind() == io::ErrorKind::Interrupted
+            {
+                let _ = remove_path(&dest);
+            }
+            result?;
+            if verified {
+                verify(source, &dest, &mut report)?;
+            }
+            Ok(())
+        });
+        if let Err(e) = &result
+            && e.kind() == io::ErrorKind::Interrupted
+        {
+            report.cancelled = true;
+            break;
+        }
+        report.record(source, result);
+    }
+    report
+}
+
+/// Bytes in the regular files at and under `path`.
+fn tree_size(path: &Path) -> u64 {
+    match path.symlink_metadata() {
+        Ok(metadata) if metadata.is_dir() => std::fs::read_dir(path)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| tree_size(&entry.path()))
+                    .sum()
+            })
+            .unwrap_or(0),
+        Ok(metadata) if metadata.is_file() => metadata.len(),
+        _ => 0,
+    }
+}
+
+/// Compares the regular files under `source` and `copy`, recording each
+/// mismatch as an error. Only fails when the trees can't be walked.
+fn verify(source: &Path, copy: &Path, report: &mut BatchReport) -> io::Result<()> {
+    let metadata = source.symlink_metadata()?;
+    if metadata.is_dir() {
+        for entry in std::fs::read_dir(source)? {
+            let entry = entry?;
+            verify(&entry.path(), &copy.join(entry.file_name()), report)?;
+        }
+    } else if metadata.is_file() {
+        match (checksum::file(source), checksum::file(copy)) {
+            (Ok(a), Ok(b)) if a == b => report.verified += 1,
+            (Ok(a), Ok(b)) => report.errors.push(BrowrsError::io(
+                "verify",
+                copy,
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!(
+                        "checksum mismatch: source {}, copy {}",
+                        &checksum::hex(&a)[..16],
+                        &checksum::hex(&b)[..16]
+                    ),
+                ),
+            )),
+            (Err(e), _) | (_, Err(e)) => report.errors.push(BrowrsError::io("verify", copy, e)),
+        }
+    }
+    Ok(())
+}
+
+/// Moves every source to `dest`, copying across filesystems.
+pub fn move_all(sources: &[PathBuf], dest: &Path, progress: &Progress) -> BatchReport {
+    each("move", sources, progress, |source| {
+        destination(source, dest, sources.len() == 1).and_then(|dest| move_path(source, &dest))
+    })
+}
+
+/// Links every source from `dest`: a symlink to its absolute path, or a
+/// hard link to the same file if `hard` is set.
+pub fn link_all(sources: &[PathBuf], dest: &Path, hard: bool) -> BatchReport {
+    let mut report = BatchReport::new(if hard { "hard link" } else { "link" });
+    for source in sources {
+        let result = target(source, dest, sources.len() == 1).and_then(|link| {
+            if vfs::is_archive_member(source) {
+                Err(io::Error::new(
+                    io::ErrorKind::Unsupported,
+                    "entries in an archive cannot be linked to",
+                ))
+            } else if hard {
+                std::fs::hard_link(source, &link)
+            } else {
+                symlink(source, &link)
+            }
+        });
+        report.record(source, result);
+    }
+    report
+}
+
+#[cfg(unix)]
+fn symlink(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(not(unix))]
+fn symlink(_target: &Path, _link: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "symlinks are only made on Unix",
+    ))
+}
+
+/// Renames `source` to `dest`, replacing an existing entry only when
+/// `overwrite` is set. The replaced entry's files go to the history first,
+/// up to `keep` copies each.
+pub fn rename(source: &Path, dest: &Path, overwrite: bool, keep: usize) -> io::Result<()> {
+    if dest.symlink_metadata().is_ok() {
+        if !overwrite {
+            return Err(already_exists(dest));
+        }
+        keep_history(dest, keep);
+        remove_path(dest)?;
+    }
+    move_path(source, dest)
+}
+
+/// Creates an empty file, or a directory if `dir` is set, along with any
+/// missing directories above it. An existing entry is left alone.
+pub fn create(path: &Path, dir: bool) -> io::Result<()> {
+    if path.symlink_metadata().is_ok() {
+        return Err(already_exists(path));
+    }
+    if dir {
+        return std::fs::create_dir_all(path);
+    }
+    create_file(path, &[])
+}
+
+/// Creates a file holding `contents`, and the directories leading to it,
+/// unless something is already there.
+pub fn create_file(path: &Path, contents: &[u8]) -> io::Result<()> {
+    if path.symlink_metadata().is_ok() {
+        return Err(already_exists(path));
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)?
+        .write_all(contents)
+}
+
+/// Permanently removes every source, keeping up to `keep` copies of each
+/// file in the history first, as many files as one save takes.
+pub fn delete_all(sources: &[PathBuf], keep: usize, progress: &Progress) -> BatchReport {
+    let mut unkept = 0;
+    let mut report = each("delete", sources, progress, |source| {
+        if !keep_history(source, keep) {
+            unkept += 1;
+        }
+        remove_path(source)
+    });
+    report.unkept = unkept;
+    report
+}
+
+/// Keeps copies of the files of `path` in the history, as many as one save
+/// takes; returns whether every file got one. Not being able to is logged
+/// rather than keeping `path` from being removed.
+fn keep_history(path: &Path, keep: usize) -> bool {
+    match history::save(path, keep) {
+        Ok(saved) if saved.partial => {
+            log::info!(
+                "kept {} files of {} in the history, not the rest",
+                saved.files,
+                path.display()
+            );
+            false
+        }
+        Ok(_) => true,
+        Err(e) => {
+            log::error!("no history kept for {}: {}", path.display(), e);
+            false
+        }
+    }
+}
+
+/// Where `source` ends up: inside `dest` when it is a directory, otherwise
+/// at `dest` itself if it is the only source (like `cp a b`). Refuses to
+/// overwrite anything or to put a directory inside itself.
+fn destination(source: &Path, dest: &Path, single: bool) -> io::Result<PathBuf> {
+    let target = target(source, dest, single)?;
+    if let (Ok(source), Some(Ok(dest_dir))) = (
+        source.canonicalize(),
+        target.parent().map(Path::canonicalize),
+    ) && dest_dir.starts_with(&source)
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "cannot copy or move a directory into itself",
+        ));
+    }
+    Ok(target)
+}
+
+/// Where `source` ends up like [`destination`], nested or not.
+fn target(source: &Path, dest: &Path, single: bool) -> io::Result<PathBuf> {
+    let name = source
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no file name"))?;
+    let target = if dest.is_dir() || !single {
+        dest.join(name)
+    } else {
+        dest.to_path_buf()
+    };
+    if target.symlink_metadata().is_ok() {
+        return Err(already_exists(&target));
+    }
+    Ok(target)
+}
+
+fn already_exists(path: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        format!("{} already exists", path.display()),
+    )
+}
+
+pub fn copy_recursive(source: &Path, dest: &Path) -> io::Result<()> {
+    copy_tree(source, dest, &mut |_| Ok(()))
+}
+
+/// Copies like [`copy_recursive`], passing each file's size to `copied`
+/// once it is done and stopping if that fails.
+fn copy_tree(
+    source: &Path,
+    dest: &Path,
+    copied: &mut dyn FnMut(u64) -> io::Result<()>,
+) -> io::Result<()> {
+    let metadata = source.symlink_metadata()?;
+    if metadata.file_type().is_symlink() {
+        copy_symlink(source, dest)
+    } else if metadata.is_dir() {
+        std::fs::create_dir(dest)?;
+        for entry in std::fs::read_dir(source)? {
+            let entry = entry?;
+            copy_tree(&entry.path(), &dest.join(entry.file_name()), copied)?;
+        }
+        std::fs::set_permissions(dest, metadata.permissions())
+    } else if !metadata.is_file() {
+        // Copying would read a named pipe or device, which may never end
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "not a regular file, directory or link",
+        ))
+    } else {
+        std::fs::copy(source, dest)?;
+        copied(metadata.len())
+    }
+}
+
+#[cfg(unix)]
+fn copy_symlink(source: &Path, dest: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(std::fs::read_link(source)?, dest)
+}
+
+#[cfg(not(unix))]
+fn copy_symlink(source: &Path, dest: &Path) -> io::Result<()> {
+    std::fs::copy(source, dest).map(|_| ())
+}
+
+pub fn move_path(source: &Path, dest: &Path) -> io::Result<()> {
+    match std::fs::rename(source, dest) {
+        Ok(()) => Ok(()),
+        // Renaming across filesystems fails, fall back to copy and delete
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices => {
+            copy_recursive(source, dest)?;
+            remove_path(source)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+pub fn remove_path(path: &Path) -> io::Result<()> {
+    if path.symlink_metadata()?.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    }
+}
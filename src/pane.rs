@@ -0,0 +1,588 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Component, Path, PathBuf},
+};
+
+use crate::{
+    annotations::Ratings,
+    entry::Entry,
+    error::{self, Context},
+    filter, ignore,
+    kinds::{Kind, Kinds},
+    notes::{self, Note},
+    sort::Sort,
+    vfs::{self, read_dir},
+};
+
+/// Lines of a directory's note shown in the banner above the list.
+const NOTE_BANNER_LINES: usize = 3;
+
+/// Entries numbered for opening with a digit while filtering.
+const QUICK_OPEN: usize = 9;
+
+/// Directories remembered for going back.
+const MAX_HISTORY: usize = 100;
+
+/// How directories are listed, shared by every pane.
+#[derive(Debug, Clone)]
+pub struct Listing {
+    pub show_hidden: bool,
+    pub sort: Sort,
+    /// Directories listed before files, whatever the sort.
+    pub dirs_first: bool,
+    /// Stars given to files, shown with them and sorted by.
+    pub ratings: Ratings,
+    /// Leaves out what the repository's `.gitignore` files ignore.
+    pub hide_ignored: bool,
+}
+
+/// A directory that was left, and where the cursor was in it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Visit {
+    pub dir: PathBuf,
+    /// Name of the entry under the cursor.
+    pub selected: Option<String>,
+    pub scroll: usize,
+}
+
+/// A directory listing and where the cursor is in it.
+#[derive(Debug)]
+pub struct Pane {
+    pub current_dir: PathBuf,
+    pub entries: Vec<Entry>,
+    /// Cursor position where visual range marking started, and the marks
+    /// that existed before it.
+    pub visual: Option<(usize, Vec<bool>)>,
+    pub selected: usize,
+    pub scroll: usize,
+    pub note: Option<Note>,
+    /// Pattern narrowing the listing, kept when changing directory.
+    pub filter: Option<String>,
+    /// Kinds of entries the listing is narrowed to, also kept.
+    pub kinds: Kinds,
+    /// Stars a file needs to be listed, also kept; 0 lists every file.
+    pub min_rating: u8,
+    /// Entries in the directory before filtering, not counting `..` or
+    /// the ignored ones hidden.
+    pub total: usize,
+    /// Entries left out for being ignored by git.
+    pub ignored: usize,
+    /// Directories visited before this one, most recent last.
+    pub history: Vec<Visit>,
+    /// Directories gone back from, most recent last.
+    pub forward: Vec<Visit>,
+    /// Entry the cursor was on in each directory left, by path.
+    pub positions: HashMap<PathBuf, String>,
+    /// Places jumped from, like a directory changed from or the first entry
+    /// gone to from far down, oldest first.
+    pub jumps: Vec<Visit>,
+    /// Where in `jumps` the pane is: `jumps.len()` until it goes back.
+    pub jump: usize,
+    /// Where the pane was when its tab's panes were synced; the same path
+    /// relative to it is followed while they are.
+    pub sync_root: Option<PathBuf>,
+}
+
+impl Pane {
+    pub fn open(dir: PathBuf, listing: Listing) -> error::Result<Self> {
+        Self::open_filtered(dir, listing, None, Kinds::default(), 0)
+    }
+
+    fn open_filtered(
+        dir: PathBuf,
+        listing: Listing,
+        filter: Option<String>,
+        kinds: Kinds,
+        min_rating: u8,
+    ) -> error::Result<Self> {
+        let mut entries = read_dir(&dir, listing.clone()).context("open", &dir)?;
+        let ignored = drop_ignored(&mut entries, &dir, &listing);
+        let total = entries.len() - 1;
+        apply_filter(&mut entries, &dir, filter.as_deref(), &kinds, min_rating);
+        Ok(Self {
+            note: notes::find(&dir, NOTE_BANNER_LINES),
+            current_dir: dir,
+            entries,
+            visual: None,
+            selected: 0,
+            scroll: 0,
+            filter,
+            kinds,
+            min_rating,
+            total,
+            ignored,
+            history: Vec::new(),
+            forward: Vec::new(),
+            positions: HashMap::new(),
+            jumps: Vec::new(),
+            jump: 0,
+            sync_root: None,
+        })
+    }
+
+    /// Short label: the directory's name.
+    pub fn title(&self) -> String {
+        self.current_dir
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| self.current_dir.display().to_string())
+    }
+
+    /// Switches to `dir`, leaving the pane as it was if it can't be read.
+    /// The directory left is remembered in `history` and as a jump. The
+    /// cursor goes on the directory just come out of, or where it was when
+    /// `dir` was last left.
+    pub fn change_dir(&mut self, dir: PathBuf, listing: Listing) -> error::Result<()> {
+        let left = self.visit();
+        self.replace(dir, listing)?;
+        self.push_jump(left.clone());
+        self.remember(&left);
+        let came_out_of = left
+            .dir
+            .strip_prefix(&self.current_dir)
+            .ok()
+            .and_then(|rest| rest.components().next())
+            .map(|child| child.as_os_str().to_string_lossy().into_owned());
+        if let Some(name) = came_out_of.or_else(|| self.positions.get(&self.current_dir).cloned()) {
+            self.select_name(&name);
+        }
+        if left.dir != self.current_dir {
+            push_visit(&mut self.history, left);
+            self.forward.clear();
+        }
+        Ok(())
+    }
+
+    /// Returns to the directory visited before this one; false if there is
+    /// none.
+    pub fn back(&mut self, listing: Listing) -> error::Result<bool> {
+        let Some(visit) = self.history.pop() else {
+            return Ok(false);
+        };
+        let left = self.visit();
+        self.return_to(visit, listing)?;
+        self.remember(&left);
+        self.push_jump(left.clone());
+        push_visit(&mut self.forward, left);
+        Ok(true)
+    }
+
+    /// Undoes [`Pane::back`]; false if there is nothing to go forward to.
+    pub fn forward(&mut self, listing: Listing) -> error::Result<bool> {
+        let Some(visit) = self.forward.pop() else {
+            return Ok(false);
+        };
+        let left = self.visit();
+        self.return_to(visit, listing)?;
+        self.remember(&left);
+        self.push_jump(left.clone());
+        push_visit(&mut self.history, left);
+        Ok(true)
+    }
+
+    /// Remembers where the pane is before it jumps elsewhere, dropping the
+    /// places gone back past.
+    pub fn record_jump(&mut self) {
+        self.push_jump(self.visit());
+    }
+
+    fn push_jump(&mut self, from: Visit) {
+        self.jumps.truncate(self.jump);
+        self.jumps
+            .retain(|jump| jump.dir != from.dir || jump.selected != from.selected);
+        push_visit(&mut self.jumps, from);
+        self.jump = self.jumps.len();
+    }
+
+    /// Goes back to the place last jumped from, with the cursor on the same
+    /// entry, or forward again; false if there is nowhere to go.
+    pub fn jump(&mut self, forward: bool, listing: Listing) -> error::Result<bool> {
+        let target = if forward {
+            self.jump + 1
+        } else {
+            self.jump.wrapping_sub(1)
+        };
+        if target >= self.jumps.len() {
+            return Ok(false);
+        }
+        let left = self.visit();
+        self.return_to(self.jumps[target].clone(), listing)?;
+        self.remember(&left);
+        // Going back from the newest place keeps it to come forward to
+        if self.jump == self.jumps.len() {
+            self.jumps.push(left);
+        }
+        self.jump = target;
+        Ok(true)
+    }
+
+    /// Where the pane is now, for coming back to it.
+    fn visit(&self) -> Visit {
+        Visit {
+            dir: self.current_dir.clone(),
+            selected: self.entries.get(self.selected).map(|e| e.name.clone()),
+            scroll: self.scroll,
+        }
+    }
+
+    fn remember(&mut self, visit: &Visit) {
+        if let Some(name) = &visit.selected {
+            self.positions.insert(visit.dir.clone(), name.clone());
+        }
+    }
+
+    fn return_to(&mut self, visit: Visit, listing: Listing) -> error::Result<()> {
+        self.replace(visit.dir, listing)?;
+        if let Some(name) = visit.selected {
+            self.select_name(&name);
+        }
+        self.scroll = visit.scroll;
+        Ok(())
+    }
+
+    /// Lists `dir` instead, keeping the filter and history.
+    fn replace(&mut self, dir: PathBuf, listing: Listing) -> error::Result<()> {
+        let mut pane = Self::open_filtered(
+            dir,
+            listing,
+            self.filter.clone(),
+            self.kinds.clone(),
+            self.min_rating,
+        )?;
+        pane.history = std::mem::take(&mut self.history);
+        pane.forward = std::mem::take(&mut self.forward);
+        pane.positions = std::mem::take(&mut self.positions);
+        pane.jumps = std::mem::take(&mut self.jumps);
+        pane.jump = self.jump;
+        pane.sync_root = self.sync_root.take();
+        *self = pane;
+        Ok(())
+    }
+
+    /// Like [`Pane::reload`], also keeping the marks on the entries still
+    /// listed and the scroll position, for reading the same directory again
+    /// rather than after an operation.
+    pub fn reread(&mut self, listing: Listing) {
+        let marked: HashSet<String> = self
+            .entries
+            .iter()
+            .filter(|e| e.marked)
+            .map(|e| e.name.clone())
+            .collect();
+        let (dir, scroll) = (self.current_dir.clone(), self.scroll);
+        self.reload(listing);
+        if self.current_dir != dir {
+            return;
+        }
+        for entry in &mut self.entries {
+            entry.marked = marked.contains(&entry.name);
+        }
+        self.scroll = scroll.min(self.entries.len().saturating_sub(1));
+    }
+
+    /// Re-reads the listing, keeping the cursor on the same entry if it is
+    /// still listed, or where it was if not. A directory that is gone, like
+    /// that of a process in /proc that exited, is left for the nearest one
+    /// above it that is still there.
+    pub fn reload(&mut self, listing: Listing) {
+        let current = self.entries.get(self.selected).map(|e| e.name.clone());
+        match read_dir(&self.current_dir, listing.clone()) {
+            Ok(mut entries) => {
+                self.ignored = drop_ignored(&mut entries, &self.current_dir, &listing);
+                self.total = entries.len() - 1;
+                apply_filter(
+                    &mut entries,
+                    &self.current_dir,
+                    self.filter.as_deref(),
+                    &self.kinds,
+                    self.min_rating,
+                );
+                self.selected = current
+                    .and_then(|name| entries.iter().position(|e| e.name == name))
+                    .unwrap_or(self.selected.min(entries.len() - 1));
+                self.entries = entries;
+                self.visual = None;
+                self.scroll = 0;
+                self.note = notes::find(&self.current_dir, NOTE_BANNER_LINES);
+            }
+            Err(_) if !vfs::is_dir(&self.current_dir) => {
+                let gone = self.current_dir.clone();
+                if let Some(dir) = gone.ancestors().skip(1).find(|dir| vfs::is_dir(dir))
+                    && self.replace(dir.to_path_buf(), listing).is_ok()
+                    && let Ok(rest) = gone.strip_prefix(&self.current_dir)
+                    && let Some(child) = rest.components().next()
+                {
+                    self.select_name(&child.as_os_str().to_string_lossy());
+                }
+            }
+            Err(_) => {}
+        }
+    }
+
+    /// Narrows the listing to files matching `filter`, or lists everything
+    /// again with `None`.
+    pub fn set_filter(&mut self, filter: Option<String>, listing: Listing) {
+        self.filter = filter;
+        self.reload(listing);
+    }
+
+    /// Turns narrowing the listing to `kind` on or off; `None` turns every
+    /// kind off.
+    pub fn toggle_kind(&mut self, kind: Option<Kind>, listing: Listing) {
+        match kind {
+            Some(kind) => self.kinds.toggle(kind),
+            None => {
+                self.kinds.clear();
+                self.min_rating = 0;
+            }
+        }
+        self.reload(listing);
+    }
+
+    /// Lists only files with at least `stars`, or every file with 0.
+    pub fn set_min_rating(&mut self, stars: u8, listing: Listing) {
+        self.min_rating = stars;
+        self.reload(listing);
+    }
+
+    /// Entries numbered 1 to 9 while filtering, the first ones listed from
+    /// `start` apart from `..`.
+    pub fn numbered(&self, start: usize) -> Vec<usize> {
+        (start..self.entries.len())
+            .filter(|&i| !self.entries[i].is_parent())
+            .take(QUICK_OPEN)
+            .collect()
+    }
+
+    /// Moves the cursor onto the entry named `name`; returns whether it is
+    /// listed.
+    pub fn select_name(&mut self, name: &str) -> bool {
+        let name = name.trim_end_matches('/');
+        let position = self.entries.iter().position(|e| e.name == name);
+        if let Some(position) = position {
+            self.selected = position;
+        }
+        position.is_some()
+    }
+
+    pub fn selected_path(&self) -> Option<PathBuf> {
+        self.entries
+            .get(self.selected)
+            .filter(|e| !e.is_parent())
+            .map(|e| self.current_dir.join(&e.name))
+    }
+
+    pub fn marked_paths(&self) -> Vec<PathBuf> {
+        self.entries
+            .iter()
+            .filter(|e| e.marked)
+            .map(|e| self.current_dir.join(&e.name))
+            .collect()
+    }
+
+    /// Paths an operation applies to: the marked entries, or the entry under
+    /// the cursor when nothing is marked.
+    pub fn targets(&self) -> Vec<PathBuf> {
+        let marked = self.marked_paths();
+        if marked.is_empty() {
+            self.selected_path().into_iter().collect()
+        } else {
+            marked
+        }
+    }
+
+    pub fn marked_count(&self) -> usize {
+        self.entries.iter().filter(|e| e.marked).count()
+    }
+
+    pub fn toggle_mark_all(&mut self) {
+        let all_marked = self
+            .entries
+            .iter()
+            .filter(|e| !e.is_parent())
+            .all(|e| e.marked);
+        for entry in self.entries.iter_mut().filter(|e| !e.is_parent()) {
+            entry.marked = !all_marked;
+        }
+        self.visual = None;
+    }
+
+    pub fn clear_marks(&mut self) {
+        for entry in &mut self.entries {
+            entry.marked = false;
+        }
+        self.visual = None;
+    }
+
+    pub fn toggle_visual(&mut self) {
+        if self.visual.take().is_none() {
+            let base = self.entries.iter().map(|e| e.marked).collect();
+            self.visual = Some((self.selected, base));
+            self.update_visual();
+        }
+    }
+
+    /// Marks everything between the visual anchor and the cursor.
+    pub fn update_visual(&mut self) {
+        let Some((anchor, base)) = &self.visual else {
+            return;
+        };
+        let range = (*anchor).min(self.selected)..=(*anchor).max(self.selected);
+        for (i, entry) in self.entries.iter_mut().enumerate() {
+            entry.marked = !entry.is_parent() && (base[i] || range.contains(&i));
+        }
+    }
+
+    pub fn scroll_for_height(&self, max_visible: usize) -> usize {
+        if max_visible == 0 {
+            return self.scroll;
+        }
+
+        let scroll_threshold = 3.min(max_visible);
+
+        let visible_pos = self.selected.saturating_sub(self.scroll);
+
+        let mut scroll = self.scroll;
+        if visible_pos >= max_visible.saturating_sub(scroll_threshold) {
+            let max_scroll = self.entries.len().saturating_sub(max_visible);
+            if scroll < max_scroll {
+                scroll = (self.selected + scroll_threshold).saturating_sub(max_visible - 1);
+                scroll = scroll.min(max_scroll);
+            }
+        } else if visible_pos < scroll_threshold {
+            if self.selected >= scroll_threshold {
+                scroll = self.selected.saturating_sub(scroll_threshold);
+            } else {
+                scroll = 0;
+            }
+        }
+        scroll
+    }
+}
+
+/// `dir` with the home directory as `~` and every directory above the
+/// last cut to its first letter, like `~/p/browrs`.
+pub fn short_path(dir: &Path) -> String {
+    // With the home directory at `/`, every path would start with `~`
+    let home = dirs::home_dir().filter(|home| home.parent().is_some());
+    let (mut short, rest) = match home.as_deref().and_then(|home| dir.strip_prefix(home).ok()) {
+        Some(rest) => ("~".to_string(), rest),
+        None => (String::new(), dir),
+    };
+    let names: Vec<String> = rest
+        .components()
+        .filter_map(|part| match part {
+            Component::Normal(name) => Some(name.to_string_lossy().into_owned()),
+            _ => None,
+        })
+        .collect();
+    for (i, name) in names.iter().enumerate() {
+        short.push('/');
+        if i + 1 == names.len() {
+            short.push_str(name);
+        } else {
+            // Hidden directories keep their dot
+            let keep = if name.starts_with('.') { 2 } else { 1 };
+            short.extend(name.chars().take(keep));
+        }
+    }
+    if short.is_empty() {
+        short.push('/');
+    }
+    short
+}
+
+/// `dir` in full, with the home directory as `~`.
+pub fn tilde_path(dir: &Path) -> String {
+    let home = dirs::home_dir().filter(|home| home.parent().is_some());
+    match home.as_deref().and_then(|home| dir.strip_prefix(home).ok()) {
+        Some(rest) if rest.as_os_str().is_empty() => "~".to_string(),
+        Some(rest) => format!("~/{}", rest.display()),
+        None => dir.display().to_string(),
+    }
+}
+
+/// Completes the last component of an absolute or `~` path to the
+/// directories it could name, as far as they agree, with a `/` once only
+/// one fits; their names come back too. Hidden directories fit only when
+/// the component starts with a dot.
+pub fn complete_dir(input: &str) -> (String, Vec<String>) {
+    let (head, partial) = match input.rfind('/') {
+        Some(slash) => input.split_at(slash + 1),
+        None if input == "~" => return ("~/".to_string(), vec![]),
+        None => return (input.to_string(), vec![]),
+    };
+    let dir = crate::config::expand_tilde(head);
+    if !dir.is_absolute() {
+        return (input.to_string(), vec![]);
+    }
+    let mut fits: Vec<String> = std::fs::read_dir(&dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| name.starts_with(partial))
+        .filter(|name| partial.starts_with('.') || !name.starts_with('.'))
+        .collect();
+    fits.sort();
+    if let [only] = fits.as_slice() {
+        return (format!("{}{}/", head, only), vec![]);
+    }
+    let first = fits.first().map_or(partial, String::as_str);
+    let common = fits.iter().fold(first, |common, name| {
+        let len = common
+            .chars()
+            .zip(name.chars())
+            .take_while(|(a, b)| a == b)
+            .map(|(a, _)| a.len_utf8())
+            .sum();
+        &common[..len]
+    });
+    let names = fits.iter().map(|name| format!("{}/", name)).collect();
+    (format!("{}{}", head, common), names)
+}
+
+fn push_visit(stack: &mut Vec<Visit>, visit: Visit) {
+    if stack.len() == MAX_HISTORY {
+        stack.remove(0);
+    }
+    stack.push(visit);
+}
+
+/// Drops the entries the repository's `.gitignore` files ignore, if the
+/// listing hides them, returning how many there were.
+fn drop_ignored(entries: &mut Vec<Entry>, dir: &Path, listing: &Listing) -> usize {
+    if !listing.hide_ignored || vfs::in_archive(dir) {
+        return 0;
+    }
+    let Some(rules) = ignore::Rules::git(dir) else {
+        return 0;
+    };
+    let before = entries.len();
+    entries.retain(|entry| {
+        entry.is_parent() || !rules.is_ignored(&dir.join(&entry.name), entry.is_dir)
+    });
+    before - entries.len()
+}
+
+/// Drops files that don't match `filter` or have fewer than `min_rating`
+/// stars, and entries of none of `kinds`. Directories stay for the pattern
+/// and the rating so there is still somewhere to go.
+fn apply_filter(
+    entries: &mut Vec<Entry>,
+    dir: &Path,
+    filter: Option<&str>,
+    kinds: &Kinds,
+    min_rating: u8,
+) {
+    if let Some(filter) = filter {
+        entries.retain(|entry| entry.is_dir || filter::matches(filter, &entry.name));
+    }
+    if min_rating > 0 {
+        entries.retain(|entry| entry.is_dir || entry.rating >= min_rating);
+    }
+    if !kinds.is_empty() {
+        entries.retain(|entry| kinds.keeps(dir, entry));
+    }
+}
@@ -0,0 +1,161 @@
+//! A dialog for the permission bits of the targeted entries: a checkbox
+//! for each, or the octal mode typed in.
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Style, Stylize},
+    symbols::border,
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph, Widget},
+};
+
+use crate::{entry::Meta, theme};
+
+const ROWS: [&str; 4] = ["Owner", "Group", "Others", "Special"];
+const COLUMNS: [&str; 3] = ["Read", "Write", "Execute"];
+/// What the checkboxes in the last row stand for.
+const SPECIAL: [&str; 3] = ["setuid", "setgid", "sticky"];
+
+/// Result of feeding a key to the dialog.
+pub enum PermissionsAction {
+    None,
+    Cancel,
+    /// Set the targets' permissions to this mode.
+    Apply(u32),
+}
+
+#[derive(Debug, Clone)]
+pub struct PermissionsEditor {
+    /// What the title says the mode applies to.
+    subject: String,
+    /// The permission bits, without the file type.
+    mode: u32,
+    /// The mode as typed so far, while typing one.
+    typed: Option<String>,
+    /// Row and column of the checkbox the cursor is on.
+    row: usize,
+    column: usize,
+}
+
+impl PermissionsEditor {
+    pub fn new(subject: impl Into<String>, mode: u32) -> Self {
+        Self {
+            subject: subject.into(),
+            mode: mode & 0o7777,
+            typed: None,
+            row: 0,
+            column: 0,
+        }
+    }
+
+    /// The bit of the checkbox at `row` and `column`.
+    fn bit(row: usize, column: usize) -> u32 {
+        if row == 3 {
+            0o4000 >> column
+        } else {
+            1 << (8 - 3 * row - column)
+        }
+    }
+
+    pub fn handle_key(&mut self, key_event: KeyEvent) -> PermissionsAction {
+        match key_event.code {
+            KeyCode::Esc => return PermissionsAction::Cancel,
+            KeyCode::Enter => return PermissionsAction::Apply(self.mode),
+            KeyCode::Char(c @ '0'..='7') => {
+                let typed = self.typed.get_or_insert_with(String::new);
+                if typed.len() < 4 {
+                    typed.push(c);
+                }
+                self.mode = u32::from_str_radix(typed, 8).unwrap_or(0);
+                return PermissionsAction::None;
+            }
+            KeyCode::Backspace => {
+                if let Some(typed) = self.typed.as_mut() {
+                    typed.pop();
+                    self.mode = u32::from_str_radix(typed, 8).unwrap_or(0);
+                }
+                return PermissionsAction::None;
+            }
+            KeyCode::Up | KeyCode::Char('k') => self.row = self.row.saturating_sub(1),
+            KeyCode::Down | KeyCode::Char('j') => self.row = (self.row + 1).min(ROWS.len() - 1),
+            KeyCode::Left | KeyCode::Char('h') => self.column = self.column.saturating_sub(1),
+            KeyCode::Right | KeyCode::Char('l') => {
+                self.column = (self.column + 1).min(COLUMNS.len() - 1)
+            }
+            KeyCode::Char(' ') | KeyCode::Char('x') => {
+                self.mode ^= Self::bit(self.row, self.column)
+            }
+            _ => return PermissionsAction::None,
+        }
+        // Checkboxes take over from typing
+        self.typed = None;
+        PermissionsAction::None
+    }
+}
+
+impl Widget for &PermissionsEditor {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let theme = theme::current();
+        let width = area.width.saturating_sub(4).min(56);
+        let height = 9.min(area.height);
+        let rect = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + area.height.saturating_sub(height) / 2,
+            width,
+            height,
+        };
+
+        let mut lines = vec![Line::from(
+            std::iter::once(format!("{:9}", ""))
+                .chain(COLUMNS.iter().map(|name| format!("{:10}", name)))
+                .collect::<String>()
+                .fg(theme.dim),
+        )];
+        for (row, name) in ROWS.iter().enumerate() {
+            let mut spans = vec![Span::raw(format!("{:9}", name))];
+            for (column, special) in SPECIAL.iter().enumerate() {
+                let set = self.mode & PermissionsEditor::bit(row, column) != 0;
+                let mut checkbox = Span::raw(if set { "[x]" } else { "[ ]" });
+                if (row, column) == (self.row, self.column) {
+                    checkbox = checkbox.reversed();
+                }
+                spans.push(checkbox);
+                let label = if row == 3 { special } else { "" };
+                spans.push(format!(" {:6}", label).fg(theme.dim));
+            }
+            lines.push(Line::from(spans));
+        }
+        let meta = Meta {
+            mode: self.mode,
+            ..Default::default()
+        };
+        // Without the file type, the first letter is always a dash
+        let symbolic = &meta.permissions()[1..];
+        let mode = match &self.typed {
+            Some(typed) => Span::styled(format!("{}▏", typed), Style::new().fg(theme.highlight)),
+            None => Span::raw(format!("{:04o}", self.mode)),
+        };
+        lines.push(Line::default());
+        lines.push(Line::from(vec![
+            "Mode: ".into(),
+            mode.bold(),
+            format!("  {}", symbolic).fg(theme.dim),
+        ]));
+
+        Clear.render(rect, buf);
+        let block = Block::bordered()
+            .title(
+                format!(" Permissions of {} ", self.subject)
+                    .fg(theme.accent)
+                    .bold(),
+            )
+            .title_bottom(
+                Line::from(" Space toggle · 0-7 mode · Enter apply · Esc cancel ").right_aligned(),
+            )
+            .border_style(theme.border)
+            .border_set(border::ROUNDED);
+        Paragraph::new(lines).block(block).render(rect, buf);
+    }
+}
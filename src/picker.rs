@@ -0,0 +1,152 @@
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style, Stylize},
+    symbols::border,
+    text::{Line, Text},
+    widgets::{Block, Clear, Paragraph, Widget},
+};
+
+use std::path::PathBuf;
+
+use crate::{
+    cleanup::Candidate, file_actions::FileAction, npm::Package, sendto::SendTarget,
+    snapshots::Version, tasks::Target, theme, trash::TrashedItem,
+};
+
+/// What the picked item refers to.
+#[derive(Debug, Clone)]
+pub enum PickerKind {
+    Trash(Vec<TrashedItem>),
+    SendTo(Vec<SendTarget>),
+    /// Snapshot and history copies of the file at the path.
+    Versions(PathBuf, Vec<Version>),
+    /// Deleted files with their newest copy in the history.
+    Deleted(Vec<(PathBuf, Version)>),
+    /// The bookmarked directories, as listed in the app's bookmarks.
+    Bookmarks,
+    /// Every option in [`crate::settings::ALL`], with its value.
+    Settings,
+    /// Recent log lines, newest first.
+    Log,
+    /// What a command run as a job printed.
+    CommandOutput,
+    /// The scripts of a JavaScript package.
+    Scripts(Package),
+    /// Makefile targets and justfile recipes in the directory.
+    Tasks(PathBuf, Vec<Target>),
+    /// Programs to open the file with, followed by an entry for typing one.
+    OpenWith(PathBuf, Vec<String>),
+    /// What can be done with the file, by its type.
+    Actions(PathBuf, Vec<FileAction>),
+    /// Caches and build output found under the current directory.
+    Cleanup(Vec<Candidate>),
+    /// The entries collected in the app's scratch.
+    Scratch,
+}
+
+/// Result of feeding a key to a picker.
+pub enum PickerAction {
+    None,
+    Cancel,
+    Choose(usize),
+    /// A letter key the picker doesn't handle itself.
+    Key(char),
+}
+
+/// A scrollable list shown as a centered overlay.
+#[derive(Debug, Clone)]
+pub struct Picker {
+    pub kind: PickerKind,
+    pub title: String,
+    pub hint: String,
+    pub items: Vec<String>,
+    pub selected: usize,
+}
+
+impl Picker {
+    pub fn new(
+        kind: PickerKind,
+        title: impl Into<String>,
+        hint: impl Into<String>,
+        items: Vec<String>,
+    ) -> Self {
+        Self {
+            kind,
+            title: title.into(),
+            hint: hint.into(),
+            items,
+            selected: 0,
+        }
+    }
+
+    pub fn handle_key(&mut self, key_event: KeyEvent) -> PickerAction {
+        match key_event.code {
+            KeyCode::Esc | KeyCode::Char('q') => PickerAction::Cancel,
+            KeyCode::Up => {
+                self.selected = self.selected.saturating_sub(1);
+                PickerAction::None
+            }
+            KeyCode::Down => {
+                if self.selected + 1 < self.items.len() {
+                    self.selected += 1;
+                }
+                PickerAction::None
+            }
+            KeyCode::Enter if !self.items.is_empty() => PickerAction::Choose(self.selected),
+            KeyCode::Char(c) => PickerAction::Key(c),
+            _ => PickerAction::None,
+        }
+    }
+}
+
+impl Widget for &Picker {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let theme = theme::current();
+        let width = (area.width * 7 / 10).max(20).min(area.width);
+        let height = (area.height * 6 / 10).max(5).min(area.height);
+        let rect = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+
+        let max_visible = height.saturating_sub(2) as usize;
+        let start = (self.selected + 1).saturating_sub(max_visible);
+        let lines: Vec<Line> = if self.items.is_empty() {
+            vec![Line::from("(empty)".fg(theme.dim))]
+        } else {
+            self.items
+                .iter()
+                .enumerate()
+                .skip(start)
+                .take(max_visible)
+                .map(|(i, item)| {
+                    if i == self.selected {
+                        Line::from(item.clone()).style(
+                            Style::default()
+                                .bg(theme.selection)
+                                .fg(theme.selection_text)
+                                .add_modifier(Modifier::BOLD),
+                        )
+                    } else {
+                        Line::from(item.clone())
+                    }
+                })
+                .collect()
+        };
+
+        Clear.render(rect, buf);
+        Paragraph::new(Text::from(lines))
+            .block(
+                Block::bordered()
+                    .title(format!(" {} ", self.title).fg(theme.accent).bold())
+                    .title_bottom(Line::from(format!(" {} ", self.hint)).right_aligned())
+                    .border_style(theme.border)
+                    .border_set(border::ROUNDED),
+            )
+            .render(rect, buf);
+    }
+}
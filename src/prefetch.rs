@@ -0,0 +1,200 @@
+//! Previews read ahead of the cursor while nothing else is going on, and
+//! kept for when it gets there.
+
+use std::{
+    collections::{HashMap, VecDeque},
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicU64, Ordering},
+        mpsc::{self, Receiver, Sender},
+    },
+    thread,
+    time::SystemTime,
+};
+
+use crate::{
+    preview::{self, Preview},
+    previewer::Previewers,
+    theme::{self, Theme},
+};
+
+/// Previews kept at most, the least recently stored going first.
+const CAPACITY: usize = 32;
+/// Entries of a selected directory read ahead, in name order.
+const INSIDE: usize = 3;
+
+/// When a file was modified and how big it is: a preview is only used again
+/// while these stay the same.
+type Stamp = (SystemTime, u64);
+
+fn stamp(path: &Path) -> Option<Stamp> {
+    let metadata = std::fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+/// Paths to read ahead, dropped if the cursor moves on before the worker
+/// gets to them.
+#[derive(Debug)]
+struct Request {
+    generation: u64,
+    paths: Vec<PathBuf>,
+    /// A directory whose first entries are read too.
+    inside: Option<PathBuf>,
+    show_hidden: bool,
+    max_size: u64,
+    theme: Theme,
+    previewers: Previewers,
+}
+
+#[derive(Debug)]
+pub struct Previews {
+    cache: HashMap<PathBuf, (Stamp, Preview)>,
+    order: VecDeque<PathBuf>,
+    sender: Sender<Request>,
+    receiver: Receiver<(u64, PathBuf, Stamp, Preview)>,
+    /// Bumped to cancel what was asked for before.
+    generation: Arc<AtomicU64>,
+    /// The generation the cache was last cleared at; anything read for
+    /// one before is out of date.
+    cleared: u64,
+}
+
+impl Previews {
+    pub fn new() -> Self {
+        let (sender, requests) = mpsc::channel::<Request>();
+        let (results, receiver) = mpsc::channel();
+        let generation = Arc::new(AtomicU64::new(0));
+
+        let current = Arc::clone(&generation);
+        thread::spawn(move || {
+            for request in requests {
+                theme::set_current(request.theme);
+                let mut paths = request.paths;
+                paths.extend(
+                    request
+                        .inside
+                        .iter()
+                        .flat_map(|dir| first_entries(dir, request.show_hidden)),
+                );
+                for path in paths {
+                    if current.load(Ordering::Relaxed) != request.generation {
+                        break;
+                    }
+                    let Some(stamp) = stamp(&path) else {
+                        continue;
+                    };
+                    let preview = preview::preview(
+                        &path,
+                        request.show_hidden,
+                        request.max_size,
+                        &request.previewers,
+                    );
+                    if results
+                        .send((request.generation, path, stamp, preview))
+                        .is_err()
+                    {
+                        return;
+                    }
+                }
+            }
+        });
+
+        Self {
+            cache: HashMap::new(),
+            order: VecDeque::new(),
+            sender,
+            receiver,
+            generation,
+            cleared: 0,
+        }
+    }
+
+    /// The preview of `path` if it was read since it last changed.
+    pub fn get(&self, path: &Path) -> Option<Preview> {
+        let (stamped, preview) = self.cache.get(path)?;
+        (stamp(path) == Some(*stamped)).then(|| preview.clone())
+    }
+
+    /// Keeps `preview`, read when `path` had `stamp`.
+    fn insert(&mut self, path: PathBuf, stamp: Stamp, preview: Preview) {
+        if self.cache.insert(path.clone(), (stamp, preview)).is_none() {
+            self.order.push_back(path);
+        }
+        while self.order.len() > CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.cache.remove(&oldest);
+            }
+        }
+    }
+
+    /// Keeps a preview just read, if `path` can be told apart when it
+    /// changes.
+    pub fn store(&mut self, path: &Path, preview: &Preview) {
+        if let Some(stamp) = stamp(path) {
+            self.insert(path.to_path_buf(), stamp, preview.clone());
+        }
+    }
+
+    /// Reads `paths`, and the first entries of `inside`, in the background,
+    /// in place of what was asked for before.
+    pub fn prefetch(
+        &mut self,
+        paths: Vec<PathBuf>,
+        inside: Option<PathBuf>,
+        show_hidden: bool,
+        max_size: u64,
+        theme: Theme,
+        previewers: Previewers,
+    ) {
+        let generation = self.generation.fetch_add(1, Ordering::Relaxed) + 1;
+        let paths = paths
+            .into_iter()
+            .filter(|path| self.get(path).is_none())
+            .collect();
+        let _ = self.sender.send(Request {
+            generation,
+            paths,
+            inside,
+            show_hidden,
+            max_size,
+            theme,
+            previewers,
+        });
+    }
+
+    /// Takes in what the worker has read.
+    pub fn poll(&mut self) {
+        while let Ok((generation, path, stamp, preview)) = self.receiver.try_recv() {
+            if generation > self.cleared {
+                self.insert(path, stamp, preview);
+            }
+        }
+    }
+
+    /// Forgets every preview, for when the settings they were read with
+    /// change, and cancels what is being read.
+    pub fn clear(&mut self) {
+        self.cleared = self.generation.fetch_add(1, Ordering::Relaxed) + 1;
+        self.cache.clear();
+        self.order.clear();
+    }
+}
+
+/// The first few files in `dir` by name.
+fn first_entries(dir: &Path, show_hidden: bool) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut names: Vec<_> = entries
+        .flatten()
+        .map(|entry| entry.file_name())
+        .filter(|name| show_hidden || !name.to_string_lossy().starts_with('.'))
+        .collect();
+    names.sort();
+    names
+        .into_iter()
+        .take(INSIDE)
+        .map(|name| dir.join(name))
+        .collect()
+}
@@ -0,0 +1,552 @@
+use std::path::Path;
+
+use ratatui::{
+    style::Stylize,
+    text::{Line, Text},
+};
+
+use crate::{
+    ansi,
+    error::BrowrsError,
+    hex,
+    highlight::Highlighter,
+    ignore,
+    image::{self, Image},
+    media, notes,
+    pane::Listing,
+    previewer::{Previewer, Previewers},
+    pseudo, systemd, theme, vfs,
+};
+#[cfg(unix)]
+use crate::{
+    attrs,
+    entry::{Meta, Special},
+};
+
+/// Lines of a text file or hex dump rendered up front; the rest follow as
+/// the preview is scrolled towards them.
+const PREVIEW_CHUNK: usize = 200;
+/// Decoded previews are shrunk to this size, enough for any preview pane.
+const IMAGE_SIZE: usize = 1024;
+pub const IMAGE_EXTENSIONS: [&str; 8] = ["png", "jpg", "jpeg", "gif", "bmp", "webp", "svg", "ico"];
+
+/// Preview pane content, with an optional image drawn below the text. With
+/// an image, the text is the header above it.
+#[derive(Debug, Clone)]
+pub struct Preview {
+    pub text: Text<'static>,
+    pub image: Option<Image>,
+    /// The part of the file not rendered yet.
+    rest: Option<Rest>,
+}
+
+/// The unrendered end of a file.
+#[derive(Debug, Clone)]
+enum Rest {
+    Text {
+        content: String,
+        /// Byte offset of the first line not rendered yet.
+        offset: usize,
+        highlighter: Option<Highlighter>,
+        /// Whether a block comment or code fence is still open there.
+        open: bool,
+        /// Set for text colored with escape sequences, which are parsed
+        /// instead of highlighting it.
+        colors: Option<ansi::Parser>,
+    },
+    Hex {
+        bytes: Vec<u8>,
+        offset: usize,
+    },
+}
+
+impl From<Text<'static>> for Preview {
+    fn from(text: Text<'static>) -> Self {
+        Self {
+            text,
+            image: None,
+            rest: None,
+        }
+    }
+}
+
+impl Preview {
+    /// Shows the note attached to the file under the first header line.
+    pub fn annotate(&mut self, note: &str) {
+        let at = self.text.lines.len().min(1);
+        let line = Line::from(format!("📝 {}", note).fg(theme::current().highlight));
+        self.text.lines.insert(at, line);
+    }
+
+    /// Renders more of the file, if any is left, until there are at least
+    /// `lines` lines.
+    pub fn load_until(&mut self, lines: usize) {
+        while self.text.lines.len() < lines
+            && let Some(rest) = self.rest.as_mut()
+        {
+            let done = match rest {
+                Rest::Text {
+                    content,
+                    offset,
+                    highlighter,
+                    open,
+                    colors,
+                } => {
+                    let remaining = &content[*offset..];
+                    let mut chunk = remaining.split_inclusive('\n').take(PREVIEW_CHUNK);
+                    let end = chunk.by_ref().map(str::len).sum::<usize>();
+                    let chunk = remaining[..end].lines();
+                    match (colors, highlighter) {
+                        (Some(parser), _) => self.text.extend(chunk.map(|line| parser.line(line))),
+                        (None, Some(highlighter)) => {
+                            self.text.extend(highlighter.highlight(chunk, open))
+                        }
+                        (None, None) => self
+                            .text
+                            .extend(chunk.map(|line| Line::from(line.to_string()))),
+                    }
+                    *offset += end;
+                    *offset >= content.len()
+                }
+                Rest::Hex { bytes, offset } => {
+                    let end = (*offset + PREVIEW_CHUNK * hex::NARROW).min(bytes.len());
+                    let lines =
+                        bytes[*offset..end]
+                            .chunks(hex::NARROW)
+                            .enumerate()
+                            .map(|(i, chunk)| {
+                                let at = *offset + i * hex::NARROW;
+                                hex::line(at as u64, chunk, hex::NARROW)
+                            });
+                    self.text.extend(lines);
+                    *offset = end;
+                    *offset >= bytes.len()
+                }
+            };
+            if done {
+                self.rest = None;
+            }
+        }
+    }
+}
+
+/// Builds the preview pane content for a file or directory. A symlink
+/// previews as its target, with where it points under the header. Files
+/// one of `previewers` is for are previewed with its output.
+pub fn preview(path: &Path, show_hidden: bool, max_size: u64, previewers: &Previewers) -> Preview {
+    let name = path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    let Ok(target) = std::fs::read_link(path) else {
+        return target_preview(path, &name, show_hidden, max_size, previewers);
+    };
+    if !path.exists() {
+        return Text::from(vec![
+            Line::from(format!("🔗 {} | broken link", name)),
+            Line::from("─".repeat(40)),
+            Line::from(format!("Target: {}", target.display())),
+            Line::default(),
+            Line::from("Nothing exists there".fg(theme::current().error)),
+        ])
+        .into();
+    }
+    let mut preview = target_preview(path, &name, show_hidden, max_size, previewers);
+    let at = preview.text.lines.len().min(1);
+    let line = Line::from(format!("🔗 Link to {}", target.display()).fg(theme::current().link));
+    preview.text.lines.insert(at, line);
+    preview
+}
+
+fn target_preview(
+    path: &Path,
+    name: &str,
+    show_hidden: bool,
+    max_size: u64,
+    previewers: &Previewers,
+) -> Preview {
+    if vfs::in_archive(path) {
+        archive_preview(path, show_hidden, max_size)
+    } else if path.is_dir() {
+        read_dir_preview(path, show_hidden).into()
+    } else if path.is_file() {
+        if let Some(previewer) = previewers.find(path) {
+            return command_preview(path, name, previewer, previewers, max_size);
+        }
+        if let Some(ext) = path.extension().and_then(|e| e.to_str()) {
+            let ext = ext.to_lowercase();
+            if IMAGE_EXTENSIONS.contains(&ext.as_str()) {
+                return match image::load(path) {
+                    Ok((image, format)) => Preview {
+                        text: image_header(name, &image, format),
+                        image: Some(image.fit(IMAGE_SIZE, IMAGE_SIZE)),
+                        rest: None,
+                    },
+                    Err(e) => Text::from(format!(
+                        "📷 Image file: {}\n\nNo preview: {}\nType: {}",
+                        name,
+                        e,
+                        ext.to_uppercase()
+                    ))
+                    .into(),
+                };
+            }
+            if media::EXTENSIONS.contains(&ext.as_str())
+                && let Ok(info) = media::probe(path)
+            {
+                let size = path.metadata().map_or(0, |m| m.len());
+                return media::preview(name, size, &info).into();
+            }
+            if systemd::EXTENSIONS.contains(&ext.as_str())
+                && path.metadata().is_ok_and(|m| m.len() <= max_size)
+                && let Ok(text) = std::fs::read_to_string(path)
+            {
+                let sections = systemd::parse(&text);
+                // Anything else with the same extension previews as usual
+                if !sections.is_empty() {
+                    let status = systemd::status(path);
+                    return systemd::preview(name, &sections, status.as_deref()).into();
+                }
+            }
+        }
+        // For text files and files without extension
+        read_file_preview(path, max_size)
+    } else if let Some(text) = special_preview(path, name) {
+        text.into()
+    } else {
+        Text::from("Unable to access file").into()
+    }
+}
+
+/// What a named pipe, socket or device node is, without reading it: a
+/// pipe waits for a writer and a device may never end.
+#[cfg(unix)]
+fn special_preview(path: &Path, name: &str) -> Option<Text<'static>> {
+    let meta = Meta::of(&std::fs::metadata(path).ok()?);
+    let special = meta.special()?;
+    let icon = match special {
+        Special::Fifo => "📨",
+        Special::Socket => "🔌",
+        Special::BlockDevice | Special::CharDevice => "💽",
+    };
+    let mut lines = vec![
+        Line::from(format!("{} {} | {}", icon, name, special.name())),
+        Line::from("─".repeat(40)),
+    ];
+    if special.is_device() {
+        lines.push(Line::from(format!("Device: {}", meta.device())));
+    }
+    let owner = attrs::user_name(meta.uid).unwrap_or_else(|| meta.uid.to_string());
+    lines.push(Line::from(format!("Permissions: {}", meta.permissions())));
+    lines.push(Line::from(format!("Owner: {}", owner)));
+    lines.push(Line::default());
+    lines.push(Line::from(
+        "Not read for the preview".fg(theme::current().dim),
+    ));
+    Some(Text::from(lines))
+}
+
+#[cfg(not(unix))]
+fn special_preview(_path: &Path, _name: &str) -> Option<Text<'static>> {
+    None
+}
+
+/// The output of `previewer` for `path`, its colors kept. When it fails
+/// the file previews as usual, with why under the header.
+fn command_preview(
+    path: &Path,
+    name: &str,
+    previewer: &Previewer,
+    previewers: &Previewers,
+    max_size: u64,
+) -> Preview {
+    let content = match previewer.output(path, previewers.timeout, max_size) {
+        Ok(content) => content,
+        Err(e) => {
+            let mut preview = read_file_preview(path, max_size);
+            let at = preview.text.lines.len().min(1);
+            let line = Line::from(format!("⚠ Previewer failed: {}", e).fg(theme::current().error));
+            preview.text.lines.insert(at, line);
+            return preview;
+        }
+    };
+    let program = previewer
+        .template
+        .command
+        .split_whitespace()
+        .next()
+        .unwrap_or_default();
+    let text = Text::from(vec![
+        Line::from(format!(
+            "📄 {} | {} lines | {}",
+            name,
+            content.lines().count(),
+            program
+        )),
+        Line::from("─".repeat(40)),
+    ]);
+    let header = text.lines.len();
+    let mut preview = Preview {
+        text,
+        image: None,
+        rest: (!content.is_empty()).then_some(Rest::Text {
+            content,
+            offset: 0,
+            highlighter: None,
+            open: false,
+            colors: Some(ansi::Parser::default()),
+        }),
+    };
+    preview.load_until(header + PREVIEW_CHUNK);
+    preview
+}
+
+fn image_header(name: &str, image: &Image, format: &str) -> Text<'static> {
+    let (width, height) = image.original;
+    Text::from(vec![
+        Line::from(format!("🖼 {} | {}×{} | {}", name, width, height, format)),
+        Line::from("─".repeat(40)),
+    ])
+}
+
+fn read_file_preview(file_path: &Path, max_size: u64) -> Preview {
+    // Sizes there say nothing about what reading gives
+    if pseudo::is_pseudo(file_path) {
+        return match pseudo::read(file_path, max_size) {
+            Ok(mut bytes) => {
+                pseudo::split_strings(&mut bytes);
+                text_preview(file_path, bytes)
+            }
+            Err(e) => Text::from(BrowrsError::io("read", file_path, e).message()).into(),
+        };
+    }
+    if let Ok(metadata) = std::fs::metadata(file_path)
+        && metadata.len() > max_size
+    {
+        return Text::from(format!(
+            "📄 File too large for preview\nSize: {} bytes\nUse Enter to open in editor",
+            metadata.len()
+        ))
+        .into();
+    }
+
+    match std::fs::read(file_path) {
+        Ok(bytes) => text_preview(file_path, bytes),
+        Err(e) => Text::from(BrowrsError::io("read", file_path, e).message()).into(),
+    }
+}
+
+/// An archive or a directory in one listed like a directory, a file in one
+/// like a file on disk.
+fn archive_preview(path: &Path, show_hidden: bool, max_size: u64) -> Preview {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    if vfs::is_dir(path) {
+        let listing = Listing {
+            show_hidden,
+            sort: Default::default(),
+            dirs_first: true,
+            ratings: Default::default(),
+            hide_ignored: false,
+        };
+        return match vfs::read_dir(path, listing) {
+            Ok(entries) => {
+                let entries = &entries[1..];
+                let dirs = entries.iter().filter(|e| e.is_dir).count();
+                let mut result = format!(
+                    "📦 {}\n📊 {} directories, {} files\n{}\n",
+                    name,
+                    dirs,
+                    entries.len() - dirs,
+                    "─".repeat(40)
+                );
+                for entry in entries.iter().take(30) {
+                    let icon = if entry.is_dir { "📁" } else { "📄" };
+                    result.push_str(&format!("{} {}\n", icon, entry.display_name()));
+                }
+                if entries.len() > 30 {
+                    result.push_str(&format!("... and {} more items\n", entries.len() - 30));
+                }
+                result.push_str("\nPress Enter to browse the archive");
+                Text::from(result).into()
+            }
+            Err(e) => Text::from(BrowrsError::io("read", path, e).message()).into(),
+        };
+    }
+    match vfs::read(path, max_size + 1) {
+        Ok(bytes) if bytes.len() as u64 > max_size => Text::from(format!(
+            "📄 File too large for preview\nSize: over {} bytes\nUse c to extract it",
+            max_size
+        ))
+        .into(),
+        Ok(bytes) => text_preview(path, bytes),
+        Err(e) => Text::from(BrowrsError::io("read", path, e).message()).into(),
+    }
+}
+
+fn text_preview(file_path: &Path, bytes: Vec<u8>) -> Preview {
+    // Check if file appears to be binary; escape sequences, which may
+    // end in a bell, are text
+    if bytes
+        .iter()
+        .take(1024)
+        .any(|&b| b == 0 || (b < 32 && !matches!(b, 7 | 9 | 10 | 13 | 27)))
+    {
+        return hex_preview(file_path, bytes);
+    }
+
+    let byteslen = bytes.len();
+    match String::from_utf8(bytes) {
+        Ok(content) => {
+            let line_count = content.lines().count();
+            let first_line = content.lines().next().unwrap_or_default();
+            let colors = ansi::has_escapes(&content).then(ansi::Parser::default);
+            let highlighter = match colors {
+                Some(_) => None,
+                None => Highlighter::detect(file_path, first_line),
+            };
+
+            let file_name = file_path.file_name().unwrap_or_default().to_string_lossy();
+            let language = match (&colors, &highlighter) {
+                (Some(_), _) => " | ANSI colors".to_string(),
+                (None, Some(highlighter)) => format!(" | {}", highlighter.name()),
+                (None, None) => String::new(),
+            };
+            let text = Text::from(vec![
+                Line::from(format!(
+                    "📄 {} | {} bytes | {} lines{}",
+                    file_name, byteslen, line_count, language
+                )),
+                Line::from("─".repeat(40)),
+            ]);
+            let header = text.lines.len();
+            let mut preview = Preview {
+                text,
+                image: None,
+                rest: (!content.is_empty()).then_some(Rest::Text {
+                    content,
+                    offset: 0,
+                    highlighter,
+                    open: false,
+                    colors,
+                }),
+            };
+            preview.load_until(header + PREVIEW_CHUNK);
+            preview
+        }
+        Err(e) => hex_preview(file_path, e.into_bytes()),
+    }
+}
+
+fn hex_preview(file_path: &Path, bytes: Vec<u8>) -> Preview {
+    let file_name = file_path.file_name().unwrap_or_default().to_string_lossy();
+    let text = Text::from(vec![
+        Line::from(format!("📄 {} | {} bytes | binary", file_name, bytes.len())),
+        Line::from("─".repeat(40)),
+    ]);
+    let header = text.lines.len();
+    let mut preview = Preview {
+        text,
+        image: None,
+        rest: (!bytes.is_empty()).then_some(Rest::Hex { bytes, offset: 0 }),
+    };
+    preview.load_until(header + PREVIEW_CHUNK);
+    preview
+}
+
+fn read_dir_preview(file_path: &Path, show_hidden: bool) -> Text<'static> {
+    let hidden = (!show_hidden).then(|| ignore::Rules::browrs(file_path));
+    match std::fs::read_dir(file_path) {
+        Ok(entries) => {
+            let mut dirs = Vec::new();
+            let mut files = Vec::new();
+            let mut total_size = 0u64;
+
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().to_string();
+
+                // Skip hidden files for preview unless they are toggled on
+                if !show_hidden && name.starts_with('.') {
+                    continue;
+                }
+
+                if let Ok(file_type) = entry.file_type() {
+                    if hidden
+                        .as_ref()
+                        .is_some_and(|rules| rules.is_ignored(&entry.path(), file_type.is_dir()))
+                    {
+                        continue;
+                    }
+                    if file_type.is_dir() {
+                        dirs.push(format!("📁 {}/", name));
+                    } else {
+                        let size_info = if let Ok(metadata) = entry.metadata() {
+                            total_size += metadata.len();
+                            if metadata.len() > 1024 {
+                                format!(" ({:.1} KB)", metadata.len() as f64 / 1024.0)
+                            } else {
+                                format!(" ({} B)", metadata.len())
+                            }
+                        } else {
+                            String::new()
+                        };
+                        files.push(format!("📄 {}{}", name, size_info));
+                    }
+                }
+            }
+
+            // Sort and combine
+            dirs.sort();
+            files.sort();
+
+            let mut result = format!(
+                "📂 Directory: {}\n",
+                file_path.file_name().unwrap_or_default().to_string_lossy()
+            );
+            result.push_str(&format!(
+                "📊 {} directories, {} files",
+                dirs.len(),
+                files.len()
+            ));
+
+            if total_size > 0 {
+                if total_size > 1024 * 1024 {
+                    result.push_str(&format!(
+                        " (Total: {:.1} MB)",
+                        total_size as f64 / (1024.0 * 1024.0)
+                    ));
+                } else if total_size > 1024 {
+                    result.push_str(&format!(" (Total: {:.1} KB)", total_size as f64 / 1024.0));
+                } else {
+                    result.push_str(&format!(" (Total: {} B)", total_size));
+                }
+            }
+
+            result.push_str(&format!("\n{}\n", "─".repeat(40)));
+
+            // Add items (limit to prevent overwhelming)
+            let mut items = dirs;
+            items.extend(files);
+
+            for item in items.iter().take(30) {
+                result.push_str(&format!("{}\n", item));
+            }
+
+            if items.len() > 30 {
+                result.push_str(&format!("... and {} more items\n", items.len() - 30));
+            }
+
+            if let Some(note) = notes::find(file_path, 8) {
+                result.push_str(&format!("\n📌 {}\n{}\n", note.file_name, "─".repeat(40)));
+                for line in &note.lines {
+                    result.push_str(&format!("{}\n", line));
+                }
+            }
+
+            result.push_str("\nPress Enter to navigate into directory");
+
+            Text::from(result)
+        }
+        Err(e) => Text::from(BrowrsError::io("read", file_path, e).message()),
+    }
+}
@@ -0,0 +1,130 @@
+//! Commands from the `[previewers]` config table whose output is the
+//! preview of files matching a pattern, in place of the built-in one:
+//!
+//! ```toml
+//! [previewers]
+//! "*.json" = "jq -C . {file}"
+//! csv = "column -t -s, {file}"
+//! ```
+//!
+//! `{file}` and `{dir}` are filled in as for `[open]`, and a command with
+//! neither gets the file appended. Colors in the output are kept. A command
+//! still running after `preview_timeout_ms` is killed.
+
+use std::{
+    io::{self, Read},
+    os::unix::process::CommandExt,
+    path::Path,
+    process::{Child, Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{log, template::Template};
+
+/// How often a running command is looked at to see whether it is done.
+const POLL: Duration = Duration::from_millis(10);
+/// Bytes of error output kept to say why a command failed.
+const STDERR_SIZE: u64 = 4096;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Previewer {
+    pub template: Template,
+}
+
+/// The previewers from the config, with how long each may run.
+#[derive(Debug, Clone, Default)]
+pub struct Previewers {
+    pub rules: Vec<Previewer>,
+    pub timeout: Duration,
+}
+
+impl Previewers {
+    /// The first previewer for `path`.
+    pub fn find(&self, path: &Path) -> Option<&Previewer> {
+        self.rules.iter().find(|rule| rule.matches(path))
+    }
+}
+
+impl Previewer {
+    /// Reads one `pattern = command` entry. A bare extension such as `json`
+    /// stands for `*.json`.
+    pub fn parse(pattern: &str, command: &str) -> Result<Previewer, String> {
+        Ok(Previewer {
+            template: Template::parse(pattern, command, "preview")?,
+        })
+    }
+
+    pub fn matches(&self, path: &Path) -> bool {
+        self.template.matches(path)
+    }
+
+    /// The command for `path`, with the placeholders filled in.
+    pub fn expand(&self, path: &Path) -> String {
+        self.template.expand(path)
+    }
+
+    /// What the command prints for `path`, up to `max_size` bytes. It is
+    /// killed once it has run for `timeout`.
+    pub fn output(&self, path: &Path, timeout: Duration, max_size: u64) -> io::Result<String> {
+        let command = self.expand(path);
+        log::info!("previewing {} with sh -c {}", path.display(), command);
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg(&command)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            // A group of its own, so that what `sh` starts goes with it
+            .process_group(0)
+            .spawn()?;
+        // Read as it comes, so that a command with a lot to say doesn't
+        // block on a full pipe; past the limit the pipe is closed on it
+        let stdout = child.stdout.take().map(|out| read(out, max_size));
+        let stderr = child.stderr.take().map(|err| read(err, STDERR_SIZE));
+        let started = Instant::now();
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+            if started.elapsed() >= timeout {
+                kill_group(&child);
+                let _ = child.wait();
+                return Err(io::Error::new(
+                    io::ErrorKind::TimedOut,
+                    format!("{} took longer than {} ms", command, timeout.as_millis()),
+                ));
+            }
+            thread::sleep(POLL);
+        };
+        let collect = |reader: Option<thread::JoinHandle<Vec<u8>>>| {
+            let bytes = reader.and_then(|r| r.join().ok()).unwrap_or_default();
+            String::from_utf8_lossy(&bytes).into_owned()
+        };
+        if !status.success() {
+            let stderr = collect(stderr);
+            let reason = stderr
+                .lines()
+                .find(|line| !line.trim().is_empty())
+                .map_or_else(|| format!("exited with {}", status), str::to_string);
+            return Err(io::Error::other(reason));
+        }
+        Ok(collect(stdout))
+    }
+}
+
+/// Kills `child` and everything it started that is still in its group.
+fn kill_group(child: &Child) {
+    // The group's id is the pid of the child that leads it
+    let group = -(child.id() as libc::pid_t);
+    unsafe { libc::kill(group, libc::SIGKILL) };
+}
+
+/// Reads up to `limit` bytes of `from` on a thread of its own.
+fn read(from: impl Read + Send + 'static, limit: u64) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut bytes = Vec::new();
+        let _ = from.take(limit).read_to_end(&mut bytes);
+        bytes
+    })
+}
@@ -0,0 +1,285 @@
+use std::path::PathBuf;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::Stylize,
+    symbols::border,
+    text::{Line, Text},
+    widgets::{Block, Clear, Paragraph, Widget},
+};
+
+use crate::{export::Report, recall::Browse, smart::SmartFolder, theme};
+
+/// What a submitted prompt should be used for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PromptKind {
+    /// Editor command for files with the given extension.
+    Editor(String),
+    /// Destination directory for copying the targeted entries.
+    Copy,
+    /// Destination directory for moving the targeted entries.
+    Move,
+    /// Destination directory for links to the targeted entries, hard links
+    /// if set.
+    Link(bool),
+    /// New name for the entry under the cursor.
+    Rename,
+    /// Confirmation before a rename replaces an existing entry.
+    ConfirmRename(String),
+    /// Confirmation before moving the targeted entries to the trash.
+    ConfirmTrash,
+    /// Confirmation before permanently deleting the targeted entries.
+    ConfirmDelete,
+    /// Confirmation before permanently deleting everything in the trash.
+    ConfirmEmptyTrash,
+    /// Timestamp for touching the targeted entries.
+    Touch,
+    /// Path of a file to create under the current directory.
+    NewFile,
+    /// Path of a directory to create under the current directory.
+    NewDir,
+    /// Path of a file to create holding this text from the clipboard.
+    PasteFile(String),
+    /// Directory to unpack the archive into.
+    Extract(PathBuf),
+    /// Name of the archive to pack the targeted entries into.
+    Compress,
+    /// chmod/chown spec for the targeted entries.
+    Attributes,
+    /// `owner[:group]` for the targeted entries.
+    Owner,
+    /// Confirmation before applying an attribute change.
+    ConfirmAttributes(String),
+    /// Command to open the file with.
+    OpenWith(PathBuf),
+    /// A `:` command line.
+    Command,
+    /// Absolute, `~` or relative path to go to.
+    Goto,
+    /// Pattern for filtering the focused pane; empty clears it.
+    Filter,
+    /// New value for the option with this key on the settings screen.
+    Setting(&'static str),
+    /// Note to attach to this entry; empty removes it.
+    Annotate(PathBuf),
+    /// Name to save the search or filter under as a smart folder.
+    SaveSmartFolder(SmartFolder),
+    /// Confirmation before trashing, or deleting if `permanent`, the
+    /// cleanup candidate at `index` of the picker.
+    ConfirmCleanup { index: usize, permanent: bool },
+    /// Confirmation before downloading a pasted URL.
+    ConfirmDownload(String),
+    /// Confirmation before replacing a file with its copy from a snapshot.
+    ConfirmRestoreVersion { version: PathBuf, dest: PathBuf },
+    /// Path of the file to write the report to.
+    Export(Report),
+}
+
+/// Result of feeding a key to a prompt.
+pub enum PromptAction {
+    None,
+    Cancel,
+    Submit(String),
+}
+
+/// What Tab does to the input: the completed input, and the candidates
+/// when more than one fits.
+pub type Completer = fn(&str) -> (String, Vec<String>);
+
+/// Single-line text input shown as a centered overlay.
+#[derive(Debug, Clone)]
+pub struct Prompt {
+    pub kind: PromptKind,
+    pub title: String,
+    pub input: String,
+    /// Yes/no question shown instead of a text field.
+    pub confirm: bool,
+    /// Earlier input brought back with Up and Down.
+    recall: Browse,
+    complete: Option<Completer>,
+    /// What Tab puts in an empty input, the first one, and what is listed
+    /// under it meanwhile.
+    suggestions: Vec<String>,
+    /// A line under the input: the candidates Tab found, or what was wrong
+    /// with the input.
+    note: Option<Line<'static>>,
+}
+
+impl Prompt {
+    pub fn new(kind: PromptKind, title: impl Into<String>, input: impl Into<String>) -> Self {
+        Self {
+            kind,
+            title: title.into(),
+            input: input.into(),
+            confirm: false,
+            recall: Browse::default(),
+            complete: None,
+            suggestions: vec![],
+            note: None,
+        }
+    }
+
+    /// Lets Tab complete the input with `complete`.
+    pub fn with_completion(self, complete: Completer) -> Self {
+        Self {
+            complete: Some(complete),
+            ..self
+        }
+    }
+
+    /// Offers `suggestions` while the input is empty.
+    pub fn with_suggestions(self, suggestions: Vec<String>) -> Self {
+        Self {
+            suggestions,
+            ..self
+        }
+    }
+
+    /// Shows what was wrong with the input under it.
+    pub fn with_error(self, error: impl Into<String>) -> Self {
+        Self {
+            note: Some(Line::from(error.into().fg(theme::current().error))),
+            ..self
+        }
+    }
+
+    /// Lets Up and Down step through `entries`, oldest first.
+    pub fn with_recall(self, entries: Vec<String>) -> Self {
+        Self {
+            recall: Browse::new(entries),
+            ..self
+        }
+    }
+
+    /// A yes/no dialog asking `question`.
+    pub fn confirm(
+        kind: PromptKind,
+        title: impl Into<String>,
+        question: impl Into<String>,
+    ) -> Self {
+        Self {
+            confirm: true,
+            ..Self::new(kind, title, question)
+        }
+    }
+
+    /// Inserts pasted text, up to its first line break.
+    pub fn paste(&mut self, text: &str) {
+        if !self.confirm {
+            self.input.push_str(text.lines().next().unwrap_or_default());
+        }
+    }
+
+    pub fn handle_key(&mut self, key_event: KeyEvent) -> PromptAction {
+        self.note = None;
+        if self.confirm {
+            return match key_event.code {
+                KeyCode::Char('y') | KeyCode::Char('Y') | KeyCode::Enter => {
+                    PromptAction::Submit(String::new())
+                }
+                KeyCode::Char('n') | KeyCode::Char('N') | KeyCode::Esc => PromptAction::Cancel,
+                _ => PromptAction::None,
+            };
+        }
+        match key_event.code {
+            KeyCode::Esc => PromptAction::Cancel,
+            KeyCode::Enter => PromptAction::Submit(self.input.clone()),
+            KeyCode::Backspace => {
+                self.input.pop();
+                PromptAction::None
+            }
+            KeyCode::Tab => {
+                if self.input.is_empty()
+                    && let Some(first) = self.suggestions.first()
+                {
+                    self.input = first.clone();
+                } else if let Some(complete) = self.complete {
+                    let (input, candidates) = complete(&self.input);
+                    self.input = input;
+                    if candidates.len() > 1 {
+                        self.note = Some(Line::from(candidates.join(" ").fg(theme::current().dim)));
+                    }
+                }
+                PromptAction::None
+            }
+            KeyCode::Up | KeyCode::Down | KeyCode::Char('p') | KeyCode::Char('n')
+                if is_recall_key(key_event) =>
+            {
+                let recalled = match key_event.code {
+                    KeyCode::Up | KeyCode::Char('p') => self.recall.older(&self.input),
+                    _ => self.recall.newer(),
+                };
+                if let Some(input) = recalled {
+                    self.input = input;
+                }
+                PromptAction::None
+            }
+            KeyCode::Char('u') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.input.clear();
+                PromptAction::None
+            }
+            KeyCode::Char(c) if !key_event.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.input.push(c);
+                PromptAction::None
+            }
+            _ => PromptAction::None,
+        }
+    }
+}
+
+impl Prompt {
+    /// The line under the input, if any.
+    fn note(&self) -> Option<Line<'static>> {
+        if self.note.is_some() || !self.input.is_empty() || self.suggestions.is_empty() {
+            return self.note.clone();
+        }
+        let often = format!("Often: {}", self.suggestions.join("  "));
+        Some(Line::from(often.fg(theme::current().dim)))
+    }
+}
+
+/// Up and Down, or Ctrl+P and Ctrl+N for when the arrows do something else.
+fn is_recall_key(key_event: KeyEvent) -> bool {
+    match key_event.code {
+        KeyCode::Up | KeyCode::Down => true,
+        KeyCode::Char('p' | 'n') => key_event.modifiers.contains(KeyModifiers::CONTROL),
+        _ => false,
+    }
+}
+
+impl Widget for &Prompt {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let theme = theme::current();
+        let width = area.width.saturating_sub(4).min(60);
+        let note = self.note();
+        let height = if note.is_some() { 4 } else { 3 };
+        let rect = Rect {
+            x: area.x + (area.width.saturating_sub(width)) / 2,
+            y: area.y + area.height.saturating_sub(height) / 2,
+            width,
+            height: height.min(area.height),
+        };
+
+        let (text, hint) = if self.confirm {
+            (self.input.clone(), " y to confirm, n to cancel ")
+        } else {
+            (
+                format!("{}▏", self.input),
+                " Enter to confirm, Esc to cancel ",
+            )
+        };
+
+        Clear.render(rect, buf);
+        let block = Block::bordered()
+            .title(format!(" {} ", self.title).fg(theme.accent).bold())
+            .title_bottom(Line::from(hint).right_aligned())
+            .border_style(theme.border)
+            .border_set(border::ROUNDED);
+        let lines = std::iter::once(Line::from(text)).chain(note);
+        Paragraph::new(Text::from_iter(lines))
+            .block(block)
+            .render(rect, buf);
+    }
+}
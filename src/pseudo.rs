@@ -0,0 +1,61 @@
+//! Pseudo-filesystems such as /proc and /sys, whose files are made up by
+//! the kernel as they are read: most report a size of 0 whatever they
+//! hold, so they are read up to a limit instead of by their size.
+
+use std::{
+    io::{self, Read},
+    path::Path,
+};
+
+/// Whether `path` is on a filesystem the kernel generates.
+#[cfg(target_os = "linux")]
+pub fn is_pseudo(path: &Path) -> bool {
+    use std::{ffi::CString, os::unix::ffi::OsStrExt};
+
+    // The types of these differ from one target to the next
+    #[allow(clippy::unnecessary_cast)]
+    const MAGIC: [i64; 7] = [
+        libc::PROC_SUPER_MAGIC as i64,
+        libc::SYSFS_MAGIC as i64,
+        libc::DEBUGFS_MAGIC as i64,
+        libc::TRACEFS_MAGIC as i64,
+        libc::CGROUP_SUPER_MAGIC as i64,
+        libc::CGROUP2_SUPER_MAGIC as i64,
+        libc::SECURITYFS_MAGIC as i64,
+    ];
+    let Ok(path) = CString::new(path.as_os_str().as_bytes()) else {
+        return false;
+    };
+    // SAFETY: statfs only writes into the provided struct
+    let mut stat: libc::statfs = unsafe { std::mem::zeroed() };
+    if unsafe { libc::statfs(path.as_ptr(), &mut stat) } != 0 {
+        return false;
+    }
+    #[allow(clippy::unnecessary_cast)]
+    MAGIC.contains(&(stat.f_type as i64))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn is_pseudo(_path: &Path) -> bool {
+    false
+}
+
+/// Up to `limit` bytes of the file, however big it says it is.
+pub fn read(path: &Path, limit: u64) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    std::fs::File::open(path)?
+        .take(limit)
+        .read_to_end(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Files like `/proc/PID/cmdline` and `environ` separate their strings
+/// with NUL bytes; with them on lines of their own, they read as text.
+pub fn split_strings(bytes: &mut [u8]) {
+    let printable = |&b: &u8| b == 0 || b >= 32 || matches!(b, 9 | 10 | 13);
+    if bytes.contains(&0) && bytes.iter().all(printable) {
+        for byte in bytes.iter_mut().filter(|b| **b == 0) {
+            *byte = b'\n';
+        }
+    }
+}
@@ -0,0 +1,545 @@
+//! Minimal QR code encoder: byte mode, error correction level L, versions
+//! 1 to 10 (up to 271 bytes), which is plenty for a URL.
+
+use std::path::Path;
+
+use crossterm::event::{KeyCode, KeyEvent};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style, Stylize},
+    symbols::border,
+    text::{Line, Span, Text},
+    widgets::{Block, Clear, Paragraph, Widget},
+};
+
+use crate::theme;
+
+/// Error correction codewords per block, per version.
+const ECC_PER_BLOCK: [usize; 10] = [7, 10, 15, 20, 26, 18, 20, 24, 30, 18];
+/// Number of error correction blocks, per version.
+const BLOCKS: [usize; 10] = [1, 1, 1, 1, 1, 2, 2, 2, 2, 4];
+/// Format bits identifying error correction level L.
+const LEVEL_L: u32 = 1;
+/// Modules of light border drawn around the code.
+const QUIET_ZONE: isize = 2;
+/// Bytes the biggest version holds.
+pub const MAX_BYTES: usize = 271;
+
+#[derive(Debug, Clone)]
+pub struct QrCode {
+    size: usize,
+    modules: Vec<bool>,
+    function: Vec<bool>,
+}
+
+impl QrCode {
+    /// Encodes `data` in the smallest version that fits it.
+    pub fn encode(data: &[u8]) -> Option<QrCode> {
+        let version = (1..=10).find(|&v| {
+            let header = 4 + if v < 10 { 8 } else { 16 };
+            header + data.len() * 8 <= data_codewords(v) * 8
+        })?;
+        let codewords = add_ecc_and_interleave(version, &encode_data(version, data));
+
+        let size = version * 4 + 17;
+        let mut qr = QrCode {
+            size,
+            modules: vec![false; size * size],
+            function: vec![false; size * size],
+        };
+        qr.draw_function_patterns(version);
+        qr.draw_codewords(&codewords);
+
+        let mut best = (0, i32::MAX);
+        for mask in 0..8 {
+            qr.apply_mask(mask);
+            qr.draw_format_bits(mask);
+            let penalty = qr.penalty();
+            if penalty < best.1 {
+                best = (mask, penalty);
+            }
+            qr.apply_mask(mask);
+        }
+        qr.apply_mask(best.0);
+        qr.draw_format_bits(best.0);
+        Some(qr)
+    }
+
+    /// Draws the code with half blocks, two modules per cell, in fixed
+    /// colors so it scans on dark terminal themes too.
+    pub fn lines(&self) -> Vec<Line<'static>> {
+        let size = self.size as isize;
+        let color = |dark: bool| if dark { Color::Black } else { Color::White };
+        (-QUIET_ZONE..size + QUIET_ZONE)
+            .step_by(2)
+            .map(|y| {
+                let spans: Vec<Span> = (-QUIET_ZONE..size + QUIET_ZONE)
+                    .map(|x| {
+                        Span::styled(
+                            "▀",
+                            Style::default()
+                                .fg(color(self.is_dark(x, y)))
+                                .bg(color(self.is_dark(x, y + 1))),
+                        )
+                    })
+                    .collect();
+                Line::from(spans)
+            })
+            .collect()
+    }
+
+    /// Whether the module at column `x`, row `y` is dark. Coordinates
+    /// outside the symbol are light, which doubles as the quiet zone.
+    pub fn is_dark(&self, x: isize, y: isize) -> bool {
+        let size = self.size as isize;
+        (0..size).contains(&x) && (0..size).contains(&y) && self.get(x as usize, y as usize)
+    }
+
+    fn get(&self, x: usize, y: usize) -> bool {
+        self.modules[y * self.size + x]
+    }
+
+    fn set_function(&mut self, x: usize, y: usize, dark: bool) {
+        self.modules[y * self.size + x] = dark;
+        self.function[y * self.size + x] = true;
+    }
+
+    fn draw_function_patterns(&mut self, version: usize) {
+        let size = self.size;
+        for i in 0..size {
+            self.set_function(6, i, i % 2 == 0);
+            self.set_function(i, 6, i % 2 == 0);
+        }
+
+        for (x, y) in [(3, 3), (size - 4, 3), (3, size - 4)] {
+            for dy in -4isize..=4 {
+                for dx in -4isize..=4 {
+                    let (xx, yy) = (x as isize + dx, y as isize + dy);
+                    if (0..size as isize).contains(&xx) && (0..size as isize).contains(&yy) {
+                        let dist = dx.abs().max(dy.abs());
+                        self.set_function(xx as usize, yy as usize, dist != 2 && dist != 4);
+                    }
+                }
+            }
+        }
+
+        let positions = alignment_positions(version);
+        let last = positions.len().saturating_sub(1);
+        for (i, &x) in positions.iter().enumerate() {
+            for (j, &y) in positions.iter().enumerate() {
+                // Skip the three corners taken by finder patterns
+                if (i == 0 && (j == 0 || j == last)) || (i == last && j == 0) {
+                    continue;
+                }
+                for dy in -2isize..=2 {
+                    for dx in -2isize..=2 {
+                        let dark = dx.abs().max(dy.abs()) != 1;
+                        self.set_function(
+                            (x as isize + dx) as usize,
+                            (y as isize + dy) as usize,
+                            dark,
+                        );
+                    }
+                }
+            }
+        }
+
+        // Reserve the format areas; the real bits are drawn once the mask is known
+        self.draw_format_bits(0);
+
+        if version >= 7 {
+            let mut rem = version as u32;
+            for _ in 0..12 {
+                rem = (rem << 1) ^ ((rem >> 11) * 0x1F25);
+            }
+            let bits = (version as u32) << 12 | rem;
+            for i in 0..18 {
+                let dark = (bits >> i) & 1 != 0;
+                let (a, b) = (size - 11 + i % 3, i / 3);
+                self.set_function(a, b, dark);
+                self.set_function(b, a, dark);
+            }
+        }
+    }
+
+    fn draw_format_bits(&mut self, mask: u32) {
+        let data = LEVEL_L << 3 | mask;
+        let mut rem = data;
+        for _ in 0..10 {
+            rem = (rem << 1) ^ ((rem >> 9) * 0x537);
+        }
+        let bits = (data << 10 | rem) ^ 0x5412;
+        let bit = |i: usize| (bits >> i) & 1 != 0;
+        let size = self.size;
+
+        for i in 0..=5 {
+            self.set_function(8, i, bit(i));
+        }
+        self.set_function(8, 7, bit(6));
+        self.set_function(8, 8, bit(7));
+        self.set_function(7, 8, bit(8));
+        for i in 9..15 {
+            self.set_function(14 - i, 8, bit(i));
+        }
+
+        for i in 0..8 {
+            self.set_function(size - 1 - i, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set_function(8, size - 15 + i, bit(i));
+        }
+        self.set_function(8, size - 8, true);
+    }
+
+    /// Places data bits in the zigzag order, skipping function modules.
+    fn draw_codewords(&mut self, codewords: &[u8]) {
+        let size = self.size;
+        let mut i = 0;
+        let mut right = size - 1;
+        loop {
+            if right == 6 {
+                right = 5;
+            }
+            for vert in 0..size {
+                for j in 0..2 {
+                    let x = right - j;
+                    let upward = (right + 1) & 2 == 0;
+                    let y = if upward { size - 1 - vert } else { vert };
+                    if !self.function[y * size + x] && i < codewords.len() * 8 {
+                        self.modules[y * size + x] = (codewords[i >> 3] >> (7 - (i & 7))) & 1 != 0;
+                        i += 1;
+                    }
+                }
+            }
+            if right < 2 {
+                break;
+            }
+            right -= 2;
+        }
+    }
+
+    /// XORs the data modules with a mask pattern; applying it twice undoes it.
+    fn apply_mask(&mut self, mask: u32) {
+        let size = self.size;
+        for y in 0..size {
+            for x in 0..size {
+                let invert = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                if invert && !self.function[y * size + x] {
+                    self.modules[y * size + x] ^= true;
+                }
+            }
+        }
+    }
+
+    /// Scores how hard the symbol is to scan, following the spec's rules.
+    fn penalty(&self) -> i32 {
+        let size = self.size;
+        let mut penalty = 0;
+
+        for vertical in [false, true] {
+            let at = |a: usize, b: usize| {
+                if vertical {
+                    self.get(a, b)
+                } else {
+                    self.get(b, a)
+                }
+            };
+            for a in 0..size {
+                // Runs of five or more same-colored modules
+                let mut run = 1;
+                for b in 1..size {
+                    if at(a, b) == at(a, b - 1) {
+                        run += 1;
+                    } else {
+                        run = 1;
+                    }
+                    if run == 5 {
+                        penalty += 3;
+                    } else if run > 5 {
+                        penalty += 1;
+                    }
+                }
+                // Finder-like 1:1:3:1:1 patterns with light space on one side
+                for b in 0..size.saturating_sub(10) {
+                    let window: Vec<bool> = (b..b + 11).map(|i| at(a, i)).collect();
+                    const PATTERN: [bool; 11] = [
+                        true, false, true, true, true, false, true, false, false, false, false,
+                    ];
+                    let reversed = PATTERN.iter().rev().copied().collect::<Vec<_>>();
+                    if window == PATTERN || window == reversed {
+                        penalty += 40;
+                    }
+                }
+            }
+        }
+
+        for y in 0..size - 1 {
+            for x in 0..size - 1 {
+                let color = self.get(x, y);
+                if color == self.get(x + 1, y)
+                    && color == self.get(x, y + 1)
+                    && color == self.get(x + 1, y + 1)
+                {
+                    penalty += 3;
+                }
+            }
+        }
+
+        let dark = self.modules.iter().filter(|&&m| m).count() as i32;
+        let total = (size * size) as i32;
+        let k = ((dark * 20 - total * 10).abs() + total - 1) / total - 1;
+        penalty + k * 10
+    }
+}
+
+fn alignment_positions(version: usize) -> Vec<usize> {
+    if version == 1 {
+        return Vec::new();
+    }
+    let count = version / 7 + 2;
+    let step = (version * 4 + count * 2 + 1) / (count * 2 - 2) * 2;
+    let mut positions = vec![6];
+    let mut pos = version * 4 + 17 - 7;
+    for _ in 0..count - 1 {
+        positions.insert(1, pos);
+        pos -= step;
+    }
+    positions
+}
+
+fn raw_data_modules(version: usize) -> usize {
+    let mut result = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let count = version / 7 + 2;
+        result -= (25 * count - 10) * count - 55;
+        if version >= 7 {
+            result -= 36;
+        }
+    }
+    result
+}
+
+fn data_codewords(version: usize) -> usize {
+    raw_data_modules(version) / 8 - ECC_PER_BLOCK[version - 1] * BLOCKS[version - 1]
+}
+
+/// Byte-mode segment, terminator and padding, as data codewords.
+fn encode_data(version: usize, data: &[u8]) -> Vec<u8> {
+    let mut bits: Vec<bool> = Vec::new();
+    let mut push = |value: u32, len: usize| {
+        for i in (0..len).rev() {
+            bits.push((value >> i) & 1 != 0);
+        }
+    };
+    push(0b0100, 4);
+    push(data.len() as u32, if version < 10 { 8 } else { 16 });
+    for &byte in data {
+        push(byte as u32, 8);
+    }
+
+    let capacity = data_codewords(version) * 8;
+    let terminator = (capacity - bits.len()).min(4);
+    bits.extend(std::iter::repeat_n(false, terminator));
+    while !bits.len().is_multiple_of(8) {
+        bits.push(false);
+    }
+
+    let mut codewords: Vec<u8> = bits
+        .chunks(8)
+        .map(|chunk| chunk.iter().fold(0, |acc, &b| acc << 1 | b as u8))
+        .collect();
+    for pad in [0xEC, 0x11].into_iter().cycle() {
+        if codewords.len() >= data_codewords(version) {
+            break;
+        }
+        codewords.push(pad);
+    }
+    codewords
+}
+
+fn add_ecc_and_interleave(version: usize, data: &[u8]) -> Vec<u8> {
+    let blocks = BLOCKS[version - 1];
+    let ecc_len = ECC_PER_BLOCK[version - 1];
+    let raw_codewords = raw_data_modules(version) / 8;
+    let short_blocks = blocks - raw_codewords % blocks;
+    let short_len = raw_codewords / blocks;
+
+    let divisor = reed_solomon_divisor(ecc_len);
+    let mut split = Vec::new();
+    let mut offset = 0;
+    for i in 0..blocks {
+        let len = short_len - ecc_len + usize::from(i >= short_blocks);
+        let mut block = data[offset..offset + len].to_vec();
+        offset += len;
+        let ecc = reed_solomon_remainder(&block, &divisor);
+        if i < short_blocks {
+            // Keeps every block the same length for interleaving
+            block.push(0);
+        }
+        block.extend(ecc);
+        split.push(block);
+    }
+
+    let mut result = Vec::with_capacity(raw_codewords);
+    for i in 0..split[0].len() {
+        for (j, block) in split.iter().enumerate() {
+            if i != short_len - ecc_len || j >= short_blocks {
+                result.push(block[i]);
+            }
+        }
+    }
+    result
+}
+
+fn reed_solomon_divisor(degree: usize) -> Vec<u8> {
+    let mut result = vec![0u8; degree];
+    result[degree - 1] = 1;
+    let mut root = 1u8;
+    for _ in 0..degree {
+        for j in 0..degree {
+            result[j] = gf_multiply(result[j], root);
+            if j + 1 < degree {
+                result[j] ^= result[j + 1];
+            }
+        }
+        root = gf_multiply(root, 0x02);
+    }
+    result
+}
+
+fn reed_solomon_remainder(data: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut result = vec![0u8; divisor.len()];
+    for &byte in data {
+        let factor = byte ^ result.remove(0);
+        result.push(0);
+        for (r, &d) in result.iter_mut().zip(divisor) {
+            *r ^= gf_multiply(d, factor);
+        }
+    }
+    result
+}
+
+/// Multiplication in GF(2^8) modulo x^8 + x^4 + x^3 + x^2 + 1.
+fn gf_multiply(x: u8, y: u8) -> u8 {
+    let mut z: u32 = 0;
+    for i in (0..8).rev() {
+        z = (z << 1) ^ ((z >> 7) * 0x11D);
+        z ^= ((y as u32 >> i) & 1) * x as u32;
+    }
+    z as u8
+}
+
+/// What a [`QrView`] can show the code of.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Payload {
+    Path,
+    Contents,
+}
+
+/// An entry's path, or a small text file's contents, as a QR code over
+/// the listing.
+#[derive(Debug, Clone)]
+pub struct QrView {
+    path: String,
+    /// The file's text, when it is small enough to encode.
+    contents: Option<String>,
+    showing: Payload,
+    code: QrCode,
+}
+
+impl QrView {
+    /// The code of `path`'s text when it is a small text file, of the path
+    /// otherwise; `None` if neither fits.
+    pub fn open(path: &Path) -> Option<Self> {
+        let contents = path
+            .metadata()
+            .ok()
+            .filter(|metadata| metadata.is_file() && metadata.len() <= MAX_BYTES as u64)
+            .and_then(|_| std::fs::read(path).ok())
+            .filter(|bytes| !bytes.is_empty() && !bytes.contains(&0))
+            .and_then(|bytes| String::from_utf8(bytes).ok());
+        let path = path.display().to_string();
+        let (showing, code) = match &contents {
+            Some(text) => (Payload::Contents, QrCode::encode(text.as_bytes())?),
+            None => (Payload::Path, QrCode::encode(path.as_bytes())?),
+        };
+        Some(Self {
+            path,
+            contents,
+            showing,
+            code,
+        })
+    }
+
+    /// Handles a key; returns whether to close the view.
+    pub fn handle_key(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') | KeyCode::Enter => return true,
+            KeyCode::Tab => {
+                let (showing, text) = match (&self.showing, &self.contents) {
+                    (Payload::Contents, _) => (Payload::Path, &self.path),
+                    (Payload::Path, Some(contents)) => (Payload::Contents, contents),
+                    (Payload::Path, None) => return false,
+                };
+                if let Some(code) = QrCode::encode(text.as_bytes()) {
+                    self.showing = showing;
+                    self.code = code;
+                }
+            }
+            _ => {}
+        }
+        false
+    }
+}
+
+impl Widget for &QrView {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let theme = theme::current();
+        let lines = self.code.lines();
+        let code_width = lines.first().map_or(0, |line| line.width() as u16);
+        let width = (code_width + 2).max(40).min(area.width);
+        let height = (lines.len() as u16 + 3).min(area.height);
+        let rect = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+        let (title, hint) = match (&self.showing, &self.contents) {
+            (Payload::Contents, _) => (" QR: file contents ", " Tab path, Esc close "),
+            (Payload::Path, Some(_)) => (" QR: path ", " Tab contents, Esc close "),
+            (Payload::Path, None) => (" QR: path ", " Esc close "),
+        };
+        let block = Block::bordered()
+            .title(title.fg(theme.accent).bold())
+            .title_bottom(Line::from(hint).right_aligned())
+            .border_style(theme.border)
+            .border_set(border::ROUNDED);
+        let inner = block.inner(rect);
+        Clear.render(rect, buf);
+        block.render(rect, buf);
+
+        let mut text: Vec<Line> =
+            if lines.len() as u16 + 1 > inner.height || code_width > inner.width {
+                vec![Line::from(
+                    "Make the terminal bigger to show the whole code".fg(theme.error),
+                )]
+            } else {
+                lines
+            };
+        text.push(Line::from(self.path.clone().fg(theme.dim)).centered());
+        Paragraph::new(Text::from(text))
+            .centered()
+            .render(inner, buf);
+    }
+}
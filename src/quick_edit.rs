@@ -0,0 +1,283 @@
+//! A small built-in editor for quick fixes to short text files, like a
+//! config, without starting the real editor. It only inserts and deletes
+//! text and lines; anything more goes to the editor as usual.
+
+use std::{cell::Cell, io, path::PathBuf};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style, Stylize},
+    symbols::border,
+    text::{Line, Span, Text},
+    widgets::{Block, Clear, Paragraph, Widget},
+};
+
+use crate::theme;
+
+/// Files bigger than this are left to the editor.
+pub const MAX_SIZE: u64 = 64 * 1024;
+
+/// Result of feeding a key to the editor.
+pub enum EditAction {
+    None,
+    Close,
+    Save,
+}
+
+#[derive(Debug, Clone)]
+pub struct QuickEdit {
+    pub path: PathBuf,
+    lines: Vec<String>,
+    /// Line and character the cursor is on.
+    row: usize,
+    col: usize,
+    /// First line and column on screen, as last drawn.
+    top: Cell<usize>,
+    left: Cell<usize>,
+    /// Whether the file ended with a newline, kept when saving.
+    final_newline: bool,
+    pub modified: bool,
+    /// Set by Esc with unsaved changes; a second Esc discards them.
+    confirm_close: bool,
+    /// Lines on screen at the last render, for paging.
+    height: Cell<usize>,
+}
+
+impl QuickEdit {
+    /// Reads `path` for editing, refusing anything that isn't a small
+    /// UTF-8 text file.
+    pub fn open(path: PathBuf) -> io::Result<Self> {
+        let size = std::fs::metadata(&path)?.len();
+        if size > MAX_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("bigger than {} KB, use the editor", MAX_SIZE / 1024),
+            ));
+        }
+        let bytes = std::fs::read(&path)?;
+        if bytes.contains(&0) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "not a text file",
+            ));
+        }
+        let text = String::from_utf8(bytes)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "not UTF-8 text"))?;
+        let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+        if lines.is_empty() {
+            lines.push(String::new());
+        }
+        Ok(Self {
+            path,
+            lines,
+            row: 0,
+            col: 0,
+            top: Cell::new(0),
+            left: Cell::new(0),
+            final_newline: text.is_empty() || text.ends_with('\n'),
+            modified: false,
+            confirm_close: false,
+            height: Cell::new(20),
+        })
+    }
+
+    pub fn contents(&self) -> String {
+        let mut text = self.lines.join("\n");
+        if self.final_newline {
+            text.push('\n');
+        }
+        text
+    }
+
+    pub fn handle_key(&mut self, key: KeyEvent) -> EditAction {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        if key.code != KeyCode::Esc {
+            self.confirm_close = false;
+        }
+        match key.code {
+            KeyCode::Esc if self.modified && !self.confirm_close => self.confirm_close = true,
+            KeyCode::Esc => return EditAction::Close,
+            KeyCode::Char('s') if ctrl => return EditAction::Save,
+            KeyCode::Char('k') if ctrl => self.delete_line(),
+            KeyCode::Char(c) if !ctrl => self.insert(c),
+            KeyCode::Enter => self.split_line(),
+            KeyCode::Backspace => self.backspace(),
+            KeyCode::Delete => self.delete(),
+            KeyCode::Up => self.move_to(self.row.saturating_sub(1), self.col),
+            KeyCode::Down => self.move_to(self.row + 1, self.col),
+            KeyCode::PageUp => self.move_to(self.row.saturating_sub(self.height.get()), self.col),
+            KeyCode::PageDown => self.move_to(self.row + self.height.get(), self.col),
+            KeyCode::Left if self.col > 0 => self.col -= 1,
+            KeyCode::Left if self.row > 0 => self.move_to(self.row - 1, usize::MAX),
+            KeyCode::Right if self.col < self.line_len() => self.col += 1,
+            KeyCode::Right if self.row + 1 < self.lines.len() => self.move_to(self.row + 1, 0),
+            KeyCode::Home => self.col = 0,
+            KeyCode::End => self.col = self.line_len(),
+            _ => {}
+        }
+        EditAction::None
+    }
+
+    /// Saved; changes after this count as new ones.
+    pub fn saved(&mut self) {
+        self.modified = false;
+    }
+
+    fn line_len(&self) -> usize {
+        self.lines[self.row].chars().count()
+    }
+
+    fn move_to(&mut self, row: usize, col: usize) {
+        self.row = row.min(self.lines.len() - 1);
+        self.col = col.min(self.line_len());
+    }
+
+    /// Byte offset of the cursor in its line.
+    fn offset(&self) -> usize {
+        let line = &self.lines[self.row];
+        line.char_indices()
+            .nth(self.col)
+            .map_or(line.len(), |(i, _)| i)
+    }
+
+    fn insert(&mut self, c: char) {
+        let at = self.offset();
+        self.lines[self.row].insert(at, c);
+        self.col += 1;
+        self.modified = true;
+    }
+
+    fn split_line(&mut self) {
+        let at = self.offset();
+        let rest = self.lines[self.row].split_off(at);
+        self.lines.insert(self.row + 1, rest);
+        self.move_to(self.row + 1, 0);
+        self.modified = true;
+    }
+
+    fn backspace(&mut self) {
+        if self.col > 0 {
+            self.col -= 1;
+            let at = self.offset();
+            self.lines[self.row].remove(at);
+        } else if self.row > 0 {
+            let line = self.lines.remove(self.row);
+            self.move_to(self.row - 1, usize::MAX);
+            self.lines[self.row].push_str(&line);
+        } else {
+            return;
+        }
+        self.modified = true;
+    }
+
+    fn delete(&mut self) {
+        if self.col < self.line_len() {
+            let at = self.offset();
+            self.lines[self.row].remove(at);
+        } else if self.row + 1 < self.lines.len() {
+            let next = self.lines.remove(self.row + 1);
+            self.lines[self.row].push_str(&next);
+        } else {
+            return;
+        }
+        self.modified = true;
+    }
+
+    fn delete_line(&mut self) {
+        if self.lines.len() == 1 {
+            self.lines[0].clear();
+        } else {
+            self.lines.remove(self.row);
+        }
+        self.move_to(self.row, self.col);
+        self.modified = true;
+    }
+
+    /// Moves the view just enough to keep the cursor in an area `width`
+    /// by `height`.
+    fn scroll_into_view(&self, width: usize, height: usize) {
+        let (width, height) = (width.max(1), height.max(1));
+        self.height.set(height);
+        let top = self
+            .top
+            .get()
+            .clamp(self.row.saturating_sub(height - 1), self.row);
+        self.top.set(top);
+        let left = self
+            .left
+            .get()
+            .clamp(self.col.saturating_sub(width - 1), self.col);
+        self.left.set(left);
+    }
+}
+
+/// Draws the editor over most of the area.
+impl Widget for &QuickEdit {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let theme = theme::current();
+        let width = (area.width * 9 / 10).max(20).min(area.width);
+        let height = (area.height * 9 / 10).max(5).min(area.height);
+        let rect = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+        let name = self.path.file_name().unwrap_or_default().to_string_lossy();
+        let title = format!(" ✎ {}{} ", name, if self.modified { " [+]" } else { "" });
+        let hint = if self.confirm_close {
+            " Unsaved changes: Esc again discards them, Ctrl+S saves "
+                .fg(theme.error)
+                .bold()
+        } else {
+            " Ctrl+S save · Ctrl+K delete line · Esc close ".into()
+        };
+        let block = Block::bordered()
+            .title(title.fg(theme.highlight).bold())
+            .title_bottom(Line::from(hint).right_aligned())
+            .border_style(theme.border)
+            .border_set(border::ROUNDED);
+        let inner = block.inner(rect);
+        Clear.render(rect, buf);
+        block.render(rect, buf);
+
+        let gutter = self.lines.len().to_string().len() + 1;
+        let text_width = (inner.width as usize).saturating_sub(gutter);
+        self.scroll_into_view(text_width, inner.height as usize);
+        let (top, left) = (self.top.get(), self.left.get());
+        let lines: Vec<Line> = self
+            .lines
+            .iter()
+            .enumerate()
+            .skip(top)
+            .take(inner.height as usize)
+            .map(|(row, line)| {
+                let number = format!("{:>1$} ", row + 1, gutter - 1).fg(theme.dim);
+                // Tabs would throw the columns off, so they show as spaces
+                let shown = line
+                    .chars()
+                    .map(|c| if c == '\t' { ' ' } else { c })
+                    .skip(left)
+                    .take(text_width);
+                if row != self.row {
+                    return Line::from(vec![number, Span::raw(shown.collect::<String>())]);
+                }
+                // Room for the cursor after the last character
+                let shown: Vec<char> = shown.chain(std::iter::once(' ')).collect();
+                let at = (self.col - left).min(shown.len() - 1);
+                Line::from(vec![
+                    number,
+                    Span::raw(shown[..at].iter().collect::<String>()),
+                    Span::styled(
+                        shown[at].to_string(),
+                        Style::new().add_modifier(Modifier::REVERSED),
+                    ),
+                    Span::raw(shown[at + 1..].iter().collect::<String>()),
+                ])
+            })
+            .collect();
+        Paragraph::new(Text::from(lines)).render(inner, buf);
+    }
+}
@@ -0,0 +1,159 @@
+//! What was typed into the command line, the search and the filter before,
+//! for Up and Down to bring back. Each is kept in its own file under
+//! `$XDG_STATE_HOME/browrs/recall`, one entry per line, oldest first, and
+//! read again every time it is needed so tabs and other running browrs
+//! share it.
+
+use std::{collections::HashMap, io, path::PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Kind {
+    Command,
+    Search,
+    Filter,
+    Goto,
+}
+
+impl Kind {
+    fn file_name(self) -> &'static str {
+        match self {
+            Kind::Command => "command",
+            Kind::Search => "search",
+            Kind::Filter => "filter",
+            Kind::Goto => "goto",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Recall {
+    /// Where the files are; `None` keeps entries in memory only.
+    dir: Option<PathBuf>,
+    /// Entries kept per kind, 0 keeps none.
+    pub size: usize,
+    /// Whether entries are written to `dir`; off, they last for the session.
+    pub save: bool,
+    session: HashMap<Kind, Vec<String>>,
+}
+
+impl Default for Recall {
+    fn default() -> Self {
+        Self {
+            dir: None,
+            size: 100,
+            save: true,
+            session: HashMap::new(),
+        }
+    }
+}
+
+impl Recall {
+    pub fn default_dir() -> Option<PathBuf> {
+        dirs::state_dir()
+            .or_else(dirs::cache_dir)
+            .map(|dir| dir.join("browrs").join("recall"))
+    }
+
+    /// Keeps the entries in `dir`.
+    pub fn in_dir(dir: PathBuf) -> Self {
+        Self {
+            dir: Some(dir),
+            ..Self::default()
+        }
+    }
+
+    fn file(&self, kind: Kind) -> Option<PathBuf> {
+        let dir = self.dir.as_ref().filter(|_| self.save)?;
+        Some(dir.join(kind.file_name()))
+    }
+
+    /// The entries for `kind`, oldest first.
+    pub fn entries(&self, kind: Kind) -> Vec<String> {
+        let entries = match self.file(kind) {
+            Some(file) => std::fs::read_to_string(file)
+                .unwrap_or_default()
+                .lines()
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect(),
+            None => self.session.get(&kind).cloned().unwrap_or_default(),
+        };
+        let skip = entries.len().saturating_sub(self.size);
+        entries.into_iter().skip(skip).collect()
+    }
+
+    /// Adds `entry` as the newest, moving it there if it was already kept,
+    /// and drops the oldest past the size.
+    pub fn add(&mut self, kind: Kind, entry: &str) -> io::Result<()> {
+        let entry = entry.trim();
+        if entry.is_empty() || entry.contains('\n') || self.size == 0 {
+            return Ok(());
+        }
+        let mut entries = self.entries(kind);
+        entries.retain(|e| e != entry);
+        entries.push(entry.to_string());
+        let skip = entries.len().saturating_sub(self.size);
+        entries.drain(..skip);
+
+        let Some(file) = self.file(kind) else {
+            self.session.insert(kind, entries);
+            return Ok(());
+        };
+        if let Some(parent) = file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // Written whole and renamed into place, so another browrs reading
+        // it never sees half a file
+        let mut contents = entries.join("\n");
+        contents.push('\n');
+        let partial = file.with_extension(format!("{}.tmp", std::process::id()));
+        std::fs::write(&partial, contents)?;
+        std::fs::rename(&partial, &file)
+    }
+}
+
+/// Stepping through earlier entries in a text field, newest first, with
+/// what was typed before the first step kept to come back to.
+#[derive(Debug, Clone, Default)]
+pub struct Browse {
+    entries: Vec<String>,
+    /// The entry shown, counted from the newest.
+    shown: Option<usize>,
+    draft: String,
+}
+
+impl Browse {
+    pub fn new(entries: Vec<String>) -> Self {
+        Self {
+            entries,
+            ..Self::default()
+        }
+    }
+
+    /// The entry before the one shown, given the text in the field now.
+    pub fn older(&mut self, current: &str) -> Option<String> {
+        let next = self.shown.map_or(0, |shown| shown + 1);
+        if next >= self.entries.len() {
+            return None;
+        }
+        if self.shown.is_none() {
+            self.draft = current.to_string();
+        }
+        self.shown = Some(next);
+        Some(self.entries[self.entries.len() - 1 - next].clone())
+    }
+
+    /// The entry after the one shown, or what was typed before browsing.
+    pub fn newer(&mut self) -> Option<String> {
+        match self.shown? {
+            0 => {
+                self.shown = None;
+                Some(std::mem::take(&mut self.draft))
+            }
+            shown => {
+                self.shown = Some(shown - 1);
+                Some(self.entries[self.entries.len() - shown].clone())
+            }
+        }
+    }
+}
@@ -0,0 +1,390 @@
+use std::{
+    cell::Cell,
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, TryRecvError},
+    },
+    thread,
+};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Modifier, Style, Stylize},
+    symbols::border,
+    text::{Line, Text},
+    widgets::{Block, Paragraph, Widget},
+};
+
+use crate::{recall::Browse, theme, walk::Policy};
+
+/// Results kept after ranking; more than this is never visible anyway.
+const MAX_RESULTS: usize = 500;
+/// Results numbered for opening with Alt and a digit.
+const QUICK_OPEN: usize = 9;
+/// Paths sent from the walker per channel message.
+const BATCH_SIZE: usize = 256;
+
+/// A ranked match, `path` is relative to the search root.
+#[derive(Debug, Clone)]
+pub struct SearchMatch {
+    pub path: PathBuf,
+    pub is_dir: bool,
+    pub score: i64,
+}
+
+/// Incremental fuzzy filename search fed by a background directory walker.
+#[derive(Debug)]
+pub struct Search {
+    pub root: PathBuf,
+    pub query: String,
+    pub results: Vec<SearchMatch>,
+    pub selected: usize,
+    candidates: Vec<(PathBuf, bool)>,
+    receiver: Receiver<Vec<(PathBuf, bool)>>,
+    cancel: Arc<AtomicBool>,
+    done: bool,
+    /// First result on screen as last drawn, where the numbering starts.
+    first_visible: Cell<usize>,
+    /// Earlier queries brought back with Ctrl+P and Ctrl+N.
+    recall: Browse,
+    /// Results marked for a batch operation, kept while the query changes.
+    marked: BTreeSet<PathBuf>,
+    /// Notes attached to files under the root, matched like their names.
+    notes: HashMap<PathBuf, String>,
+}
+
+impl Search {
+    pub fn start(root: PathBuf, show_hidden: bool, policy: &Policy) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        let walker_root = root.clone();
+        let walker_cancel = Arc::clone(&cancel);
+        let walk = policy.walk(&root, show_hidden);
+        thread::spawn(move || {
+            let mut batch = Vec::with_capacity(BATCH_SIZE);
+            for found in walk {
+                if walker_cancel.load(Ordering::Relaxed) {
+                    return;
+                }
+                if let Ok(relative) = found.path.strip_prefix(&walker_root) {
+                    batch.push((relative.to_path_buf(), found.metadata.is_dir()));
+                }
+                if batch.len() >= BATCH_SIZE && sender.send(std::mem::take(&mut batch)).is_err() {
+                    return;
+                }
+            }
+            let _ = sender.send(batch);
+        });
+
+        Self {
+            root,
+            query: String::new(),
+            results: vec![],
+            selected: 0,
+            candidates: vec![],
+            receiver,
+            cancel,
+            done: false,
+            first_visible: Cell::new(0),
+            recall: Browse::default(),
+            marked: BTreeSet::new(),
+            notes: HashMap::new(),
+        }
+    }
+
+    /// Also finds entries by their notes, keyed by path under the root.
+    pub fn with_notes(mut self, notes: Vec<(PathBuf, String)>) -> Self {
+        self.notes = notes.into_iter().collect();
+        self
+    }
+
+    /// Lets [`Search::recall`] step through `queries`, oldest first.
+    pub fn with_recall(mut self, queries: Vec<String>) -> Self {
+        self.recall = Browse::new(queries);
+        self
+    }
+
+    /// Starts out searching for `query`.
+    pub fn with_query(mut self, query: &str) -> Self {
+        self.query = query.to_string();
+        self.rerank();
+        self
+    }
+
+    /// Replaces the query with the one before it, or the one after.
+    pub fn recall(&mut self, older: bool) {
+        let query = if older {
+            self.recall.older(&self.query)
+        } else {
+            self.recall.newer()
+        };
+        if let Some(query) = query {
+            self.query = query;
+            self.rerank();
+        }
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    pub fn scanned(&self) -> usize {
+        self.candidates.len()
+    }
+
+    /// Pulls newly walked paths from the worker; returns whether any arrived.
+    pub fn poll(&mut self) -> bool {
+        let mut changed = false;
+        loop {
+            match self.receiver.try_recv() {
+                Ok(batch) => {
+                    let start = self.candidates.len();
+                    self.candidates.extend(batch);
+                    self.rank_from(start);
+                    changed = true;
+                }
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    changed |= !self.done;
+                    self.done = true;
+                    break;
+                }
+            }
+        }
+        changed
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.query.push(c);
+        self.rerank();
+    }
+
+    pub fn pop(&mut self) {
+        self.query.pop();
+        self.rerank();
+    }
+
+    pub fn selected_match(&self) -> Option<&SearchMatch> {
+        self.results.get(self.selected)
+    }
+
+    /// Marks or unmarks the selected result and moves on to the next.
+    pub fn toggle_mark(&mut self) {
+        let Some(path) = self.selected_match().map(|m| m.path.clone()) else {
+            return;
+        };
+        if !self.marked.remove(&path) {
+            self.marked.insert(path);
+        }
+        if self.selected + 1 < self.results.len() {
+            self.selected += 1;
+        }
+    }
+
+    /// Marks every result, or unmarks them all if they already are.
+    pub fn toggle_mark_all(&mut self) {
+        if self.results.iter().all(|m| self.marked.contains(&m.path)) {
+            for m in &self.results {
+                self.marked.remove(&m.path);
+            }
+        } else {
+            self.marked
+                .extend(self.results.iter().map(|m| m.path.clone()));
+        }
+    }
+
+    pub fn marked_count(&self) -> usize {
+        self.marked.len()
+    }
+
+    /// Full paths of the marked results, or of the selected one if none are.
+    pub fn targets(&self) -> Vec<PathBuf> {
+        if self.marked.is_empty() {
+            self.selected_match()
+                .map(|m| vec![self.root.join(&m.path)])
+                .unwrap_or_default()
+        } else {
+            self.marked
+                .iter()
+                .map(|path| self.root.join(path))
+                .collect()
+        }
+    }
+
+    /// Index of the result shown with `number`, 1 to 9.
+    pub fn numbered(&self, number: usize) -> Option<usize> {
+        let index = self.first_visible.get() + number.checked_sub(1)?;
+        (number <= QUICK_OPEN && index < self.results.len()).then_some(index)
+    }
+
+    fn rerank(&mut self) {
+        self.results.clear();
+        self.selected = 0;
+        self.rank_from(0);
+    }
+
+    /// Scores candidates from `start` onwards and merges them into results.
+    fn rank_from(&mut self, start: usize) {
+        let query = self.query.to_lowercase();
+        for (path, is_dir) in &self.candidates[start..] {
+            let by_note = self
+                .notes
+                .get(path)
+                .and_then(|note| note_score(&query, note, path));
+            if let Some(score) = fuzzy_score(&query, path).max(by_note) {
+                self.results.push(SearchMatch {
+                    path: path.clone(),
+                    is_dir: *is_dir,
+                    score,
+                });
+            }
+        }
+        self.results.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| a.path.as_os_str().len().cmp(&b.path.as_os_str().len()))
+        });
+        self.results.truncate(MAX_RESULTS);
+        self.selected = self.selected.min(self.results.len().saturating_sub(1));
+    }
+}
+
+impl Drop for Search {
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Widget for &Search {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let theme = theme::current();
+        let max_visible = area.height.saturating_sub(2) as usize;
+        let start = (self.selected + 1).saturating_sub(max_visible);
+        self.first_visible.set(start);
+
+        let lines: Vec<Line> = self
+            .results
+            .iter()
+            .enumerate()
+            .skip(start)
+            .take(max_visible)
+            .map(|(i, m)| {
+                let mut name = m.path.to_string_lossy().to_string();
+                if m.is_dir {
+                    name.push('/');
+                }
+                let number = match i - start + 1 {
+                    n if n <= QUICK_OPEN => format!("{} ", n),
+                    _ => "  ".to_string(),
+                };
+                let mut line = if self.marked.contains(&m.path) {
+                    Line::from(vec![
+                        number.fg(theme.dim),
+                        format!("* {}", name).fg(theme.marked).bold(),
+                    ])
+                } else {
+                    Line::from(vec![number.fg(theme.dim), name.into()])
+                };
+                if let Some(note) = self.notes.get(&m.path) {
+                    line.push_span(format!("  📝 {}", note).fg(theme.dim));
+                }
+                if i == self.selected {
+                    line.style(
+                        Style::default()
+                            .bg(theme.selection)
+                            .fg(theme.selection_text)
+                            .add_modifier(Modifier::BOLD),
+                    )
+                } else {
+                    line
+                }
+            })
+            .collect();
+
+        let mut status = if self.done {
+            format!(" {} matches ", self.results.len())
+        } else {
+            format!(
+                " {} matches, scanned {}… ",
+                self.results.len(),
+                self.scanned()
+            )
+        };
+        if !self.marked.is_empty() {
+            status = format!(" {} marked,{}", self.marked.len(), status);
+        }
+
+        Paragraph::new(Text::from(lines))
+            .block(
+                Block::bordered()
+                    .title(
+                        format!(" Search: {}▏", self.query)
+                            .fg(theme.highlight)
+                            .bold(),
+                    )
+                    .title_bottom(Line::from(status).right_aligned())
+                    .border_style(theme.border)
+                    .border_set(border::PLAIN),
+            )
+            .render(area, buf);
+    }
+}
+
+/// A note containing `query` whole ranks like a file name that starts
+/// with it.
+fn note_score(query: &str, note: &str, path: &Path) -> Option<i64> {
+    if query.is_empty() || !note.to_lowercase().contains(query) {
+        return None;
+    }
+    let run = query.chars().count() as i64 * 8 + 8;
+    Some(run * 10 - path.as_os_str().len() as i64)
+}
+
+/// Subsequence match of `query` (already lowercased) against a path,
+/// rewarding consecutive runs, word starts and hits in the file name.
+pub fn fuzzy_score(query: &str, path: &Path) -> Option<i64> {
+    if query.is_empty() {
+        return Some(0);
+    }
+    let haystack = path.to_string_lossy().to_lowercase();
+    let name_start = haystack.rfind('/').map(|i| i + 1).unwrap_or(0);
+
+    let mut score = 0i64;
+    let mut query_chars = query.chars().peekable();
+    let mut previous_matched = false;
+    let mut previous_char = '/';
+
+    for (i, c) in haystack.char_indices() {
+        let Some(&wanted) = query_chars.peek() else {
+            break;
+        };
+        if c == wanted {
+            query_chars.next();
+            score += 1;
+            if previous_matched {
+                score += 5;
+            }
+            if matches!(previous_char, '/' | '_' | '-' | '.' | ' ') {
+                score += 8;
+            }
+            if i >= name_start {
+                score += 2;
+            }
+            previous_matched = true;
+        } else {
+            previous_matched = false;
+        }
+        previous_char = c;
+    }
+
+    if query_chars.peek().is_some() {
+        return None;
+    }
+    // Prefer shorter, shallower paths among equal matches
+    Some(score * 10 - haystack.len() as i64)
+}
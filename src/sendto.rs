@@ -0,0 +1,119 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use crate::{config::Config, editor, jobs::Progress, log, ops, template::shell_quote};
+
+/// An entry of the "send to" menu.
+#[derive(Debug, Clone)]
+pub enum SendTarget {
+    /// Shell command template using `{file}` (run once per file) or
+    /// `{files}` (run once with every file).
+    Command { name: String, template: String },
+    /// Directory the files get copied into.
+    Folder { name: String, path: PathBuf },
+}
+
+impl SendTarget {
+    pub fn label(&self) -> String {
+        match self {
+            SendTarget::Command { name, template } => format!("{}  ({})", name, template),
+            SendTarget::Folder { name, path } => format!("{}  → {}", name, path.display()),
+        }
+    }
+}
+
+/// Menu entries from the config, with e-mail offered when nothing is set up.
+pub fn targets(config: &Config) -> Vec<SendTarget> {
+    let mut targets: Vec<SendTarget> = config
+        .send_to
+        .iter()
+        .map(|(name, template)| SendTarget::Command {
+            name: name.clone(),
+            template: template.clone(),
+        })
+        .chain(
+            config
+                .send_to_folders
+                .iter()
+                .map(|(name, path)| SendTarget::Folder {
+                    name: name.clone(),
+                    path: path.clone(),
+                }),
+        )
+        .collect();
+    if targets.is_empty() && editor::is_available("xdg-email") {
+        targets.push(SendTarget::Command {
+            name: "E-mail".into(),
+            template: "xdg-email --attach {file}".into(),
+        });
+    }
+    targets
+}
+
+/// Sends `files` to `target`; meant to run as a job, counting the files
+/// off as their commands end.
+pub fn send(target: &SendTarget, files: &[PathBuf], progress: &Progress) -> ops::BatchReport {
+    match target {
+        SendTarget::Folder { path, .. } => ops::copy_all(files, path, progress),
+        SendTarget::Command { template, .. } => {
+            let mut report = ops::BatchReport::new("send");
+            if template.contains("{files}") {
+                let quoted: Vec<String> = files.iter().map(|f| shell_quote(f)).collect();
+                let command = template.replace("{files}", &quoted.join(" "));
+                let result = run(&command);
+                for file in files {
+                    report.record(file, clone_result(&result));
+                }
+            } else {
+                for (done, file) in files.iter().enumerate() {
+                    if progress.is_cancelled() {
+                        report.cancelled = true;
+                        break;
+                    }
+                    progress.count(done, files.len());
+                    report.record(file, run(&expand(template, file)));
+                }
+            }
+            report
+        }
+    }
+}
+
+/// Fills `{file}` in, appending the file when the template has no placeholder.
+fn expand(template: &str, file: &Path) -> String {
+    if template.contains("{file}") {
+        template.replace("{file}", &shell_quote(file))
+    } else {
+        format!("{} {}", template, shell_quote(file))
+    }
+}
+
+fn run(command: &str) -> io::Result<()> {
+    log::info!("running sh -c {}", command);
+    let _timer = log::timer(|| format!("sh -c {}", command));
+    let output = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::null())
+        .output()?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(io::Error::other(format!(
+            "{} ({})",
+            output.status,
+            stderr.lines().last().unwrap_or_default()
+        )))
+    }
+}
+
+fn clone_result(result: &io::Result<()>) -> io::Result<()> {
+    match result {
+        Ok(()) => Ok(()),
+        Err(e) => Err(io::Error::new(e.kind(), e.to_string())),
+    }
+}
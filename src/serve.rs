@@ -0,0 +1,550 @@
+use std::{
+    hash::{BuildHasher, Hasher, RandomState},
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{IpAddr, Ipv4Addr, SocketAddr, TcpListener, TcpStream, UdpSocket},
+    path::{Component, Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, SystemTime},
+};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::Stylize,
+    symbols::border,
+    text::{Line, Text},
+    widgets::{Block, Clear, Paragraph, Widget, Wrap},
+};
+
+use crate::{datetime, qr::QrCode, theme, url};
+
+/// Transfer log lines kept for the panel.
+const MAX_LOG: usize = 200;
+/// Connections answered at once; more are turned away until one ends.
+const MAX_CONNECTIONS: usize = 16;
+/// Bytes of request line and headers read before a request is refused.
+const MAX_HEAD: u64 = 8192;
+/// Headers read before a request is refused.
+const MAX_HEADERS: usize = 64;
+/// Cookie the device that used the link is known by afterwards.
+const COOKIE: &str = "browrs_session";
+
+/// What the server exposes.
+#[derive(Debug, Clone)]
+pub enum Share {
+    /// A directory and everything below it.
+    Dir(PathBuf),
+    /// Individual entries, listed together at the top level.
+    Entries(Vec<PathBuf>),
+}
+
+impl Share {
+    /// Maps a decoded request path onto the filesystem, refusing anything
+    /// that would step outside the shared entries, through `..` or through
+    /// a symlink.
+    fn resolve(&self, request: &Path, show_hidden: bool) -> Option<PathBuf> {
+        let mut parts = Vec::new();
+        for component in request.components() {
+            match component {
+                Component::Normal(part) if show_hidden || !is_hidden(part) => parts.push(part),
+                Component::CurDir => {}
+                _ => return None,
+            }
+        }
+        let (top, rest) = match self {
+            Share::Dir(root) => (root, &parts[..]),
+            Share::Entries(entries) => {
+                // The top level only exists as the generated listing
+                let Some((first, rest)) = parts.split_first() else {
+                    return Some(PathBuf::new());
+                };
+                let entry = entries.iter().find(|e| e.file_name() == Some(*first))?;
+                (entry, rest)
+            }
+        };
+        let mut path = top.clone();
+        for part in rest {
+            path.push(part);
+        }
+        let real = path.canonicalize().ok()?;
+        real.starts_with(top.canonicalize().ok()?).then_some(path)
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Share::Dir(root) => root.display().to_string(),
+            Share::Entries(entries) => format!("{} marked entries", entries.len()),
+        }
+    }
+}
+
+/// A background HTTP server sharing files behind a random token, good for
+/// one device: the first to use it gets a cookie to go on with.
+#[derive(Debug)]
+pub struct Server {
+    pub url: String,
+    share: Share,
+    qr: Option<QrCode>,
+    log: Arc<Mutex<Vec<String>>>,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Server {
+    pub fn start(share: Share, show_hidden: bool) -> io::Result<Self> {
+        let listener = TcpListener::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+        listener.set_nonblocking(true)?;
+        let port = listener.local_addr()?.port();
+
+        let site = Site {
+            share: share.clone(),
+            token: token(),
+            session: token(),
+            used: Arc::new(AtomicBool::new(false)),
+            show_hidden,
+        };
+        let url = format!("http://{}:{}/{}/", lan_address(), port, site.token);
+        crate::log::info!("sharing on port {}", port);
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let handle = {
+            let (log, stop) = (Arc::clone(&log), Arc::clone(&stop));
+            thread::spawn(move || accept_loop(listener, site, log, stop))
+        };
+
+        Ok(Self {
+            qr: QrCode::encode(url.as_bytes()),
+            url,
+            share,
+            log,
+            stop,
+            handle: Some(handle),
+        })
+    }
+
+    pub fn log(&self) -> Vec<String> {
+        self.log.lock().map(|log| log.clone()).unwrap_or_default()
+    }
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Everything a connection handler needs to answer requests.
+#[derive(Debug, Clone)]
+struct Site {
+    share: Share,
+    /// In the link, taken once.
+    token: String,
+    /// The cookie's value for whoever took the token.
+    session: String,
+    /// Whether the token was taken.
+    used: Arc<AtomicBool>,
+    show_hidden: bool,
+}
+
+/// 128 random bits in hex, new for every server.
+fn token() -> String {
+    let mut bytes = [0; 16];
+    if std::fs::File::open("/dev/urandom")
+        .and_then(|mut random| random.read_exact(&mut bytes))
+        .is_err()
+    {
+        // Hashers are seeded randomly per process, and differently each
+        // time within one
+        for half in bytes.chunks_mut(8) {
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write_u128(
+                SystemTime::now()
+                    .duration_since(SystemTime::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_nanos(),
+            );
+            half.copy_from_slice(&hasher.finish().to_le_bytes());
+        }
+    }
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Address other devices on the network can reach us at. Connecting a UDP
+/// socket sends nothing, it just picks the outgoing interface.
+fn lan_address() -> IpAddr {
+    UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))
+        .and_then(|socket| {
+            socket.connect((Ipv4Addr::new(192, 0, 2, 1), 80))?;
+            socket.local_addr()
+        })
+        .map(|addr| addr.ip())
+        .unwrap_or(IpAddr::V4(Ipv4Addr::LOCALHOST))
+}
+
+fn accept_loop(
+    listener: TcpListener,
+    site: Site,
+    log: Arc<Mutex<Vec<String>>>,
+    stop: Arc<AtomicBool>,
+) {
+    let open = Arc::new(AtomicUsize::new(0));
+    while !stop.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((mut stream, peer)) => {
+                if open.load(Ordering::Relaxed) >= MAX_CONNECTIONS {
+                    let _ = stream.set_nonblocking(false);
+                    let _ = respond(
+                        &mut stream,
+                        "503 Service Unavailable",
+                        "text/plain",
+                        b"Busy\n",
+                    );
+                    push_log(&log, peer, "→ 503 too many connections".to_string());
+                    continue;
+                }
+                open.fetch_add(1, Ordering::Relaxed);
+                let (site, log, open) = (site.clone(), Arc::clone(&log), Arc::clone(&open));
+                thread::spawn(move || {
+                    let _open = Open(open);
+                    let line = match handle_connection(stream, &site) {
+                        Ok(line) => line,
+                        Err(e) => format!("error: {}", e),
+                    };
+                    push_log(&log, peer, line);
+                });
+            }
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                thread::sleep(Duration::from_millis(100));
+            }
+            Err(_) => thread::sleep(Duration::from_millis(100)),
+        }
+    }
+}
+
+/// Counts a connection as open until it is dropped.
+struct Open(Arc<AtomicUsize>);
+
+impl Drop for Open {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+fn push_log(log: &Mutex<Vec<String>>, peer: SocketAddr, line: String) {
+    let now = datetime::local(SystemTime::now());
+    if let Ok(mut log) = log.lock() {
+        log.push(format!(
+            "{:02}:{:02}:{:02} {} {}",
+            now.hour,
+            now.minute,
+            now.second,
+            peer.ip(),
+            line
+        ));
+        if log.len() > MAX_LOG {
+            log.remove(0);
+        }
+    }
+}
+
+/// Answers one request and returns the line describing it for the log.
+fn handle_connection(stream: TcpStream, site: &Site) -> io::Result<String> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(Duration::from_secs(10)))?;
+    let mut reader = BufReader::new(stream);
+    let head = read_head(&mut reader)?;
+    let mut stream = reader.into_inner();
+    let Some((request_line, headers)) = head else {
+        respond(
+            &mut stream,
+            "431 Request Header Fields Too Large",
+            "text/plain",
+            b"Request too large\n",
+        )?;
+        return Ok("→ 431".to_string());
+    };
+
+    let mut parts = request_line.split_whitespace();
+    let (method, target) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+    let summary = format!("{} {}", method, target);
+    let target = target.split('?').next().unwrap_or_default();
+
+    if method != "GET" && method != "HEAD" {
+        respond(&mut stream, "405 Method Not Allowed", "text/plain", b"")?;
+        return Ok(format!("{} → 405", summary));
+    }
+
+    let with_token = target
+        .strip_prefix('/')
+        .and_then(|t| t.strip_prefix(&site.token))
+        .filter(|rest| rest.is_empty() || rest.starts_with('/'));
+    if let Some(rest) = with_token {
+        // Swapped rather than loaded, so that two devices racing for the
+        // link can't both get in
+        if site.used.swap(true, Ordering::Relaxed) {
+            respond(
+                &mut stream,
+                "403 Forbidden",
+                "text/plain",
+                b"This link was already used\n",
+            )?;
+            return Ok(format!("{} → 403 link already used", method));
+        }
+        // Keep the token out of the address bar and the log from here on
+        let location = if rest.is_empty() { "/" } else { rest };
+        write!(
+            stream,
+            "HTTP/1.1 303 See Other\r\nSet-Cookie: {}={}; Path=/; HttpOnly; SameSite=Strict\r\n\
+             Location: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            COOKIE, site.session, location
+        )?;
+        return Ok(format!("{} {} → 303 link used", method, location));
+    }
+    if !has_session(&headers, &site.session) {
+        respond(&mut stream, "403 Forbidden", "text/plain", b"Forbidden\n")?;
+        return Ok(format!("{} → 403 no session", summary));
+    }
+    let Some(path) = site.share.resolve(
+        Path::new(&url::decode(target.trim_start_matches('/'))),
+        site.show_hidden,
+    ) else {
+        respond(&mut stream, "404 Not Found", "text/plain", b"Not found\n")?;
+        return Ok(format!("{} → 404", summary));
+    };
+
+    let at_root = target.trim_matches('/').is_empty();
+    if at_root || path.is_dir() {
+        if !target.ends_with('/') {
+            write!(
+                stream,
+                "HTTP/1.1 301 Moved Permanently\r\nLocation: {}/\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+                target
+            )?;
+            return Ok(format!("{} → 301", summary));
+        }
+        let listing = if at_root && let Share::Entries(entries) = &site.share {
+            Ok(entries.clone())
+        } else {
+            list_dir(&path, site.show_hidden)
+        };
+        let body = match listing {
+            Ok(entries) => index_page(target, &entries),
+            Err(e) => {
+                respond(&mut stream, "403 Forbidden", "text/plain", b"Forbidden\n")?;
+                return Ok(format!("{} → 403 {}", summary, e));
+            }
+        };
+        let body = if method == "HEAD" { "" } else { &body };
+        respond(
+            &mut stream,
+            "200 OK",
+            "text/html; charset=utf-8",
+            body.as_bytes(),
+        )?;
+        return Ok(format!("{} → 200 listing", summary));
+    }
+
+    let mut file = match std::fs::File::open(&path) {
+        Ok(file) => file,
+        Err(e) => {
+            respond(&mut stream, "404 Not Found", "text/plain", b"Not found\n")?;
+            return Ok(format!("{} → 404 {}", summary, e));
+        }
+    };
+    let len = file.metadata()?.len();
+    write!(
+        stream,
+        "HTTP/1.1 200 OK\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        content_type(&path),
+        len
+    )?;
+    if method == "HEAD" {
+        return Ok(format!("{} → 200", summary));
+    }
+    let sent = io::copy(&mut file, &mut stream)?;
+    Ok(format!("{} → 200 {} bytes", summary, sent))
+}
+
+/// The request line and headers, without line ends. `None` when there are
+/// more of them than `MAX_HEAD` bytes or `MAX_HEADERS` headers.
+fn read_head(reader: &mut BufReader<TcpStream>) -> io::Result<Option<(String, Vec<String>)>> {
+    let mut limited = reader.by_ref().take(MAX_HEAD);
+    let mut request_line = String::new();
+    limited.read_line(&mut request_line)?;
+    if !request_line.ends_with('\n') {
+        return Ok(None);
+    }
+    let mut headers = Vec::new();
+    loop {
+        let mut header = String::new();
+        limited.read_line(&mut header)?;
+        if !header.ends_with('\n') {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            return Ok(Some((request_line.trim_end().to_string(), headers)));
+        }
+        if headers.len() == MAX_HEADERS {
+            return Ok(None);
+        }
+        headers.push(header.to_string());
+    }
+}
+
+/// Whether the request carries the cookie of the device that used the link.
+fn has_session(headers: &[String], session: &str) -> bool {
+    headers
+        .iter()
+        .filter_map(|header| header.split_once(':'))
+        .filter(|(name, _)| name.trim().eq_ignore_ascii_case("cookie"))
+        .flat_map(|(_, cookies)| cookies.split(';'))
+        .filter_map(|cookie| cookie.trim().split_once('='))
+        .any(|(name, value)| name == COOKIE && value == session)
+}
+
+fn respond(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &[u8],
+) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    )?;
+    stream.write_all(body)
+}
+
+fn list_dir(path: &Path, show_hidden: bool) -> io::Result<Vec<PathBuf>> {
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(path)?
+        .flatten()
+        .filter(|entry| show_hidden || !is_hidden(&entry.file_name()))
+        .map(|entry| entry.path())
+        .collect();
+    entries.sort();
+    Ok(entries)
+}
+
+fn is_hidden(name: &std::ffi::OsStr) -> bool {
+    name.to_string_lossy().starts_with('.')
+}
+
+fn index_page(request: &str, entries: &[PathBuf]) -> String {
+    let title = html_escape(if request.is_empty() { "/" } else { request });
+    let mut page = format!(
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\">\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\
+         <title>{0}</title></head>\n<body><h1>{0}</h1>\n<ul>\n",
+        title
+    );
+    if !request.trim_matches('/').is_empty() {
+        page.push_str("<li><a href=\"../\">../</a></li>\n");
+    }
+    for entry in entries {
+        let name = entry.file_name().unwrap_or_default().to_string_lossy();
+        let slash = if entry.is_dir() { "/" } else { "" };
+        page.push_str(&format!(
+            "<li><a href=\"{}{}\">{}{}</a></li>\n",
+            url::encode(&name),
+            slash,
+            html_escape(&name),
+            slash
+        ));
+    }
+    page.push_str("</ul></body></html>\n");
+    page
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn content_type(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or_default()
+        .to_lowercase();
+    match ext.as_str() {
+        "txt" | "md" | "rs" | "py" | "toml" | "log" => "text/plain; charset=utf-8",
+        "html" | "htm" => "text/html; charset=utf-8",
+        "json" => "application/json",
+        "pdf" => "application/pdf",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "svg" => "image/svg+xml",
+        "mp3" => "audio/mpeg",
+        "mp4" => "video/mp4",
+        "zip" => "application/zip",
+        _ => "application/octet-stream",
+    }
+}
+
+impl Widget for &Server {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let theme = theme::current();
+        let width = (area.width * 9 / 10).max(20).min(area.width);
+        let height = (area.height * 9 / 10).max(5).min(area.height);
+        let rect = Rect {
+            x: area.x + (area.width - width) / 2,
+            y: area.y + (area.height - height) / 2,
+            width,
+            height,
+        };
+        let block = Block::bordered()
+            .title(" HTTP share ".fg(theme.accent).bold())
+            .title_bottom(Line::from(" x stop server, Esc hide ").right_aligned())
+            .border_style(theme.border)
+            .border_set(border::ROUNDED);
+        let inner = block.inner(rect);
+        Clear.render(rect, buf);
+        block.render(rect, buf);
+
+        let qr_lines = self.qr.as_ref().map(QrCode::lines).unwrap_or_default();
+        let qr_width = qr_lines.first().map_or(0, |l| l.width() as u16);
+        let [qr_rect, info_rect] =
+            Layout::horizontal([Constraint::Length(qr_width + 1), Constraint::Min(0)]).areas(inner);
+        Paragraph::new(Text::from(qr_lines)).render(qr_rect, buf);
+
+        let mut lines = vec![
+            Line::from(self.url.clone().fg(theme.info).bold()),
+            Line::from(format!("Sharing {}", self.share.describe())),
+            Line::from(
+                "The first device on your network to open this link can download these files."
+                    .fg(theme.dim),
+            ),
+            Line::from(""),
+            Line::from("Transfers".bold()),
+        ];
+        let log = self.log();
+        let room = info_rect.height.saturating_sub(lines.len() as u16) as usize;
+        if log.is_empty() {
+            lines.push(Line::from("(none yet)".fg(theme.dim)));
+        }
+        lines.extend(
+            log.iter()
+                .skip(log.len().saturating_sub(room))
+                .map(|l| Line::from(l.clone())),
+        );
+        Paragraph::new(Text::from(lines))
+            .wrap(Wrap { trim: false })
+            .render(info_rect, buf);
+    }
+}
@@ -0,0 +1,255 @@
+//! The options shown on the settings screen, and writing changed values
+//! back into the config file without disturbing the rest of it.
+
+use crate::{config::Config, theme};
+
+/// How an option is edited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// Enter flips it.
+    Toggle,
+    /// Enter moves to the next value.
+    Choice(&'static [&'static str]),
+    /// Typed into a prompt.
+    Number,
+    Text,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Setting {
+    /// Name in the config file, with its table for table entries, like
+    /// `colors.accent`.
+    pub key: &'static str,
+    pub kind: Kind,
+}
+
+const SORTS: &[&str] = &["name", "natural", "extension", "size", "modified", "rating"];
+const PROTOCOLS: &[&str] = &["auto", "kitty", "iterm2", "sixel", "halfblocks"];
+
+pub const ALL: &[Setting] = &[
+    Setting::new("start_dir", Kind::Text),
+    Setting::new("show_hidden", Kind::Toggle),
+    Setting::new("sort", Kind::Choice(SORTS)),
+    Setting::new("dirs_first", Kind::Toggle),
+    Setting::new("hide_ignored", Kind::Toggle),
+    Setting::new("detailed", Kind::Toggle),
+    Setting::new("wrap_around", Kind::Toggle),
+    Setting::new("scroll_acceleration", Kind::Number),
+    Setting::new("pane_title", Kind::Text),
+    Setting::new("terminal_title", Kind::Text),
+    Setting::new("dual_pane", Kind::Toggle),
+    Setting::new("editor", Kind::Text),
+    Setting::new("opener", Kind::Text),
+    Setting::new("watch_interval_ms", Kind::Number),
+    Setting::new("preview_max_size", Kind::Number),
+    Setting::new("preview_timeout_ms", Kind::Number),
+    Setting::new("image_protocol", Kind::Choice(PROTOCOLS)),
+    Setting::new("recent_minutes", Kind::Number),
+    Setting::new("line_counts", Kind::Toggle),
+    Setting::new("stale_days", Kind::Number),
+    Setting::new("size_colors", Kind::Toggle),
+    Setting::new("size_tiers", Kind::Text),
+    Setting::new("verify_copies", Kind::Toggle),
+    Setting::new("keep_versions", Kind::Number),
+    Setting::new("history_size", Kind::Number),
+    Setting::new("save_history", Kind::Toggle),
+    Setting::new("suggest_places", Kind::Toggle),
+    Setting::new("notify_after", Kind::Number),
+    Setting::new("notifier", Kind::Text),
+    Setting::new("paste_command", Kind::Text),
+    Setting::new("max_depth", Kind::Number),
+    Setting::new("max_entries", Kind::Number),
+    Setting::new("follow_symlinks", Kind::Toggle),
+    Setting::new("exclude", Kind::Text),
+    Setting::new("ls_colors", Kind::Toggle),
+    Setting::new("theme", Kind::Choice(theme::NAMES)),
+    Setting::new("colors.selection", Kind::Text),
+    Setting::new("colors.marked", Kind::Text),
+    Setting::new("colors.accent", Kind::Text),
+    Setting::new("colors.size_medium", Kind::Text),
+    Setting::new("colors.size_large", Kind::Text),
+    Setting::new("colors.size_huge", Kind::Text),
+    Setting::new("colors.stale", Kind::Text),
+];
+
+impl Setting {
+    const fn new(key: &'static str, kind: Kind) -> Self {
+        Self { key, kind }
+    }
+
+    /// The current value, written as the config file would have it; empty
+    /// for options that aren't set.
+    pub fn value(&self, config: &Config) -> String {
+        let flag = |b: bool| b.to_string();
+        let path = |p: &Option<std::path::PathBuf>| {
+            p.as_ref()
+                .map(|p| p.display().to_string())
+                .unwrap_or_default()
+        };
+        match self.key {
+            "start_dir" => path(&config.start_dir),
+            "show_hidden" => flag(config.show_hidden),
+            "sort" => config.sort.label().to_string(),
+            "dirs_first" => flag(config.dirs_first),
+            "hide_ignored" => flag(config.hide_ignored),
+            "detailed" => flag(config.detailed),
+            "wrap_around" => flag(config.wrap_around),
+            "scroll_acceleration" => config.scroll_acceleration.to_string(),
+            "pane_title" => config.pane_title.clone().unwrap_or_default(),
+            "terminal_title" => config.terminal_title.clone().unwrap_or_default(),
+            "dual_pane" => flag(config.dual_pane),
+            "editor" => config.editor.clone().unwrap_or_default(),
+            "opener" => config.opener.clone().unwrap_or_default(),
+            "watch_interval_ms" => config.watch_interval_ms.to_string(),
+            "preview_max_size" => size(config.preview_max_size),
+            "preview_timeout_ms" => config.preview_timeout_ms.to_string(),
+            "image_protocol" => config
+                .image_protocol
+                .clone()
+                .unwrap_or_else(|| "auto".to_string()),
+            "recent_minutes" => config.recent_minutes.to_string(),
+            "line_counts" => flag(config.line_counts),
+            "stale_days" => config.stale_days.to_string(),
+            "size_colors" => flag(config.size_colors),
+            "size_tiers" => config.size_tiers.map(size).join(" "),
+            "verify_copies" => flag(config.verify_copies),
+            "keep_versions" => config.keep_versions.to_string(),
+            "history_size" => config.history_size.to_string(),
+            "save_history" => flag(config.save_history),
+            "suggest_places" => flag(config.suggest_places),
+            "notify_after" => config.notify_after.to_string(),
+            "notifier" => config.notifier.clone().unwrap_or_default(),
+            "paste_command" => config.paste_command.clone().unwrap_or_default(),
+            "max_depth" => config.walk.max_depth.to_string(),
+            "max_entries" => config.walk.max_entries.to_string(),
+            "follow_symlinks" => flag(config.walk.follow_symlinks),
+            "exclude" => config.walk.exclude.join(", "),
+            "ls_colors" => flag(config.ls_colors.is_some()),
+            "theme" => config.theme.name.to_string(),
+            key => match key.strip_prefix("colors.") {
+                Some(name) => config
+                    .theme
+                    .color(name)
+                    .map(|color| color.to_string())
+                    .unwrap_or_default(),
+                None => String::new(),
+            },
+        }
+    }
+
+    /// What Enter changes a toggle or choice to, `None` for options that
+    /// are typed.
+    pub fn next(&self, config: &Config) -> Option<String> {
+        let current = self.value(config);
+        match self.kind {
+            Kind::Toggle => Some((current != "true").to_string()),
+            Kind::Choice(values) => {
+                let index = values.iter().position(|v| *v == current).unwrap_or(0);
+                Some(values[(index + 1) % values.len()].to_string())
+            }
+            Kind::Number | Kind::Text => None,
+        }
+    }
+
+    /// How the value is written in the file: strings are quoted.
+    fn literal(&self, value: &str) -> String {
+        match self.kind {
+            Kind::Toggle | Kind::Number => value.to_string(),
+            Kind::Choice(_) | Kind::Text => format!("\"{}\"", value.replace('"', "")),
+        }
+    }
+
+    /// Sets the option in `config`, leaving it as it was if `value` doesn't
+    /// parse.
+    pub fn apply(&self, config: &mut Config, value: &str) -> Result<(), String> {
+        if self.is_unset(value) {
+            match self.key {
+                "start_dir" => config.start_dir = None,
+                "editor" => config.editor = None,
+                "opener" => config.opener = None,
+                "image_protocol" => config.image_protocol = None,
+                "exclude" => config.walk.exclude.clear(),
+                _ => return Err("a value is needed".to_string()),
+            }
+            return Ok(());
+        }
+        let (table, key) = self.key.split_once('.').unwrap_or(("", self.key));
+        config.apply(table, key, value.to_string())
+    }
+
+    /// Whether `value` means the option is unset.
+    pub fn is_unset(&self, value: &str) -> bool {
+        value.is_empty() || (self.key == "image_protocol" && value == "auto")
+    }
+}
+
+pub fn find(key: &str) -> Option<&'static Setting> {
+    ALL.iter().find(|s| s.key == key)
+}
+
+fn size(bytes: u64) -> String {
+    for (unit, scale) in [("G", 1 << 30), ("M", 1 << 20), ("K", 1 << 10)] {
+        if bytes >= scale && bytes.is_multiple_of(scale) {
+            return format!("{}{}", bytes / scale, unit);
+        }
+    }
+    bytes.to_string()
+}
+
+/// Returns `content` with `setting` set to `value`, or removed when the
+/// value leaves it unset. An existing line for the option is replaced in
+/// place; otherwise the line goes at the end of its table, which is added
+/// if the file has none.
+pub fn write(content: &str, setting: &Setting, value: &str) -> String {
+    let (table, key) = setting.key.split_once('.').unwrap_or(("", setting.key));
+    let line = (!setting.is_unset(value)).then(|| format!("{} = {}", key, setting.literal(value)));
+
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let mut section = "";
+    // Where the table's last entry is, to append after it
+    let mut table_end = (table.is_empty()).then_some(0);
+    let mut existing = None;
+    for (i, text) in lines.iter().enumerate() {
+        let text = text.trim();
+        if let Some(name) = text.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+            section = name.trim();
+            if section == table {
+                table_end = Some(i + 1);
+            }
+            continue;
+        }
+        if section != table {
+            continue;
+        }
+        if !text.is_empty() && !text.starts_with('#') {
+            table_end = Some(i + 1);
+        }
+        let name = text
+            .split_once('=')
+            .map(|(k, _)| k.trim().trim_matches('"'));
+        if name == Some(key) {
+            existing = Some(i);
+        }
+    }
+
+    match (existing, line) {
+        (Some(i), Some(line)) => lines[i] = line,
+        (Some(i), None) => {
+            lines.remove(i);
+        }
+        (None, None) => {}
+        (None, Some(line)) => match table_end {
+            Some(end) => lines.insert(end, line),
+            None => {
+                if lines.last().is_some_and(|l| !l.trim().is_empty()) {
+                    lines.push(String::new());
+                }
+                lines.push(format!("[{}]", table));
+                lines.push(line);
+            }
+        },
+    }
+    let mut content = lines.join("\n");
+    content.push('\n');
+    content
+}
@@ -0,0 +1,44 @@
+//! Shell functions printed by `browrs --init SHELL`. They define `brcd`,
+//! which runs browrs with `--cwd-file` and changes to the directory left
+//! with `Q`.
+
+/// Shells `--init` knows.
+pub const SHELLS: &[&str] = &["bash", "zsh", "fish"];
+
+const POSIX: &str = r#"# Run browrs and cd to the directory it was left in with Q.
+# Add to your shell's rc file: eval "$(browrs --init SHELL)"
+brcd() {
+    local tmp dir
+    tmp="$(mktemp)" || return
+    command browrs --cwd-file="$tmp" "$@"
+    dir="$(cat -- "$tmp")"
+    rm -f -- "$tmp"
+    if [ -n "$dir" ] && [ -d "$dir" ] && [ "$dir" != "$PWD" ]; then
+        cd -- "$dir"
+    fi
+}
+"#;
+
+const FISH: &str = r#"# Run browrs and cd to the directory it was left in with Q.
+# Add to ~/.config/fish/config.fish: browrs --init fish | source
+function brcd
+    set -l tmp (mktemp); or return
+    command browrs --cwd-file=$tmp $argv
+    set -l dir (cat -- $tmp)
+    rm -f -- $tmp
+    if test -n "$dir" -a -d "$dir" -a "$dir" != "$PWD"
+        cd -- $dir
+    end
+end
+"#;
+
+/// The script for `shell`, or `None` for one it doesn't know.
+pub fn init(shell: &str) -> Option<String> {
+    let script = match shell {
+        "bash" => POSIX.replace("SHELL", "bash"),
+        "zsh" => POSIX.replace("SHELL", "zsh"),
+        "fish" => FISH.to_string(),
+        _ => return None,
+    };
+    Some(script)
+}
@@ -0,0 +1,142 @@
+//! Smart folders: searches and filters saved under a name and run again
+//! whenever they are opened. Kept in `~/.config/browrs/smart_folders`, one
+//! per line as `name<TAB>search|filter<TAB>root<TAB>query`.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+/// How a smart folder's query is run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryKind {
+    /// A fuzzy search of the tree under the root.
+    Search,
+    /// A filter pattern on the root's listing.
+    Filter,
+}
+
+impl QueryKind {
+    fn name(self) -> &'static str {
+        match self {
+            QueryKind::Search => "search",
+            QueryKind::Filter => "filter",
+        }
+    }
+
+    fn parse(name: &str) -> Option<QueryKind> {
+        match name {
+            "search" => Some(QueryKind::Search),
+            "filter" => Some(QueryKind::Filter),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SmartFolder {
+    pub name: String,
+    pub kind: QueryKind,
+    pub root: PathBuf,
+    pub query: String,
+}
+
+impl SmartFolder {
+    fn parse(line: &str) -> Option<SmartFolder> {
+        let mut fields = line.splitn(4, '\t');
+        let name = fields.next()?.trim();
+        let kind = QueryKind::parse(fields.next()?)?;
+        let root = PathBuf::from(fields.next()?);
+        let query = fields.next()?.to_string();
+        (!name.is_empty()).then(|| SmartFolder {
+            name: name.to_string(),
+            kind,
+            root,
+            query,
+        })
+    }
+
+    /// How the folder shows up among the places.
+    pub fn label(&self) -> String {
+        let icon = match self.kind {
+            QueryKind::Search => "🔎",
+            QueryKind::Filter => "⧩",
+        };
+        format!(
+            "{} {}  {} in {}",
+            icon,
+            self.name,
+            self.query,
+            self.root.display()
+        )
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SmartFolders {
+    /// Where the list is saved; `None` keeps it in memory only.
+    file: Option<PathBuf>,
+    pub folders: Vec<SmartFolder>,
+}
+
+impl SmartFolders {
+    pub fn default_file() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("browrs").join("smart_folders"))
+    }
+
+    /// Reads the folders kept in `file`, skipping lines it can't make sense
+    /// of and starting empty if it doesn't exist.
+    pub fn load(file: PathBuf) -> Self {
+        let folders = std::fs::read_to_string(&file)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(SmartFolder::parse)
+            .collect();
+        Self {
+            file: Some(file),
+            folders,
+        }
+    }
+
+    pub fn file(&self) -> Option<&Path> {
+        self.file.as_deref()
+    }
+
+    /// Saves `folder`, replacing one of the same name.
+    pub fn add(&mut self, mut folder: SmartFolder) -> io::Result<()> {
+        folder.name = folder.name.replace(['\t', '\n'], " ").trim().to_string();
+        folder.query = folder.query.replace(['\t', '\n'], " ");
+        match self.folders.iter_mut().find(|f| f.name == folder.name) {
+            Some(existing) => *existing = folder,
+            None => self.folders.push(folder),
+        }
+        self.save()
+    }
+
+    pub fn remove(&mut self, index: usize) -> io::Result<()> {
+        if index < self.folders.len() {
+            self.folders.remove(index);
+        }
+        self.save()
+    }
+
+    fn save(&self) -> io::Result<()> {
+        let Some(file) = &self.file else {
+            return Ok(());
+        };
+        if let Some(parent) = file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut contents = String::new();
+        for folder in &self.folders {
+            contents.push_str(&format!(
+                "{}\t{}\t{}\t{}\n",
+                folder.name,
+                folder.kind.name(),
+                folder.root.to_string_lossy(),
+                folder.query
+            ));
+        }
+        std::fs::write(file, contents)
+    }
+}
@@ -0,0 +1,88 @@
+//! Older copies of files kept in filesystem snapshots: ZFS exposes them
+//! under `.zfs/snapshot/<name>/`, snapper and similar btrfs tools under
+//! `.snapshots/<name>/snapshot/` or `.snapshots/<name>/` at the subvolume
+//! root.
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+/// A copy of a file in one snapshot.
+#[derive(Debug, Clone)]
+pub struct Version {
+    pub snapshot: String,
+    pub path: PathBuf,
+    pub modified: Option<SystemTime>,
+    pub size: u64,
+}
+
+/// Copies of `path` found in snapshots of any of its ancestors, newest
+/// first. Snapshots holding the same copy are listed once.
+pub fn versions(path: &Path) -> Vec<Version> {
+    let mut versions: Vec<Version> = Vec::new();
+    for ancestor in path.ancestors().skip(1) {
+        let Ok(relative) = path.strip_prefix(ancestor) else {
+            continue;
+        };
+        for (snapshot, root) in snapshot_roots(ancestor) {
+            let copy = root.join(relative);
+            let Ok(metadata) = std::fs::metadata(&copy) else {
+                continue;
+            };
+            if !metadata.is_file() {
+                continue;
+            }
+            let modified = metadata.modified().ok();
+            if versions
+                .iter()
+                .any(|v| v.size == metadata.len() && v.modified == modified)
+            {
+                continue;
+            }
+            versions.push(Version {
+                snapshot,
+                path: copy,
+                modified,
+                size: metadata.len(),
+            });
+        }
+    }
+    versions.sort_by_key(|v| std::cmp::Reverse(v.modified));
+    versions
+}
+
+/// Snapshots taken of `dir`, as (name, directory mirroring `dir`).
+fn snapshot_roots(dir: &Path) -> Vec<(String, PathBuf)> {
+    let mut roots = Vec::new();
+    for (base, nested) in [(".zfs/snapshot", None), (".snapshots", Some("snapshot"))] {
+        let Ok(entries) = std::fs::read_dir(dir.join(base)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let mut root = entry.path();
+            // snapper keeps the tree in a `snapshot` subdirectory next to
+            // its metadata
+            if let Some(nested) = nested
+                && root.join(nested).is_dir()
+            {
+                root = root.join(nested);
+            }
+            if root.is_dir() {
+                roots.push((name, root));
+            }
+        }
+    }
+    roots
+}
+
+/// Puts `version` back at `dest`, moving the current file to the trash
+/// first so the restore can itself be undone.
+pub fn restore(version: &Path, dest: &Path) -> io::Result<()> {
+    if dest.symlink_metadata().is_ok() {
+        crate::trash::trash(dest)?;
+    }
+    std::fs::copy(version, dest).map(|_| ())
+}
@@ -0,0 +1,219 @@
+use std::{cmp::Ordering, sync::Arc, time::SystemTime};
+
+use crate::entry::Entry;
+
+/// Order of the file list.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Sort {
+    #[default]
+    Name,
+    /// By name, with runs of digits compared as numbers (`file2` before
+    /// `file10`) and case ignored.
+    Natural,
+    /// By extension, then name.
+    Extension,
+    /// Largest first.
+    Size,
+    /// Most recently modified first.
+    Modified,
+    /// Most stars first.
+    Rating,
+    /// An order of its own from the `[sorts]` table.
+    Custom(Arc<Custom>),
+}
+
+/// A sort order made of keys tried in turn, each deciding only what the
+/// ones before it left tied, like `dirs, extension, natural`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Custom {
+    pub name: String,
+    keys: Vec<Key>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Key {
+    by: By,
+    /// Written with a leading `-`: the other way around.
+    reverse: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum By {
+    /// Directories before files.
+    Dirs,
+    Builtin(Sort),
+}
+
+impl Custom {
+    /// Reads the comma-separated keys: `dirs` (or `files` for the other
+    /// way around) and the built-in sorts' names, each with an optional
+    /// `-` in front to reverse it.
+    pub fn parse(name: &str, spec: &str) -> Result<Self, String> {
+        let keys = spec
+            .split(',')
+            .map(|key| {
+                let key = key.trim();
+                let (reverse, key) = match key.strip_prefix('-') {
+                    Some(rest) => (true, rest.trim()),
+                    None => (false, key),
+                };
+                let by = match key {
+                    "dirs" => By::Dirs,
+                    "files" => {
+                        return Ok(Key {
+                            by: By::Dirs,
+                            reverse: !reverse,
+                        });
+                    }
+                    _ => By::Builtin(Sort::parse(key).ok_or(format!("unknown sort key {}", key))?),
+                };
+                Ok(Key { by, reverse })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(Self {
+            name: name.to_string(),
+            keys,
+        })
+    }
+
+    fn compare(&self, a: &Entry, b: &Entry) -> Ordering {
+        self.keys.iter().fold(Ordering::Equal, |ordering, key| {
+            ordering.then_with(|| {
+                let ordering = match &key.by {
+                    By::Dirs => b.is_dir.cmp(&a.is_dir),
+                    By::Builtin(sort) => sort.compare(a, b),
+                };
+                if key.reverse {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            })
+        })
+    }
+}
+
+impl Sort {
+    const ALL: [Sort; 6] = [
+        Sort::Name,
+        Sort::Natural,
+        Sort::Extension,
+        Sort::Size,
+        Sort::Modified,
+        Sort::Rating,
+    ];
+
+    pub fn parse(name: &str) -> Option<Sort> {
+        match name {
+            "name" => Some(Sort::Name),
+            "natural" => Some(Sort::Natural),
+            "extension" | "ext" => Some(Sort::Extension),
+            "size" => Some(Sort::Size),
+            "modified" | "mtime" => Some(Sort::Modified),
+            "rating" | "stars" => Some(Sort::Rating),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            Sort::Name => "name",
+            Sort::Natural => "natural",
+            Sort::Extension => "extension",
+            Sort::Size => "size",
+            Sort::Modified => "modified",
+            Sort::Rating => "rating",
+            Sort::Custom(custom) => &custom.name,
+        }
+    }
+
+    /// The built-in sort or one of `custom` called `name`.
+    pub fn named(name: &str, custom: &[Sort]) -> Option<Sort> {
+        Sort::parse(name).or_else(|| custom.iter().find(|sort| sort.label() == name).cloned())
+    }
+
+    /// The mode after this one, wrapping around: the built-in ones, then
+    /// those of `custom`.
+    pub fn next(&self, custom: &[Sort]) -> Sort {
+        let all: Vec<&Sort> = Sort::ALL.iter().chain(custom).collect();
+        let index = all.iter().position(|s| *s == self).unwrap_or(0);
+        all[(index + 1) % all.len()].clone()
+    }
+
+    /// Sorts the entries, ties falling back to the name. With `dirs_first`
+    /// directories come before everything else.
+    pub fn apply(&self, entries: &mut [Entry], dirs_first: bool) {
+        entries.sort_by(|a, b| {
+            let group = if dirs_first {
+                b.is_dir.cmp(&a.is_dir)
+            } else {
+                Ordering::Equal
+            };
+            group
+                .then_with(|| self.compare(a, b))
+                .then_with(|| a.display_name().cmp(&b.display_name()))
+        });
+    }
+
+    fn compare(&self, a: &Entry, b: &Entry) -> Ordering {
+        match self {
+            Sort::Name => Ordering::Equal,
+            Sort::Natural => natural(&a.name, &b.name),
+            Sort::Extension => extension(a).cmp(&extension(b)),
+            Sort::Size => b.size.cmp(&a.size),
+            Sort::Modified => b
+                .modified
+                .unwrap_or(SystemTime::UNIX_EPOCH)
+                .cmp(&a.modified.unwrap_or(SystemTime::UNIX_EPOCH)),
+            Sort::Rating => b.rating.cmp(&a.rating),
+            Sort::Custom(custom) => custom.compare(a, b),
+        }
+    }
+}
+
+/// Lowercased extension; directories and dotfiles have none.
+fn extension(entry: &Entry) -> String {
+    match entry.name.rsplit_once('.') {
+        Some((stem, ext)) if !entry.is_dir && !stem.is_empty() => ext.to_lowercase(),
+        _ => String::new(),
+    }
+}
+
+/// Compares names chunk by chunk, digit runs by value and the rest
+/// ignoring case.
+fn natural(a: &str, b: &str) -> Ordering {
+    let (mut a, mut b) = (a.chars().peekable(), b.chars().peekable());
+    loop {
+        match (a.peek().copied(), b.peek().copied()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let take_number = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+                    let mut digits = String::new();
+                    while let Some(c) = chars.next_if(char::is_ascii_digit) {
+                        digits.push(c);
+                    }
+                    digits
+                };
+                let (x, y) = (take_number(&mut a), take_number(&mut b));
+                let (x_trim, y_trim) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+                let ordering = x_trim
+                    .len()
+                    .cmp(&y_trim.len())
+                    .then_with(|| x_trim.cmp(y_trim));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ordering = x.to_lowercase().cmp(y.to_lowercase());
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                a.next();
+                b.next();
+            }
+        }
+    }
+}
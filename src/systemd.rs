@@ -0,0 +1,137 @@
+//! systemd unit files: their sections and settings, and the unit's state
+//! from `systemctl status` when the host runs systemd.
+
+use std::{
+    io::Read,
+    path::Path,
+    process::{Command, Stdio},
+    thread,
+    time::{Duration, Instant},
+};
+
+use ratatui::{
+    style::Stylize,
+    text::{Line, Text},
+};
+
+use crate::theme;
+
+pub const EXTENSIONS: [&str; 7] = [
+    "service", "timer", "socket", "target", "mount", "path", "slice",
+];
+
+/// How long `systemctl status` may take before the preview goes on without
+/// it.
+const STATUS_TIMEOUT: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    pub name: String,
+    /// Keys and values in the order written; keys may repeat.
+    pub settings: Vec<(String, String)>,
+}
+
+/// The sections of a unit file. Comments are left out and lines ending in
+/// a backslash are joined with the next.
+pub fn parse(text: &str) -> Vec<Section> {
+    let mut sections: Vec<Section> = Vec::new();
+    let mut pending = String::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if pending.is_empty() && (line.starts_with(['#', ';']) || line.is_empty()) {
+            continue;
+        }
+        if let Some(start) = line.strip_suffix('\\') {
+            pending.push_str(start.trim_end());
+            pending.push(' ');
+            continue;
+        }
+        let line = std::mem::take(&mut pending) + line;
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            sections.push(Section {
+                name: name.to_string(),
+                settings: Vec::new(),
+            });
+        } else if let Some((key, value)) = line.split_once('=')
+            && let Some(section) = sections.last_mut()
+        {
+            section
+                .settings
+                .push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    sections
+}
+
+/// What `systemctl status` says about the unit, `None` if the host doesn't
+/// run systemd, it doesn't know the unit or it took too long.
+pub fn status(path: &Path) -> Option<String> {
+    if !Path::new("/run/systemd/system").is_dir() {
+        return None;
+    }
+    let name = path.file_name()?.to_str()?;
+    let mut command = Command::new("systemctl");
+    // Units under a systemd/user directory belong to the user's manager
+    if path.to_string_lossy().contains("/systemd/user/") {
+        command.arg("--user");
+    }
+    let mut child = command
+        .args(["status", "--no-pager", "--lines=3", "--", name])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait().ok()? {
+            break status;
+        }
+        if started.elapsed() > STATUS_TIMEOUT {
+            let _ = child.kill();
+            let _ = child.wait();
+            return None;
+        }
+        thread::sleep(Duration::from_millis(10));
+    };
+    // 4 is for a unit systemd has never heard of
+    if status.code() == Some(4) {
+        return None;
+    }
+    let mut output = String::new();
+    child.stdout.take()?.read_to_string(&mut output).ok()?;
+    let output = output.trim_end();
+    (!output.is_empty()).then(|| output.to_string())
+}
+
+/// The unit's state if there is one to show, then its settings by section.
+pub fn preview(name: &str, sections: &[Section], status: Option<&str>) -> Text<'static> {
+    let theme = theme::current();
+    let kind = name.rsplit('.').next().unwrap_or_default();
+    let mut lines = vec![
+        Line::from(format!("⚙ {} | systemd {}", name, kind)),
+        Line::from("─".repeat(40)),
+    ];
+    if let Some(status) = status {
+        lines.extend(status.lines().map(|line| Line::from(line.to_string())));
+        lines.push(Line::from("─".repeat(40)));
+    }
+    for section in sections {
+        lines.push(Line::from(
+            format!("[{}]", section.name).fg(theme.info).bold(),
+        ));
+        let width = section
+            .settings
+            .iter()
+            .map(|(key, _)| key.chars().count())
+            .max()
+            .unwrap_or(0);
+        for (key, value) in &section.settings {
+            lines.push(Line::from(vec![
+                format!("  {:width$} ", key).fg(theme.highlight),
+                value.clone().into(),
+            ]));
+        }
+    }
+    Text::from(lines)
+}
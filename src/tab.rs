@@ -0,0 +1,308 @@
+use std::path::PathBuf;
+
+use ratatui::layout::{Direction, Rect};
+
+use crate::{
+    error,
+    pane::{Listing, Pane},
+};
+
+/// How a tab's panes are arranged when more than one is shown: a tree of
+/// splits with an index into [`Tab::panes`] at each leaf.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Split {
+    Pane(usize),
+    /// Two parts side by side (`Horizontal`) or one above the other
+    /// (`Vertical`), sharing the space evenly.
+    Parts {
+        direction: Direction,
+        first: Box<Split>,
+        second: Box<Split>,
+    },
+}
+
+impl Split {
+    /// The pane indices in reading order.
+    pub fn panes(&self) -> Vec<usize> {
+        match self {
+            Split::Pane(index) => vec![*index],
+            Split::Parts { first, second, .. } => {
+                let mut panes = first.panes();
+                panes.extend(second.panes());
+                panes
+            }
+        }
+    }
+
+    /// Where each pane goes when the whole layout fills `area`.
+    pub fn areas(&self, area: Rect) -> Vec<(usize, Rect)> {
+        match self {
+            Split::Pane(index) => vec![(*index, area)],
+            Split::Parts {
+                direction,
+                first,
+                second,
+            } => {
+                let (a, b) = match direction {
+                    Direction::Horizontal => {
+                        let width = area.width / 2;
+                        (
+                            Rect { width, ..area },
+                            Rect {
+                                x: area.x + width,
+                                width: area.width - width,
+                                ..area
+                            },
+                        )
+                    }
+                    Direction::Vertical => {
+                        let height = area.height / 2;
+                        (
+                            Rect { height, ..area },
+                            Rect {
+                                y: area.y + height,
+                                height: area.height - height,
+                                ..area
+                            },
+                        )
+                    }
+                };
+                let mut areas = first.areas(a);
+                areas.extend(second.areas(b));
+                areas
+            }
+        }
+    }
+
+    /// Replaces the leaf for pane `index` with `split`.
+    fn replace(&mut self, index: usize, split: Split) {
+        match self {
+            Split::Pane(i) if *i == index => *self = split,
+            Split::Pane(_) => {}
+            Split::Parts { first, second, .. } => {
+                first.replace(index, split.clone());
+                second.replace(index, split);
+            }
+        }
+    }
+
+    /// Takes the leaf for pane `index` out, its sibling taking the space.
+    /// Returns false for the last pane. The panes after it are left for the
+    /// caller to renumber, once.
+    fn remove(&mut self, index: usize) -> bool {
+        match self {
+            Split::Pane(_) => false,
+            Split::Parts { first, second, .. } => {
+                if **first == Split::Pane(index) {
+                    *self = (**second).clone();
+                    true
+                } else if **second == Split::Pane(index) {
+                    *self = (**first).clone();
+                    true
+                } else {
+                    first.remove(index) || second.remove(index)
+                }
+            }
+        }
+    }
+
+    fn renumber(&mut self, removed: usize) {
+        match self {
+            Split::Pane(i) if *i > removed => *i -= 1,
+            Split::Pane(_) => {}
+            Split::Parts { first, second, .. } => {
+                first.renumber(removed);
+                second.renumber(removed);
+            }
+        }
+    }
+}
+
+/// A way to move the focus between split panes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// One open tab: directory panes split across the screen in dual-pane
+/// mode, of which only the focused one is shown otherwise.
+#[derive(Debug)]
+pub struct Tab {
+    pub panes: Vec<Pane>,
+    pub focus: usize,
+    pub layout: Split,
+}
+
+impl Tab {
+    /// Opens `dir` in two panes side by side.
+    pub fn open(dir: PathBuf, listing: Listing) -> error::Result<Self> {
+        Ok(Self {
+            panes: vec![
+                Pane::open(dir.clone(), listing.clone())?,
+                Pane::open(dir, listing)?,
+            ],
+            focus: 0,
+            layout: Split::Parts {
+                direction: Direction::Horizontal,
+                first: Box::new(Split::Pane(0)),
+                second: Box::new(Split::Pane(1)),
+            },
+        })
+    }
+
+    pub fn pane(&self) -> &Pane {
+        &self.panes[self.focus]
+    }
+
+    pub fn pane_mut(&mut self) -> &mut Pane {
+        &mut self.panes[self.focus]
+    }
+
+    /// The pane after the focused one, where copies and moves go by default.
+    pub fn other_pane(&self) -> &Pane {
+        &self.panes[self.next_pane()]
+    }
+
+    /// Focuses the next pane in reading order.
+    pub fn switch_focus(&mut self) {
+        self.focus = self.next_pane();
+    }
+
+    fn next_pane(&self) -> usize {
+        let order = self.layout.panes();
+        let at = order.iter().position(|&i| i == self.focus).unwrap_or(0);
+        order[(at + 1) % order.len()]
+    }
+
+    /// Splits the focused pane in two, opening its directory in the new
+    /// half, which gets the focus.
+    pub fn split(&mut self, direction: Direction, listing: Listing) -> error::Result<()> {
+        let pane = Pane::open(self.pane().current_dir.clone(), listing)?;
+        let index = self.panes.len();
+        self.panes.push(pane);
+        self.layout.replace(
+            self.focus,
+            Split::Parts {
+                direction,
+                first: Box::new(Split::Pane(self.focus)),
+                second: Box::new(Split::Pane(index)),
+            },
+        );
+        self.focus = index;
+        Ok(())
+    }
+
+    /// Closes the focused pane, focusing the one that took its place;
+    /// returns false for the last pane.
+    pub fn close_split(&mut self) -> bool {
+        let order = self.layout.panes();
+        let at = order.iter().position(|&i| i == self.focus).unwrap_or(0);
+        if !self.layout.remove(self.focus) {
+            return false;
+        }
+        self.layout.renumber(self.focus);
+        self.panes.remove(self.focus);
+        let order = self.layout.panes();
+        self.focus = order[at.min(order.len() - 1)];
+        true
+    }
+
+    /// Focuses the nearest pane on `side` of the focused one, as laid out
+    /// in `area`; returns false if there is none.
+    pub fn focus_towards(&mut self, side: Side, area: Rect) -> bool {
+        let areas = self.layout.areas(area);
+        let Some(&(_, from)) = areas.iter().find(|(i, _)| *i == self.focus) else {
+            return false;
+        };
+        let center = |r: Rect| (r.x * 2 + r.width, r.y * 2 + r.height);
+        let (cx, cy) = center(from);
+        let best = areas
+            .iter()
+            .filter_map(|&(index, to)| {
+                let overlaps_x = to.x < from.right() && from.x < to.right();
+                let overlaps_y = to.y < from.bottom() && from.y < to.bottom();
+                // Distance to it, then how far off center it is
+                let (x, y) = center(to);
+                match side {
+                    Side::Left if overlaps_y && to.right() <= from.x => {
+                        Some((from.x - to.right(), y.abs_diff(cy), index))
+                    }
+                    Side::Right if overlaps_y && to.x >= from.right() => {
+                        Some((to.x - from.right(), y.abs_diff(cy), index))
+                    }
+                    Side::Up if overlaps_x && to.bottom() <= from.y => {
+                        Some((from.y - to.bottom(), x.abs_diff(cx), index))
+                    }
+                    Side::Down if overlaps_x && to.y >= from.bottom() => {
+                        Some((to.y - from.bottom(), x.abs_diff(cx), index))
+                    }
+                    _ => None,
+                }
+            })
+            .min();
+        match best {
+            Some((_, _, index)) => {
+                self.focus = index;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Syncs the panes from where they are now, or stops syncing them;
+    /// returns whether they are synced.
+    pub fn toggle_sync(&mut self) -> bool {
+        let sync = !self.is_synced();
+        for pane in &mut self.panes {
+            pane.sync_root = sync.then(|| pane.current_dir.clone());
+        }
+        sync
+    }
+
+    pub fn is_synced(&self) -> bool {
+        self.pane().sync_root.is_some()
+    }
+
+    /// Where pane `index` should be to match the focused one, if both are
+    /// synced and the focused one is under its root.
+    pub fn synced_dir(&self, index: usize) -> Option<PathBuf> {
+        let focused = self.pane();
+        let relative = focused
+            .current_dir
+            .strip_prefix(focused.sync_root.as_ref()?)
+            .ok()?;
+        Some(self.panes[index].sync_root.as_ref()?.join(relative))
+    }
+
+    /// Takes the other synced panes to the focused one's path under their
+    /// own roots, with the cursor on the same name. Panes without that
+    /// directory stay where they are.
+    pub fn mirror(&mut self, listing: Listing) {
+        let focused = self.pane();
+        let name = focused
+            .entries
+            .get(focused.selected)
+            .map(|entry| entry.name.clone());
+        for index in 0..self.panes.len() {
+            let Some(dir) = self.synced_dir(index).filter(|_| index != self.focus) else {
+                continue;
+            };
+            let pane = &mut self.panes[index];
+            if pane.current_dir != dir
+                && (!dir.is_dir() || pane.change_dir(dir, listing.clone()).is_err())
+            {
+                continue;
+            }
+            if let Some(name) = &name {
+                pane.select_name(name);
+            }
+        }
+    }
+
+    pub fn title(&self) -> String {
+        self.pane().title()
+    }
+}
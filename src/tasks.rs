@@ -0,0 +1,167 @@
+//! Targets of the Makefile and recipes of the justfile in a directory, read
+//! from the files themselves, to run with `make` or `just`.
+
+use std::{
+    io::{self, BufRead, IsTerminal, Write},
+    path::Path,
+    process::{Command, ExitStatus},
+};
+
+/// Names make looks for, in the order it does.
+const MAKEFILES: [&str; 3] = ["GNUmakefile", "makefile", "Makefile"];
+/// Names just looks for.
+const JUSTFILES: [&str; 3] = ["justfile", "Justfile", ".justfile"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Runner {
+    Make,
+    Just,
+}
+
+impl Runner {
+    pub fn program(self) -> &'static str {
+        match self {
+            Runner::Make => "make",
+            Runner::Just => "just",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Target {
+    pub runner: Runner,
+    pub name: String,
+    /// From the comment above it, or a `## ` comment after it.
+    pub description: Option<String>,
+}
+
+impl Target {
+    /// How it is run, e.g. `make build`.
+    pub fn label(&self) -> String {
+        format!("{} {}", self.runner.program(), self.name)
+    }
+
+    pub fn command(&self, dir: &Path) -> Command {
+        let mut command = Command::new(self.runner.program());
+        command.arg(&self.name).current_dir(dir);
+        command
+    }
+
+    /// Runs it on the terminal, then waits for Enter so what it printed
+    /// can be read before the app takes the screen back.
+    pub fn run_in_terminal(&self, dir: &Path) -> io::Result<ExitStatus> {
+        println!("$ {}", self.label());
+        let status = self.command(dir).status()?;
+        if io::stdin().is_terminal() {
+            print!("\n[{}] Press Enter to return to browrs", status);
+            io::stdout().flush()?;
+            io::stdin().lock().read_line(&mut String::new())?;
+        }
+        Ok(status)
+    }
+}
+
+/// The targets of the Makefile, then the recipes of the justfile, in `dir`.
+pub fn find(dir: &Path) -> Vec<Target> {
+    let read = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| std::fs::read_to_string(dir.join(name)).ok())
+    };
+    let mut targets = Vec::new();
+    if let Some(text) = read(&MAKEFILES) {
+        targets.extend(make_targets(&text));
+    }
+    if let Some(text) = read(&JUSTFILES) {
+        targets.extend(just_recipes(&text));
+    }
+    targets
+}
+
+/// The comment on the line before, if it is one of its own.
+fn comment_above(previous: Option<&str>) -> Option<String> {
+    let comment = previous?.strip_prefix('#')?;
+    let comment = comment.trim_start_matches('#').trim();
+    (!comment.is_empty()).then(|| comment.to_string())
+}
+
+/// Explicit targets, leaving out special ones like `.PHONY`, pattern rules
+/// and anything defined in a `define` block.
+fn make_targets(text: &str) -> Vec<Target> {
+    let mut targets: Vec<Target> = Vec::new();
+    let mut previous = None;
+    let mut in_define = false;
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("define ") || trimmed == "define" {
+            in_define = true;
+        } else if trimmed == "endef" {
+            in_define = false;
+        } else if !in_define
+            && !line.starts_with(['\t', ' ', '#'])
+            && let Some((names, rest)) = line.split_once(':')
+            // `:=` and `::=` assign variables
+            && !rest.starts_with('=')
+            && !rest.starts_with(":=")
+            && !names.contains(['=', '$', '%'])
+        {
+            let description = rest
+                .split_once("## ")
+                .map(|(_, d)| d.trim().to_string())
+                .or_else(|| comment_above(previous));
+            for name in names.split_whitespace() {
+                if !name.starts_with('.') && !targets.iter().any(|t| t.name == name) {
+                    targets.push(Target {
+                        runner: Runner::Make,
+                        name: name.to_string(),
+                        description: description.clone(),
+                    });
+                }
+            }
+        }
+        previous = Some(line);
+    }
+    targets
+}
+
+/// Public recipes: not starting with `_` or marked `[private]`.
+fn just_recipes(text: &str) -> Vec<Target> {
+    let mut recipes = Vec::new();
+    let mut previous = None;
+    let mut private = false;
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if line.starts_with('[') {
+            // Attributes apply to the recipe below, the doc comment is above
+            private |= trimmed.contains("private");
+            continue;
+        }
+        if !line.starts_with([' ', '\t', '#'])
+            && let Some((head, rest)) = line.split_once(':')
+            && !rest.starts_with('=')
+        {
+            let name = head
+                .trim_start_matches('@')
+                .split_whitespace()
+                .next()
+                .unwrap_or_default();
+            let keyword = ["set", "alias", "export", "import", "mod"].contains(&name);
+            let valid = !name.is_empty()
+                && name
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-');
+            if valid && !keyword && !name.starts_with('_') && !private {
+                recipes.push(Target {
+                    runner: Runner::Just,
+                    name: name.to_string(),
+                    description: comment_above(previous),
+                });
+            }
+        }
+        if !trimmed.is_empty() {
+            private = false;
+        }
+        previous = Some(line);
+    }
+    recipes
+}
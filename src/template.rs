@@ -0,0 +1,78 @@
+//! Commands from config tables like `[open]` and `[previewers]` that are run
+//! on files whose name matches a pattern, and the quoting that goes with
+//! them.
+
+use std::path::Path;
+
+use crate::filter;
+
+/// One `pattern = command` entry. `{file}` in the command is the file and
+/// `{dir}` its directory; a command with neither gets the file appended.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    /// Glob the file name has to match, like `*.pdf`.
+    pub pattern: String,
+    /// `sh` command with the placeholders still in it.
+    pub command: String,
+}
+
+impl Template {
+    /// Reads one entry. A bare extension such as `pdf` stands for `*.pdf`;
+    /// `verb` says what the command does, for the errors.
+    pub fn parse(pattern: &str, command: &str, verb: &str) -> Result<Template, String> {
+        let pattern = pattern.trim();
+        if pattern.is_empty() {
+            return Err(format!("nothing to {} without a pattern", verb));
+        }
+        let pattern = if pattern.contains(['*', '?', '[']) {
+            pattern.to_string()
+        } else {
+            format!("*.{}", pattern.trim_start_matches('.'))
+        };
+        let command = command.trim();
+        if command.is_empty() {
+            return Err(format!("no command to {} {} with", verb, pattern));
+        }
+        Ok(Template {
+            pattern,
+            command: command.to_string(),
+        })
+    }
+
+    pub fn matches(&self, path: &Path) -> bool {
+        path.file_name()
+            .is_some_and(|name| filter::matches(&self.pattern, &name.to_string_lossy()))
+    }
+
+    /// The command for `path`, with the placeholders filled in.
+    pub fn expand(&self, path: &Path) -> String {
+        if !self.command.contains("{file}") && !self.command.contains("{dir}") {
+            return format!("{} {}", self.command, shell_quote(path));
+        }
+        let dir = path.parent().unwrap_or(Path::new("."));
+        let (file, dir) = (shell_quote(path), shell_quote(dir));
+        // One pass, so a name with `{dir}` in it is never filled in again
+        let mut expanded = String::new();
+        let mut rest = self.command.as_str();
+        while let Some(start) = rest.find('{') {
+            expanded.push_str(&rest[..start]);
+            rest = &rest[start..];
+            let (value, len) = if rest.starts_with("{file}") {
+                (file.as_str(), "{file}".len())
+            } else if rest.starts_with("{dir}") {
+                (dir.as_str(), "{dir}".len())
+            } else {
+                ("{", 1)
+            };
+            expanded.push_str(value);
+            rest = &rest[len..];
+        }
+        expanded.push_str(rest);
+        expanded
+    }
+}
+
+/// Single-quotes a path for `sh`.
+pub fn shell_quote(path: &Path) -> String {
+    format!("'{}'", path.to_string_lossy().replace('\'', r"'\''"))
+}
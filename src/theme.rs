@@ -0,0 +1,299 @@
+//! Named color schemes for everything drawn, picked with `theme` in the
+//! config file and adjusted color by color in its `[colors]` table.
+
+use std::cell::Cell;
+
+use ratatui::style::Color;
+
+/// The colors of the interface, by what they are used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    pub name: &'static str,
+    /// Background of the entry under the cursor.
+    pub selection: Color,
+    pub selection_text: Color,
+    /// Background of the cursor in panes without focus.
+    pub cursor: Color,
+    pub marked: Color,
+    /// Titles of focused panes and dialogs, and the tab bar.
+    pub accent: Color,
+    /// The name at the top of the screen.
+    pub title: Color,
+    pub border: Color,
+    /// Details, hints and what is out of focus.
+    pub dim: Color,
+    /// The entry described in the status bar.
+    pub status: Color,
+    /// Search matches, notes, ratings and changed files.
+    pub highlight: Color,
+    pub error: Color,
+    pub success: Color,
+    /// Progress, addresses and headings of unit files.
+    pub info: Color,
+    pub link: Color,
+    pub broken_link: Color,
+    pub fifo: Color,
+    pub socket: Color,
+    pub device: Color,
+    /// Paths of grep results and the git branch.
+    pub path: Color,
+    /// Entries by type, where `$LS_COLORS` doesn't color them.
+    pub directory: Color,
+    pub executable: Color,
+    pub archive: Color,
+    pub image: Color,
+    /// Audio and video.
+    pub media: Color,
+    /// Syntax highlighting of previews.
+    pub keyword: Color,
+    pub string: Color,
+    pub comment: Color,
+    pub number: Color,
+    pub type_name: Color,
+    pub heading: Color,
+    /// Files at least as big as each of the size tiers.
+    pub size_medium: Color,
+    pub size_large: Color,
+    pub size_huge: Color,
+    /// Entries not modified for `stale_days`.
+    pub stale: Color,
+}
+
+pub const NAMES: &[&str] = &["default", "gruvbox", "solarized", "dracula"];
+
+/// The terminal's own palette.
+const DEFAULT: Theme = Theme {
+    name: "default",
+    selection: Color::Blue,
+    selection_text: Color::White,
+    cursor: Color::DarkGray,
+    marked: Color::Magenta,
+    accent: Color::Blue,
+    title: Color::Green,
+    border: Color::Reset,
+    dim: Color::DarkGray,
+    status: Color::DarkGray,
+    highlight: Color::Yellow,
+    error: Color::Red,
+    success: Color::Green,
+    info: Color::Cyan,
+    link: Color::Cyan,
+    broken_link: Color::Red,
+    fifo: Color::Yellow,
+    socket: Color::Magenta,
+    device: Color::LightYellow,
+    path: Color::Magenta,
+    directory: Color::Blue,
+    executable: Color::Green,
+    archive: Color::Red,
+    image: Color::Magenta,
+    media: Color::Cyan,
+    keyword: Color::Magenta,
+    string: Color::Green,
+    comment: Color::DarkGray,
+    number: Color::Cyan,
+    type_name: Color::Yellow,
+    heading: Color::Blue,
+    size_medium: Color::Yellow,
+    size_large: Color::LightRed,
+    size_huge: Color::Red,
+    stale: Color::DarkGray,
+};
+
+const GRUVBOX: Theme = Theme {
+    name: "gruvbox",
+    selection: rgb(0x458588),
+    selection_text: rgb(0xfbf1c7),
+    cursor: rgb(0x3c3836),
+    marked: rgb(0xd3869b),
+    accent: rgb(0x83a598),
+    title: rgb(0xb8bb26),
+    border: rgb(0x665c54),
+    dim: rgb(0x928374),
+    status: rgb(0xa89984),
+    highlight: rgb(0xfabd2f),
+    error: rgb(0xfb4934),
+    success: rgb(0xb8bb26),
+    info: rgb(0x8ec07c),
+    link: rgb(0x8ec07c),
+    broken_link: rgb(0xfb4934),
+    fifo: rgb(0xfabd2f),
+    socket: rgb(0xd3869b),
+    device: rgb(0xfe8019),
+    path: rgb(0xd3869b),
+    directory: rgb(0x83a598),
+    executable: rgb(0xb8bb26),
+    archive: rgb(0xfb4934),
+    image: rgb(0xd3869b),
+    media: rgb(0x8ec07c),
+    keyword: rgb(0xfb4934),
+    string: rgb(0xb8bb26),
+    comment: rgb(0x928374),
+    number: rgb(0xd3869b),
+    type_name: rgb(0xfabd2f),
+    heading: rgb(0x83a598),
+    size_medium: rgb(0xfabd2f),
+    size_large: rgb(0xfe8019),
+    size_huge: rgb(0xfb4934),
+    stale: rgb(0x7c6f64),
+};
+
+const SOLARIZED: Theme = Theme {
+    name: "solarized",
+    selection: rgb(0x268bd2),
+    selection_text: rgb(0xfdf6e3),
+    cursor: rgb(0x073642),
+    marked: rgb(0xd33682),
+    accent: rgb(0x268bd2),
+    title: rgb(0x859900),
+    border: rgb(0x586e75),
+    dim: rgb(0x586e75),
+    status: rgb(0x657b83),
+    highlight: rgb(0xb58900),
+    error: rgb(0xdc322f),
+    success: rgb(0x859900),
+    info: rgb(0x2aa198),
+    link: rgb(0x2aa198),
+    broken_link: rgb(0xdc322f),
+    fifo: rgb(0xb58900),
+    socket: rgb(0xd33682),
+    device: rgb(0xcb4b16),
+    path: rgb(0x6c71c4),
+    directory: rgb(0x268bd2),
+    executable: rgb(0x859900),
+    archive: rgb(0xdc322f),
+    image: rgb(0xd33682),
+    media: rgb(0x2aa198),
+    keyword: rgb(0x859900),
+    string: rgb(0x2aa198),
+    comment: rgb(0x586e75),
+    number: rgb(0xd33682),
+    type_name: rgb(0xb58900),
+    heading: rgb(0x268bd2),
+    size_medium: rgb(0xb58900),
+    size_large: rgb(0xcb4b16),
+    size_huge: rgb(0xdc322f),
+    stale: rgb(0x586e75),
+};
+
+const DRACULA: Theme = Theme {
+    name: "dracula",
+    selection: rgb(0x44475a),
+    selection_text: rgb(0xf8f8f2),
+    cursor: rgb(0x343746),
+    marked: rgb(0xff79c6),
+    accent: rgb(0xbd93f9),
+    title: rgb(0x50fa7b),
+    border: rgb(0x6272a4),
+    dim: rgb(0x6272a4),
+    status: rgb(0x6272a4),
+    highlight: rgb(0xf1fa8c),
+    error: rgb(0xff5555),
+    success: rgb(0x50fa7b),
+    info: rgb(0x8be9fd),
+    link: rgb(0x8be9fd),
+    broken_link: rgb(0xff5555),
+    fifo: rgb(0xf1fa8c),
+    socket: rgb(0xff79c6),
+    device: rgb(0xffb86c),
+    path: rgb(0xff79c6),
+    directory: rgb(0xbd93f9),
+    executable: rgb(0x50fa7b),
+    archive: rgb(0xff5555),
+    image: rgb(0xff79c6),
+    media: rgb(0x8be9fd),
+    keyword: rgb(0xff79c6),
+    string: rgb(0xf1fa8c),
+    comment: rgb(0x6272a4),
+    number: rgb(0xbd93f9),
+    type_name: rgb(0x8be9fd),
+    heading: rgb(0xbd93f9),
+    size_medium: rgb(0xf1fa8c),
+    size_large: rgb(0xffb86c),
+    size_huge: rgb(0xff5555),
+    stale: rgb(0x6272a4),
+};
+
+const fn rgb(hex: u32) -> Color {
+    Color::Rgb((hex >> 16) as u8, (hex >> 8) as u8, hex as u8)
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        DEFAULT
+    }
+}
+
+impl Theme {
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(DEFAULT),
+            "gruvbox" => Some(GRUVBOX),
+            "solarized" => Some(SOLARIZED),
+            "dracula" => Some(DRACULA),
+            _ => None,
+        }
+    }
+
+    /// The color `[colors]` calls `name`.
+    pub fn color(&self, name: &str) -> Option<Color> {
+        let mut theme = *self;
+        theme.color_mut(name).map(|color| *color)
+    }
+
+    pub fn color_mut(&mut self, name: &str) -> Option<&mut Color> {
+        Some(match name {
+            "selection" => &mut self.selection,
+            "selection_text" => &mut self.selection_text,
+            "cursor" => &mut self.cursor,
+            "marked" => &mut self.marked,
+            "accent" => &mut self.accent,
+            "title" => &mut self.title,
+            "border" => &mut self.border,
+            "dim" => &mut self.dim,
+            "status" => &mut self.status,
+            "highlight" => &mut self.highlight,
+            "error" => &mut self.error,
+            "success" => &mut self.success,
+            "info" => &mut self.info,
+            "link" => &mut self.link,
+            "broken_link" => &mut self.broken_link,
+            "fifo" => &mut self.fifo,
+            "socket" => &mut self.socket,
+            "device" => &mut self.device,
+            "path" => &mut self.path,
+            "directory" => &mut self.directory,
+            "executable" => &mut self.executable,
+            "archive" => &mut self.archive,
+            "image" => &mut self.image,
+            "media" => &mut self.media,
+            "keyword" => &mut self.keyword,
+            "string" => &mut self.string,
+            "comment" => &mut self.comment,
+            "number" => &mut self.number,
+            "type_name" => &mut self.type_name,
+            "heading" => &mut self.heading,
+            "size_medium" => &mut self.size_medium,
+            "size_large" => &mut self.size_large,
+            "size_huge" => &mut self.size_huge,
+            "stale" => &mut self.stale,
+            _ => return None,
+        })
+    }
+}
+
+thread_local! {
+    /// What widgets are drawn with on this thread. The interface sets it
+    /// before drawing, and threads reading previews to the theme they were
+    /// started with.
+    static CURRENT: Cell<Theme> = const { Cell::new(DEFAULT) };
+}
+
+/// The theme to draw with.
+pub fn current() -> Theme {
+    CURRENT.get()
+}
+
+pub fn set_current(theme: Theme) {
+    CURRENT.set(theme);
+}
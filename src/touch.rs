@@ -0,0 +1,171 @@
+use std::{
+    io,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use crate::datetime::{self, DateTime};
+
+/// A parsed touch request: `[-a] [now | @EPOCH | ±N(s|m|h|d) | YYYY-MM-DD[ HH:MM[:SS]]]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Touch {
+    pub time: SystemTime,
+    /// Also update the access time.
+    pub access: bool,
+}
+
+impl Touch {
+    pub fn parse(spec: &str, now: SystemTime) -> Result<Self, String> {
+        let mut access = false;
+        let mut rest = vec![];
+        for token in spec.split_whitespace() {
+            if token == "-a" {
+                access = true;
+            } else {
+                rest.push(token);
+            }
+        }
+        let time = parse_time(&rest.join(" "), now)?;
+        Ok(Self { time, access })
+    }
+}
+
+pub fn parse_time(input: &str, now: SystemTime) -> Result<SystemTime, String> {
+    let input = input.trim();
+    if input.is_empty() || input == "now" {
+        return Ok(now);
+    }
+
+    if let Some(epoch) = input.strip_prefix('@') {
+        let secs: u64 = epoch
+            .parse()
+            .map_err(|_| format!("Invalid epoch seconds: {epoch}"))?;
+        return after_epoch(secs);
+    }
+
+    if let Some(sign @ ('+' | '-')) = input.chars().next() {
+        let body = &input[1..];
+        let unit = body.chars().last().unwrap_or_default();
+        let amount: u64 = body[..body.len() - unit.len_utf8().min(body.len())]
+            .parse()
+            .map_err(|_| format!("Invalid relative time: {input}"))?;
+        let unit_seconds = match unit {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            'd' => 86_400,
+            _ => return Err(format!("Unknown unit '{unit}', use s, m, h or d")),
+        };
+        let offset = amount
+            .checked_mul(unit_seconds)
+            .map(Duration::from_secs)
+            .ok_or_else(out_of_range)?;
+        return if sign == '+' {
+            now.checked_add(offset)
+                .filter(|time| *time <= latest())
+                .ok_or_else(out_of_range)
+        } else {
+            now.checked_sub(offset)
+                .filter(|time| *time >= UNIX_EPOCH)
+                .ok_or_else(|| "Time before the epoch".to_string())
+        };
+    }
+
+    let (date, time) = input.split_once([' ', 'T']).unwrap_or((input, "00:00:00"));
+    let date: Vec<i64> = date
+        .split('-')
+        .map(|part| part.parse().map_err(|_| format!("Invalid date: {input}")))
+        .collect::<Result<_, _>>()?;
+    let time: Vec<i64> = time
+        .split(':')
+        .map(|part| part.parse().map_err(|_| format!("Invalid time: {input}")))
+        .collect::<Result<_, _>>()?;
+    let ([year, month, day], [hour, minute, rest @ ..]) = (date.as_slice(), time.as_slice()) else {
+        return Err(format!("Expected YYYY-MM-DD [HH:MM[:SS]], got {input}"));
+    };
+    let second = rest.first().copied().unwrap_or(0);
+    // Years past 9999 don't fit the C library's date fields
+    if !(1..=9999).contains(year)
+        || !(1..=12).contains(month)
+        || !(1..=31).contains(day)
+        || *hour > 23
+        || *minute > 59
+        || !(0..=60).contains(&second)
+    {
+        return Err(format!("Date out of range: {input}"));
+    }
+
+    let epoch = datetime::local_to_epoch(DateTime {
+        year: *year,
+        month: *month,
+        day: *day,
+        hour: *hour,
+        minute: *minute,
+        second,
+    })
+    .ok_or_else(|| "Unable to convert local time".to_string())?;
+    let secs = u64::try_from(epoch).map_err(|_| "Time before the epoch".to_string())?;
+    after_epoch(secs)
+}
+
+/// `secs` seconds after the epoch, if no later than [`latest`].
+fn after_epoch(secs: u64) -> Result<SystemTime, String> {
+    UNIX_EPOCH
+        .checked_add(Duration::from_secs(secs))
+        .filter(|time| *time <= latest())
+        .ok_or_else(out_of_range)
+}
+
+/// The end of 9999, the last time a date can be written for.
+fn latest() -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(253_402_300_799)
+}
+
+fn out_of_range() -> String {
+    "Time out of range".to_string()
+}
+
+/// Sets the modification (and optionally access) time of `path` without
+/// opening it, so FIFOs and unreadable files are handled too.
+#[cfg(unix)]
+pub fn touch(path: &Path, touch: &Touch) -> io::Result<()> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let timespec = |time: SystemTime| {
+        let since = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+        libc::timespec {
+            tv_sec: since.as_secs() as libc::time_t,
+            tv_nsec: since.subsec_nanos() as _,
+        }
+    };
+    let accessed = if touch.access {
+        timespec(touch.time)
+    } else {
+        libc::timespec {
+            tv_sec: 0,
+            tv_nsec: libc::UTIME_OMIT,
+        }
+    };
+    let times = [accessed, timespec(touch.time)];
+    let path = std::ffi::CString::new(path.as_os_str().as_bytes())?;
+    // SAFETY: path is NUL-terminated and times points to two timespecs
+    let result = unsafe { libc::utimensat(libc::AT_FDCWD, path.as_ptr(), times.as_ptr(), 0) };
+    if result == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+/// Sets the modification (and optionally access) time of `path`.
+#[cfg(not(unix))]
+pub fn touch(path: &Path, touch: &Touch) -> io::Result<()> {
+    let mut times = std::fs::FileTimes::new().set_modified(touch.time);
+    if touch.access {
+        times = times.set_accessed(touch.time);
+    }
+    std::fs::File::options()
+        .write(true)
+        .open(path)?
+        .set_times(times)
+}
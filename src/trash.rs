@@ -0,0 +1,163 @@
+use std::{
+    io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::{datetime, jobs::Progress, ops, url};
+
+/// Where trashed files and their restore information live.
+struct TrashDirs {
+    files: PathBuf,
+    info: PathBuf,
+}
+
+/// The XDG home trash, `$XDG_DATA_HOME/Trash`.
+#[cfg(not(target_os = "macos"))]
+fn trash_dirs() -> io::Result<TrashDirs> {
+    let base = dirs::data_dir()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no data directory"))?
+        .join("Trash");
+    Ok(TrashDirs {
+        files: base.join("files"),
+        info: base.join("info"),
+    })
+}
+
+/// `~/.Trash`, with restore information kept by browrs since Finder's own
+/// bookkeeping is private.
+#[cfg(target_os = "macos")]
+fn trash_dirs() -> io::Result<TrashDirs> {
+    let not_found = || io::Error::new(io::ErrorKind::NotFound, "no home directory");
+    Ok(TrashDirs {
+        files: dirs::home_dir().ok_or_else(not_found)?.join(".Trash"),
+        info: dirs::data_local_dir()
+            .ok_or_else(not_found)?
+            .join("browrs")
+            .join("trashinfo"),
+    })
+}
+
+/// A trashed entry that can be restored.
+#[derive(Debug, Clone)]
+pub struct TrashedItem {
+    /// Name inside the trash `files` directory.
+    pub name: String,
+    pub original: Option<PathBuf>,
+    pub deleted: String,
+}
+
+/// Moves `path` into the trash, recording where it came from.
+pub fn trash(path: &Path) -> io::Result<()> {
+    let dirs = trash_dirs()?;
+    std::fs::create_dir_all(&dirs.files)?;
+    std::fs::create_dir_all(&dirs.info)?;
+
+    let original = std::path::absolute(path)?;
+    let base = original
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "no file name"))?
+        .to_string_lossy()
+        .to_string();
+
+    // Claim a unique name by creating its info file exclusively
+    let mut counter = 1;
+    let (name, info_path) = loop {
+        let name = if counter == 1 {
+            base.clone()
+        } else {
+            format!("{}.{}", base, counter)
+        };
+        let info_path = dirs.info.join(format!("{}.trashinfo", name));
+        if dirs.files.join(&name).symlink_metadata().is_err() {
+            match std::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&info_path)
+            {
+                Ok(_) => break (name, info_path),
+                Err(e) if e.kind() != io::ErrorKind::AlreadyExists => return Err(e),
+                Err(_) => {}
+            }
+        }
+        counter += 1;
+    };
+
+    let info = format!(
+        "[Trash Info]\nPath={}\nDeletionDate={}\n",
+        url::encode(&original.to_string_lossy()),
+        datetime::local(SystemTime::now()).iso()
+    );
+    let result = std::fs::write(&info_path, info)
+        .and_then(|_| ops::move_path(&original, &dirs.files.join(&name)));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&info_path);
+    }
+    result
+}
+
+pub fn trash_all(sources: &[PathBuf], progress: &Progress) -> ops::BatchReport {
+    ops::each("trash", sources, progress, trash)
+}
+
+/// Everything currently in the trash, most recently deleted first.
+pub fn list() -> io::Result<Vec<TrashedItem>> {
+    let dirs = trash_dirs()?;
+    let Ok(entries) = std::fs::read_dir(&dirs.files) else {
+        return Ok(vec![]);
+    };
+
+    let mut items: Vec<TrashedItem> = entries
+        .flatten()
+        .map(|entry| {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let info = std::fs::read_to_string(dirs.info.join(format!("{}.trashinfo", name)))
+                .unwrap_or_default();
+            let field = |key: &str| {
+                info.lines()
+                    .find_map(|line| line.strip_prefix(key))
+                    .map(str::to_string)
+            };
+            TrashedItem {
+                original: field("Path=").map(|p| PathBuf::from(url::decode(&p))),
+                deleted: field("DeletionDate=").unwrap_or_default(),
+                name,
+            }
+        })
+        .collect();
+    items.sort_by(|a, b| b.deleted.cmp(&a.deleted));
+    Ok(items)
+}
+
+/// Moves a trashed entry back to where it was deleted from.
+pub fn restore(item: &TrashedItem) -> io::Result<PathBuf> {
+    let dirs = trash_dirs()?;
+    let original = item
+        .original
+        .clone()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "original location is unknown"))?;
+    if original.symlink_metadata().is_ok() {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!("{} already exists", original.display()),
+        ));
+    }
+    if let Some(parent) = original.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    ops::move_path(&dirs.files.join(&item.name), &original)?;
+    let _ = std::fs::remove_file(dirs.info.join(format!("{}.trashinfo", item.name)));
+    Ok(original)
+}
+
+/// Permanently deletes everything in the trash.
+pub fn empty() -> io::Result<ops::BatchReport> {
+    let dirs = trash_dirs()?;
+    let mut report = ops::BatchReport::new("delete");
+    for item in list()? {
+        let path = dirs.files.join(&item.name);
+        report.record(&path, ops::remove_path(&path));
+        let _ = std::fs::remove_file(dirs.info.join(format!("{}.trashinfo", item.name)));
+    }
+    Ok(report)
+}
@@ -0,0 +1,259 @@
+//! Where a listed path lives: on disk, or inside an archive browsed like a
+//! directory. Paths into an archive continue past the archive file, so
+//! `/tmp/src.zip/lib/a.rs` is `lib/a.rs` in `/tmp/src.zip`.
+
+use std::{
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    archive::{self, Format, Member},
+    entry::{Entry, Link},
+    ignore, log, ops,
+    pane::Listing,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Location {
+    Disk(PathBuf),
+    Archive {
+        archive: PathBuf,
+        /// Path inside the archive, empty for its top level.
+        inner: PathBuf,
+    },
+}
+
+impl Location {
+    pub fn of(path: &Path) -> Location {
+        if let Ok(metadata) = std::fs::metadata(path)
+            && (metadata.is_dir() || Format::detect(path).is_none())
+        {
+            return Location::Disk(path.to_path_buf());
+        }
+        for ancestor in path.ancestors() {
+            if Format::detect(ancestor).is_some() && ancestor.is_file() {
+                return Location::Archive {
+                    archive: ancestor.to_path_buf(),
+                    inner: path
+                        .strip_prefix(ancestor)
+                        .unwrap_or(Path::new(""))
+                        .to_path_buf(),
+                };
+            }
+        }
+        Location::Disk(path.to_path_buf())
+    }
+}
+
+/// Whether `path` is an archive or somewhere inside one, so read-only.
+pub fn in_archive(path: &Path) -> bool {
+    matches!(Location::of(path), Location::Archive { .. })
+}
+
+/// Whether `path` is an entry inside an archive, rather than on disk or
+/// the archive file itself.
+pub fn is_archive_member(path: &Path) -> bool {
+    matches!(Location::of(path), Location::Archive { inner, .. } if !inner.as_os_str().is_empty())
+}
+
+/// Whether `path` can be listed: a directory, an archive, or a directory
+/// inside one.
+pub fn is_dir(path: &Path) -> bool {
+    match Location::of(path) {
+        Location::Disk(path) => path.is_dir(),
+        Location::Archive { archive, inner } => {
+            inner.as_os_str().is_empty()
+                || archive::members(&archive).is_ok_and(|members| {
+                    members.iter().any(|m| {
+                        (m.path == inner && m.is_dir)
+                            || (m.path.starts_with(&inner) && m.path != inner)
+                    })
+                })
+        }
+    }
+}
+
+/// Lists `path`, sorted, with `..` first.
+pub fn read_dir(path: &Path, listing: Listing) -> io::Result<Vec<Entry>> {
+    let _timer = log::timer(|| format!("read {}", path.display()));
+    let mut entries = match Location::of(path) {
+        Location::Disk(path) => read_disk_dir(&path, &listing)?,
+        Location::Archive { archive, inner } => {
+            archive_children(&archive::members(&archive)?, &inner, &listing)
+        }
+    };
+    listing.sort.apply(&mut entries, listing.dirs_first);
+    entries.insert(0, Entry::parent());
+    Ok(entries)
+}
+
+fn read_disk_dir(path: &Path, listing: &Listing) -> io::Result<Vec<Entry>> {
+    let mut entries = vec![];
+    // What .browrsignore files hide, shown along with hidden files
+    let hidden = (!listing.show_hidden).then(|| ignore::Rules::browrs(path));
+    for entry in std::fs::read_dir(path)? {
+        // Entries of /proc and the like can be gone by the time they are
+        // looked at
+        let Ok(entry) = entry else {
+            continue;
+        };
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if listing.show_hidden || !file_name.starts_with('.') {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            // Symlinks are sized and dated by what they point to
+            let target = std::fs::metadata(entry.path());
+            let is_dir = match &target {
+                Ok(metadata) if file_type.is_symlink() => metadata.is_dir(),
+                _ => file_type.is_dir(),
+            };
+            if hidden
+                .as_ref()
+                .is_some_and(|rules| rules.is_ignored(&entry.path(), is_dir))
+            {
+                continue;
+            }
+            let mut item = Entry::new(file_name, is_dir);
+            if file_type.is_symlink() {
+                item.link = Some(Link {
+                    target: std::fs::read_link(entry.path()).unwrap_or_default(),
+                    broken: target.is_err(),
+                });
+            }
+            if let Ok(metadata) = target.or_else(|_| entry.metadata()) {
+                item.size = metadata.len();
+                item.modified = metadata.modified().ok();
+            }
+            #[cfg(unix)]
+            if let Ok(metadata) = entry.metadata() {
+                item.meta = Some(crate::entry::Meta::of(&metadata));
+            }
+            item.rating = listing.ratings.get(&entry.path()).copied().unwrap_or(0);
+            entries.push(item);
+        }
+    }
+    Ok(entries)
+}
+
+/// The entries directly under `inner`. Archives often leave out entries for
+/// directories, so those are made up from the paths below them.
+fn archive_children(members: &[Member], inner: &Path, listing: &Listing) -> Vec<Entry> {
+    let mut entries: Vec<Entry> = Vec::new();
+    for member in members {
+        let Ok(rest) = member.path.strip_prefix(inner) else {
+            continue;
+        };
+        let mut components = rest.components();
+        let Some(name) = components.next() else {
+            continue;
+        };
+        let name = name.as_os_str().to_string_lossy().to_string();
+        if !listing.show_hidden && name.starts_with('.') {
+            continue;
+        }
+        let direct = components.next().is_none();
+        let is_dir = !direct || member.is_dir;
+        match entries.iter_mut().find(|e| e.name == name) {
+            Some(entry) => entry.is_dir |= is_dir,
+            None => {
+                let mut entry = Entry::new(name, is_dir);
+                if direct {
+                    entry.size = member.size;
+                    entry.modified = member.modified;
+                }
+                entries.push(entry);
+            }
+        }
+    }
+    entries
+}
+
+/// Up to `limit` bytes of the file at `path`.
+pub fn read(path: &Path, limit: u64) -> io::Result<Vec<u8>> {
+    match Location::of(path) {
+        Location::Disk(path) => {
+            let mut bytes = Vec::new();
+            std::fs::File::open(path)?
+                .take(limit)
+                .read_to_end(&mut bytes)?;
+            Ok(bytes)
+        }
+        Location::Archive { archive, inner } => {
+            let members = archive::members(&archive)?;
+            let member = members
+                .iter()
+                .find(|m| m.path == inner && !m.is_dir)
+                .ok_or_else(|| not_found(&inner))?;
+            archive::read(&archive, &member.raw, limit)
+        }
+    }
+}
+
+/// Extracts the file or directory at `path` inside an archive to `dest`.
+pub fn extract(path: &Path, dest: &Path) -> io::Result<()> {
+    let Location::Archive { archive, inner } = Location::of(path) else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} is not in an archive", path.display()),
+        ));
+    };
+    let raws: Vec<String> = archive::members(&archive)?
+        .into_iter()
+        .filter(|m| m.path.starts_with(&inner))
+        .map(|m| m.raw)
+        .collect();
+    if raws.is_empty() || inner.as_os_str().is_empty() {
+        return Err(not_found(&inner));
+    }
+    // Unpacked next to the destination first, then moved into place
+    let parent = dest.parent().unwrap_or(Path::new("."));
+    let staging = parent.join(format!(".browrs-extract-{}", std::process::id()));
+    std::fs::create_dir_all(&staging)?;
+    let result = archive::extract(&archive, &raws, &staging)
+        .and_then(|()| ops::move_path(&staging.join(&inner), dest));
+    let _ = ops::remove_path(&staging);
+    result
+}
+
+/// Extracts the file at `path` inside an archive to a temporary directory,
+/// for opening it in a program; returns where it went. Anything else is
+/// already a file to open.
+pub fn extract_temporary(path: &Path) -> io::Result<PathBuf> {
+    let Location::Archive { archive, inner } = Location::of(path) else {
+        return Ok(path.to_path_buf());
+    };
+    if inner.as_os_str().is_empty() {
+        return Ok(path.to_path_buf());
+    }
+    let dir = temporary_dir().join(archive.file_name().unwrap_or_default());
+    let dest = dir.join(&inner);
+    if dest.exists() {
+        ops::remove_path(&dest)?;
+    }
+    if let Some(parent) = dest.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    extract(path, &dest)?;
+    Ok(dest)
+}
+
+/// Removes what [`extract_temporary`] extracted.
+pub fn remove_temporary() {
+    let dir = temporary_dir();
+    if dir.exists() {
+        let _ = ops::remove_path(&dir);
+    }
+}
+
+fn temporary_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("browrs-{}", std::process::id()))
+}
+
+fn not_found(inner: &Path) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("{} is not in the archive", inner.display()),
+    )
+}
@@ -0,0 +1,97 @@
+//! How often each directory was opened, to suggest the usual ones among
+//! the places. Kept in `$XDG_STATE_HOME/browrs/visits` as `count<TAB>path`
+//! lines and read again every time, so every running browrs adds to it.
+
+use std::{
+    collections::HashMap,
+    io,
+    path::{Path, PathBuf},
+};
+
+/// Directories remembered; the least visited go first past this.
+const MAX_DIRS: usize = 500;
+
+#[derive(Debug, Clone, Default)]
+pub struct Visits {
+    /// Where the counts are kept; `None` keeps them in memory only.
+    file: Option<PathBuf>,
+    session: HashMap<PathBuf, u32>,
+}
+
+impl Visits {
+    pub fn default_file() -> Option<PathBuf> {
+        dirs::state_dir()
+            .or_else(dirs::cache_dir)
+            .map(|dir| dir.join("browrs").join("visits"))
+    }
+
+    /// Keeps the counts in `file`.
+    pub fn in_file(file: PathBuf) -> Self {
+        Self {
+            file: Some(file),
+            session: HashMap::new(),
+        }
+    }
+
+    fn counts(&self) -> HashMap<PathBuf, u32> {
+        let Some(file) = &self.file else {
+            return self.session.clone();
+        };
+        std::fs::read_to_string(file)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| {
+                let (count, path) = line.split_once('\t')?;
+                Some((PathBuf::from(path), count.parse().ok()?))
+            })
+            .collect()
+    }
+
+    /// Counts one more visit to `dir`.
+    pub fn record(&mut self, dir: &Path) -> io::Result<()> {
+        let mut counts = self.counts();
+        *counts.entry(dir.to_path_buf()).or_default() += 1;
+        self.save(counts)
+    }
+
+    /// Stops suggesting `dir` until it is visited again.
+    pub fn forget(&mut self, dir: &Path) -> io::Result<()> {
+        let mut counts = self.counts();
+        counts.remove(dir);
+        self.save(counts)
+    }
+
+    /// The `count` most visited directories that still exist and aren't
+    /// `excluded`, most visited first.
+    pub fn top(&self, count: usize, excluded: impl Fn(&Path) -> bool) -> Vec<(PathBuf, u32)> {
+        let mut counts: Vec<(PathBuf, u32)> = self
+            .counts()
+            .into_iter()
+            .filter(|(dir, _)| !excluded(dir) && dir.is_dir())
+            .collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counts.truncate(count);
+        counts
+    }
+
+    fn save(&mut self, counts: HashMap<PathBuf, u32>) -> io::Result<()> {
+        let mut counts: Vec<(PathBuf, u32)> = counts.into_iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counts.truncate(MAX_DIRS);
+        let Some(file) = &self.file else {
+            self.session = counts.into_iter().collect();
+            return Ok(());
+        };
+        if let Some(parent) = file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut contents = String::new();
+        for (dir, count) in counts {
+            contents.push_str(&format!("{}\t{}\n", count, dir.to_string_lossy()));
+        }
+        // Renamed into place, so another browrs never reads half a file
+        let partial = file.with_extension(format!("{}.tmp", std::process::id()));
+        std::fs::write(&partial, contents)?;
+        std::fs::rename(&partial, file)
+    }
+}
@@ -0,0 +1,154 @@
+//! How the recursive tools (search, grep, disk usage, duplicates and the
+//! cleanup scan) go through a tree: how deep, how far, whether into
+//! symlinked directories, and what they leave out.
+
+use std::{
+    collections::{HashSet, VecDeque},
+    fs::{DirEntry, Metadata},
+    path::{Path, PathBuf},
+};
+
+use crate::{ignore::Rules, log};
+
+/// Limits on walking a tree, the same for every tool.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Policy {
+    /// Levels below the top gone into, 0 for no limit.
+    pub max_depth: usize,
+    /// Entries gone through before stopping, 0 for no limit.
+    pub max_entries: usize,
+    /// Goes into symlinked directories, each real directory once so that
+    /// links in circles end.
+    pub follow_symlinks: bool,
+    /// Patterns in gitignore syntax, from the top, of what is left out.
+    pub exclude: Vec<String>,
+}
+
+impl Policy {
+    /// Everything under `root`, hidden entries only if `show_hidden`.
+    pub fn walk(&self, root: &Path, show_hidden: bool) -> Walk {
+        let mut seen = HashSet::new();
+        if self.follow_symlinks
+            && let Ok(real) = root.canonicalize()
+        {
+            seen.insert(real);
+        }
+        Walk {
+            policy: self.clone(),
+            show_hidden,
+            excluded: Rules::patterns(root, &self.exclude),
+            pending: vec![(root.to_path_buf(), 1)],
+            entries: VecDeque::new(),
+            depth: 0,
+            seen,
+            count: 0,
+            stopped: false,
+        }
+    }
+}
+
+/// An entry found on a walk.
+#[derive(Debug)]
+pub struct Found {
+    pub path: PathBuf,
+    /// 1 for entries right under the top.
+    pub depth: usize,
+    /// Of the target for followed links, of the entry itself otherwise.
+    pub metadata: Metadata,
+}
+
+/// The entries under a directory, each directory's in name order.
+#[derive(Debug)]
+pub struct Walk {
+    policy: Policy,
+    show_hidden: bool,
+    excluded: Rules,
+    /// Directories still to read, with the depth of what is in them.
+    pending: Vec<(PathBuf, usize)>,
+    /// The rest of the directory being read.
+    entries: VecDeque<DirEntry>,
+    depth: usize,
+    /// Real paths of the directories gone into, when following links.
+    seen: HashSet<PathBuf>,
+    count: usize,
+    stopped: bool,
+}
+
+impl Walk {
+    /// Whether it stopped at `max_entries` with entries left.
+    pub fn stopped(&self) -> bool {
+        self.stopped
+    }
+
+    /// Leaves out what is under `dir`, just found.
+    pub fn prune(&mut self, dir: &Path) {
+        self.pending.retain(|(pending, _)| pending != dir);
+    }
+
+    /// Whether to go into the directory at `path`.
+    fn enters(&mut self, path: &Path, depth: usize) -> bool {
+        if self.policy.max_depth > 0 && depth >= self.policy.max_depth {
+            return false;
+        }
+        if !self.policy.follow_symlinks {
+            return true;
+        }
+        match path.canonicalize() {
+            Ok(real) => self.seen.insert(real),
+            Err(_) => false,
+        }
+    }
+}
+
+impl Iterator for Walk {
+    type Item = Found;
+
+    fn next(&mut self) -> Option<Found> {
+        loop {
+            if self.policy.max_entries > 0 && self.count >= self.policy.max_entries {
+                if !self.stopped && (!self.entries.is_empty() || !self.pending.is_empty()) {
+                    log::info!("walk stopped after {} entries", self.count);
+                    self.stopped = true;
+                }
+                return None;
+            }
+            let Some(entry) = self.entries.pop_front() else {
+                let (dir, depth) = self.pending.pop()?;
+                let Ok(entries) = std::fs::read_dir(&dir) else {
+                    continue;
+                };
+                let mut entries: Vec<DirEntry> = entries.flatten().collect();
+                entries.sort_by_key(|entry| entry.file_name());
+                self.entries = entries.into();
+                self.depth = depth;
+                continue;
+            };
+            if !self.show_hidden && entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
+            let path = entry.path();
+            let Ok(mut metadata) = entry.metadata() else {
+                continue;
+            };
+            if metadata.is_symlink()
+                && self.policy.follow_symlinks
+                && let Ok(target) = std::fs::metadata(&path)
+            {
+                metadata = target;
+            }
+            if self.excluded.is_ignored(&path, metadata.is_dir()) {
+                continue;
+            }
+            let depth = self.depth;
+            if metadata.is_dir() && self.enters(&path, depth) {
+                self.pending.push((path.clone(), depth + 1));
+            }
+            self.count += 1;
+            return Some(Found {
+                path,
+                depth,
+                metadata,
+            });
+        }
+    }
+}
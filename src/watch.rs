@@ -0,0 +1,108 @@
+//! Notices directories changing on disk, from outside browrs, by looking at
+//! them again every so often on a background thread.
+
+use std::{
+    collections::HashMap,
+    hash::{DefaultHasher, Hash, Hasher},
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver},
+    },
+    thread,
+    time::Duration,
+};
+
+/// Entries of a directory looked at one by one; in bigger ones only
+/// entries coming and going are noticed, not changes to files.
+const MAX_ENTRIES: usize = 2000;
+
+#[derive(Debug)]
+pub struct Watcher {
+    dirs: Arc<Mutex<Vec<PathBuf>>>,
+    receiver: Receiver<PathBuf>,
+    stop: Arc<AtomicBool>,
+}
+
+impl Watcher {
+    /// Looks at the watched directories every `interval`.
+    pub fn start(interval: Duration) -> Self {
+        let dirs = Arc::new(Mutex::new(Vec::<PathBuf>::new()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+
+        let (watched, stopped) = (Arc::clone(&dirs), Arc::clone(&stop));
+        thread::spawn(move || {
+            let mut seen: HashMap<PathBuf, u64> = HashMap::new();
+            while !stopped.load(Ordering::Relaxed) {
+                let dirs = watched.lock().map(|dirs| dirs.clone()).unwrap_or_default();
+                seen.retain(|dir, _| dirs.contains(dir));
+                for dir in dirs {
+                    let Some(now) = signature(&dir) else {
+                        continue;
+                    };
+                    let before = seen.insert(dir.clone(), now);
+                    if before.is_some_and(|before| before != now) && sender.send(dir).is_err() {
+                        return;
+                    }
+                }
+                thread::sleep(interval);
+            }
+        });
+
+        Self {
+            dirs,
+            receiver,
+            stop,
+        }
+    }
+
+    /// Watches `dirs` from now on, instead of the ones before.
+    pub fn watch(&self, mut dirs: Vec<PathBuf>) {
+        dirs.sort();
+        dirs.dedup();
+        if let Ok(mut watched) = self.dirs.lock() {
+            *watched = dirs;
+        }
+    }
+
+    /// The directories that changed since last asked.
+    pub fn changed(&self) -> Vec<PathBuf> {
+        let mut changed: Vec<PathBuf> = self.receiver.try_iter().collect();
+        changed.sort();
+        changed.dedup();
+        changed
+    }
+}
+
+impl Drop for Watcher {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+/// What `dir` holds, boiled down to a number that changes with it: the
+/// names, sizes and modification times of its entries. `None` if it can't
+/// be read.
+fn signature(dir: &Path) -> Option<u64> {
+    let mut hasher = DefaultHasher::new();
+    std::fs::metadata(dir)
+        .ok()?
+        .modified()
+        .ok()
+        .hash(&mut hasher);
+    let mut entries: Vec<_> = std::fs::read_dir(dir).ok()?.flatten().collect();
+    entries.len().hash(&mut hasher);
+    if entries.len() <= MAX_ENTRIES {
+        entries.sort_by_key(|entry| entry.file_name());
+        for entry in entries {
+            entry.file_name().hash(&mut hasher);
+            if let Ok(metadata) = entry.metadata() {
+                metadata.len().hash(&mut hasher);
+                metadata.modified().ok().hash(&mut hasher);
+            }
+        }
+    }
+    Some(hasher.finish())
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use browrs::annotations::Annotations;
+use common::Harness;
+
+fn harness(entries: &[&str]) -> Harness {
+    let mut h = Harness::new(entries);
+    let file = h.fixture.path("config/annotations");
+    h.app.set_annotations(Annotations::load(file));
+    h
+}
+
+#[test]
+fn notes_show_in_the_preview_and_persist() {
+    let mut h = harness(&["invoice.pdf=%PDF", "other.txt=hello"]);
+    h.select("invoice.pdf");
+    h.press("#");
+    assert!(h.screen().contains("Note for invoice.pdf"));
+    h.type_text("  paid in March\ttax ");
+    h.press("enter");
+    let screen = h.screen();
+    assert!(screen.contains("📝 paid in March tax"), "{}", screen);
+
+    h.select("other.txt");
+    assert!(!h.screen().contains("📝"));
+
+    let invoice = h.fixture.path("invoice.pdf");
+    assert_eq!(
+        h.fixture.read("config/annotations"),
+        format!("{}\tpaid in March tax\n", invoice.display())
+    );
+    let reloaded = Annotations::load(h.fixture.path("config/annotations"));
+    assert_eq!(reloaded.get(&invoice), Some("paid in March tax"));
+}
+
+#[test]
+fn an_empty_note_removes_it() {
+    let mut h = harness(&["a.txt=a"]);
+    h.select("a.txt");
+    h.press("#");
+    h.type_text("draft");
+    h.press("enter");
+    assert!(h.screen().contains("📝 draft"));
+
+    h.press("#");
+    assert!(h.screen().contains("draft"));
+    h.press("ctrl+u enter");
+    assert!(!h.screen().contains("📝"));
+    assert_eq!(h.fixture.read("config/annotations"), "");
+}
+
+#[test]
+fn search_finds_files_by_their_notes() {
+    let mut h = harness(&["docs/scan-0042.pdf=%PDF", "docs/scan-0043.pdf=%PDF"]);
+    h.select("docs/");
+    h.press("enter");
+    h.select("scan-0042.pdf");
+    h.press("#");
+    h.type_text("Lease agreement");
+    h.press("enter backspace /");
+    h.type_text("lease");
+    h.wait_until(|h| h.screen().contains(" 1 matches "));
+    let screen = h.screen();
+    assert!(
+        screen.contains("docs/scan-0042.pdf  📝 Lease agreement"),
+        "{}",
+        screen
+    );
+}
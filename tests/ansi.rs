@@ -0,0 +1,44 @@
+mod common;
+
+use common::Harness;
+use ratatui::style::{Color, Modifier};
+
+fn preview_of(contents: &str) -> Harness {
+    let mut h = Harness::new(&["build.log"]);
+    std::fs::write(h.fixture.path("build.log"), contents).unwrap();
+    h.select("build.log");
+    h
+}
+
+#[test]
+fn color_codes_become_styles() {
+    let mut h = preview_of(
+        "\x1b[1;31merror\x1b[0m: oops\n\x1b[38;5;208mwarning\x1b[m and \x1b[38;2;1;2;3mtrue color\n",
+    );
+    let screen = h.screen();
+    assert!(screen.contains("ANSI colors"), "{}", screen);
+    assert!(screen.contains("error: oops"), "{}", screen);
+    assert!(!screen.contains("[1;31m"), "{}", screen);
+    assert_eq!(h.color_of("error:"), Color::Red);
+    let style = h.style_of("error:");
+    assert!(style.add_modifier.contains(Modifier::BOLD));
+    assert_eq!(h.color_of(": oops"), Color::Reset);
+    assert_eq!(h.color_of("warning"), Color::Indexed(208));
+    assert_eq!(h.color_of("true color"), Color::Rgb(1, 2, 3));
+}
+
+#[test]
+fn colors_carry_over_to_the_next_line() {
+    let mut h = preview_of("\x1b[32mfirst\nsecond\x1b[0m\nthird\n");
+    assert_eq!(h.color_of("second"), Color::Green);
+    assert_eq!(h.color_of("third"), Color::Reset);
+}
+
+#[test]
+fn other_sequences_are_dropped() {
+    let h = &mut preview_of("\x1b]0;title\x07\x1b[2Kcleared\x1b[1Aline\n");
+    let screen = h.screen();
+    assert!(screen.contains("clearedline"), "{}", screen);
+    assert!(!screen.contains("title"), "{}", screen);
+    assert!(!screen.contains("binary"), "{}", screen);
+}
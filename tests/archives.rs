@@ -0,0 +1,167 @@
+mod common;
+
+use std::process::Command;
+
+use common::Harness;
+
+/// `data/` with `notes.txt` and `lib/deep/code.rs` packed by `pack` into
+/// `arc/`, and the cursor on the archive named `name`.
+fn archive(name: &str, pack: &[&str]) -> Harness {
+    let mut h = Harness::new(&[
+        "data/notes.txt=hello from the archive",
+        "data/lib/deep/code.rs=fn main() {}",
+        "arc/",
+        "out/",
+    ]);
+    let archive = h.fixture.path("arc").join(name);
+    let status = Command::new(pack[0])
+        .args(&pack[1..])
+        .arg(&archive)
+        .arg(".")
+        .current_dir(h.fixture.path("data"))
+        .status()
+        .unwrap();
+    assert!(status.success());
+    h.select("arc/");
+    h.press("enter");
+    h.select(name);
+    h
+}
+
+#[test]
+fn tarballs_are_browsed_like_directories() {
+    let mut h = archive("data.tar.gz", &["tar", "czf"]);
+    assert!(h.screen().contains("📦 data.tar.gz"));
+    h.press("enter");
+    assert_eq!(h.current_dir(), h.fixture.path("arc/data.tar.gz"));
+    assert_eq!(h.listing(), ["..", "lib/", "notes.txt"]);
+
+    h.select("notes.txt");
+    assert!(h.screen().contains("hello from the archive"));
+    // Opened from a temporary copy
+    h.press("enter");
+    assert!(!h.screen().contains("❌"));
+    let temporary = std::env::temp_dir().join(format!("browrs-{}", std::process::id()));
+    assert!(temporary.join("data.tar.gz/notes.txt").is_file());
+    let _ = std::fs::remove_dir_all(temporary);
+
+    h.select("lib/");
+    h.press("enter");
+    h.select("deep/");
+    h.press("enter");
+    assert_eq!(h.listing(), ["..", "code.rs"]);
+
+    h.press("backspace backspace backspace");
+    assert_eq!(h.current_dir(), h.fixture.path("arc"));
+    assert_eq!(h.selected(), "data.tar.gz");
+}
+
+#[test]
+fn entries_are_extracted_by_copying_them_out() {
+    let mut h = archive("data.zip", &["zip", "-qr"]);
+    h.press("enter");
+    assert_eq!(h.listing(), ["..", "lib/", "notes.txt"]);
+    h.select("lib/");
+    h.press("c ctrl+u");
+    h.type_text(&h.fixture.path("out").display().to_string());
+    h.press("enter");
+    h.finish_jobs();
+    assert_eq!(h.fixture.read("out/lib/deep/code.rs"), "fn main() {}");
+
+    h.select("notes.txt");
+    h.press("c ctrl+u");
+    h.type_text(&h.fixture.path("out/copy.txt").display().to_string());
+    h.press("enter");
+    h.finish_jobs();
+    assert_eq!(h.fixture.read("out/copy.txt"), "hello from the archive");
+}
+
+#[test]
+fn archive_files_copy_like_any_other_file() {
+    for (name, pack) in [
+        ("data.tar.gz", &["tar", "czf"][..]),
+        ("data.zip", &["zip", "-qr"][..]),
+    ] {
+        let mut h = archive(name, pack);
+        let packed = std::fs::read(h.fixture.path("arc").join(name)).unwrap();
+        h.press("c ctrl+u");
+        h.type_text(&h.fixture.path("out").display().to_string());
+        h.press("enter");
+        h.finish_jobs();
+        let copied = std::fs::read(h.fixture.path("out").join(name)).unwrap();
+        assert_eq!(copied, packed, "{}", name);
+    }
+}
+
+#[test]
+fn archive_files_can_be_linked_to() {
+    let mut h = archive("data.tar", &["tar", "cf"]);
+    let out = h.fixture.path("out").display().to_string();
+    h.press("alt+s ctrl+u");
+    h.type_text(&out);
+    h.press("enter");
+    let link = h.fixture.path("out/data.tar");
+    assert_eq!(
+        std::fs::read_link(&link).unwrap(),
+        h.fixture.path("arc/data.tar")
+    );
+
+    std::fs::remove_file(&link).unwrap();
+    h.press("alt+h ctrl+u");
+    h.type_text(&out);
+    h.press("enter");
+    assert!(!h.screen().contains("cannot be linked"));
+    assert!(link.is_file() && !link.is_symlink());
+}
+
+#[test]
+fn archives_are_read_only() {
+    let mut h = archive("data.tar", &["tar", "cf"]);
+    h.press("enter");
+    h.select("notes.txt");
+    h.press("d");
+    assert!(h.screen().contains("Archives are read-only"));
+    h.press("n");
+    assert!(!h.screen().contains("New file"));
+}
+
+#[test]
+fn member_names_are_never_read_as_options() {
+    for (name, pack) in [("odd.tar", ["tar", "cf"]), ("odd.zip", ["zip", "-qr"])] {
+        let mut h = archive(name, &pack);
+        // Added after packing, as tar would run a command for the first
+        // and unzip would extract into the directory the second names
+        for member in ["--to-command=true", "-d"] {
+            std::fs::write(h.fixture.path("data").join(member), member).unwrap();
+            let status = Command::new(pack[0])
+                .arg(if pack[0] == "tar" { "rf" } else { "-q" })
+                .arg(h.fixture.path("arc").join(name))
+                .arg(format!("./{}", member))
+                .current_dir(h.fixture.path("data"))
+                .status()
+                .unwrap();
+            assert!(status.success());
+        }
+        h.press("enter");
+        for member in ["--to-command=true", "-d"] {
+            h.select(member);
+            assert!(
+                h.screen().contains(&format!("{} |", member)),
+                "{}",
+                h.screen()
+            );
+            h.press("c ctrl+u");
+            h.type_text(&h.fixture.path("out").display().to_string());
+            h.press("enter");
+            h.finish_jobs();
+            let copied = h.fixture.path("out").join(member);
+            assert_eq!(
+                std::fs::read_to_string(&copied).unwrap(),
+                member,
+                "{}",
+                name
+            );
+            std::fs::remove_file(copied).unwrap();
+        }
+    }
+}
@@ -0,0 +1,76 @@
+mod common;
+
+use browrs::bookmarks::Bookmarks;
+use common::Harness;
+
+fn harness(entries: &[&str]) -> Harness {
+    let mut h = Harness::new(entries);
+    let file = h.fixture.path("config/bookmarks");
+    h.app.set_bookmarks(Bookmarks::load(file));
+    h
+}
+
+#[test]
+fn bookmarks_persist_to_the_file() {
+    let mut h = harness(&["projects/app/", "notes/"]);
+    h.select("projects/");
+    h.press("enter");
+    h.select("app/");
+    h.press("enter b");
+    assert!(h.screen().contains("🔖 Bookmarked"));
+    h.press("b");
+    assert!(h.screen().contains("already"));
+
+    let app = h.fixture.path("projects/app");
+    assert_eq!(
+        h.fixture.read("config/bookmarks"),
+        format!("{}\n", app.display())
+    );
+    let reloaded = Bookmarks::load(h.fixture.path("config/bookmarks"));
+    assert_eq!(reloaded.dirs, [app]);
+}
+
+#[test]
+fn jumping_to_a_bookmark_records_where_it_came_from() {
+    let mut h = harness(&["projects/app/", "notes/"]);
+    h.select("projects/");
+    h.press("enter");
+    h.select("app/");
+    h.press("enter b");
+    h.select("..");
+    h.press("enter");
+    h.select("..");
+    h.press("enter");
+    h.select("notes/");
+    h.press("enter");
+
+    h.press("'");
+    assert!(h.screen().contains("Bookmarks"));
+    h.press("enter");
+    assert_eq!(h.current_dir(), h.fixture.path("projects/app"));
+    let last = h.app.pane().history.last().unwrap();
+    assert_eq!(last.dir, h.fixture.path("notes"));
+}
+
+#[test]
+fn bookmarks_can_be_removed_from_the_picker() {
+    let mut h = harness(&["a/", "b/"]);
+    for dir in ["a/", "b/"] {
+        h.select(dir);
+        h.press("enter b");
+        h.select("..");
+        h.press("enter");
+    }
+    h.press("' d");
+    let screen = h.screen();
+    assert!(
+        !screen.contains(&h.fixture.path("a").display().to_string()),
+        "{}",
+        screen
+    );
+    h.press("esc");
+    assert_eq!(
+        h.fixture.read("config/bookmarks"),
+        format!("{}\n", h.fixture.path("b").display())
+    );
+}
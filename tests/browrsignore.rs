@@ -0,0 +1,53 @@
+mod common;
+
+use common::Harness;
+
+fn with_ignore_file() -> Harness {
+    Harness::new(&[
+        "data/.browrsignore=*.tmp\ncache/\n!keep.tmp\n",
+        "data/a.csv",
+        "data/b.tmp",
+        "data/keep.tmp",
+        "data/cache/raw.bin",
+        "data/sub/c.tmp",
+        "data/sub/d.csv",
+        "top.tmp",
+    ])
+}
+
+#[test]
+fn hides_matching_entries_below_its_directory() {
+    let mut h = with_ignore_file();
+    assert!(h.listing().contains(&"top.tmp".to_string()));
+
+    h.select("data/");
+    let screen = h.screen();
+    assert!(screen.contains("a.csv"), "{}", screen);
+    assert!(!screen.contains("b.tmp"), "{}", screen);
+    assert!(!screen.contains("cache/"), "{}", screen);
+
+    h.press("enter");
+    assert_eq!(h.listing(), ["..", "a.csv", "keep.tmp", "sub/"]);
+    h.select("sub/");
+    h.press("enter");
+    assert_eq!(h.listing(), ["..", "d.csv"]);
+}
+
+#[test]
+fn hidden_files_being_shown_shows_them_too() {
+    let mut h = with_ignore_file();
+    h.select("data/");
+    h.press("enter .");
+    assert_eq!(
+        h.listing(),
+        [
+            "..",
+            ".browrsignore",
+            "a.csv",
+            "b.tmp",
+            "cache/",
+            "keep.tmp",
+            "sub/"
+        ]
+    );
+}
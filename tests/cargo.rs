@@ -0,0 +1,41 @@
+mod common;
+
+use common::Harness;
+
+const MANIFEST: &str =
+    "Cargo.toml=[package]\nname = \"demo\"\nversion = \"1.2.3\"\nedition = \"2021\"\n";
+
+#[test]
+fn inside_a_crate_a_bar_shows_its_name_and_version() {
+    let mut h = Harness::new(&[MANIFEST, "src/lib.rs", "notes/"]);
+    assert!(h.screen().contains("🦀 demo 1.2.3"));
+    h.select("src/");
+    h.press("enter");
+    // Still the same crate in its subdirectories
+    assert!(h.screen().contains("🦀 demo 1.2.3"));
+}
+
+#[test]
+fn cargo_commands_run_as_jobs_and_show_their_output() {
+    let mut h = Harness::new(&[MANIFEST, "src/lib.rs=pub fn answer() -> u32 { 42 }\n"]);
+    h.press("K b");
+    h.finish_jobs();
+    let screen = h.screen();
+    assert!(screen.contains("cargo build ✔"), "{}", screen);
+    assert!(screen.contains("Compiling demo v1.2.3"), "{}", screen);
+    assert!(h.fixture.exists("target"));
+
+    h.press("esc K c");
+    h.finish_jobs();
+    assert!(h.screen().contains("cargo clean ✔"));
+    assert!(!h.fixture.exists("target"));
+}
+
+#[test]
+fn outside_a_crate_there_is_nothing_to_run() {
+    let mut h = Harness::new(&["notes.txt"]);
+    assert!(!h.screen().contains("🦀"));
+    h.press("K t");
+    assert!(!h.app.is_busy());
+    assert!(h.screen().contains("Not in a Cargo project"));
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use browrs::{
+    cleanup::{self, Places},
+    walk::Policy,
+};
+use common::{Fixture, Harness};
+
+#[test]
+fn caches_are_listed_biggest_first_and_deleted_after_asking() {
+    let mut h = Harness::new(&[
+        "app/Cargo.toml",
+        "app/src/main.rs",
+        "app/target/debug/app=compiled program",
+        "web/package.json",
+        "web/node_modules/left-pad.js=pad",
+        "notes/target/",
+    ]);
+    h.press("C");
+    h.finish_jobs();
+    let screen = h.screen();
+    let target = screen
+        .find("app/target  (Cargo build output)")
+        .expect(&screen);
+    let modules = screen
+        .find("web/node_modules  (npm packages)")
+        .expect(&screen);
+    assert!(target < modules, "{}", screen);
+    // Only next to a Cargo.toml is a target directory build output
+    assert!(!screen.contains("notes/target"), "{}", screen);
+
+    h.press("down D");
+    assert!(
+        h.screen()
+            .contains("Permanently delete node_modules (3 B)?")
+    );
+    h.press("y");
+    h.finish_jobs();
+    assert!(!h.fixture.exists("web/node_modules"));
+    assert!(h.fixture.exists("web/package.json"));
+    let screen = h.screen();
+    assert!(
+        screen.contains("app/target  (Cargo build output)"),
+        "{}",
+        screen
+    );
+    assert!(!screen.contains("node_modules  ("), "{}", screen);
+}
+
+#[test]
+fn nothing_to_clean_says_so() {
+    let mut h = Harness::new(&["src/main.rs"]);
+    h.press("C");
+    h.finish_jobs();
+    assert!(h.screen().contains("No caches or build output"));
+}
+
+#[test]
+fn everything_in_the_cache_directory_is_suggested() {
+    let fixture = Fixture::new(&[
+        "home/.cache/thumbnails/a.png",
+        "home/.cache/pip/wheel",
+        "home/projects/cache/CACHEDIR.TAG",
+        "home/projects/readme.md",
+    ]);
+    let places = Places {
+        cache_dir: Some(fixture.path("home/.cache")),
+        temp_dir: None,
+    };
+    let found = cleanup::scan(&fixture.path("home"), &places, &Policy::default(), &|| {
+        false
+    });
+    let mut paths: Vec<_> = found
+        .iter()
+        .map(|c| (c.path.clone(), c.what.as_str()))
+        .collect();
+    paths.sort();
+    assert_eq!(
+        paths,
+        [
+            (fixture.path("home/.cache/pip"), "cache"),
+            (fixture.path("home/.cache/thumbnails"), "cache"),
+            (fixture.path("home/projects/cache"), "cache"),
+        ]
+    );
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use std::path::PathBuf;
+
+use browrs::{cli::Args, log::Level};
+use common::{Fixture, Harness};
+
+fn parse(args: &[&str]) -> Result<Args, String> {
+    Args::parse(args.iter().map(|a| a.to_string()))
+}
+
+#[test]
+fn options_and_path_are_read() {
+    let args = parse(&[
+        "-a",
+        "--no-preview",
+        "--select=b.txt",
+        "--choose-file",
+        "-vv",
+        "--log-file",
+        "/tmp/x.log",
+        "/some/dir",
+    ])
+    .unwrap();
+    assert!(args.show_hidden && args.no_preview && args.choose_file);
+    assert_eq!(args.select, Some(PathBuf::from("b.txt")));
+    assert_eq!(args.level, Level::Debug);
+    assert_eq!(args.log_file, Some(PathBuf::from("/tmp/x.log")));
+    assert_eq!(args.path, Some(PathBuf::from("/some/dir")));
+
+    assert_eq!(parse(&[]).unwrap().level, Level::Warn);
+    assert_eq!(parse(&["--verbose=trace"]).unwrap().level, Level::Trace);
+    assert_eq!(
+        parse(&["--", "-odd"]).unwrap().path,
+        Some(PathBuf::from("-odd"))
+    );
+}
+
+#[test]
+fn bad_arguments_are_errors() {
+    assert_eq!(parse(&["--nope"]).unwrap_err(), "unknown option: --nope");
+    assert_eq!(parse(&["--select"]).unwrap_err(), "--select needs a value");
+    assert!(parse(&["--verbose=loud"]).is_err());
+    assert!(parse(&["a", "b"]).is_err());
+}
+
+#[test]
+fn a_file_path_starts_in_its_directory_with_it_selected() {
+    let fixture = Fixture::new(&["docs/notes.txt=x"]);
+    let docs = fixture.path("docs").canonicalize().unwrap();
+    let file = fixture.path("docs/notes.txt");
+    let path = |p: &PathBuf| p.to_string_lossy().to_string();
+
+    let args = parse(&[&path(&file)]).unwrap();
+    assert_eq!(args.start(), Some((docs.clone(), Some("notes.txt".into()))));
+
+    let args = parse(&["--select", "notes.txt", &path(&fixture.path("docs"))]).unwrap();
+    assert_eq!(args.start(), Some((docs.clone(), Some("notes.txt".into()))));
+
+    let args = parse(&[&path(&fixture.path("docs"))]).unwrap();
+    assert_eq!(args.start(), Some((docs, None)));
+    assert_eq!(parse(&[]).unwrap().start(), None);
+}
+
+#[test]
+fn choosing_a_file_exits_with_its_path() {
+    let mut h = Harness::new(&["docs/", "a.txt=a", "b.txt=b"]);
+    h.app.choose_file();
+    h.select("docs/");
+    h.press("enter");
+    assert!(!h.app.is_exiting());
+    h.press("backspace");
+    h.select("b.txt");
+    h.press("enter");
+    assert!(h.app.is_exiting());
+    assert_eq!(h.app.chosen(), Some(h.fixture.path("b.txt").as_path()));
+}
+
+#[test]
+fn without_the_preview_the_listing_takes_the_width() {
+    let mut h = Harness::new(&["a.txt=first line"]);
+    h.select("a.txt");
+    assert!(h.screen().contains("first line"));
+    h.app.hide_preview();
+    assert!(!h.screen().contains("first line"));
+    h.press("p");
+    assert!(h.screen().contains("first line"));
+}
+
+#[test]
+fn picker_options_turn_on_choosing() {
+    assert!(parse(&["--picker"]).unwrap().choose_file);
+    let args = parse(&["--chooser-file=/tmp/chosen"]).unwrap();
+    assert!(args.choose_file);
+    assert_eq!(args.chooser_file, Some(PathBuf::from("/tmp/chosen")));
+}
@@ -0,0 +1,264 @@
+//! Shared test harness: temporary directory trees built from a short
+//! description, and an [`App`] driven by scripted keys and rendered into an
+//! in-memory terminal.
+
+#![allow(dead_code)]
+
+use std::{
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    time::{Duration, Instant},
+};
+
+use browrs::{App, Config, keys::Chord};
+use crossterm::event::{
+    Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+};
+use ratatui::{
+    Terminal,
+    backend::TestBackend,
+    style::{Color, Style},
+};
+
+/// A temporary directory tree, removed again on drop.
+pub struct Fixture {
+    pub root: PathBuf,
+}
+
+impl Fixture {
+    /// Builds a tree from entries like `"docs/"` (a directory),
+    /// `"docs/a.txt"` (an empty file) or `"notes.md=hello"` (a file with
+    /// contents). Parent directories are created as needed.
+    pub fn new(entries: &[&str]) -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let root = std::env::temp_dir().join(format!(
+            "browrs-test-{}-{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        let fixture = Self { root };
+        for entry in entries {
+            fixture.add(entry);
+        }
+        fixture
+    }
+
+    /// Adds one entry, described as for [`Fixture::new`].
+    pub fn add(&self, entry: &str) {
+        let (path, contents) = entry.split_once('=').unwrap_or((entry, ""));
+        let full = self.root.join(path);
+        if path.ends_with('/') {
+            std::fs::create_dir_all(&full).unwrap();
+        } else {
+            std::fs::create_dir_all(full.parent().unwrap()).unwrap();
+            std::fs::write(&full, contents).unwrap();
+        }
+    }
+
+    pub fn path(&self, relative: &str) -> PathBuf {
+        self.root.join(relative)
+    }
+
+    pub fn exists(&self, relative: &str) -> bool {
+        self.path(relative).symlink_metadata().is_ok()
+    }
+
+    pub fn read(&self, relative: &str) -> String {
+        std::fs::read_to_string(self.path(relative)).unwrap()
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
+
+/// An app opened on a fixture with default settings.
+pub struct Harness {
+    pub fixture: Fixture,
+    pub app: App,
+    terminal: Terminal<TestBackend>,
+}
+
+impl Harness {
+    pub fn new(entries: &[&str]) -> Self {
+        Self::with_config(entries, test_config())
+    }
+
+    pub fn with_config(entries: &[&str], config: Config) -> Self {
+        let fixture = Fixture::new(entries);
+        let app = App::with_config(config, fixture.root.clone()).unwrap();
+        Self {
+            fixture,
+            app,
+            terminal: Terminal::new(TestBackend::new(120, 24)).unwrap(),
+        }
+    }
+
+    /// Presses each whitespace-separated chord in turn, written as in the
+    /// `[keys]` config table: `"down down enter"`, `"ctrl+w"`, `"D y"`.
+    pub fn press(&mut self, keys: &str) {
+        for spec in keys.split_whitespace() {
+            let chord = Chord::parse(spec).unwrap_or_else(|| panic!("bad key {:?}", spec));
+            self.app.handle(Event::Key(chord.to_event()));
+        }
+    }
+
+    /// Types `text` one character at a time, as into a prompt.
+    pub fn type_text(&mut self, text: &str) {
+        for c in text.chars() {
+            let event = KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+            self.app.handle(Event::Key(event));
+        }
+    }
+
+    pub fn paste(&mut self, text: &str) {
+        self.app.handle(Event::Paste(text.to_string()));
+    }
+
+    /// Names in the focused pane, as listed.
+    pub fn listing(&self) -> Vec<String> {
+        let pane = self.app.pane();
+        pane.entries.iter().map(|e| e.display_name()).collect()
+    }
+
+    pub fn selected(&self) -> String {
+        let pane = self.app.pane();
+        pane.entries[pane.selected].display_name()
+    }
+
+    /// Moves the cursor to the entry listed as `name`.
+    pub fn select(&mut self, name: &str) {
+        let index = self
+            .listing()
+            .iter()
+            .position(|n| n == name)
+            .unwrap_or_else(|| panic!("{:?} not in {:?}", name, self.listing()));
+        while self.app.pane().selected > index {
+            self.press("up");
+        }
+        while self.app.pane().selected < index {
+            self.press("down");
+        }
+        assert_eq!(self.selected(), name);
+    }
+
+    pub fn current_dir(&self) -> &Path {
+        &self.app.pane().current_dir
+    }
+
+    /// Renders a frame and returns it as text, one line per row.
+    pub fn screen(&mut self) -> String {
+        let app = &self.app;
+        self.terminal.draw(|frame| app.draw(frame)).unwrap();
+        // The backend prints each row quoted, with wide characters taking
+        // their own width and a note on the cells they cover
+        self.terminal
+            .backend()
+            .to_string()
+            .lines()
+            .map(|line| {
+                let row = line.strip_prefix('"').unwrap_or(line);
+                let row = match row.split_once("\" Hidden by multi-width symbols") {
+                    Some((row, _)) => row,
+                    None => row.strip_suffix('"').unwrap_or(row),
+                };
+                row.trim_end()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Foreground color `text` is drawn in, where it first appears.
+    pub fn color_of(&mut self, text: &str) -> Color {
+        let (x, y) = self.position_of(text);
+        self.terminal.backend().buffer()[(x, y)].fg
+    }
+
+    /// Colors and modifiers `text` is drawn with, where it first appears.
+    pub fn style_of(&mut self, text: &str) -> Style {
+        let (x, y) = self.position_of(text);
+        self.terminal.backend().buffer()[(x, y)].style()
+    }
+
+    /// Column and row where `text` first appears on screen.
+    pub fn position_of(&mut self, text: &str) -> (u16, u16) {
+        self.screen();
+        let buffer = self.terminal.backend().buffer();
+        let area = buffer.area;
+        for y in 0..area.height {
+            let cells: Vec<_> = (0..area.width).map(|x| &buffer[(x, y)]).collect();
+            let row: String = cells.iter().map(|cell| cell.symbol()).collect();
+            if let Some(index) = row.find(text) {
+                // Cells before it may hold more than one byte
+                let mut bytes = 0;
+                for (x, cell) in cells.iter().enumerate() {
+                    if bytes == index {
+                        return (x as u16, y);
+                    }
+                    bytes += cell.symbol().len();
+                }
+            }
+        }
+        panic!("{:?} not on screen\n{}", text, self.screen());
+    }
+
+    /// Clicks the left button on `text` as it is on screen.
+    pub fn click(&mut self, text: &str) {
+        let (x, y) = self.position_of(text);
+        self.mouse(MouseEventKind::Down(MouseButton::Left), x, y);
+        self.mouse(MouseEventKind::Up(MouseButton::Left), x, y);
+    }
+
+    /// Turns the mouse wheel a notch with the pointer over `text`.
+    pub fn scroll(&mut self, text: &str, down: bool) {
+        let (x, y) = self.position_of(text);
+        let kind = if down {
+            MouseEventKind::ScrollDown
+        } else {
+            MouseEventKind::ScrollUp
+        };
+        self.mouse(kind, x, y);
+    }
+
+    fn mouse(&mut self, kind: MouseEventKind, column: u16, row: u16) {
+        self.app.handle(Event::Mouse(MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::NONE,
+        }));
+    }
+
+    /// Lets background work run until `done` holds, failing after a few
+    /// seconds.
+    pub fn wait_until(&mut self, mut done: impl FnMut(&mut Self) -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while !done(self) {
+            assert!(Instant::now() < deadline, "timed out\n{}", self.screen());
+            std::thread::sleep(Duration::from_millis(10));
+            self.app.tick();
+        }
+    }
+
+    /// Waits for every background job, like a copy, to end.
+    pub fn finish_jobs(&mut self) {
+        self.wait_until(|h| !h.app.is_busy());
+    }
+}
+
+/// Defaults that don't depend on the terminal or the clock, with an
+/// "editor" that returns at once. Nothing is re-read behind a test's back.
+pub fn test_config() -> Config {
+    Config {
+        watch_interval_ms: 0,
+        editor: Some("true".to_string()),
+        image_protocol: Some("halfblocks".to_string()),
+        recent_minutes: 0,
+        suggest_places: false,
+        ..Config::default()
+    }
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use common::Harness;
+
+#[test]
+fn marked_entries_are_packed_in_the_background() {
+    let mut h = Harness::new(&["a.txt=a", "docs/b.md=b", "skip.txt"]);
+    h.select("a.txt");
+    h.press("space");
+    h.select("docs/");
+    h.press("space z");
+    assert!(h.screen().contains("Compress 2 entries into"));
+    h.press("ctrl+u");
+    h.type_text("both.zip");
+    h.press("enter");
+    h.wait_until(|h| h.screen().contains("📦 Created"));
+
+    // And unpacked again somewhere else
+    h.select("both.zip");
+    h.press("x");
+    assert!(h.screen().contains("Extract both.zip into"));
+    h.press("ctrl+u");
+    h.type_text("out");
+    h.press("enter");
+    h.wait_until(|h| h.screen().contains("📦 Extracted"));
+    assert_eq!(h.fixture.read("out/a.txt"), "a");
+    assert_eq!(h.fixture.read("out/docs/b.md"), "b");
+    assert!(!h.fixture.exists("out/skip.txt"));
+}
+
+#[test]
+fn the_name_picks_the_format() {
+    let mut h = Harness::new(&["data/x.txt=x"]);
+    h.select("data/");
+    h.press("z");
+    assert!(h.screen().contains("data.tar.gz"));
+    h.press("enter");
+    h.wait_until(|h| h.screen().contains("📦 Created"));
+    h.select("data.tar.gz");
+    h.press("enter");
+    assert_eq!(h.listing(), ["..", "data/"]);
+
+    h.press("backspace");
+    h.select("data/");
+    h.press("z ctrl+u");
+    h.type_text("data.rar");
+    h.press("enter");
+    h.wait_until(|h| h.screen().contains("name it .zip"));
+}
+
+#[test]
+fn extracting_never_overwrites() {
+    let mut h = Harness::new(&["a.txt=packed"]);
+    h.select("a.txt");
+    h.press("z ctrl+u");
+    h.type_text("both.tar.gz");
+    h.press("enter");
+    h.wait_until(|h| h.screen().contains("📦 Created"));
+    h.fixture.add("a.txt=edited since");
+
+    // Into a directory of its own by default
+    h.select("both.tar.gz");
+    h.press("x enter");
+    h.wait_until(|h| h.screen().contains("📦 Extracted"));
+    assert_eq!(h.fixture.read("both/a.txt"), "packed");
+    assert_eq!(h.fixture.read("a.txt"), "edited since");
+
+    // Over what is already there it stops before starting
+    h.select("both.tar.gz");
+    h.press("x ctrl+u");
+    h.type_text(".");
+    h.press("enter");
+    h.wait_until(|h| h.screen().contains("already exists"));
+    assert_eq!(h.fixture.read("a.txt"), "edited since");
+}
+
+#[test]
+fn only_archives_are_extracted() {
+    let mut h = Harness::new(&["notes.txt", "docs/"]);
+    h.select("notes.txt");
+    h.press("x");
+    // Other files are shown in hex instead
+    assert!(!h.screen().contains("Extract notes.txt into"));
+    h.press("x");
+    h.select("docs/");
+    h.press("x");
+    assert!(h.screen().contains("Not a zip or tar archive"));
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use std::time::{Duration, SystemTime};
+
+use browrs::Config;
+use common::Harness;
+
+#[test]
+fn bad_lines_are_reported_with_their_number() {
+    let (config, errors) = Config::parse_checked(
+        "show_hidden = yes\nsort = size\nfrobnicate = 1\n[keys]\nj = jump\n[colours]\n",
+    );
+    assert!(matches!(config.sort, browrs::sort::Sort::Size));
+    let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+    assert_eq!(
+        errors,
+        [
+            "Config line 1: expected true or false, not yes",
+            "Config line 3: unknown option frobnicate",
+            "Config line 5: No action or bound key named: jump",
+            "Config line 6: unknown section [colours]",
+        ]
+    );
+}
+
+/// A harness watching a config file in its fixture, written long enough
+/// ago that the next write counts as a change.
+fn watching(entries: &[&str], config: &str) -> Harness {
+    let mut h = Harness::new(entries);
+    h.fixture.add(&format!("config.toml={}", config));
+    let file = h.fixture.path("config.toml");
+    std::fs::File::options()
+        .write(true)
+        .open(&file)
+        .unwrap()
+        .set_modified(SystemTime::now() - Duration::from_secs(60))
+        .unwrap();
+    h.app.watch_config(file);
+    h
+}
+
+#[test]
+fn changes_to_the_config_file_apply_live() {
+    let mut h = watching(&[".hidden", "shown"], "");
+    assert_eq!(h.listing(), ["..", "shown"]);
+    std::fs::write(
+        h.fixture.path("config.toml"),
+        "show_hidden = true\n[keys]\nj = \"down\"\n",
+    )
+    .unwrap();
+    h.wait_until(|h| h.listing().len() == 4);
+    assert!(h.screen().contains("⚙️ Reloaded"));
+    h.press("j");
+    assert_eq!(h.selected(), ".hidden");
+}
+
+#[test]
+fn invalid_changes_are_shown_and_the_rest_applied() {
+    let mut h = watching(&["b", "a"], "");
+    std::fs::write(
+        h.fixture.path("config.toml"),
+        "sort = sideways\ndirs_first = true\n",
+    )
+    .unwrap();
+    h.wait_until(|h| h.screen().contains("❌ Config line 1"));
+    assert!(h.screen().contains("unknown sort sideways"));
+    assert!(h.screen().contains("dirs first"));
+}
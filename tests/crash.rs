@@ -0,0 +1,38 @@
+use std::backtrace::Backtrace;
+
+use browrs::crash;
+
+#[test]
+fn report_names_the_panic_version_and_os() {
+    let report = crash::report("boom at src/app.rs:1:1", &Backtrace::force_capture());
+    assert!(report.starts_with(&format!("browrs {} crashed", env!("CARGO_PKG_VERSION"))));
+    assert!(report.contains("Panic: boom at src/app.rs:1:1"));
+    assert!(report.contains("\nOS: "));
+    assert!(report.contains("Recent log:"));
+    assert!(report.contains("Backtrace:"));
+}
+
+#[test]
+fn a_panic_writes_a_report() {
+    let dir = std::env::temp_dir().join(format!("browrs-test-crash-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    crash::install(Some(dir.clone()));
+    let _ = std::thread::spawn(|| panic!("deliberate")).join();
+    let _ = std::panic::take_hook();
+
+    let reports: Vec<_> = std::fs::read_dir(&dir).unwrap().flatten().collect();
+    assert_eq!(reports.len(), 1);
+    let name = reports[0].file_name().to_string_lossy().into_owned();
+    assert!(
+        name.starts_with("crash-") && name.ends_with(".txt"),
+        "{}",
+        name
+    );
+    let report = std::fs::read_to_string(reports[0].path()).unwrap();
+    assert!(
+        report.contains("Panic: deliberate at tests/crash.rs"),
+        "{}",
+        report
+    );
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use common::Harness;
+
+#[test]
+fn n_creates_a_file_and_selects_it() {
+    let mut h = Harness::new(&["a.txt", "c.txt"]);
+    h.press("n");
+    assert!(h.screen().contains("New file"));
+    h.type_text("b.txt");
+    h.press("enter");
+    assert_eq!(h.fixture.read("b.txt"), "");
+    assert_eq!(h.selected(), "b.txt");
+    assert_eq!(h.listing(), ["..", "a.txt", "b.txt", "c.txt"]);
+}
+
+#[test]
+fn nested_paths_create_the_directories_on_the_way() {
+    let mut h = Harness::new(&["z.txt"]);
+    h.press("N");
+    h.type_text("a/b/c");
+    h.press("enter");
+    assert!(h.fixture.path("a/b/c").is_dir());
+    assert_eq!(h.selected(), "a/");
+
+    // A trailing slash makes n create a directory too
+    h.press("n");
+    h.type_text("x/y/");
+    h.press("enter");
+    assert!(h.fixture.path("x/y").is_dir());
+    h.press("n");
+    h.type_text("x/y/notes.md");
+    h.press("enter");
+    assert!(h.fixture.path("x/y/notes.md").is_file());
+}
+
+#[test]
+fn existing_entries_are_left_alone() {
+    let mut h = Harness::new(&["a.txt=keep"]);
+    h.press("n");
+    h.type_text("a.txt");
+    h.press("enter");
+    assert_eq!(h.fixture.read("a.txt"), "keep");
+    assert!(h.screen().contains("already exists"));
+}
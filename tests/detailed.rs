@@ -0,0 +1,55 @@
+mod common;
+
+use std::os::unix::fs::PermissionsExt;
+
+use browrs::{config::Config, entry::Meta};
+use common::{Harness, test_config};
+
+#[test]
+fn the_table_lists_size_time_permissions_and_owner() {
+    let mut h = Harness::new(&["notes.txt=hello", "src/"]);
+    std::fs::set_permissions(
+        h.fixture.path("notes.txt"),
+        std::fs::Permissions::from_mode(0o640),
+    )
+    .unwrap();
+    // A new tab lists the directory again
+    h.press("t i");
+    let screen = h.screen();
+    assert!(screen.contains("Name"), "{}", screen);
+    assert!(screen.contains("Permissions"), "{}", screen);
+    let row = screen
+        .lines()
+        .find(|line| line.contains("notes.txt"))
+        .unwrap_or_else(|| panic!("{}", screen));
+    assert!(row.contains("5 B"), "{}", row);
+    assert!(row.contains("-rw-r-----"), "{}", row);
+    let dir = screen.lines().find(|line| line.contains("src/")).unwrap();
+    assert!(dir.contains("drwx"), "{}", dir);
+
+    h.press("i");
+    assert!(!h.screen().contains("Permissions"));
+}
+
+#[test]
+fn clicks_land_on_the_rows_below_the_column_names() {
+    let config = Config {
+        detailed: true,
+        ..test_config()
+    };
+    let mut h = Harness::with_config(&["a.txt", "b.txt", "c.txt"], config);
+    h.click("b.txt");
+    assert_eq!(h.selected(), "b.txt");
+}
+
+#[test]
+fn special_bits_are_shown_like_ls() {
+    let meta = |mode| Meta {
+        mode,
+        ..Default::default()
+    };
+    assert_eq!(meta(0o104755).permissions(), "-rwsr-xr-x");
+    assert_eq!(meta(0o041777).permissions(), "drwxrwxrwt");
+    assert_eq!(meta(0o102644).permissions(), "-rw-r-Sr--");
+    assert_eq!(meta(0o120777).permissions(), "lrwxrwxrwx");
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use common::Harness;
+
+/// Left pane in `new`, right pane in `old`, left focused.
+fn panes() -> Harness {
+    let mut h = Harness::new(&[
+        "new/notes.txt=same\nchanged\n",
+        "new/only.txt=x",
+        "new/copy.txt=x",
+        "old/notes.txt=same\noriginal\n",
+        "old/copy.txt=x",
+    ]);
+    h.press("|");
+    h.select("new/");
+    h.press("enter tab");
+    h.select("old/");
+    h.press("enter tab");
+    h
+}
+
+#[test]
+fn diffs_against_the_same_name_in_the_other_pane() {
+    let mut h = panes();
+    h.select("notes.txt");
+    h.press("ctrl+d");
+    let screen = h.screen();
+    assert!(screen.contains("-original"));
+    assert!(screen.contains("+changed"));
+    assert!(screen.contains(" same"));
+}
+
+#[test]
+fn says_when_there_is_nothing_to_compare() {
+    let mut h = panes();
+    h.select("copy.txt");
+    h.press("ctrl+d");
+    assert!(h.screen().contains("Identical"));
+    h.select("only.txt");
+    h.press("ctrl+d");
+    assert!(h.screen().contains("No only.txt in"));
+}
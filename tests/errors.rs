@@ -0,0 +1,38 @@
+mod common;
+
+use common::Harness;
+
+#[test]
+fn opening_a_vanished_directory_reports_it() {
+    let mut h = Harness::new(&["gone/"]);
+    h.select("gone/");
+    h.press("enter");
+    std::fs::remove_dir(h.fixture.path("gone")).unwrap();
+    h.press("t");
+    let screen = h.screen();
+    assert!(screen.contains("❌ Unable to open"), "{}", screen);
+    assert!(screen.contains("No such"), "{}", screen);
+}
+
+#[test]
+fn failed_operations_name_the_operation_and_path() {
+    let mut h = Harness::new(&["a.txt=a"]);
+    h.select("a.txt");
+    h.press("c ctrl+u");
+    let dest = h.fixture.path("missing/dir/b.txt");
+    h.type_text(&dest.display().to_string());
+    h.press("enter");
+    h.finish_jobs();
+    let screen = h.screen();
+    assert!(screen.contains("❌ 1 errors:"), "{}", screen);
+    assert!(screen.contains("Unable to copy"), "{}", screen);
+}
+
+#[test]
+fn bad_prompt_input_is_reported() {
+    let mut h = Harness::new(&[]);
+    h.press(":");
+    h.type_text("frobnicate");
+    h.press("enter");
+    assert!(h.screen().contains("❌ Unknown command: frobnicate"));
+}
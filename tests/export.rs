@@ -0,0 +1,72 @@
+mod common;
+
+use common::Harness;
+
+fn run(h: &mut Harness, command: &str) {
+    h.press(":");
+    h.type_text(command);
+    h.press("enter");
+}
+
+#[test]
+fn exports_the_listing_as_csv_and_markdown() {
+    let mut h = Harness::new(&["a.txt", "b, c.txt", "sub/"]);
+    run(&mut h, "export listing out.csv");
+    let csv = h.fixture.read("out.csv");
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "name,type,size,modified,permissions");
+    assert!(lines[1].starts_with("a.txt,file,0,"), "{}", csv);
+    assert!(lines[2].starts_with("\"b, c.txt\",file,"), "{}", csv);
+    assert!(lines[3].starts_with("sub,directory,"), "{}", csv);
+    assert!(h.listing().contains(&"out.csv".to_string()));
+
+    // Without a path it asks for one
+    run(&mut h, "export ls");
+    h.press("ctrl+u");
+    h.type_text("out.md");
+    h.press("enter");
+    let markdown = h.fixture.read("out.md");
+    assert!(
+        markdown.starts_with("| name | type | size |"),
+        "{}",
+        markdown
+    );
+    assert!(markdown.contains("| --- |"), "{}", markdown);
+}
+
+#[test]
+fn exports_duplicates_as_json() {
+    let mut h = Harness::new(&["one/a.txt", "two/b.txt", "c.txt"]);
+    std::fs::write(h.fixture.path("one/a.txt"), "same").unwrap();
+    std::fs::write(h.fixture.path("two/b.txt"), "same").unwrap();
+    std::fs::write(h.fixture.path("c.txt"), "diff").unwrap();
+    run(&mut h, "export duplicates dupes.json");
+    h.finish_jobs();
+    let json = h.fixture.read("dupes.json");
+    assert_eq!(
+        json,
+        "[\n  {\"group\": 1, \"size\": 4, \"path\": \"one/a.txt\"},\n  \
+         {\"group\": 1, \"size\": 4, \"path\": \"two/b.txt\"}\n]\n"
+    );
+}
+
+#[test]
+fn exports_disk_usage_biggest_first() {
+    let mut h = Harness::new(&["small.txt", "big/inner.txt"]);
+    std::fs::write(h.fixture.path("small.txt"), "1").unwrap();
+    std::fs::write(h.fixture.path("big/inner.txt"), "12345").unwrap();
+    run(&mut h, "export du usage.csv");
+    h.finish_jobs();
+    assert_eq!(
+        h.fixture.read("usage.csv"),
+        "name,type,size\nbig,directory,5\nsmall.txt,file,1\n"
+    );
+}
+
+#[test]
+fn refuses_unknown_formats() {
+    let mut h = Harness::new(&["a.txt"]);
+    run(&mut h, "export listing out.xls");
+    assert!(!h.fixture.exists("out.xls"));
+    assert!(h.screen().contains(".csv, .json or .md"), "{}", h.screen());
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use browrs::Config;
+use common::{Harness, test_config};
+
+fn with_actions(entries: &[&str], table: &str) -> Harness {
+    let config = Config {
+        actions: Config::parse(&format!("[actions]\n{}", table)).actions,
+        ..test_config()
+    };
+    Harness::with_config(entries, config)
+}
+
+#[test]
+fn archives_offer_extracting() {
+    let mut h = Harness::new(&["data.tar"]);
+    h.select("data.tar");
+    h.press("a");
+    let screen = h.screen();
+    assert!(screen.contains("Actions for data.tar"), "{}", screen);
+    assert!(screen.contains("Extract…"), "{}", screen);
+    assert!(
+        screen.contains("Open with default application"),
+        "{}",
+        screen
+    );
+
+    h.press("down enter");
+    assert!(h.screen().contains("Extract data.tar into"));
+}
+
+#[test]
+fn configured_actions_apply_to_matching_files() {
+    let mut h = with_actions(
+        &["quiet.txt=hush", "other.md"],
+        "\"*.txt, *.log: Shout\" = \"tr a-z A-Z < {file} > {dir}/loud.txt\"",
+    );
+    h.select("other.md");
+    h.press("a");
+    assert!(!h.screen().contains("Shout"));
+    h.press("esc");
+
+    h.select("quiet.txt");
+    h.press("a");
+    assert!(h.screen().contains("Shout"));
+    h.press("down enter");
+    assert_eq!(h.fixture.read("loud.txt"), "HUSH");
+    assert!(h.listing().contains(&"loud.txt".to_string()));
+}
+
+#[cfg(unix)]
+#[test]
+fn executables_can_be_run() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut h = Harness::new(&["run.sh=#!/bin/sh\ntouch \"$(dirname \"$0\")/ran\"\n"]);
+    let script = h.fixture.path("run.sh");
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    h.select("run.sh");
+    h.press("a");
+    assert!(h.screen().contains("Run"));
+    h.press("down enter");
+    assert!(h.fixture.exists("ran"));
+}
+
+#[test]
+fn action_keys_need_a_label() {
+    let (_, errors) = Config::parse_checked("[actions]\n\"*.gpg\" = \"gpg -d {file}\"\n");
+    assert_eq!(errors.len(), 1);
+    assert!(errors[0].to_string().contains("patterns: label"));
+}
@@ -0,0 +1,70 @@
+mod common;
+
+use std::os::unix::fs::{PermissionsExt, symlink};
+
+use browrs::{config::Config, file_colors::LsColors};
+use common::{Harness, test_config};
+use ratatui::style::{Color, Modifier};
+
+const ENTRIES: [&str; 6] = [
+    "box/",
+    "run.sh=echo hi",
+    "pack.tar.gz",
+    "photo.png",
+    "song.flac",
+    "notes.txt",
+];
+
+/// A harness listing one of each type, in a directory of its own so that
+/// its files can be set up before it is listed.
+fn listing(ls_colors: Option<&str>) -> Harness {
+    let entries: Vec<String> = ENTRIES.iter().map(|e| format!("types/{}", e)).collect();
+    let entries: Vec<&str> = entries.iter().map(String::as_str).collect();
+    let config = Config {
+        ls_colors: ls_colors.map(LsColors::parse),
+        ..test_config()
+    };
+    let mut h = Harness::with_config(&entries, config);
+    let script = h.fixture.path("types/run.sh");
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    symlink("notes.txt", h.fixture.path("types/to-notes")).unwrap();
+    h.select("types/");
+    h.press("enter");
+    h
+}
+
+#[test]
+fn entries_are_colored_by_type() {
+    let mut h = listing(None);
+    assert_eq!(h.color_of("box/"), Color::Blue);
+    assert!(h.style_of("box/").add_modifier.contains(Modifier::BOLD));
+    assert_eq!(h.color_of("run.sh"), Color::Green);
+    assert_eq!(h.color_of("pack.tar.gz"), Color::Red);
+    assert_eq!(h.color_of("photo.png"), Color::Magenta);
+    assert_eq!(h.color_of("song.flac"), Color::Cyan);
+    assert_eq!(h.color_of("notes.txt"), Color::Reset);
+}
+
+#[test]
+fn ls_colors_take_over_where_they_say_how() {
+    let mut h = listing(Some(
+        "di=01;31:ex=35:ln=93:*.txt=04;33:*.gz=34:*.tar.gz=36:*.PNG=32",
+    ));
+    assert_eq!(h.color_of("box/"), Color::Red);
+    assert_eq!(h.color_of("run.sh"), Color::Magenta);
+    assert_eq!(h.color_of("to-notes"), Color::LightYellow);
+    // The longest suffix wins, whatever the case
+    assert_eq!(h.color_of("pack.tar.gz"), Color::Cyan);
+    assert_eq!(h.color_of("photo.png"), Color::Green);
+    let notes = h.style_of("notes.txt");
+    assert_eq!(notes.fg, Some(Color::Yellow));
+    assert!(notes.add_modifier.contains(Modifier::UNDERLINED));
+    // Nothing says how to color audio, so the theme does
+    assert_eq!(h.color_of("song.flac"), Color::Cyan);
+}
+
+#[test]
+fn links_can_take_the_color_of_their_target() {
+    let mut h = listing(Some("ln=target:*.txt=32"));
+    assert_eq!(h.color_of("to-notes"), Color::Green);
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use common::Harness;
+
+const TREE: &[&str] = &[
+    "src/main.rs",
+    "src/lib.rs",
+    "src/notes.txt",
+    "Cargo.toml",
+    "README.md",
+    "build.rs",
+];
+
+#[test]
+fn glob_filter_narrows_the_listing() {
+    let mut h = Harness::new(TREE);
+    h.press("f");
+    h.type_text("*.rs");
+    h.press("enter");
+    assert_eq!(h.listing(), ["..", "build.rs", "src/"]);
+    assert!(
+        h.screen().contains("*.rs: 2 of 4 entries"),
+        "{}",
+        h.screen()
+    );
+}
+
+#[test]
+fn substring_filter_ignores_case() {
+    let mut h = Harness::new(TREE);
+    h.press("f");
+    h.type_text("read");
+    h.press("enter");
+    assert_eq!(h.listing(), ["..", "README.md", "src/"]);
+}
+
+#[test]
+fn filter_persists_while_navigating_until_cleared() {
+    let mut h = Harness::new(TREE);
+    h.press("f");
+    h.type_text("*.rs");
+    h.press("enter");
+    h.select("src/");
+    h.press("enter");
+    assert_eq!(h.listing(), ["..", "lib.rs", "main.rs"]);
+    assert!(h.screen().contains("*.rs: 2 of 3 entries"));
+
+    h.press("f ctrl+u enter");
+    assert_eq!(h.listing(), ["..", "lib.rs", "main.rs", "notes.txt"]);
+    assert!(!h.screen().contains("entries"));
+}
+
+#[test]
+fn glob_classes_and_wildcards() {
+    let mut h = Harness::new(&["a1.log", "b2.log", "c3.txt", "[x].md"]);
+    h.press("f");
+    h.type_text("[ab]?.log");
+    h.press("enter");
+    assert_eq!(h.listing(), ["..", "a1.log", "b2.log"]);
+    h.press("f ctrl+u");
+    h.type_text("[!a]*");
+    h.press("enter");
+    assert_eq!(h.listing(), ["..", "[x].md", "b2.log", "c3.txt"]);
+    h.press("f ctrl+u");
+    h.type_text("[x*");
+    h.press("enter");
+    assert_eq!(h.listing(), ["..", "[x].md"]);
+}
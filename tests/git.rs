@@ -0,0 +1,94 @@
+mod common;
+
+use std::process::Command;
+
+use common::Harness;
+
+fn git(h: &Harness, args: &[&str]) {
+    let status = Command::new("git")
+        .arg("-C")
+        .arg(h.fixture.path("repo"))
+        .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .output()
+        .unwrap()
+        .status;
+    assert!(status.success(), "git {:?}", args);
+}
+
+/// A repository with a change of every kind, entered once it is set up.
+fn in_repo() -> Harness {
+    let mut h = Harness::new(&[
+        "repo/.gitignore=target/\n*.log\n",
+        "repo/changed.txt=one",
+        "repo/staged.txt=one",
+        "repo/same.txt=one",
+        "repo/src/lib.rs=fn main() {}",
+    ]);
+    git(&h, &["init", "-q", "-b", "trunk"]);
+    git(&h, &["add", "."]);
+    git(&h, &["commit", "-q", "-m", "First"]);
+    h.fixture.add("repo/changed.txt=two");
+    h.fixture.add("repo/staged.txt=two");
+    git(&h, &["add", "staged.txt"]);
+    h.fixture.add("repo/src/lib.rs=fn main() { todo!() }");
+    h.fixture.add("repo/new.txt=new");
+    h.fixture.add("repo/debug.log=log");
+    h.fixture.add("repo/target/out");
+    h.select("repo/");
+    h.press("enter");
+    h
+}
+
+/// The row `name` is listed on, as drawn.
+fn row(h: &mut Harness, name: &str) -> String {
+    let screen = h.screen();
+    // Only the listing, not the preview beside it
+    let listed = screen
+        .lines()
+        .filter_map(|line| line.split('│').nth(1))
+        .find(|row| row.starts_with(name));
+    let row = listed.unwrap_or_else(|| panic!("{:?} not listed\n{}", name, screen));
+    row.trim_end().to_string()
+}
+
+#[test]
+fn entries_are_marked_by_status() {
+    let mut h = in_repo();
+    assert!(row(&mut h, "changed.txt").ends_with(" M"));
+    assert!(row(&mut h, "staged.txt").ends_with(" +"));
+    assert!(row(&mut h, "new.txt").ends_with(" ?"));
+    assert!(row(&mut h, "debug.log").ends_with(" !"));
+    assert!(row(&mut h, "target/").ends_with(" !"));
+    assert_eq!(row(&mut h, "same.txt"), "same.txt");
+    // A directory takes the mark of what changed inside
+    assert!(row(&mut h, "src/").ends_with(" M"));
+}
+
+#[test]
+fn the_branch_is_in_the_header() {
+    let mut h = in_repo();
+    let screen = h.screen();
+    assert!(screen.contains("⎇ trunk · sort: name"), "{}", screen);
+
+    h.select("..");
+    h.press("enter");
+    assert!(!h.screen().contains("⎇"));
+}
+
+#[test]
+fn marks_follow_changes_after_a_refresh() {
+    let mut h = in_repo();
+    h.select("new.txt");
+    h.press("r ctrl+u");
+    h.type_text("renamed.txt");
+    h.press("enter");
+    assert!(row(&mut h, "renamed.txt").ends_with(" ?"));
+
+    git(&h, &["add", "renamed.txt"]);
+    h.select("..");
+    h.press("enter");
+    h.select("repo/");
+    h.press("enter");
+    assert!(row(&mut h, "renamed.txt").ends_with(" +"));
+}
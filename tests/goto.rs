@@ -0,0 +1,106 @@
+mod common;
+
+use browrs::{config::Config, visits::Visits};
+use common::{Harness, test_config};
+
+#[test]
+fn g_p_goes_to_a_typed_path_completing_directories_with_tab() {
+    let mut h = Harness::new(&["projects/browrs/src/", "photos/", "notes.txt"]);
+    let root = h.fixture.root.display().to_string();
+    h.press("g p");
+    // It starts from the current directory
+    assert!(h.screen().contains(&format!("{}/", root)), "{}", h.screen());
+
+    h.press("ctrl+u");
+    h.type_text(&format!("{}/p", root));
+    h.press("tab");
+    let screen = h.screen();
+    assert!(screen.contains("photos/ projects/"), "{}", screen);
+
+    h.type_text("r");
+    h.press("tab");
+    h.type_text("b");
+    h.press("tab");
+    assert!(
+        h.screen().contains(&format!("{}/projects/browrs/", root)),
+        "{}",
+        h.screen()
+    );
+    h.press("enter");
+    assert_eq!(h.current_dir(), h.fixture.path("projects/browrs"));
+    assert_eq!(h.listing(), ["..", "src/"]);
+}
+
+#[test]
+fn cd_command_goes_to_relative_paths_and_selects_files() {
+    let mut h = Harness::new(&["sub/a.txt", "sub/b.txt"]);
+    h.press(":");
+    h.type_text("cd sub/b.txt");
+    h.press("enter");
+    assert_eq!(h.current_dir(), h.fixture.path("sub"));
+    assert_eq!(h.selected(), "b.txt");
+
+    h.press(":");
+    h.type_text("cd ../missing");
+    h.press("enter");
+    assert_eq!(h.current_dir(), h.fixture.path("sub"));
+    assert!(h.screen().contains("missing"), "{}", h.screen());
+}
+
+#[test]
+fn completes_the_path_after_cd_on_the_command_line() {
+    let mut h = Harness::new(&["only/"]);
+    let root = h.fixture.root.display().to_string();
+    h.press(":");
+    h.type_text(&format!("cd {}/o", root));
+    h.press("tab enter");
+    assert_eq!(h.current_dir(), h.fixture.path("only"));
+}
+
+#[test]
+fn up_brings_back_paths_gone_to_before() {
+    let mut h = Harness::new(&["photos/", "work/"]);
+    let photos = h.fixture.path("photos").display().to_string();
+    h.press("g p ctrl+u");
+    h.type_text(&photos);
+    h.press("enter backspace");
+    assert_eq!(h.current_dir(), h.fixture.root);
+
+    h.press("g p up");
+    assert!(h.screen().contains(&photos), "{}", h.screen());
+    h.press("enter");
+    assert_eq!(h.current_dir(), h.fixture.path("photos"));
+}
+
+#[test]
+fn the_most_visited_directories_are_offered_on_an_empty_input() {
+    let config = Config {
+        suggest_places: true,
+        ..test_config()
+    };
+    let mut h = Harness::with_config(&["music/", "work/"], config);
+    h.app
+        .set_visits(Visits::in_file(h.fixture.path("state/visits")));
+    for dir in ["music/", "work/", "work/"] {
+        h.select(dir);
+        h.press("enter backspace");
+    }
+    let root = h.fixture.root.display().to_string();
+
+    h.press("g p ctrl+u");
+    let screen = h.screen();
+    // Most visited first
+    assert!(
+        screen.contains(&format!("Often: {}/work/  ", root)),
+        "{}",
+        screen
+    );
+    h.press("tab enter");
+    assert_eq!(h.current_dir(), h.fixture.path("work"));
+
+    // And with Up, after what was typed
+    h.press("g p up up");
+    assert!(h.screen().contains(&format!("{}/music/", root)));
+    h.press("enter");
+    assert_eq!(h.current_dir(), h.fixture.path("music"));
+}
@@ -0,0 +1,74 @@
+mod common;
+
+use browrs::config::Config;
+use common::{Fixture, Harness, test_config};
+
+/// Greps for `query` and waits for the search to end.
+fn grep(h: &mut Harness, query: &str) {
+    h.press("ctrl+f");
+    h.type_text(query);
+    h.press("enter");
+    h.wait_until(|h| {
+        let screen = h.screen();
+        screen.contains(" matches in ") && !screen.contains("searching")
+    });
+}
+
+#[test]
+fn matches_show_file_line_and_text() {
+    let mut h = Harness::new(&[
+        "src/main.rs=fn main() {\n    println!(\"Needle\");\n}\n",
+        "notes.txt=nothing here\nneedle in a haystack\n",
+        "blob.bin=needle\0",
+        ".hidden=needle",
+    ]);
+    grep(&mut h, "needle");
+    let screen = h.screen();
+    assert!(screen.contains(" 2 matches in 2 files "), "{}", screen);
+    assert!(screen.contains("notes.txt:2: needle in a haystack"));
+    assert!(screen.contains("src/main.rs:2: println!(\"Needle\");"));
+    assert!(!screen.contains("blob.bin:"));
+    assert!(!screen.contains(".hidden:"));
+
+    // Capitals make it match case
+    h.press("esc");
+    grep(&mut h, "Needle");
+    assert!(h.screen().contains(" 1 matches in 1 files "));
+}
+
+#[test]
+fn enter_opens_the_editor_at_the_matching_line() {
+    // An "editor" that writes down what it was run with
+    let tools = Fixture::new(&[]);
+    let args = tools.path("args");
+    tools.add(&format!("record.sh=echo \"$@\" > {}", args.display()));
+    let config = Config {
+        editor: Some(format!("sh {}", tools.path("record.sh").display())),
+        ..test_config()
+    };
+    let mut h = Harness::with_config(&["a/b/found.txt=one\ntwo\nthree target\n"], config);
+    grep(&mut h, "target");
+    h.press("enter");
+    assert!(!h.screen().contains("Grep:"));
+    assert!(h.current_dir().ends_with("a/b"));
+    assert_eq!(h.selected(), "found.txt");
+    let args = std::fs::read_to_string(&args).unwrap();
+    assert_eq!(
+        args.trim(),
+        format!("+3 {}", h.fixture.path("a/b/found.txt").display())
+    );
+}
+
+#[test]
+fn editing_the_query_asks_to_search_again() {
+    let mut h = Harness::new(&["one.txt=alpha\nbeta\n"]);
+    grep(&mut h, "alpha");
+    h.press("backspace");
+    let screen = h.screen();
+    assert!(screen.contains("Grep: alph▏"));
+    assert!(screen.contains(" Enter to search again, 1 matches in 1 files "));
+    h.press("enter");
+    assert!(h.screen().contains("Grep: alph▏"));
+    h.press("esc");
+    assert!(!h.screen().contains("Grep:"));
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use browrs::config::Config;
+use common::{Harness, test_config};
+
+/// Greps for `needle` with the files of every match marked.
+fn marked(h: &mut Harness) {
+    h.press("ctrl+f");
+    h.type_text("needle");
+    h.press("enter");
+    h.wait_until(|h| {
+        let screen = h.screen();
+        screen.contains(" matches in ") && !screen.contains("searching")
+    });
+    h.press("ctrl+a");
+}
+
+#[test]
+fn marked_files_are_copied_to_a_directory() {
+    let mut h = Harness::new(&[
+        "deep/er/one.txt=needle\nand a needle again\n",
+        "deep/two.md=a needle",
+        "other.txt=hay",
+        "out/",
+    ]);
+    marked(&mut h);
+    // Two matches in one file mark it once
+    assert!(h.screen().contains(" 2 marked, 3 matches in 2 files "));
+    h.press("ctrl+y");
+    assert!(h.screen().contains("Copy 2 entries to"));
+    h.type_text("/out");
+    h.press("enter");
+    h.finish_jobs();
+    assert!(h.fixture.read("out/one.txt").starts_with("needle"));
+    assert_eq!(h.fixture.read("out/two.md"), "a needle");
+    assert!(!h.fixture.exists("out/other.txt"));
+    assert!(!h.screen().contains("Grep:"));
+}
+
+#[test]
+fn tab_marks_the_file_of_one_match() {
+    let mut h = Harness::new(&["a.txt=needle", "b.txt=needle"]);
+    marked(&mut h);
+    h.press("ctrl+a");
+    assert!(!h.screen().contains("marked"));
+    h.press("tab");
+    let screen = h.screen();
+    assert!(screen.contains(" 1 marked, "), "{}", screen);
+    assert!(screen.contains("* a.txt:1: needle"), "{}", screen);
+
+    h.press("ctrl+d");
+    assert!(
+        h.screen().contains("Move a.txt to the trash?"),
+        "{}",
+        h.screen()
+    );
+    h.press("esc");
+    assert!(h.screen().contains(" 1 marked, "));
+}
+
+#[test]
+fn marked_files_open_in_one_editor() {
+    // cp with both files copies the first over the second
+    let config = Config {
+        editor: Some("cp".to_string()),
+        ..test_config()
+    };
+    let mut h = Harness::with_config(&["a/x.txt=needle A", "b/x.txt=needle B"], config);
+    marked(&mut h);
+    h.press("ctrl+o");
+    assert_eq!(h.fixture.read("b/x.txt"), "needle A");
+    assert!(!h.screen().contains("Grep:"));
+}
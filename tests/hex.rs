@@ -0,0 +1,52 @@
+mod common;
+
+use common::Harness;
+
+#[test]
+fn binary_files_preview_as_a_hex_dump() {
+    let mut h = Harness::new(&["blob.bin=\u{7f}ELF\0\u{1}\u{2}hi"]);
+    h.select("blob.bin");
+    let screen = h.screen();
+    assert!(screen.contains("blob.bin | 9 bytes | binary"), "{}", screen);
+    assert!(
+        screen.contains("00000000  7f 45 4c 46  00 01 02 68"),
+        "{}",
+        screen
+    );
+    assert!(screen.contains("│.ELF...h│"), "{}", screen);
+}
+
+#[test]
+fn long_dumps_load_further_as_the_preview_scrolls() {
+    let bytes: String = (0..4000).map(|i| char::from(b"\0\x01ab"[i % 4])).collect();
+    let mut h = Harness::new(&[&format!("data.bin={}", bytes)]);
+    h.select("data.bin");
+    h.screen();
+    h.press("tab G");
+    let screen = h.screen();
+    assert!(screen.contains("00000f98"), "{}", screen);
+}
+
+#[test]
+fn x_toggles_a_full_screen_view_of_any_other_file() {
+    let bytes: String = (0..1024)
+        .map(|i| char::from(b'a' + (i % 26) as u8))
+        .collect();
+    let mut h = Harness::new(&[&format!("letters.txt={}", bytes)]);
+    h.select("letters.txt");
+    h.press("x");
+    let screen = h.screen();
+    assert!(screen.contains(" letters.txt "), "{}", screen);
+    assert!(screen.contains("0 of 400"), "{}", screen);
+    assert!(screen.contains("│abcdefghijklmnop│"), "{}", screen);
+
+    h.press("pagedown");
+    let screen = h.screen();
+    assert!(!screen.contains("00000000  61"), "{}", screen);
+    h.press("G");
+    assert!(h.screen().contains("000003f0"));
+
+    h.press("x");
+    assert!(!h.screen().contains("0 of 400"));
+    assert_eq!(h.selected(), "letters.txt");
+}
@@ -0,0 +1,89 @@
+mod common;
+
+use common::Harness;
+
+const TREE: &[&str] = &["a/x/", "a/y/", "a/z/", "b/", "c.txt"];
+
+#[test]
+fn back_and_forward_restore_the_selection() {
+    let mut h = Harness::new(TREE);
+    h.select("a/");
+    h.press("enter");
+    h.select("z/");
+    h.press("enter");
+    assert_eq!(h.current_dir(), h.fixture.path("a/z"));
+
+    h.press("backspace");
+    assert_eq!(h.current_dir(), h.fixture.path("a"));
+    assert_eq!(h.selected(), "z/");
+    h.press("alt+left");
+    assert_eq!(h.current_dir(), h.fixture.root);
+    assert_eq!(h.selected(), "a/");
+
+    h.press("alt+right");
+    assert_eq!(h.current_dir(), h.fixture.path("a"));
+    assert_eq!(h.selected(), "z/");
+    h.press("alt+right");
+    assert_eq!(h.current_dir(), h.fixture.path("a/z"));
+    // Nothing further to go to
+    h.press("alt+right");
+    assert_eq!(h.current_dir(), h.fixture.path("a/z"));
+}
+
+#[test]
+fn visiting_somewhere_new_drops_the_forward_history() {
+    let mut h = Harness::new(TREE);
+    h.select("a/");
+    h.press("enter backspace");
+    h.select("b/");
+    h.press("enter alt+right");
+    assert_eq!(h.current_dir(), h.fixture.path("b"));
+    h.press("backspace backspace");
+    assert_eq!(h.current_dir(), h.fixture.root);
+}
+
+#[test]
+fn going_back_to_a_removed_directory_reports_it() {
+    let mut h = Harness::new(TREE);
+    h.select("b/");
+    h.press("enter");
+    h.select("..");
+    h.press("enter");
+    std::fs::remove_dir(h.fixture.path("b")).unwrap();
+    h.press("backspace");
+    assert_eq!(h.current_dir(), h.fixture.root);
+    assert!(h.screen().contains("❌ Unable to open"));
+}
+
+#[test]
+fn leaving_through_the_parent_puts_the_cursor_on_the_directory_left() {
+    let mut h = Harness::new(TREE);
+    h.select("a/");
+    h.press("enter");
+    h.select("y/");
+    h.press("enter");
+    h.select("..");
+    h.press("enter");
+    assert_eq!(h.current_dir(), h.fixture.path("a"));
+    assert_eq!(h.selected(), "y/");
+    h.select("..");
+    h.press("enter");
+    assert_eq!(h.selected(), "a/");
+}
+
+#[test]
+fn each_directory_remembers_its_cursor() {
+    let mut h = Harness::new(TREE);
+    h.select("a/");
+    h.press("enter");
+    h.select("z/");
+    h.press("backspace");
+    h.select("b/");
+    h.press("enter");
+    h.select("..");
+    h.press("enter");
+    assert_eq!(h.selected(), "b/");
+    h.select("a/");
+    h.press("enter");
+    assert_eq!(h.selected(), "z/");
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use browrs::config::Config;
+use common::{Harness, test_config};
+
+fn hiding() -> Config {
+    Config {
+        hide_ignored: true,
+        ..test_config()
+    }
+}
+
+#[test]
+fn ignored_entries_are_hidden_on_request() {
+    let mut h = Harness::new(&[
+        "repo/.git/",
+        "repo/.gitignore=target/\n*.log\n!keep.log\n",
+        "repo/target/debug",
+        "repo/build.log",
+        "repo/keep.log",
+        "repo/src/.gitignore=gen/\n",
+        "repo/src/gen/out.rs",
+        "repo/src/main.rs",
+        "repo/src/target",
+    ]);
+    h.select("repo/");
+    h.press("enter");
+    assert_eq!(
+        h.listing(),
+        ["..", "build.log", "keep.log", "src/", "target/"]
+    );
+
+    h.press("alt+g");
+    assert_eq!(h.listing(), ["..", "keep.log", "src/"]);
+    assert!(h.screen().contains(" 2 ignored hidden "));
+
+    // Rules of the directories above apply too, and a file isn't a directory
+    h.select("src/");
+    h.press("enter");
+    assert_eq!(h.listing(), ["..", "main.rs", "target"]);
+
+    h.press("alt+g");
+    assert_eq!(h.listing(), ["..", "gen/", "main.rs", "target"]);
+    assert!(!h.screen().contains("ignored hidden"));
+}
+
+#[test]
+fn patterns_with_a_slash_match_from_their_directory() {
+    let mut h = Harness::with_config(
+        &[
+            "repo/.git/info/exclude=*.swp\n",
+            "repo/.gitignore=/build\ndocs/**/*.tmp\n",
+            "repo/build/out",
+            "repo/notes.swp",
+            "repo/docs/a/b/draft.tmp",
+            "repo/docs/a/b/page.md",
+            "repo/docs/a/build/",
+        ],
+        hiding(),
+    );
+    h.select("repo/");
+    h.press("enter");
+    assert_eq!(h.listing(), ["..", "docs/"]);
+    h.select("docs/");
+    h.press("enter");
+    h.select("a/");
+    h.press("enter");
+    assert_eq!(h.listing(), ["..", "b/", "build/"]);
+    h.select("b/");
+    h.press("enter");
+    assert_eq!(h.listing(), ["..", "page.md"]);
+}
+
+#[test]
+fn nothing_is_hidden_outside_a_repository() {
+    let h = Harness::with_config(&[".gitignore=*.txt\n", "a.txt", "b.rs"], hiding());
+    assert_eq!(h.listing(), ["..", "a.txt", "b.rs"]);
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use std::fs;
+
+use common::Harness;
+
+fn segment(marker: u8, body: &[u8]) -> Vec<u8> {
+    let mut out = vec![0xFF, marker];
+    out.extend_from_slice(&((body.len() + 2) as u16).to_be_bytes());
+    out.extend_from_slice(body);
+    out
+}
+
+/// Huffman table `id` of `class` with one 1-bit code, `0`, for `symbol`.
+fn huffman(class: u8, symbol: u8) -> Vec<u8> {
+    let mut body = vec![class << 4, 1];
+    body.extend([0; 15]);
+    body.push(symbol);
+    segment(0xC4, &body)
+}
+
+/// A grayscale baseline JPEG `blocks` 8x8 blocks wide whose every block has
+/// the DC category `dc` with all bits set, and no AC terms.
+fn jpeg(blocks: u16, dc: u8, quant: u16) -> Vec<u8> {
+    let mut out = vec![0xFF, 0xD8];
+    let mut table = vec![0x10];
+    table.extend(quant.to_be_bytes().repeat(64));
+    out.extend(segment(0xDB, &table));
+    let [high, low] = (blocks * 8).to_be_bytes();
+    out.extend(segment(0xC0, &[8, 0, 8, high, low, 1, 1, 0x11, 0]));
+    out.extend(huffman(0, dc));
+    out.extend(huffman(1, 0));
+    out.extend(segment(0xDA, &[1, 1, 0x00, 0, 63, 0]));
+
+    // Per block: the DC code, `dc` one bits, the end-of-block code
+    let mut bits = vec![];
+    for _ in 0..blocks {
+        bits.push(0);
+        bits.extend(std::iter::repeat_n(1, dc.min(16) as usize));
+        bits.push(0);
+    }
+    for byte in bits.chunks(8) {
+        let byte = byte.iter().fold(0u8, |acc, bit| acc << 1 | bit) << (8 - byte.len());
+        out.push(byte);
+        if byte == 0xFF {
+            out.push(0);
+        }
+    }
+    out.extend([0xFF, 0xD9]);
+    out
+}
+
+fn preview(image: Vec<u8>) -> String {
+    let mut h = Harness::new(&["a.txt", "b.jpg"]);
+    fs::write(h.fixture.path("b.jpg"), image).unwrap();
+    h.select("b.jpg");
+    h.screen()
+}
+
+#[test]
+fn a_dc_size_past_the_limit_is_refused() {
+    let screen = preview(jpeg(1, 255, 1));
+    assert!(
+        screen.contains("No preview: bad JPEG DC size"),
+        "{}",
+        screen
+    );
+    let screen = preview(jpeg(1, 12, 1));
+    assert!(screen.contains("bad JPEG DC size"), "{}", screen);
+}
+
+#[test]
+fn a_runaway_dc_predictor_still_decodes() {
+    // 40 blocks of +2047 times 65535 is past i32
+    let screen = preview(jpeg(40, 11, 0xFFFF));
+    assert!(screen.contains("320×8"), "{}", screen);
+    assert!(!screen.contains("No preview"), "{}", screen);
+}
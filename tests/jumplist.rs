@@ -0,0 +1,68 @@
+mod common;
+
+use common::Harness;
+
+#[test]
+fn jumps_within_a_directory_come_back() {
+    let mut h = Harness::new(&["a.txt", "m.txt", "z.txt"]);
+    h.select("m.txt");
+    h.press("G");
+    assert_eq!(h.selected(), "z.txt");
+    h.press("g g");
+    assert_eq!(h.selected(), "..");
+
+    h.press("ctrl+o");
+    assert_eq!(h.selected(), "z.txt");
+    h.press("ctrl+o");
+    assert_eq!(h.selected(), "m.txt");
+    // Nothing further back
+    h.press("ctrl+o");
+    assert_eq!(h.selected(), "m.txt");
+
+    h.press("ctrl+n ctrl+n");
+    assert_eq!(h.selected(), "..");
+}
+
+#[test]
+fn jumping_forward_leaves_tab_to_the_panes() {
+    let mut h = Harness::new(&["a.txt", "z.txt"]);
+    h.press("G g g ctrl+o");
+    // What terminals send for Ctrl+I
+    h.press("tab");
+    assert_eq!(h.selected(), "z.txt");
+    h.press("tab ctrl+n");
+    assert_eq!(h.selected(), "..");
+}
+
+#[test]
+fn jumps_restore_the_directory_and_entry() {
+    let mut h = Harness::new(&["docs/guide.md", "docs/intro.md", "notes.txt"]);
+    h.select("docs/");
+    h.press("enter");
+    h.select("intro.md");
+    h.press("backspace");
+    h.select("notes.txt");
+
+    h.press("ctrl+o");
+    assert_eq!(h.current_dir(), h.fixture.path("docs"));
+    assert_eq!(h.selected(), "intro.md");
+    h.press("ctrl+o");
+    assert_eq!(h.current_dir(), h.fixture.root);
+    assert_eq!(h.selected(), "docs/");
+
+    h.press("ctrl+n ctrl+n");
+    assert_eq!(h.current_dir(), h.fixture.root);
+    assert_eq!(h.selected(), "notes.txt");
+}
+
+#[test]
+fn a_new_jump_drops_the_ones_gone_back_past() {
+    let mut h = Harness::new(&["a.txt", "z.txt"]);
+    h.press("G g g ctrl+o");
+    assert_eq!(h.selected(), "z.txt");
+    h.press("up G");
+    h.press("ctrl+n");
+    assert_eq!(h.selected(), "z.txt");
+    h.press("ctrl+o");
+    assert_eq!(h.selected(), "a.txt");
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use browrs::Config;
+use common::{Harness, test_config};
+
+fn with_keys(entries: &[&str], keys: &str) -> Harness {
+    let config = Config {
+        keymap: Config::parse(&format!("[keys]\n{}", keys)).keymap,
+        ..test_config()
+    };
+    Harness::with_config(entries, config)
+}
+
+#[test]
+fn g_g_and_shift_g_jump_to_the_ends() {
+    let mut h = Harness::new(&["a", "b", "c"]);
+    h.press("G");
+    assert_eq!(h.selected(), "c");
+    h.press("g g");
+    assert_eq!(h.selected(), "..");
+}
+
+#[test]
+fn a_key_breaking_off_a_sequence_still_counts() {
+    let mut h = Harness::new(&["a", "b"]);
+    h.press("g down");
+    assert_eq!(h.selected(), "a");
+}
+
+#[test]
+fn binds_keys_to_actions_and_other_keys() {
+    let mut h = with_keys(
+        &["a", "b", "c"],
+        "j = \"move_down\"\n\"ctrl+n\" = \"down\"\n",
+    );
+    h.press("j ctrl+n");
+    assert_eq!(h.selected(), "b");
+}
+
+#[test]
+fn binds_sequences_with_modifiers() {
+    let mut h = with_keys(&["a", "b"], "\"ctrl+x q\" = \"quit\"\nq = \"none\"\n");
+    h.press("q");
+    assert!(!h.app.is_exiting());
+    h.press("ctrl+x q");
+    assert!(h.app.is_exiting());
+}
+
+#[test]
+fn footer_shows_remapped_keys() {
+    let mut h = with_keys(&[], "x = \"quit\"\nq = \"none\"\n");
+    assert!(h.screen().contains("Quit <X>"));
+}
+
+#[test]
+fn ignores_unknown_actions() {
+    let mut h = with_keys(&["a"], "z = \"fly\"\n");
+    h.press("z");
+    assert_eq!(h.selected(), "..");
+}
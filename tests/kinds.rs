@@ -0,0 +1,59 @@
+mod common;
+
+use common::Harness;
+
+const TREE: &[&str] = &[
+    "docs/",
+    "photo.png",
+    "Logo.JPG",
+    "notes.txt",
+    "backup.tar.gz",
+    "run.sh=#!/bin/sh",
+];
+
+#[test]
+fn kind_keys_toggle_and_combine() {
+    let mut h = Harness::new(TREE);
+    h.press("alt+i");
+    assert_eq!(h.listing(), ["..", "Logo.JPG", "photo.png"]);
+    assert!(h.screen().contains("only images: 2 of 6 entries"));
+
+    h.press("alt+d");
+    assert_eq!(h.listing(), ["..", "Logo.JPG", "docs/", "photo.png"]);
+    assert!(h.screen().contains("images+dirs: 3 of 6 entries"));
+
+    h.press("alt+i");
+    assert_eq!(h.listing(), ["..", "docs/"]);
+    h.press("alt+0");
+    assert_eq!(h.listing().len(), 7);
+    assert!(!h.screen().contains("only"));
+}
+
+#[test]
+fn kinds_layer_over_the_pattern_and_follow_the_pane() {
+    let mut h = Harness::new(&["a/b.txt", "a/c.png", "d.txt", "e.tar", "f.zip"]);
+    h.press("alt+a");
+    assert_eq!(h.listing(), ["..", "e.tar", "f.zip"]);
+    h.press("f");
+    h.type_text("*.zip");
+    h.press("enter");
+    assert_eq!(h.listing(), ["..", "f.zip"]);
+
+    h.press("alt+0 alt+d alt+i");
+    h.press("f ctrl+u enter");
+    h.select("a/");
+    h.press("enter");
+    assert_eq!(h.listing(), ["..", "c.png"]);
+}
+
+#[cfg(unix)]
+#[test]
+fn executables_are_told_by_their_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut h = Harness::new(TREE);
+    let script = h.fixture.path("run.sh");
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    h.press("alt+x");
+    assert_eq!(h.listing(), ["..", "run.sh"]);
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use browrs::config::Config;
+use common::{Harness, test_config};
+
+fn with_line_counts(line_counts: bool) -> Harness {
+    let config = Config {
+        line_counts,
+        ..test_config()
+    };
+    Harness::with_config(
+        &[
+            "script.sh=one\ntwo\nthree",
+            "empty.txt",
+            "blob.bin=a\0b\n",
+            "dir/",
+        ],
+        config,
+    )
+}
+
+/// The text at the end of the row listing `name`.
+fn row_end(h: &mut Harness, name: &str) -> String {
+    let screen = h.screen();
+    let row = screen
+        .lines()
+        .find(|line| line.contains(name))
+        .unwrap_or_else(|| panic!("{}", screen));
+    row.split('│').nth(1).unwrap().trim_end().to_string()
+}
+
+#[test]
+fn small_text_files_show_their_line_count() {
+    let mut h = with_line_counts(true);
+    assert!(row_end(&mut h, "script.sh").ends_with(" 3 lines"));
+    assert!(row_end(&mut h, "empty.txt").ends_with(" 0 lines"));
+    assert!(!row_end(&mut h, "blob.bin").contains("lines"));
+    assert!(!row_end(&mut h, "dir/").contains("lines"));
+
+    // Counted again once the file changes
+    std::fs::write(h.fixture.path("script.sh"), "one\n").unwrap();
+    h.press("t");
+    assert!(row_end(&mut h, "script.sh").ends_with(" 1 line"));
+}
+
+#[test]
+fn the_column_is_off_by_default() {
+    let mut h = with_line_counts(false);
+    assert!(!h.screen().contains("lines"));
+}
@@ -0,0 +1,75 @@
+mod common;
+
+use std::{path::PathBuf, sync::OnceLock};
+
+use browrs::log::{self, Level};
+use common::Harness;
+
+/// Logging is process-wide, so every test shares one file.
+fn log_file() -> &'static PathBuf {
+    static FILE: OnceLock<PathBuf> = OnceLock::new();
+    FILE.get_or_init(|| {
+        let path = std::env::temp_dir().join(format!("browrs-test-{}.log", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        log::init(Level::Trace, Some(path.clone())).unwrap()
+    })
+}
+
+fn logged(text: &str) -> bool {
+    log::recent().iter().any(|line| line.contains(text))
+}
+
+#[test]
+fn navigation_is_logged_to_the_file() {
+    let file = log_file();
+    let mut h = Harness::new(&["docs/"]);
+    h.select("docs/");
+    h.press("enter");
+    let cd = format!("cd {}", h.fixture.path("docs").display());
+    assert!(logged(&cd));
+    assert!(logged(&format!(
+        "read {} took",
+        h.fixture.path("docs").display()
+    )));
+    assert!(std::fs::read_to_string(file).unwrap().contains(&cd));
+}
+
+#[test]
+fn failed_operations_are_logged_as_errors() {
+    log_file();
+    let mut h = Harness::new(&["a.txt=a"]);
+    h.select("a.txt");
+    h.press("c ctrl+u");
+    let dest = h.fixture.path("missing/b.txt");
+    h.type_text(&dest.display().to_string());
+    h.press("enter");
+    h.finish_jobs();
+    assert!(logged("INFO  copy: 0 changed, 1 failed"));
+    let source = h.fixture.path("a.txt");
+    assert!(logged(&format!(
+        "ERROR Unable to copy {}",
+        source.display()
+    )));
+}
+
+#[test]
+fn log_popup_lists_recent_lines() {
+    log_file();
+    let mut h = Harness::new(&["docs/"]);
+    h.select("docs/");
+    h.press("enter L");
+    let screen = h.screen();
+    assert!(screen.contains("Log: "), "{}", screen);
+    assert!(screen.contains("INFO  cd "), "{}", screen);
+    h.press("esc");
+    assert!(!h.screen().contains("Log: "));
+}
+
+#[test]
+fn verbosity_flags_map_to_levels() {
+    assert_eq!(Level::from_verbosity(0), Level::Warn);
+    assert_eq!(Level::from_verbosity(2), Level::Debug);
+    assert_eq!(Level::from_verbosity(5), Level::Trace);
+    assert_eq!(Level::parse("Info"), Some(Level::Info));
+    assert_eq!(Level::parse("loud"), None);
+}
@@ -0,0 +1,125 @@
+mod common;
+
+use common::Harness;
+
+/// A harness with `name` holding `bytes`, selected.
+fn preview_of(name: &str, bytes: Vec<u8>) -> String {
+    let mut h = Harness::new(&[name]);
+    std::fs::write(h.fixture.path(name), bytes).unwrap();
+    h.select(name);
+    h.screen()
+}
+
+fn flac() -> Vec<u8> {
+    let mut bytes = b"fLaC".to_vec();
+    bytes.extend([0, 0, 0, 34]);
+    bytes.extend([0x10, 0x00, 0x10, 0x00, 0, 0, 0, 0, 0, 0]);
+    // 44.1 kHz, stereo, 16 bits, 200 seconds of samples
+    let samples: u64 = 44100 * 200;
+    let packed = 44100 << 44 | 1 << 41 | 15 << 36 | samples;
+    bytes.extend(packed.to_be_bytes());
+    bytes.extend([0; 16]);
+
+    let mut comments = Vec::new();
+    let vendor = b"test";
+    comments.extend((vendor.len() as u32).to_le_bytes());
+    comments.extend(vendor);
+    let tags = ["TITLE=Night Drive", "ARTIST=The Examples", "ALBUM=Fixtures"];
+    comments.extend((tags.len() as u32).to_le_bytes());
+    for tag in tags {
+        comments.extend((tag.len() as u32).to_le_bytes());
+        comments.extend(tag.as_bytes());
+    }
+    bytes.push(0x84);
+    bytes.extend(&(comments.len() as u32).to_be_bytes()[1..]);
+    bytes.extend(comments);
+    bytes
+}
+
+fn mp4_box(kind: &[u8; 4], parts: &[&[u8]]) -> Vec<u8> {
+    let body = parts.concat();
+    let mut bytes = ((body.len() + 8) as u32).to_be_bytes().to_vec();
+    bytes.extend(kind);
+    bytes.extend(body);
+    bytes
+}
+
+fn mp4() -> Vec<u8> {
+    let ftyp = mp4_box(b"ftyp", &[b"isom", &[0, 0, 2, 0]]);
+    let mut mvhd = vec![0; 100];
+    mvhd[12..16].copy_from_slice(&1000u32.to_be_bytes());
+    mvhd[16..20].copy_from_slice(&90_000u32.to_be_bytes());
+    let mut hdlr = vec![0; 24];
+    hdlr[8..12].copy_from_slice(b"vide");
+    let mut avc1 = vec![0; 78];
+    avc1[24..26].copy_from_slice(&1920u16.to_be_bytes());
+    avc1[26..28].copy_from_slice(&1080u16.to_be_bytes());
+    let stsd = mp4_box(
+        b"stsd",
+        &[&[0, 0, 0, 0, 0, 0, 0, 1], &mp4_box(b"avc1", &[&avc1])],
+    );
+    let stbl = mp4_box(b"stbl", &[&stsd]);
+    let minf = mp4_box(b"minf", &[&stbl]);
+    let mdia = mp4_box(b"mdia", &[&mp4_box(b"hdlr", &[&hdlr]), &minf]);
+    let trak = mp4_box(b"trak", &[&mdia]);
+    let data = mp4_box(b"data", &[&[0, 0, 0, 1, 0, 0, 0, 0], b"Holiday"]);
+    let ilst = mp4_box(b"ilst", &[&mp4_box(b"\xa9nam", &[&data])]);
+    let udta = mp4_box(b"udta", &[&mp4_box(b"meta", &[&[0, 0, 0, 0], &ilst])]);
+    let moov = mp4_box(b"moov", &[&mp4_box(b"mvhd", &[&mvhd]), &trak, &udta]);
+    [ftyp, mp4_box(b"mdat", &[&[0; 64]]), moov].concat()
+}
+
+#[test]
+fn flac_shows_its_stream_and_tags() {
+    let screen = preview_of("drive.flac", flac());
+    assert!(screen.contains("🎵 drive.flac"), "{}", screen);
+    assert!(screen.contains("Duration: 3:20"), "{}", screen);
+    assert!(
+        screen.contains("Audio:    FLAC 16 bit, 44.1 kHz, stereo"),
+        "{}",
+        screen
+    );
+    assert!(screen.contains("Title:    Night Drive"), "{}", screen);
+    assert!(screen.contains("Artist:   The Examples"), "{}", screen);
+    assert!(screen.contains("Album:    Fixtures"), "{}", screen);
+}
+
+#[test]
+fn mp3_reads_the_id3_tag_and_the_frame_header() {
+    let mut bytes = b"ID3\x03\x00\x00".to_vec();
+    let mut frame = b"TPE1".to_vec();
+    frame.extend(7u32.to_be_bytes());
+    frame.extend([0, 0, 0]);
+    frame.extend(b"Singer");
+    bytes.extend([0, 0, 0, frame.len() as u8]);
+    bytes.extend(frame);
+    // 128 kb/s at 44.1 kHz, 3.6 seconds of frames
+    for _ in 0..138 {
+        bytes.extend([0xff, 0xfb, 0x90, 0x00]);
+        bytes.extend([0; 413]);
+    }
+    let screen = preview_of("song.mp3", bytes);
+    assert!(screen.contains("Duration: 0:04"), "{}", screen);
+    assert!(screen.contains("Bitrate:  128 kb/s"), "{}", screen);
+    assert!(
+        screen.contains("Audio:    MPEG-1 Layer III, 44.1 kHz, stereo"),
+        "{}",
+        screen
+    );
+    assert!(screen.contains("Artist:   Singer"), "{}", screen);
+}
+
+#[test]
+fn mp4_shows_the_resolution_of_its_video() {
+    let screen = preview_of("clip.mp4", mp4());
+    assert!(screen.contains("🎬 clip.mp4"), "{}", screen);
+    assert!(screen.contains("Duration: 1:30"), "{}", screen);
+    assert!(screen.contains("Video:    H.264, 1920×1080"), "{}", screen);
+    assert!(screen.contains("Title:    Holiday"), "{}", screen);
+}
+
+#[test]
+fn unreadable_media_falls_back_to_the_plain_preview() {
+    let screen = preview_of("notes.mkv", b"not really a video\n".to_vec());
+    assert!(screen.contains("not really a video"), "{}", screen);
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use common::Harness;
+
+#[test]
+fn clicking_selects_and_double_clicking_opens() {
+    let mut h = Harness::new(&["a.txt", "b.txt", "docs/inner.md"]);
+    h.click("b.txt");
+    assert_eq!(h.selected(), "b.txt");
+
+    h.click("docs/");
+    assert_eq!(h.selected(), "docs/");
+    h.click("docs/");
+    assert_eq!(h.current_dir(), h.fixture.path("docs"));
+    assert_eq!(h.listing(), ["..", "inner.md"]);
+}
+
+#[test]
+fn the_wheel_moves_through_the_listing() {
+    let files: Vec<String> = (0..10).map(|i| format!("f{}", i)).collect();
+    let files: Vec<&str> = files.iter().map(String::as_str).collect();
+    let mut h = Harness::new(&files);
+    h.scroll("f0", true);
+    assert_eq!(h.selected(), "f2");
+    h.scroll("f0", true);
+    assert_eq!(h.selected(), "f5");
+    h.scroll("f0", false);
+    assert_eq!(h.selected(), "f2");
+}
+
+#[test]
+fn the_preview_scrolls_and_takes_the_arrow_keys_once_clicked() {
+    let text: String = (1..=60).map(|i| format!("line {}\n", i)).collect();
+    let mut h = Harness::new(&[&format!("long.txt={}", text), "other.txt"]);
+    h.select("long.txt");
+    assert!(h.screen().contains("line 1 "));
+    h.scroll("line 2", true);
+    let screen = h.screen();
+    assert!(!screen.contains("line 1 "), "{}", screen);
+    assert!(screen.contains("line 2 "), "{}", screen);
+
+    h.click("line 10");
+    h.press("down");
+    assert_eq!(h.selected(), "long.txt");
+    assert!(!h.screen().contains("line 2 "));
+
+    // Clicking the listing hands the keys back
+    h.click("other.txt");
+    h.press("up");
+    assert_eq!(h.selected(), "long.txt");
+}
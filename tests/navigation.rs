@@ -0,0 +1,128 @@
+mod common;
+
+use browrs::config::Config;
+use common::{Harness, test_config};
+
+#[test]
+fn lists_parent_then_entries_by_name() {
+    let h = Harness::new(&["b.txt", "a.txt", "docs/"]);
+    assert_eq!(h.listing(), ["..", "a.txt", "b.txt", "docs/"]);
+    assert_eq!(h.selected(), "..");
+}
+
+#[test]
+fn enters_directories_and_goes_back_up() {
+    let mut h = Harness::new(&["docs/guide.md", "readme.md"]);
+    h.select("docs/");
+    h.press("enter");
+    assert_eq!(h.current_dir(), h.fixture.path("docs"));
+    assert_eq!(h.listing(), ["..", "guide.md"]);
+
+    h.press("enter");
+    assert_eq!(h.current_dir(), h.fixture.root);
+}
+
+#[test]
+fn cursor_stops_at_both_ends() {
+    let mut h = Harness::new(&["a", "b"]);
+    h.press("up");
+    assert_eq!(h.selected(), "..");
+    h.press("down down down down");
+    assert_eq!(h.selected(), "b");
+}
+
+#[test]
+fn cursor_wraps_around_when_asked_to() {
+    let config = Config {
+        wrap_around: true,
+        ..test_config()
+    };
+    let mut h = Harness::with_config(&["a", "b"], config);
+    h.press("up");
+    assert_eq!(h.selected(), "b");
+    h.press("down");
+    assert_eq!(h.selected(), "..");
+
+    h.press("end");
+    assert_eq!(h.selected(), "b");
+    h.press("home");
+    assert_eq!(h.selected(), "..");
+}
+
+#[test]
+fn a_held_arrow_speeds_up_on_long_listings() {
+    let config = Config {
+        scroll_acceleration: 10,
+        wrap_around: true,
+        ..test_config()
+    };
+    let names: Vec<String> = (0..200).map(|i| format!("{:03}", i)).collect();
+    let names: Vec<&str> = names.iter().map(String::as_str).collect();
+    let mut h = Harness::with_config(&names, config);
+    h.screen();
+    // Repeats come quicker than anyone presses a key: 1 + 2 + 3 + 4 + 5
+    h.press("down down down down down");
+    assert_eq!(h.selected(), "014");
+
+    // Held, it stops at the end instead of going round
+    for _ in 0..40 {
+        h.press("down");
+    }
+    assert_eq!(h.selected(), "199");
+}
+
+#[test]
+fn toggles_hidden_entries() {
+    let mut h = Harness::new(&[".hidden", "shown"]);
+    assert_eq!(h.listing(), ["..", "shown"]);
+    h.press(".");
+    assert_eq!(h.listing(), ["..", ".hidden", "shown"]);
+    h.press("alt+.");
+    assert_eq!(h.listing(), ["..", "shown"]);
+}
+
+#[test]
+fn backspace_never_toggles_hidden_entries() {
+    let mut h = Harness::new(&["dir/.hidden", "dir/shown"]);
+    h.select("dir/");
+    h.press("enter");
+    // What many terminals send for Ctrl+H
+    h.press("backspace");
+    assert_eq!(h.current_dir(), h.fixture.root);
+    h.select("dir/");
+    h.press("enter");
+    assert_eq!(h.listing(), ["..", "shown"]);
+}
+
+#[test]
+fn search_jumps_to_the_match() {
+    let mut h = Harness::new(&["deep/er/needle.txt", "other.txt"]);
+    h.press("/");
+    h.type_text("needle");
+    h.wait_until(|h| h.screen().contains("needle.txt"));
+    h.press("enter");
+    assert_eq!(h.current_dir(), h.fixture.path("deep/er"));
+    assert_eq!(h.selected(), "needle.txt");
+}
+
+#[test]
+fn tabs_keep_their_own_directory() {
+    let mut h = Harness::new(&["docs/"]);
+    h.press("t");
+    h.select("docs/");
+    h.press("enter");
+    assert_eq!(h.current_dir(), h.fixture.path("docs"));
+
+    h.press("tab");
+    assert_eq!(h.current_dir(), h.fixture.root);
+    h.press("tab ctrl+w");
+    assert_eq!(h.current_dir(), h.fixture.root);
+}
+
+#[test]
+fn quits_on_q() {
+    let mut h = Harness::new(&[]);
+    assert!(!h.app.is_exiting());
+    h.press("q");
+    assert!(h.app.is_exiting());
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use std::{path::PathBuf, time::Duration};
+
+use browrs::config::Config;
+use common::{Fixture, Harness, test_config};
+use crossterm::event::Event;
+
+const MAKEFILE: &str = "Makefile=slow:\n\tsleep 1\n";
+
+/// A config that notifies after a second through a script writing down
+/// what it was given, and where it writes it.
+fn notifying(tools: &Fixture) -> (Config, PathBuf) {
+    let sent = tools.path("sent");
+    tools.add(&format!("notify.sh=echo \"$@\" > {}", sent.display()));
+    let config = Config {
+        notify_after: 1,
+        notifier: Some(format!("sh {}", tools.path("notify.sh").display())),
+        ..test_config()
+    };
+    (config, sent)
+}
+
+/// Runs the slow target and waits for it.
+fn run_slow(h: &mut Harness) {
+    h.press("K m enter");
+    h.finish_jobs();
+}
+
+#[test]
+fn a_long_job_notifies_when_it_ends() {
+    let tools = Fixture::new(&[]);
+    let (config, sent) = notifying(&tools);
+    let mut h = Harness::with_config(&[MAKEFILE], config);
+    run_slow(&mut h);
+    h.wait_until(|_| sent.exists() && std::fs::read_to_string(&sent).unwrap().ends_with('\n'));
+    let sent = std::fs::read_to_string(&sent).unwrap();
+    assert!(sent.contains("make slow"), "{}", sent);
+    assert!(sent.contains("finished"), "{}", sent);
+}
+
+#[test]
+fn no_notification_with_focus_or_for_kinds_turned_off() {
+    let tools = Fixture::new(&[]);
+    let (config, sent) = notifying(&tools);
+    let mut h = Harness::with_config(&[MAKEFILE], config.clone());
+    h.app.handle(Event::FocusGained);
+    run_slow(&mut h);
+
+    let mut config = config;
+    config.notify.insert("command".to_string(), 0);
+    let mut h = Harness::with_config(&[MAKEFILE], config);
+    run_slow(&mut h);
+    std::thread::sleep(Duration::from_millis(200));
+    assert!(!sent.exists());
+}
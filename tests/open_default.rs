@@ -0,0 +1,45 @@
+mod common;
+
+use browrs::config::Config;
+use common::{Harness, test_config};
+
+fn with_opener(opener: &str) -> Harness {
+    let config = Config {
+        opener: Some(opener.to_string()),
+        ..test_config()
+    };
+    let mut h = Harness::with_config(&["a.txt=a"], config);
+    h.select("a.txt");
+    h
+}
+
+#[test]
+fn opens_with_the_default_application() {
+    let mut h = with_opener("true");
+    h.press("o");
+    assert!(!h.screen().contains("Open a.txt with"));
+}
+
+#[test]
+fn offers_other_programs_when_that_fails() {
+    let mut h = with_opener("false");
+    h.press("o");
+    let screen = h.screen();
+    assert!(screen.contains("Open a.txt with (exited with"));
+    assert!(screen.contains("Other…"));
+}
+
+#[test]
+fn a_typed_command_opens_it_instead() {
+    let mut h = with_opener("false");
+    h.press("o");
+    // Other… is last
+    for _ in 0..20 {
+        h.press("down");
+    }
+    h.press("enter");
+    assert!(h.screen().contains("Open a.txt with"));
+    h.type_text("rm");
+    h.press("enter");
+    assert!(!h.fixture.exists("a.txt"));
+}
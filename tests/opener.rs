@@ -0,0 +1,85 @@
+mod common;
+
+use std::path::Path;
+
+use browrs::{config::Config, opener::Rule};
+use common::{Harness, test_config};
+
+fn with_rules(table: &str) -> Harness {
+    let config = Config {
+        open_rules: Config::parse(table).open_rules,
+        ..test_config()
+    };
+    Harness::with_config(
+        &["docs/a.pdf=pdf", "docs/b.md=md", "docs/c.txt=txt"],
+        config,
+    )
+}
+
+#[test]
+fn rules_are_read_from_the_open_table() {
+    let (config, errors) = Config::parse_checked(
+        "[open]\npdf = \"zathura {file} &\"\n\"*.md\" = \"glow\"\nx = \"\"\n",
+    );
+    assert_eq!(
+        config.open_rules,
+        [
+            Rule::parse("*.pdf", "zathura {file} &").unwrap(),
+            Rule::parse("*.md", "glow").unwrap(),
+        ]
+    );
+    assert!(config.open_rules[0].gui);
+    assert!(!config.open_rules[1].gui);
+    assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn placeholders_are_filled_in_quoted() {
+    let path = Path::new("/tmp/my docs/a.pdf");
+    let rule = Rule::parse("pdf", "viewer --in {dir} {file}").unwrap();
+    assert_eq!(
+        rule.expand(path),
+        "viewer --in '/tmp/my docs' '/tmp/my docs/a.pdf'"
+    );
+    let rule = Rule::parse("pdf", "viewer").unwrap();
+    assert_eq!(rule.expand(path), "viewer '/tmp/my docs/a.pdf'");
+}
+
+#[test]
+fn placeholders_in_names_are_left_alone() {
+    let rule = Rule::parse("pdf", "viewer {file} --in {dir} {other}").unwrap();
+    assert_eq!(
+        rule.expand(Path::new("/tmp/{file}/x{dir}.pdf")),
+        "viewer '/tmp/{file}/x{dir}.pdf' --in '/tmp/{file}' {other}"
+    );
+}
+
+#[test]
+fn opening_a_matching_file_runs_its_rule() {
+    let mut h = with_rules(
+        "[open]\n\"*.md\" = \"cp {file} {dir}/ran\"\n\"*.PDF\" = \"touch {dir}/gui &\"\n",
+    );
+    h.select("docs/");
+    h.press("enter");
+    h.select("b.md");
+    h.press("enter");
+    assert_eq!(h.fixture.read("docs/ran"), "md");
+
+    // Patterns with capitals match case
+    h.select("a.pdf");
+    h.press("enter");
+    h.select("c.txt");
+    h.press("enter");
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    assert!(!h.fixture.exists("docs/gui"));
+}
+
+#[test]
+fn gui_rules_run_in_the_background() {
+    let mut h = with_rules("[open]\npdf = \"touch {dir}/gui &\"\n");
+    h.select("docs/");
+    h.press("enter");
+    h.select("a.pdf");
+    h.press("enter");
+    h.wait_until(|h| h.fixture.exists("docs/gui"));
+}
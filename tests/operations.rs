@@ -0,0 +1,77 @@
+mod common;
+
+use common::Harness;
+
+#[test]
+fn renames_the_selected_entry() {
+    let mut h = Harness::new(&["old.txt=contents"]);
+    h.select("old.txt");
+    h.press("r ctrl+u");
+    h.type_text("new.txt");
+    h.press("enter");
+    assert!(!h.fixture.exists("old.txt"));
+    assert_eq!(h.fixture.read("new.txt"), "contents");
+    assert_eq!(h.selected(), "new.txt");
+}
+
+#[test]
+fn rename_asks_before_overwriting() {
+    let mut h = Harness::new(&["a.txt=a", "b.txt=b"]);
+    h.select("a.txt");
+    h.press("r ctrl+u");
+    h.type_text("b.txt");
+    h.press("enter");
+    assert!(h.screen().contains("b.txt already exists, replace it?"));
+    h.press("n");
+    assert_eq!(h.fixture.read("b.txt"), "b");
+
+    h.press("r ctrl+u");
+    h.type_text("b.txt");
+    h.press("enter y");
+    assert!(!h.fixture.exists("a.txt"));
+    assert_eq!(h.fixture.read("b.txt"), "a");
+}
+
+#[test]
+fn copies_marked_entries_into_a_directory() {
+    let mut h = Harness::new(&["a=1", "b=2", "c=3", "dest/"]);
+    h.select("a");
+    h.press("space");
+    h.select("c");
+    h.press("space c ctrl+u");
+    let dest = h.fixture.path("dest");
+    h.type_text(&dest.display().to_string());
+    h.press("enter");
+    h.finish_jobs();
+    assert_eq!(h.fixture.read("dest/a"), "1");
+    assert_eq!(h.fixture.read("dest/c"), "3");
+    assert!(!h.fixture.exists("dest/b"));
+    assert!(h.fixture.exists("a"));
+}
+
+#[test]
+fn moves_the_selected_entry() {
+    let mut h = Harness::new(&["dir/file.txt=x", "dest/"]);
+    h.select("dir/");
+    h.press("m ctrl+u");
+    let dest = h.fixture.path("dest");
+    h.type_text(&dest.display().to_string());
+    h.press("enter");
+    h.finish_jobs();
+    assert_eq!(h.fixture.read("dest/dir/file.txt"), "x");
+    assert!(!h.fixture.exists("dir"));
+    assert_eq!(h.listing(), ["..", "dest/"]);
+}
+
+#[test]
+fn deletes_permanently_after_confirming() {
+    let mut h = Harness::new(&["keep", "gone/inner"]);
+    h.select("gone/");
+    h.press("D esc");
+    assert!(h.fixture.exists("gone"));
+    h.press("D y");
+    h.finish_jobs();
+    assert!(!h.fixture.exists("gone"));
+    assert_eq!(h.listing(), ["..", "keep"]);
+    assert!(h.screen().contains("Updated 1 entries"));
+}
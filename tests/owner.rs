@@ -0,0 +1,51 @@
+mod common;
+
+use std::os::unix::fs::MetadataExt;
+
+use common::Harness;
+
+#[cfg(target_os = "linux")]
+#[test]
+fn tab_completes_user_and_group_names() {
+    let mut h = Harness::new(&["file"]);
+    h.select("file");
+    h.press("alt+o ctrl+u");
+    h.type_text("roo");
+    h.press("tab");
+    h.type_text(":roo");
+    h.press("tab");
+    let screen = h.screen();
+    assert!(screen.contains("root:root▏"), "{}", screen);
+}
+
+#[test]
+fn an_unknown_user_is_reported_in_the_prompt() {
+    let mut h = Harness::new(&["file"]);
+    h.select("file");
+    h.press("alt+o ctrl+u");
+    h.type_text("no-such-user");
+    h.press("enter");
+    let screen = h.screen();
+    assert!(screen.contains("Unknown user: no-such-user"), "{}", screen);
+    assert!(screen.contains("no-such-user▏"), "{}", screen);
+    h.press("esc");
+    assert!(!h.screen().contains("Owner:"));
+}
+
+#[test]
+fn the_owner_of_marked_entries_changes() {
+    let mut h = Harness::new(&["a", "b", "c"]);
+    let uid = std::fs::metadata(h.fixture.path("a")).unwrap().uid();
+    if uid != 0 {
+        // Only root can give files away
+        return;
+    }
+    h.press("down space space alt+o ctrl+u");
+    h.type_text("nobody");
+    h.press("enter");
+    let owner = |name: &str| std::fs::metadata(h.fixture.path(name)).unwrap().uid();
+    assert_eq!(owner("a"), 65534);
+    assert_eq!(owner("b"), 65534);
+    assert_eq!(owner("c"), 0);
+    assert!(h.screen().contains("Updated 2 entries"));
+}
@@ -0,0 +1,52 @@
+mod common;
+
+use browrs::config::Config;
+use common::{Harness, test_config};
+
+fn titled(template: &str, entries: &[&str]) -> Harness {
+    let config = Config {
+        pane_title: Some(template.to_string()),
+        ..test_config()
+    };
+    Harness::with_config(entries, config)
+}
+
+#[test]
+fn the_title_follows_the_template() {
+    let mut h = titled("{count} in {path} by {sort}", &["a.txt", "b.txt", "docs/"]);
+    let title = format!(" 3 in {} by name ", h.fixture.root.display());
+    let screen = h.screen();
+    assert!(screen.contains(&title), "{}", screen);
+    assert!(!screen.contains("Directory:"), "{}", screen);
+    assert!(!screen.contains("sort: name"), "{}", screen);
+}
+
+#[test]
+fn paths_can_be_shortened() {
+    let mut h = titled("{shortpath}", &["projects/browrs/src/"]);
+    h.select("projects/");
+    h.press("enter");
+    h.select("browrs/");
+    h.press("enter");
+    // Every directory above the last cut to a letter
+    let root = h.fixture.root.display().to_string();
+    let letters: String = root
+        .split('/')
+        .filter(|part| !part.is_empty())
+        .map(|part| format!("/{}", &part[..1]))
+        .collect();
+    let short = format!(" {}/p/browrs ", letters);
+    let screen = h.screen();
+    assert!(screen.contains(&short), "{}", screen);
+}
+
+#[test]
+fn the_filter_and_total_fill_in_while_narrowed() {
+    let mut h = titled("{count}/{total} [{filter}]", &["a.rs", "b.rs", "c.txt"]);
+    assert!(h.screen().contains(" 3/3 [] "));
+    h.press("f");
+    h.type_text("*.rs");
+    h.press("enter");
+    let screen = h.screen();
+    assert!(screen.contains(" 2/3 [*.rs] "), "{}", screen);
+}
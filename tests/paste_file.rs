@@ -0,0 +1,48 @@
+mod common;
+
+use browrs::config::Config;
+use common::{Fixture, Harness, test_config};
+
+/// A harness whose clipboard holds what `clipboard` in a separate fixture
+/// does.
+fn with_clipboard(entries: &[&str], text: &str) -> (Harness, Fixture) {
+    let tools = Fixture::new(&[]);
+    std::fs::write(tools.path("clipboard"), text).unwrap();
+    let config = Config {
+        paste_command: Some(format!("cat {}", tools.path("clipboard").display())),
+        ..test_config()
+    };
+    (Harness::with_config(entries, config), tools)
+}
+
+#[test]
+fn ctrl_v_writes_the_clipboard_to_a_new_file() {
+    let (mut h, _tools) = with_clipboard(&["pasted.txt"], "line one\nline two\n");
+    h.press("ctrl+v");
+    let screen = h.screen();
+    assert!(screen.contains("clipboard's 2 lines"), "{}", screen);
+    assert!(screen.contains("pasted-2.txt"), "{}", screen);
+
+    h.press("ctrl+u");
+    h.type_text("logs/chat.log");
+    h.press("enter");
+    assert_eq!(h.fixture.read("logs/chat.log"), "line one\nline two\n");
+    assert_eq!(h.selected(), "logs/");
+}
+
+#[test]
+fn does_not_replace_an_existing_file() {
+    let (mut h, _tools) = with_clipboard(&["a.txt=kept"], "new");
+    h.press("ctrl+v ctrl+u");
+    h.type_text("a.txt");
+    h.press("enter");
+    assert_eq!(h.fixture.read("a.txt"), "kept");
+    assert!(h.screen().contains("already exists"), "{}", h.screen());
+}
+
+#[test]
+fn says_so_when_the_clipboard_is_empty() {
+    let (mut h, _tools) = with_clipboard(&[], "");
+    h.press("ctrl+v");
+    assert!(h.screen().contains("clipboard is empty"), "{}", h.screen());
+}
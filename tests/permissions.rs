@@ -0,0 +1,64 @@
+mod common;
+
+use std::os::unix::fs::PermissionsExt;
+
+use common::Harness;
+
+fn with_modes(entries: &[(&str, u32)]) -> Harness {
+    let names: Vec<&str> = entries.iter().map(|(name, _)| *name).collect();
+    let mut h = Harness::new(&["dir/"]);
+    for (name, mode) in entries {
+        h.fixture.add(&format!("dir/{}", name));
+        std::fs::set_permissions(
+            h.fixture.path(&format!("dir/{}", name)),
+            std::fs::Permissions::from_mode(*mode),
+        )
+        .unwrap();
+    }
+    h.select("dir/");
+    h.press("enter");
+    assert_eq!(h.listing()[1..], names);
+    h
+}
+
+fn mode_of(h: &Harness, name: &str) -> u32 {
+    let path = h.fixture.path(&format!("dir/{}", name));
+    std::fs::metadata(path).unwrap().permissions().mode() & 0o7777
+}
+
+#[test]
+fn checkboxes_toggle_bits_of_the_selected_file() {
+    let mut h = with_modes(&[("run.sh", 0o644)]);
+    h.select("run.sh");
+    assert!(h.screen().contains(" -rw-r--r-- "));
+    h.press("P");
+    let screen = h.screen();
+    assert!(screen.contains("Permissions of run.sh"), "{}", screen);
+    assert!(screen.contains("Mode: 0644"), "{}", screen);
+
+    // Execute for the owner
+    h.press("right right space");
+    assert!(h.screen().contains("Mode: 0744  rwxr--r--"));
+    h.press("enter");
+    assert_eq!(mode_of(&h, "run.sh"), 0o744);
+}
+
+#[test]
+fn a_typed_mode_applies_to_every_marked_entry() {
+    let mut h = with_modes(&[("a.key", 0o644), ("b.key", 0o664), ("c.txt", 0o644)]);
+    h.press("down space space P");
+    assert!(h.screen().contains("Permissions of 2 entries"));
+    h.press("6 0 0 enter");
+    assert_eq!(mode_of(&h, "a.key"), 0o600);
+    assert_eq!(mode_of(&h, "b.key"), 0o600);
+    assert_eq!(mode_of(&h, "c.txt"), 0o644);
+}
+
+#[test]
+fn esc_leaves_the_mode_alone() {
+    let mut h = with_modes(&[("notes", 0o640)]);
+    h.select("notes");
+    h.press("P 7 7 7 esc");
+    assert!(!h.screen().contains("Permissions of"));
+    assert_eq!(mode_of(&h, "notes"), 0o640);
+}
@@ -0,0 +1,60 @@
+mod common;
+
+use browrs::visits::Visits;
+use common::{Harness, test_config};
+
+fn harness(entries: &[&str], suggest_places: bool) -> Harness {
+    let mut config = test_config();
+    config.suggest_places = suggest_places;
+    let mut h = Harness::with_config(entries, config);
+    let file = h.fixture.path("state/visits");
+    h.app.set_visits(Visits::in_file(file));
+    h
+}
+
+/// Opens `dir` from the root and goes back again.
+fn visit(h: &mut Harness, dir: &str) {
+    h.select(dir);
+    h.press("enter backspace");
+}
+
+#[test]
+fn the_most_visited_directories_follow_the_bookmarks() {
+    let mut h = harness(&["music/", "photos/", "work/"], true);
+    visit(&mut h, "photos/");
+    visit(&mut h, "work/");
+    visit(&mut h, "work/");
+    h.press("'");
+    let screen = h.screen();
+    let work = screen.find("work  ↻ 2 visits").expect(&screen);
+    let photos = screen.find("photos  ↻ 1 visit").expect(&screen);
+    assert!(work < photos, "{}", screen);
+    assert!(!screen.contains("music  ↻"), "{}", screen);
+
+    h.press("enter");
+    assert_eq!(h.current_dir(), h.fixture.path("work"));
+}
+
+#[test]
+fn bookmarked_directories_are_not_suggested_and_d_forgets_one() {
+    let mut h = harness(&["photos/", "work/"], true);
+    visit(&mut h, "photos/");
+    h.select("work/");
+    h.press("enter b backspace");
+    h.press("'");
+    let screen = h.screen();
+    assert!(!screen.contains("work  ↻"), "{}", screen);
+    assert!(screen.contains("photos  ↻ 1 visit"), "{}", screen);
+
+    h.press("down d");
+    assert!(!h.screen().contains("photos  ↻"));
+}
+
+#[test]
+fn suggest_places_off_counts_nothing() {
+    let mut h = harness(&["photos/"], false);
+    visit(&mut h, "photos/");
+    h.press("'");
+    assert!(!h.screen().contains("↻"));
+    assert!(!h.fixture.exists("state/visits"));
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use std::time::Duration;
+
+use browrs::config::Config;
+use common::{Harness, test_config};
+
+/// A harness whose previews all time out unless they were read ahead.
+fn only_prefetched(entries: &[&str]) -> Harness {
+    let config = Config {
+        preview_timeout_ms: 0,
+        ..test_config()
+    };
+    Harness::with_config(entries, config)
+}
+
+/// Lets the keys rest long enough for the previews around to be read.
+fn idle(h: &mut Harness) {
+    for _ in 0..20 {
+        std::thread::sleep(Duration::from_millis(20));
+        h.app.tick();
+    }
+}
+
+#[test]
+fn the_entries_next_to_the_cursor_are_read_ahead() {
+    // The cursor starts on ..
+    let mut h = only_prefetched(&["a.txt=alpha", "b.txt=bravo", "c.txt=charlie"]);
+    idle(&mut h);
+    h.press("down");
+    assert!(h.screen().contains("alpha"), "{}", h.screen());
+
+    // Straight to the bottom, without reading what is on the way
+    h.press("G");
+    idle(&mut h);
+    h.press("up");
+    assert!(h.screen().contains("bravo"), "{}", h.screen());
+}
+
+#[test]
+fn the_first_entries_of_a_directory_are_read_ahead() {
+    let mut h = only_prefetched(&["docs/guide.md=the guide", "docs/intro.md=hello"]);
+    h.select("docs/");
+    idle(&mut h);
+    h.press("enter");
+    h.select("guide.md");
+    assert!(h.screen().contains("the guide"), "{}", h.screen());
+}
+
+#[test]
+fn a_file_changed_since_is_read_again() {
+    let mut h = Harness::new(&["a.txt=alpha", "b.txt=bravo"]);
+    h.select("a.txt");
+    h.press("down");
+    std::fs::write(h.fixture.path("a.txt"), "alpha, rewritten").unwrap();
+    h.press("up");
+    assert!(h.screen().contains("alpha, rewritten"));
+}
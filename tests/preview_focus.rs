@@ -0,0 +1,84 @@
+mod common;
+
+use common::Harness;
+use ratatui::style::Color;
+
+fn long_file(lines: usize) -> String {
+    let text: String = (1..=lines).map(|i| format!("line {}\n", i)).collect();
+    format!("long.txt={}", text)
+}
+
+#[test]
+fn l_hands_the_arrow_keys_to_the_preview_and_back() {
+    let mut h = Harness::new(&[&long_file(120), "other.txt"]);
+    h.select("long.txt");
+    h.screen();
+    h.press("l down down down");
+    assert_eq!(h.selected(), "long.txt");
+    let screen = h.screen();
+    assert!(!screen.contains("line 1 "), "{}", screen);
+
+    h.press("pagedown");
+    assert!(!h.screen().contains("line 10 "));
+    h.press("g g");
+    assert!(h.screen().contains("line 1 "));
+
+    h.press("l down");
+    assert_eq!(h.selected(), "other.txt");
+}
+
+#[test]
+fn previews_go_past_fifty_lines() {
+    let mut h = Harness::new(&[&long_file(120)]);
+    h.select("long.txt");
+    h.screen();
+    h.press("tab G");
+    let screen = h.screen();
+    assert!(screen.contains("line 120"), "{}", screen);
+    assert!(!screen.contains("more lines"), "{}", screen);
+
+    // Back on the listing, moving away and back starts at the top again
+    h.press("esc up down");
+    assert!(h.screen().contains("line 1 "));
+}
+
+#[test]
+fn page_keys_move_through_the_listing() {
+    let files: Vec<String> = (0..60).map(|i| format!("f{:02}", i)).collect();
+    let files: Vec<&str> = files.iter().map(String::as_str).collect();
+    let mut h = Harness::new(&files);
+    h.screen();
+    h.press("pagedown");
+    let page = h.app.pane().selected;
+    assert!(page > 10, "moved {}", page);
+    h.press("pagedown pageup");
+    assert_eq!(h.app.pane().selected, page);
+}
+
+#[test]
+fn long_files_load_further_as_the_preview_scrolls() {
+    let mut h = Harness::new(&[&long_file(5000)]);
+    h.select("long.txt");
+    let screen = h.screen();
+    assert!(screen.contains("5000 lines"), "{}", screen);
+    h.press("tab G");
+    let screen = h.screen();
+    assert!(screen.contains("line 5000"), "{}", screen);
+
+    h.press("g g");
+    assert!(h.screen().contains("line 1 "));
+}
+
+#[test]
+fn comments_stay_highlighted_past_the_first_lines() {
+    let mut text: String = (0..150).map(|_| "let x = 1;\n").collect();
+    text.push_str("/*\n");
+    text.extend((1..=100).map(|i| format!("note {}\n", i)));
+    text.push_str("zzz_last */\n");
+    let file = format!("big.rs={}", text);
+    let mut h = Harness::new(&[&file]);
+    h.select("big.rs");
+    h.screen();
+    h.press("tab G");
+    assert_eq!(h.color_of("zzz_last"), Color::DarkGray);
+}
@@ -0,0 +1,55 @@
+mod common;
+
+use browrs::{config::Config, settings};
+use common::{Harness, test_config};
+
+/// A preview that can't be read in no time at all times out.
+fn timing_out() -> Harness {
+    let config = Config {
+        preview_timeout_ms: 0,
+        ..test_config()
+    };
+    let text = "a line of text to read\n".repeat(40_000);
+    let mut h = Harness::with_config(&["small.txt=hi"], config);
+    std::fs::write(h.fixture.path("big.txt"), text).unwrap();
+    h.press("t");
+    h
+}
+
+#[test]
+fn a_slow_preview_times_out() {
+    let mut h = timing_out();
+    h.select("big.txt");
+    let screen = h.screen();
+    assert!(screen.contains("big.txt | preview timed out"), "{}", screen);
+    assert!(screen.contains("longer than 0 ms"), "{}", screen);
+    assert!(screen.contains("Press ctrl+r to try again"), "{}", screen);
+    assert!(!screen.contains("a line of text"), "{}", screen);
+}
+
+#[test]
+fn a_timed_out_preview_is_retried_on_request() {
+    let mut h = timing_out();
+    h.select("big.txt");
+    assert!(h.screen().contains("preview timed out"));
+
+    // Given more time, trying again reads it
+    h.press(",");
+    let timeout = settings::ALL
+        .iter()
+        .position(|s| s.key == "preview_timeout_ms")
+        .unwrap();
+    for _ in 0..timeout {
+        h.press("down");
+    }
+    h.press("enter ctrl+u");
+    h.type_text("10000");
+    h.press("enter esc");
+    h.select("big.txt");
+    // Left alone until then, even when selected again
+    assert!(h.screen().contains("preview timed out"));
+    h.press("ctrl+r");
+    let screen = h.screen();
+    assert!(screen.contains("a line of text"), "{}", screen);
+    assert!(!screen.contains("timed out"), "{}", screen);
+}
@@ -0,0 +1,83 @@
+mod common;
+
+use std::{io, path::Path, thread, time::Duration};
+
+use browrs::{config::Config, previewer::Previewer};
+use common::{Harness, test_config};
+use ratatui::style::Color;
+
+fn with_previewers(entries: &[&str], table: &str) -> Harness {
+    let config = Config {
+        previewers: Config::parse(&format!("[previewers]\n{}", table)).previewers,
+        ..test_config()
+    };
+    Harness::with_config(entries, config)
+}
+
+#[test]
+fn matching_files_preview_as_the_output_of_their_command() {
+    let mut h = with_previewers(
+        &[
+            "data.json={\"a\": 1}",
+            "notes.txt=plain words",
+            ".upper.sh=printf '\x1b[31m'; tr a-z A-Z < \"$1\"",
+        ],
+        "\"*.json\" = \"sh {dir}/.upper.sh {file}\"\n",
+    );
+    h.select("data.json");
+    let screen = h.screen();
+    assert!(screen.contains("{\"A\": 1}"), "{}", screen);
+    assert!(screen.contains("| sh"), "{}", screen);
+    assert_eq!(h.color_of("{\"A\": 1}"), Color::Red);
+
+    h.select("notes.txt");
+    assert!(h.screen().contains("plain words"));
+}
+
+#[test]
+fn a_failing_command_falls_back_to_the_usual_preview() {
+    let mut h = with_previewers(
+        &["table.csv=a,b\n1,2\n"],
+        "csv = \"echo no such tool >&2; exit 3\"\n",
+    );
+    h.select("table.csv");
+    let screen = h.screen();
+    assert!(
+        screen.contains("Previewer failed: no such tool"),
+        "{}",
+        screen
+    );
+    assert!(screen.contains("1,2"), "{}", screen);
+}
+
+#[test]
+fn the_file_is_appended_without_placeholders() {
+    let previewer = Previewer::parse("csv", "column -t").unwrap();
+    assert_eq!(previewer.template.pattern, "*.csv");
+    assert!(previewer.matches(Path::new("/data/it's.csv")));
+    assert_eq!(
+        previewer.expand(Path::new("/data/it's.csv")),
+        r"column -t '/data/it'\''s.csv'"
+    );
+    let previewer = Previewer::parse("*.log", "tail -n 5 {file} && ls {dir}").unwrap();
+    assert_eq!(
+        previewer.expand(Path::new("/var/app.log")),
+        "tail -n 5 '/var/app.log' && ls '/var'"
+    );
+}
+
+#[test]
+fn a_command_out_of_time_is_stopped_with_what_it_started() {
+    let h = Harness::new(&["slow.txt"]);
+    let previewer = Previewer::parse("txt", "(sleep 0.4; touch {dir}/late) & sleep 5").unwrap();
+    let error = previewer
+        .output(
+            &h.fixture.path("slow.txt"),
+            Duration::from_millis(100),
+            1024,
+        )
+        .unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::TimedOut);
+    thread::sleep(Duration::from_millis(800));
+    assert!(!h.fixture.exists("late"));
+}
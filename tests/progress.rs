@@ -0,0 +1,60 @@
+mod common;
+
+use common::Harness;
+
+fn copy_to(h: &mut Harness, dest: &str) {
+    h.press("c ctrl+u");
+    let dest = h.fixture.path(dest);
+    h.type_text(&dest.display().to_string());
+    h.press("enter");
+}
+
+#[test]
+fn operations_show_progress_until_they_end() {
+    let mut h = Harness::new(&["a.txt=aaaa", "dest/"]);
+    h.select("a.txt");
+    copy_to(&mut h, "dest");
+    let screen = h.screen();
+    assert!(screen.contains(" Progress "), "{}", screen);
+    assert!(screen.contains("⧉ Copying 1 entries"), "{}", screen);
+
+    h.finish_jobs();
+    let screen = h.screen();
+    assert!(!screen.contains(" Progress "), "{}", screen);
+    assert!(screen.contains("Updated 1 entries"), "{}", screen);
+    assert_eq!(h.fixture.read("dest/a.txt"), "aaaa");
+}
+
+#[test]
+fn the_panel_hides_and_comes_back() {
+    let mut h = Harness::new(&["a.txt", "dest/"]);
+    h.select("a.txt");
+    copy_to(&mut h, "dest");
+    h.press("enter");
+    let screen = h.screen();
+    assert!(!screen.contains(" Progress "), "{}", screen);
+    assert!(screen.contains("J to show"), "{}", screen);
+
+    h.press("J");
+    assert!(h.screen().contains(" Progress "));
+    h.finish_jobs();
+}
+
+#[test]
+fn esc_cancels_and_leaves_no_partial_copy() {
+    let files: Vec<String> = (0..300).map(|i| format!("big/{}.txt=data", i)).collect();
+    let files: Vec<&str> = files.iter().map(String::as_str).collect();
+    let mut h = Harness::new(&files);
+    h.select("big/");
+    copy_to(&mut h, "copy");
+    h.press("esc");
+    assert!(h.screen().contains("(cancelling)"));
+
+    h.finish_jobs();
+    // The copy may have finished before Esc got to it
+    if h.screen().contains("Cancelled") {
+        assert!(!h.fixture.exists("copy"));
+    } else {
+        assert!(h.fixture.exists("copy/299.txt"));
+    }
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use common::Harness;
+
+#[cfg(target_os = "linux")]
+#[test]
+fn proc_files_preview_what_reading_them_gives() {
+    let mut h = Harness::new(&["links/"]);
+    std::os::unix::fs::symlink("/proc/self", h.fixture.path("links/self")).unwrap();
+    h.select("links/");
+    h.press("enter");
+    h.select("self/");
+    h.press("enter");
+
+    // Listed with a size of 0, but not empty
+    h.select("status");
+    let screen = h.screen();
+    assert!(screen.contains("State:"), "{}", screen);
+
+    // Strings separated by NUL bytes are text, a line each
+    h.select("cmdline");
+    let screen = h.screen();
+    assert!(!screen.contains("binary"), "{}", screen);
+    assert!(screen.contains("pseudo-"), "{}", screen);
+}
+
+#[test]
+fn a_directory_that_is_gone_is_left_for_its_parent() {
+    let mut h = Harness::new(&["a/b/c.txt"]);
+    h.select("a/");
+    h.press("enter");
+    h.select("b/");
+    h.press("enter");
+    std::fs::remove_dir_all(h.fixture.path("a/b")).unwrap();
+    // Anything that lists the directory again
+    h.press(". .");
+    assert_eq!(h.current_dir(), h.fixture.path("a"));
+}
+
+#[test]
+fn the_cursor_stays_in_place_when_its_entry_goes() {
+    let mut h = Harness::new(&["one", "three", "two"]);
+    h.select("three");
+    std::fs::remove_file(h.fixture.path("three")).unwrap();
+    h.press(". .");
+    assert_eq!(h.selected(), "two");
+}
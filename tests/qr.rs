@@ -0,0 +1,34 @@
+mod common;
+
+use common::Harness;
+
+#[test]
+fn alt_q_shows_a_small_text_file_as_a_qr_code_and_tab_switches_to_its_path() {
+    let mut h = Harness::new(&["wifi.txt=WIFI:S:home;P:secret;;"]);
+    h.select("wifi.txt");
+    h.press("alt+q");
+    let screen = h.screen();
+    assert!(screen.contains("QR: file contents"), "{}", screen);
+    assert!(screen.contains("▀▀▀"), "{}", screen);
+
+    h.press("tab");
+    assert!(h.screen().contains("QR: path"), "{}", h.screen());
+    h.press("esc");
+    assert!(!h.screen().contains("QR:"), "{}", h.screen());
+}
+
+#[test]
+fn shows_the_path_of_directories_and_big_files() {
+    let big = format!("big.log={}", "x".repeat(1000));
+    let mut h = Harness::new(&[big.as_str(), "sub/"]);
+    h.select("big.log");
+    h.press("alt+q");
+    let screen = h.screen();
+    assert!(screen.contains("QR: path"), "{}", screen);
+    assert!(!screen.contains("Tab contents"), "{}", screen);
+
+    h.press("q");
+    h.select("sub/");
+    h.press("alt+q");
+    assert!(h.screen().contains("QR: path"), "{}", h.screen());
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use common::Harness;
+
+#[test]
+fn edits_are_written_back_on_ctrl_s() {
+    let mut h = Harness::new(&["app.conf=port = 80\ndebug = true\n"]);
+    h.select("app.conf");
+    h.press("E end backspace backspace");
+    h.type_text("8080");
+    h.press("down ctrl+k");
+    assert!(h.screen().contains("app.conf [+]"));
+
+    h.press("ctrl+s");
+    assert_eq!(h.fixture.read("app.conf"), "port = 8080\n");
+    assert!(!h.screen().contains("app.conf [+]"));
+
+    h.press("esc");
+    let screen = h.screen();
+    assert!(screen.contains("Saved app.conf"), "{}", screen);
+    assert!(!screen.contains("Ctrl+S save"), "{}", screen);
+}
+
+#[test]
+fn enter_splits_lines_and_a_missing_final_newline_stays_missing() {
+    let mut h = Harness::new(&["notes=ab"]);
+    h.select("notes");
+    h.press("E right enter ctrl+s esc");
+    assert_eq!(h.fixture.read("notes"), "a\nb");
+}
+
+#[test]
+fn unsaved_changes_need_a_second_esc() {
+    let mut h = Harness::new(&["notes=keep me\n"]);
+    h.select("notes");
+    h.press("E");
+    h.type_text("x");
+    h.press("esc");
+    let screen = h.screen();
+    assert!(screen.contains("Esc again discards them"), "{}", screen);
+
+    h.press("esc");
+    assert!(!h.screen().contains("notes [+]"));
+    assert_eq!(h.fixture.read("notes"), "keep me\n");
+}
+
+#[test]
+fn big_or_binary_files_are_left_to_the_editor() {
+    let big = format!("big.txt={}", "x".repeat(100 * 1024));
+    let mut h = Harness::new(&[&big, "blob.bin=a\0b"]);
+    h.select("big.txt");
+    h.press("E");
+    let screen = h.screen();
+    assert!(screen.contains("Unable to edit"), "{}", screen);
+    assert!(!screen.contains("Ctrl+S save"), "{}", screen);
+
+    h.press("esc");
+    h.select("blob.bin");
+    h.press("E");
+    let screen = h.screen();
+    assert!(screen.contains("Unable to edit"), "{}", screen);
+}
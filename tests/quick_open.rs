@@ -0,0 +1,48 @@
+mod common;
+
+use common::Harness;
+
+#[test]
+fn digits_open_numbered_entries_while_filtering() {
+    let mut h = Harness::new(&["d1/", "d2/", "notes.md", "x.rs"]);
+    h.press("f");
+    h.type_text("*.rs");
+    h.press("enter");
+    let screen = h.screen();
+    assert!(screen.contains("│  .."), "{}", screen);
+    assert!(screen.contains("│1 d1/"), "{}", screen);
+    assert!(screen.contains("│3 x.rs"), "{}", screen);
+
+    h.press("2");
+    assert_eq!(h.current_dir(), h.fixture.path("d2"));
+}
+
+#[test]
+fn digits_do_nothing_without_a_filter() {
+    let mut h = Harness::new(&["d1/", "d2/"]);
+    h.press("2");
+    assert_eq!(h.current_dir(), h.fixture.root);
+    assert!(!h.screen().contains("1 d1/"));
+}
+
+#[test]
+fn alt_digit_opens_a_search_result() {
+    let mut h = Harness::new(&["deep/er/wanted.txt", "deep/want.md"]);
+    h.press("/");
+    h.type_text("want");
+    h.wait_until(|h| h.screen().contains(" 2 matches "));
+    let screen = h.screen();
+    let second = screen
+        .lines()
+        .find_map(|line| line.split_once("│2 ").map(|(_, rest)| rest))
+        .unwrap_or_else(|| panic!("{}", screen))
+        .split_whitespace()
+        .next()
+        .unwrap()
+        .to_string();
+    let name = second.rsplit('/').next().unwrap().to_string();
+
+    h.press("alt+2");
+    assert_eq!(h.selected(), name);
+    assert!(h.current_dir().starts_with(h.fixture.path("deep")));
+}
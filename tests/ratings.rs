@@ -0,0 +1,63 @@
+mod common;
+
+use browrs::{annotations::Annotations, sort::Sort};
+use common::{Harness, test_config};
+
+fn harness(entries: &[&str], sort: Sort) -> Harness {
+    let mut config = test_config();
+    config.sort = sort;
+    let mut h = Harness::with_config(entries, config);
+    let file = h.fixture.path("data/annotations");
+    h.app.set_annotations(Annotations::load(file));
+    h
+}
+
+#[test]
+fn stars_show_as_a_column_and_persist() {
+    let mut h = harness(&["a.jpg", "b.jpg", "c.jpg"], Sort::Name);
+    h.select("b.jpg");
+    h.press("* 3");
+    let screen = h.screen();
+    let row = screen.lines().find(|l| l.contains("│b.jpg")).unwrap();
+    // Right-aligned against the pane's border
+    assert!(row.contains(" ★★★│"), "{}", screen);
+
+    h.select("a.jpg");
+    h.press("space");
+    h.select("c.jpg");
+    h.press("space * 5");
+    h.select("b.jpg");
+    h.press("* 0");
+    let reloaded = Annotations::load(h.fixture.path("data/annotations"));
+    assert_eq!(reloaded.rating(&h.fixture.path("a.jpg")), 5);
+    assert_eq!(reloaded.rating(&h.fixture.path("b.jpg")), 0);
+    assert_eq!(reloaded.rating(&h.fixture.path("c.jpg")), 5);
+}
+
+#[test]
+fn sorting_by_rating_puts_the_most_stars_first() {
+    let mut h = harness(&["a.wav", "b.wav", "c.wav"], Sort::Rating);
+    h.select("c.wav");
+    h.press("* 2");
+    h.select("b.wav");
+    h.press("* 4");
+    assert_eq!(h.listing(), ["..", "b.wav", "c.wav", "a.wav", "data/"]);
+    assert_eq!(h.selected(), "b.wav");
+}
+
+#[test]
+fn alt_and_a_digit_lists_only_files_with_that_many_stars() {
+    let mut h = harness(&["one.png", "two.png", "three.png", "raw/"], Sort::Name);
+    h.select("two.png");
+    h.press("* 2");
+    h.select("three.png");
+    h.press("* 3");
+    h.press("alt+3");
+    assert_eq!(h.listing(), ["..", "data/", "raw/", "three.png"]);
+    assert!(h.screen().contains("★3+: 3 of 5 entries"));
+
+    h.press("alt+2");
+    assert_eq!(h.listing(), ["..", "data/", "raw/", "three.png", "two.png"]);
+    h.press("alt+0");
+    assert_eq!(h.listing().len(), 6);
+}
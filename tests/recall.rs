@@ -0,0 +1,87 @@
+mod common;
+
+use browrs::{
+    config::Config,
+    recall::{Kind, Recall},
+};
+use common::{Fixture, Harness, test_config};
+
+fn harness(state: &Fixture, config: Config) -> Harness {
+    let mut h = Harness::with_config(&["a.rs=a", "b.txt=b"], config);
+    h.app.set_recall(Recall::in_dir(state.root.clone()));
+    h
+}
+
+fn run_command(h: &mut Harness, command: &str) {
+    h.press(":");
+    h.type_text(command);
+    h.press("enter");
+}
+
+#[test]
+fn up_and_down_step_through_earlier_commands() {
+    let state = Fixture::new(&[]);
+    let mut h = harness(&state, test_config());
+    run_command(&mut h, "first");
+    run_command(&mut h, "second");
+    run_command(&mut h, "first");
+
+    h.press(":");
+    h.type_text("dra");
+    h.press("up");
+    assert!(h.screen().contains("first▏"));
+    h.press("up");
+    assert!(h.screen().contains("second▏"));
+    h.press("up");
+    assert!(h.screen().contains("second▏"));
+    h.press("down down");
+    assert!(h.screen().contains("dra▏"));
+    assert_eq!(state.read("command"), "second\nfirst\n");
+}
+
+#[test]
+fn history_is_shared_with_other_instances() {
+    let state = Fixture::new(&[]);
+    let mut one = harness(&state, test_config());
+    let mut two = harness(&state, test_config());
+    one.press("f");
+    one.type_text("*.rs");
+    one.press("enter");
+    one.press("/");
+    one.type_text("b.t");
+    one.wait_until(|h| h.screen().contains("b.txt"));
+    one.press("enter");
+
+    two.press("f ctrl+u up");
+    assert!(two.screen().contains("*.rs▏"));
+    two.press("esc / ctrl+p");
+    assert!(two.screen().contains("Search: b.t▏"));
+}
+
+#[test]
+fn without_saving_history_lasts_the_session() {
+    let state = Fixture::new(&[]);
+    let config = Config {
+        save_history: false,
+        ..test_config()
+    };
+    let mut h = harness(&state, config);
+    run_command(&mut h, "kept");
+    h.press(": up");
+    assert!(h.screen().contains("kept▏"));
+    assert!(!state.exists("command"));
+}
+
+#[test]
+fn only_the_newest_entries_are_kept() {
+    let state = Fixture::new(&[]);
+    let mut recall = Recall::in_dir(state.root.clone());
+    recall.size = 2;
+    for entry in ["a", "b", " ", "c", "b"] {
+        recall.add(Kind::Search, entry).unwrap();
+    }
+    assert_eq!(recall.entries(Kind::Search), ["c", "b"]);
+    recall.size = 0;
+    recall.add(Kind::Search, "d").unwrap();
+    assert!(recall.entries(Kind::Search).is_empty());
+}
@@ -0,0 +1,44 @@
+mod common;
+
+use common::Harness;
+
+#[test]
+fn r_lists_new_entries_and_keeps_the_cursor_and_marks() {
+    let mut h = Harness::new(&["b.txt", "d.txt", "e.txt"]);
+    h.select("b.txt");
+    h.press("space");
+    h.select("e.txt");
+    h.fixture.add("a.txt");
+    h.fixture.add("c.txt");
+    h.press("R");
+    assert_eq!(
+        h.listing(),
+        ["..", "a.txt", "b.txt", "c.txt", "d.txt", "e.txt"]
+    );
+    assert_eq!(h.selected(), "e.txt");
+    // The mark stays with its entry, not its old place
+    let screen = h.screen();
+    assert!(screen.contains("* b.txt"), "{}", screen);
+    assert!(!screen.contains("* c.txt"), "{}", screen);
+}
+
+#[test]
+fn r_keeps_the_filter_and_the_scroll_position() {
+    let names: Vec<String> = (10..60).map(|n| format!("f{}.log", n)).collect();
+    let entries: Vec<&str> = names.iter().map(String::as_str).collect();
+    let mut h = Harness::new(&entries);
+    h.press("f");
+    h.type_text("*.log");
+    h.press("enter");
+    h.select("f40.log");
+    h.press("up up up up up");
+    let before = h.screen();
+    h.fixture.add("f99.log");
+    h.fixture.add("other.txt");
+    h.press("R");
+    assert_eq!(h.selected(), "f35.log");
+    assert!(h.listing().contains(&"f99.log".to_string()));
+    assert!(!h.listing().contains(&"other.txt".to_string()));
+    let first_row = |screen: &str| screen.lines().nth(3).unwrap_or_default().to_string();
+    assert_eq!(first_row(&h.screen()), first_row(&before));
+}
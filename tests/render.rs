@@ -0,0 +1,40 @@
+mod common;
+
+use common::Harness;
+
+#[test]
+fn shows_the_directory_and_its_entries() {
+    let mut h = Harness::new(&["docs/", "notes.txt"]);
+    let screen = h.screen();
+    assert!(screen.contains(&format!("Directory: {}", h.fixture.root.display())));
+    assert!(screen.contains("docs/"));
+    assert!(screen.contains("notes.txt"));
+}
+
+#[test]
+fn previews_the_selected_file() {
+    let mut h = Harness::new(&["notes.txt=first line\nsecond line\n"]);
+    h.select("notes.txt");
+    let screen = h.screen();
+    assert!(screen.contains("first line"));
+    assert!(screen.contains("second line"));
+}
+
+#[test]
+fn prompts_render_over_the_listing() {
+    let mut h = Harness::new(&["a.txt"]);
+    h.select("a.txt");
+    h.press("r");
+    assert!(h.screen().contains("Rename a.txt"));
+    h.press("esc");
+    assert!(!h.screen().contains("Rename a.txt"));
+}
+
+#[test]
+fn dual_pane_shows_both_panes() {
+    let mut h = Harness::new(&["docs/"]);
+    let title = format!("Directory: {}", h.fixture.root.display());
+    assert_eq!(h.screen().matches(&title).count(), 1);
+    h.press("|");
+    assert_eq!(h.screen().matches(&title).count(), 2);
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use common::Harness;
+
+#[test]
+fn collects_entries_from_several_directories_and_copies_them_at_once() {
+    let mut h = Harness::new(&["one/a.txt", "one/b.txt", "two/c.txt", "dest/"]);
+    h.select("one/");
+    h.press("enter");
+    h.select("a.txt");
+    h.press("y");
+    h.press("backspace");
+    h.select("two/");
+    h.press("enter");
+    h.select("c.txt");
+    h.press("y");
+    assert!(h.screen().contains("2 in the scratch"), "{}", h.screen());
+
+    h.press("Y");
+    let screen = h.screen();
+    assert!(screen.contains("Scratch (2)"), "{}", screen);
+    assert!(
+        screen.contains("a.txt") && screen.contains("c.txt"),
+        "{}",
+        screen
+    );
+
+    let dest = h.fixture.path("dest");
+    h.press("c ctrl+u");
+    h.type_text(&dest.display().to_string());
+    h.press("enter");
+    h.finish_jobs();
+    assert!(h.fixture.exists("dest/a.txt"));
+    assert!(h.fixture.exists("dest/c.txt"));
+    assert!(!h.fixture.exists("dest/b.txt"));
+    // The originals stay where they were, and the scratch starts over
+    assert!(h.fixture.exists("one/a.txt"));
+    h.press("Y");
+    assert!(
+        h.screen().contains("The scratch is empty"),
+        "{}",
+        h.screen()
+    );
+}
+
+#[test]
+fn collecting_again_takes_entries_out() {
+    let mut h = Harness::new(&["a.txt", "b.txt"]);
+    h.select("a.txt");
+    h.press("y");
+    h.select("b.txt");
+    h.press("y");
+    h.press("y");
+    assert!(h.screen().contains("1 left"), "{}", h.screen());
+    h.press("Y");
+    let screen = h.screen();
+    assert!(screen.contains("Scratch (1)"), "{}", screen);
+    assert!(!screen.contains("/b.txt"), "{}", screen);
+}
+
+#[test]
+fn enter_goes_to_the_entry_and_d_takes_it_out() {
+    let mut h = Harness::new(&["sub/deep.txt", "top.txt"]);
+    h.select("sub/");
+    h.press("enter");
+    h.select("deep.txt");
+    h.press("y backspace");
+    h.select("top.txt");
+    h.press("y");
+
+    h.press("Y enter");
+    assert_eq!(h.selected(), "deep.txt");
+
+    h.press("Y d");
+    let screen = h.screen();
+    assert!(screen.contains("Scratch (1)"), "{}", screen);
+    assert!(!screen.contains("sub/deep.txt"), "{}", screen);
+    h.press("t y");
+    h.finish_jobs();
+    assert!(!h.fixture.exists("top.txt"));
+    assert!(h.fixture.exists("sub/deep.txt"));
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use common::Harness;
+
+const PACKAGE: &str = r#"package.json={
+  "name": "web",
+  "version": "2.0.0",
+  "private": true,
+  "scripts": {
+    "greet": "echo hello from \"npm\"",
+    "lint": "eslint ."
+  },
+  "devDependencies": { "eslint": "^9.0.0" }
+}"#;
+
+#[test]
+fn a_package_shows_a_bar_and_lists_its_scripts() {
+    let mut h = Harness::new(&[PACKAGE, "yarn.lock", "src/index.js"]);
+    h.select("src/");
+    h.press("enter");
+    let screen = h.screen();
+    assert!(screen.contains("📦 web 2.0.0"), "{}", screen);
+    assert!(screen.contains("scripts <K r>"), "{}", screen);
+
+    h.press("K r");
+    let screen = h.screen();
+    assert!(screen.contains("Scripts of web (yarn)"), "{}", screen);
+    let greet = screen
+        .find("greet  echo hello from \"npm\"")
+        .expect(&screen);
+    let lint = screen.find("lint   eslint .").expect(&screen);
+    assert!(greet < lint, "{}", screen);
+}
+
+#[test]
+fn a_script_runs_as_a_job_and_shows_its_output() {
+    let mut h = Harness::new(&[PACKAGE]);
+    h.press("K r enter");
+    h.finish_jobs();
+    let screen = h.screen();
+    assert!(screen.contains("npm run greet ✔"), "{}", screen);
+    assert!(screen.contains("hello from npm"), "{}", screen);
+}
+
+#[test]
+fn without_a_valid_package_json_there_is_no_bar() {
+    let mut h = Harness::new(&[
+        "package.json={ not json",
+        "Cargo.toml=[package]\nname = \"both\"\n",
+    ]);
+    let screen = h.screen();
+    assert!(!screen.contains("📦"), "{}", screen);
+    assert!(screen.contains("🦀 both"), "{}", screen);
+    h.press("K r");
+    assert!(h.screen().contains("Not in a JavaScript package"));
+}
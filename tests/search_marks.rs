@@ -0,0 +1,69 @@
+mod common;
+
+use browrs::config::Config;
+use common::{Harness, test_config};
+
+/// Searching for `want` with both matches marked.
+fn marked(h: &mut Harness) {
+    h.press("/");
+    h.type_text("want");
+    h.wait_until(|h| h.screen().contains(" 2 matches "));
+    h.press("ctrl+a");
+    assert!(h.screen().contains(" 2 marked, 2 matches "));
+}
+
+#[test]
+fn marked_results_are_copied_to_a_directory() {
+    let mut h = Harness::new(&[
+        "deep/er/wanted.txt=1",
+        "deep/want.md=2",
+        "out/",
+        "other.txt",
+    ]);
+    marked(&mut h);
+    h.press("ctrl+y");
+    assert!(h.screen().contains("Copy 2 entries to"));
+    h.type_text("/out");
+    h.press("enter");
+    h.finish_jobs();
+    assert_eq!(h.fixture.read("out/wanted.txt"), "1");
+    assert_eq!(h.fixture.read("out/want.md"), "2");
+    assert!(!h.screen().contains("Search:"));
+}
+
+#[test]
+fn tab_marks_one_result_and_ctrl_a_clears_them() {
+    let mut h = Harness::new(&["deep/er/wanted.txt", "deep/want.md"]);
+    marked(&mut h);
+    h.press("ctrl+a");
+    assert!(!h.screen().contains("marked"));
+    h.press("tab");
+    let screen = h.screen();
+    assert!(screen.contains(" 1 marked, "));
+    assert!(screen.contains("* deep/"));
+
+    // Marks outlive a change of query
+    h.press("backspace");
+    assert!(h.screen().contains(" 1 marked, "));
+}
+
+#[test]
+fn marked_results_open_in_one_editor_and_ask_before_trashing() {
+    // cp with both files copies the first over the second
+    let config = Config {
+        editor: Some("cp".to_string()),
+        ..test_config()
+    };
+    let mut h = Harness::with_config(&["a/want.txt=A", "b/want.txt=B"], config);
+    marked(&mut h);
+    h.press("ctrl+o");
+    assert_eq!(h.fixture.read("b/want.txt"), "A");
+    assert!(!h.screen().contains("Search:"));
+
+    marked(&mut h);
+    h.press("ctrl+d");
+    assert!(h.screen().contains("Move 2 entries to the trash?"));
+    h.press("esc");
+    assert!(h.screen().contains(" 2 marked, "));
+    assert!(h.fixture.exists("a/want.txt"));
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use browrs::config::Config;
+use common::{Harness, test_config};
+
+fn with_send_to(entries: &[&str], name: &str, template: &str) -> Harness {
+    let config = Config {
+        send_to: vec![(name.to_string(), template.to_string())],
+        ..test_config()
+    };
+    Harness::with_config(entries, config)
+}
+
+#[test]
+fn commands_run_as_a_job() {
+    let mut h = with_send_to(
+        &["a.txt=words", "out/"],
+        "Slow",
+        "sleep 0.3; cp {file} \"$(dirname {file})/out/\"",
+    );
+    h.select("a.txt");
+    h.press("S");
+    h.press("enter");
+    // Back at the listing while the command is still going
+    assert!(!h.fixture.exists("out/a.txt"));
+    assert!(h.screen().contains("Sending 1 entries to Slow"));
+    h.finish_jobs();
+    assert_eq!(h.fixture.read("out/a.txt"), "words");
+}
+
+#[test]
+fn a_failing_command_is_reported() {
+    let mut h = with_send_to(&["a.txt", "b.txt"], "Broken", "echo nope >&2; exit 2");
+    h.press("space");
+    h.press("down");
+    h.press("space");
+    h.press("S");
+    h.press("enter");
+    h.finish_jobs();
+    let screen = h.screen();
+    assert!(screen.contains("nope"), "{}", screen);
+}
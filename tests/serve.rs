@@ -0,0 +1,158 @@
+mod common;
+
+use std::{
+    io::{Read, Write},
+    net::TcpStream,
+    os::unix::fs::symlink,
+};
+
+use common::Harness;
+
+/// Shares the current directory; returns the port and the token in the
+/// link the panel shows.
+fn share(h: &mut Harness) -> (u16, String) {
+    h.press("H");
+    let screen = h.screen();
+    let url = screen
+        .split_whitespace()
+        .find_map(|word| word.strip_prefix("http://"))
+        .expect(&screen);
+    let (address, token) = url.split_once('/').unwrap();
+    let port = address.rsplit(':').next().unwrap().parse().unwrap();
+    (port, token.trim_end_matches('/').to_string())
+}
+
+/// The whole response to `head`, sent as it is.
+fn send(port: u16, head: &str) -> String {
+    let mut stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    stream.write_all(head.as_bytes()).unwrap();
+    let mut response = String::new();
+    let _ = stream.read_to_string(&mut response);
+    response
+}
+
+fn get(port: u16, path: &str, session: Option<&str>) -> String {
+    let cookie = session.map_or(String::new(), |s| format!("Cookie: {}\r\n", s));
+    send(port, &format!("GET {} HTTP/1.1\r\n{}\r\n", path, cookie))
+}
+
+#[test]
+fn the_link_lets_one_device_in() {
+    let mut h = Harness::new(&["notes.txt=shared words"]);
+    let (port, token) = share(&mut h);
+
+    let first = get(port, &format!("/{}/notes.txt", token), None);
+    assert!(first.starts_with("HTTP/1.1 303"), "{}", first);
+    assert!(first.contains("Location: /notes.txt\r\n"), "{}", first);
+    let cookie = first
+        .lines()
+        .find_map(|line| line.strip_prefix("Set-Cookie: "))
+        .and_then(|cookie| cookie.split(';').next())
+        .unwrap()
+        .to_string();
+
+    let file = get(port, "/notes.txt", Some(&cookie));
+    assert!(file.starts_with("HTTP/1.1 200"), "{}", file);
+    assert!(file.ends_with("shared words"), "{}", file);
+
+    // Not again, and not without the cookie
+    let again = get(port, &format!("/{}/", token), None);
+    assert!(again.contains("already used"), "{}", again);
+    assert!(get(port, "/notes.txt", None).starts_with("HTTP/1.1 403"));
+    let guessed = get(port, "/notes.txt", Some("browrs_session=0"));
+    assert!(guessed.starts_with("HTTP/1.1 403"));
+}
+
+#[test]
+fn symlinks_out_of_the_share_are_not_followed() {
+    let mut h = Harness::new(&["shared/inside.txt=in", "secret.txt=private"]);
+    symlink(
+        h.fixture.path("secret.txt"),
+        h.fixture.path("shared/out.txt"),
+    )
+    .unwrap();
+    symlink(h.fixture.path(""), h.fixture.path("shared/up")).unwrap();
+    symlink("inside.txt", h.fixture.path("shared/alias.txt")).unwrap();
+    h.select("shared/");
+    h.press("enter");
+    let (port, token) = share(&mut h);
+    let first = get(port, &format!("/{}/", token), None);
+    let cookie = first
+        .lines()
+        .find_map(|line| line.strip_prefix("Set-Cookie: "))
+        .and_then(|cookie| cookie.split(';').next())
+        .unwrap()
+        .to_string();
+
+    for path in ["/out.txt", "/up/secret.txt", "/up/"] {
+        let response = get(port, path, Some(&cookie));
+        assert!(
+            response.starts_with("HTTP/1.1 404"),
+            "{}: {}",
+            path,
+            response
+        );
+    }
+    let alias = get(port, "/alias.txt", Some(&cookie));
+    assert!(alias.ends_with("\r\n\r\nin"), "{}", alias);
+}
+
+#[test]
+fn oversized_requests_are_refused() {
+    let mut h = Harness::new(&["a.txt"]);
+    let (port, token) = share(&mut h);
+    let long = format!(
+        "GET /{}/ HTTP/1.1\r\nX-Padding: {}\r\n\r\n",
+        token,
+        "a".repeat(10_000)
+    );
+    assert!(send(port, &long).starts_with("HTTP/1.1 431"));
+    let many = format!(
+        "GET /{}/ HTTP/1.1\r\n{}\r\n",
+        token,
+        "X-A: b\r\n".repeat(100)
+    );
+    assert!(send(port, &many).starts_with("HTTP/1.1 431"));
+    // Neither used up the link
+    let first = get(port, &format!("/{}/", token), None);
+    assert!(first.starts_with("HTTP/1.1 303"), "{}", first);
+}
+
+#[test]
+fn connections_beyond_the_limit_are_turned_away() {
+    let mut h = Harness::new(&["a.txt"]);
+    let (port, token) = share(&mut h);
+    let idle: Vec<TcpStream> = (0..16)
+        .map(|_| TcpStream::connect(("127.0.0.1", port)).unwrap())
+        .collect();
+    let busy = get(port, &format!("/{}/", token), None);
+    assert!(busy.starts_with("HTTP/1.1 503"), "{}", busy);
+
+    // Their handlers end once they go
+    drop(idle);
+    h.wait_until(|_| get(port, "/", None).starts_with("HTTP/1.1 403"));
+    let first = get(port, &format!("/{}/", token), None);
+    assert!(first.starts_with("HTTP/1.1 303"), "{}", first);
+}
+
+#[test]
+fn names_are_percent_encoded_in_links() {
+    let mut h = Harness::new(&["my notes é.txt=spaced"]);
+    let (port, token) = share(&mut h);
+    let first = get(port, &format!("/{}/", token), None);
+    let cookie = first
+        .lines()
+        .find_map(|line| line.strip_prefix("Set-Cookie: "))
+        .and_then(|cookie| cookie.split(';').next())
+        .unwrap()
+        .to_string();
+
+    let listing = get(port, "/", Some(&cookie));
+    assert!(
+        listing.contains("<a href=\"my%20notes%20%C3%A9.txt\">my notes é.txt</a>"),
+        "{}",
+        listing
+    );
+    let file = get(port, "/my%20notes%20%C3%A9.txt", Some(&cookie));
+    assert!(file.ends_with("spaced"), "{}", file);
+}
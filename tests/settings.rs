@@ -0,0 +1,73 @@
+mod common;
+
+use browrs::settings;
+use common::Harness;
+
+fn setting(key: &str) -> &'static settings::Setting {
+    settings::find(key).unwrap()
+}
+
+#[test]
+fn writing_replaces_lines_in_place_and_adds_missing_ones() {
+    let content = "# mine\nshow_hidden = false # comment\n\n[colors]\nmarked = \"red\"\n\n[keys]\nj = \"down\"\n";
+    let content = settings::write(content, setting("show_hidden"), "true");
+    let content = settings::write(&content, setting("sort"), "size");
+    let content = settings::write(&content, setting("colors.accent"), "Green");
+    assert_eq!(
+        content,
+        "# mine\nshow_hidden = true\nsort = \"size\"\n\n[colors]\nmarked = \"red\"\naccent = \"Green\"\n\n[keys]\nj = \"down\"\n"
+    );
+    let content = settings::write(&content, setting("image_protocol"), "auto");
+    assert!(!content.contains("image_protocol"));
+    let content = settings::write("", setting("keep_versions"), "3");
+    assert_eq!(content, "keep_versions = 3\n");
+    let content = settings::write("[keys]\nj = \"down\"\n", setting("colors.marked"), "Red");
+    assert_eq!(
+        content,
+        "[keys]\nj = \"down\"\n\n[colors]\nmarked = \"Red\"\n"
+    );
+}
+
+#[test]
+fn toggles_apply_live_and_save_to_the_config() {
+    let mut h = Harness::new(&[".hidden", "shown"]);
+    h.fixture.add("config.toml=[keys]\nj = \"down\"\n");
+    h.app.watch_config(h.fixture.path("config.toml"));
+    h.press(",");
+    assert!(h.screen().contains("show_hidden"), "{}", h.screen());
+    h.press("down enter");
+    assert_eq!(h.listing(), ["..", ".hidden", "config.toml", "shown"]);
+    assert!(h.screen().contains("Settings (1 unsaved)"));
+
+    h.press("w");
+    assert!(h.screen().contains("⚙️ Saved 1 settings"));
+    assert_eq!(
+        h.fixture.read("config.toml"),
+        "show_hidden = true\n[keys]\nj = \"down\"\n"
+    );
+}
+
+#[test]
+fn numbers_are_typed_and_checked() {
+    let mut h = Harness::new(&[]);
+    h.press(",");
+    let keep = settings::ALL
+        .iter()
+        .position(|s| s.key == "keep_versions")
+        .unwrap();
+    for _ in 0..keep {
+        h.press("down");
+    }
+    h.press("enter ctrl+u");
+    h.type_text("lots");
+    h.press("enter");
+    assert!(h.screen().contains("not a number: lots"), "{}", h.screen());
+    h.press("enter ctrl+u");
+    h.type_text("4");
+    h.press("enter");
+    assert!(
+        h.screen().contains("*keep_versions        4"),
+        "{}",
+        h.screen()
+    );
+}
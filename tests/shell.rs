@@ -0,0 +1,42 @@
+mod common;
+
+use std::path::PathBuf;
+
+use browrs::{cli::Args, shell};
+use common::Harness;
+
+#[test]
+fn quitting_with_shift_q_leaves_the_current_directory() {
+    let mut h = Harness::new(&["docs/"]);
+    h.select("docs/");
+    h.press("enter Q");
+    assert!(h.app.is_exiting());
+    assert_eq!(h.app.exit_dir(), Some(h.fixture.path("docs").as_path()));
+
+    let mut h = Harness::new(&["docs/"]);
+    h.press("q");
+    assert_eq!(h.app.exit_dir(), None);
+}
+
+#[test]
+fn init_scripts_use_the_cwd_file() {
+    for name in shell::SHELLS {
+        let script = shell::init(name).unwrap();
+        assert!(script.contains("brcd"), "{}", name);
+        assert!(script.contains("--cwd-file="), "{}", name);
+    }
+    assert!(
+        shell::init("bash")
+            .unwrap()
+            .contains("eval \"$(browrs --init bash)\"")
+    );
+    assert!(shell::init("fish").unwrap().contains("function brcd"));
+    assert_eq!(shell::init("tcsh"), None);
+}
+
+#[test]
+fn cwd_file_and_init_are_read() {
+    let args = Args::parse(["--cwd-file=/tmp/cwd", "--init", "zsh"].map(String::from)).unwrap();
+    assert_eq!(args.cwd_file, Some(PathBuf::from("/tmp/cwd")));
+    assert_eq!(args.init.as_deref(), Some("zsh"));
+}
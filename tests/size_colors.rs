@@ -0,0 +1,48 @@
+mod common;
+
+use browrs::config::Config;
+use common::{Harness, test_config};
+use ratatui::style::Color;
+
+fn with_sizes(size_colors: bool) -> Harness {
+    let config = Config {
+        size_colors,
+        ..test_config()
+    };
+    let h = Harness::with_config(&["big.bin", "small.txt=hi", "dir/"], config);
+    let file = std::fs::File::create(h.fixture.path("big.bin")).unwrap();
+    file.set_len(3 << 20).unwrap();
+    h
+}
+
+#[test]
+fn large_files_get_a_size_badge() {
+    let mut h = with_sizes(true);
+    // A new tab lists the directory again, with big.bin grown
+    h.press("t");
+    let screen = h.screen();
+    assert!(screen.contains("big.bin 3.0 MB"), "{}", screen);
+    assert!(!screen.contains("small.txt 2 B"));
+
+    let mut h = with_sizes(false);
+    h.press("t");
+    assert!(!h.screen().contains("3.0 MB"));
+}
+
+#[test]
+fn tiers_pick_the_color() {
+    let config = Config::parse("size_colors = true\nsize_tiers = \"1K, 1M, 1G\"\n");
+    assert_eq!(config.size_tiers, [1 << 10, 1 << 20, 1 << 30]);
+    assert_eq!(config.size_color(100), None);
+    assert_eq!(config.size_color(4 << 10), Some(Color::Yellow));
+    assert_eq!(config.size_color(1 << 20), Some(Color::LightRed));
+    assert_eq!(config.size_color(2 << 30), Some(Color::Red));
+    assert_eq!(Config::default().size_color(2 << 30), None);
+}
+
+#[test]
+fn tiers_have_to_ascend() {
+    let (config, errors) = Config::parse_checked("size_tiers = \"1G 1M 1K\"\nsize_tiers = 5\n");
+    assert_eq!(config.size_tiers, Config::default().size_tiers);
+    assert_eq!(errors.len(), 2);
+}
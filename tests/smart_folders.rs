@@ -0,0 +1,72 @@
+mod common;
+
+use browrs::smart::{QueryKind, SmartFolders};
+use common::Harness;
+
+fn harness(entries: &[&str]) -> Harness {
+    let mut h = Harness::new(entries);
+    let file = h.fixture.path("config/smart_folders");
+    h.app.set_smart_folders(SmartFolders::load(file));
+    h
+}
+
+#[test]
+fn a_saved_search_runs_again_when_opened() {
+    let mut h = harness(&["src/deep/notes.md", "src/other.rs", "docs/"]);
+    h.select("src/");
+    h.press("enter /");
+    h.type_text("md");
+    h.press("ctrl+s");
+    assert!(h.screen().contains("Save search \"md\" as"));
+    h.press("ctrl+u");
+    h.type_text("Markdown");
+    h.press("enter esc");
+
+    let folders = SmartFolders::load(h.fixture.path("config/smart_folders"));
+    assert_eq!(folders.folders.len(), 1);
+    assert_eq!(folders.folders[0].name, "Markdown");
+    assert_eq!(folders.folders[0].kind, QueryKind::Search);
+    assert_eq!(folders.folders[0].root, h.fixture.path("src"));
+
+    // Files added since show up, since the search runs anew
+    std::fs::write(h.fixture.path("src/new.md"), "").unwrap();
+    h.press("backspace");
+    h.select("docs/");
+    h.press("enter '");
+    assert!(h.screen().contains("🔎 Markdown  md in"));
+    h.press("enter");
+    h.wait_until(|h| h.screen().contains(" 2 matches "));
+    assert_eq!(h.current_dir(), h.fixture.path("src"));
+    assert!(h.screen().contains("Search: md"));
+}
+
+#[test]
+fn a_saved_filter_is_applied_to_its_directory() {
+    let mut h = harness(&["logs/a.log", "logs/b.txt", "other/"]);
+    h.press("B");
+    assert!(h.screen().contains("Filter with f first"));
+    h.select("logs/");
+    h.press("enter f");
+    h.type_text("*.log");
+    h.press("enter B enter");
+    h.press("f ctrl+u enter backspace");
+    assert_eq!(h.current_dir(), h.fixture.root);
+
+    h.press("'");
+    assert!(h.screen().contains("*.log  *.log in"));
+    h.press("enter");
+    assert_eq!(h.current_dir(), h.fixture.path("logs"));
+    assert_eq!(h.listing(), ["..", "a.log"]);
+}
+
+#[test]
+fn smart_folders_are_removed_from_the_list() {
+    let mut h = harness(&["a/x.txt"]);
+    h.press("/");
+    h.type_text("x");
+    h.press("ctrl+s enter esc '");
+    assert!(h.screen().contains("🔎 x  x in"));
+    h.press("d");
+    assert!(!h.screen().contains("🔎 x  x in"));
+    assert_eq!(h.fixture.read("config/smart_folders"), "");
+}
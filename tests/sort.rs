@@ -0,0 +1,126 @@
+mod common;
+
+use std::time::{Duration, SystemTime};
+
+use browrs::{Config, sort::Sort};
+use common::{Harness, test_config};
+
+fn sorted_by(sort: Sort, dirs_first: bool, entries: &[&str]) -> Vec<String> {
+    let config = Config {
+        sort,
+        dirs_first,
+        ..test_config()
+    };
+    Harness::with_config(entries, config).listing()
+}
+
+#[test]
+fn natural_order_compares_numbers_by_value() {
+    let listing = sorted_by(Sort::Natural, false, &["file10", "File2", "file1"]);
+    assert_eq!(listing, ["..", "file1", "File2", "file10"]);
+}
+
+#[test]
+fn extension_order_groups_file_types() {
+    let listing = sorted_by(Sort::Extension, false, &["b.rs", "a.txt", "c.md", "a.rs"]);
+    assert_eq!(listing, ["..", "c.md", "a.rs", "b.rs", "a.txt"]);
+}
+
+#[test]
+fn size_order_puts_the_largest_first() {
+    let listing = sorted_by(Sort::Size, false, &["small=1", "big=12345", "mid=123"]);
+    assert_eq!(listing, ["..", "big", "mid", "small"]);
+}
+
+#[test]
+fn modified_order_puts_the_newest_first() {
+    let h = Harness::new(&["old", "new", "older"]);
+    let now = SystemTime::now();
+    for (name, age) in [("old", 60), ("new", 0), ("older", 3600)] {
+        let file = std::fs::File::options()
+            .write(true)
+            .open(h.fixture.path(name))
+            .unwrap();
+        file.set_modified(now - Duration::from_secs(age)).unwrap();
+    }
+    let config = Config {
+        sort: Sort::Modified,
+        ..test_config()
+    };
+    let app = browrs::App::with_config(config, h.fixture.root.clone()).unwrap();
+    let names: Vec<_> = app.pane().entries.iter().map(|e| e.name.clone()).collect();
+    assert_eq!(names, ["..", "new", "old", "older"]);
+}
+
+#[test]
+fn directories_first_groups_them_before_files() {
+    let listing = sorted_by(Sort::Name, true, &["a.txt", "z/", "b/"]);
+    assert_eq!(listing, ["..", "b/", "z/", "a.txt"]);
+}
+
+#[test]
+fn s_cycles_modes_shown_in_the_header() {
+    let mut h = Harness::new(&["file10", "file9"]);
+    assert!(h.screen().contains("sort: name"));
+    assert_eq!(h.listing(), ["..", "file10", "file9"]);
+
+    h.press("s");
+    assert!(h.screen().contains("sort: natural"));
+    assert_eq!(h.listing(), ["..", "file9", "file10"]);
+
+    h.press("s s s s");
+    assert!(h.screen().contains("sort: rating"));
+    h.press("s");
+    assert!(h.screen().contains("sort: name"));
+    h.press("F");
+    assert!(h.screen().contains("sort: name, dirs first"));
+}
+
+#[test]
+fn custom_orders_combine_keys_and_join_the_cycle() {
+    let parsed = Config::parse(
+        "sort = \"code\"\n\
+         [sorts]\n\
+         code = \"dirs, extension, natural\"\n\
+         smallest = \"files, -size\"\n",
+    );
+    let config = Config {
+        sort: parsed.sort,
+        sorts: parsed.sorts,
+        ..test_config()
+    };
+    let mut h = Harness::with_config(
+        &["b10.rs=1", "b9.rs=12345", "a.md=123", "src/", "lib/"],
+        config,
+    );
+    assert!(h.screen().contains("sort: code"));
+    assert_eq!(
+        h.listing(),
+        ["..", "lib/", "src/", "a.md", "b9.rs", "b10.rs"]
+    );
+
+    h.press("s");
+    assert!(h.screen().contains("sort: smallest"));
+    assert_eq!(
+        h.listing(),
+        ["..", "b10.rs", "a.md", "b9.rs", "lib/", "src/"]
+    );
+    h.press("s");
+    assert!(h.screen().contains("sort: name"));
+}
+
+#[test]
+fn bad_custom_orders_are_reported() {
+    let (config, errors) = Config::parse_checked(
+        "sort = \"nope\"\n\
+         [sorts]\n\
+         size = \"name\"\n\
+         odd = \"dirs, colour\"\n",
+    );
+    let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+    assert_eq!(errors.len(), 3, "{:?}", errors);
+    assert!(errors[0].contains("size is a built-in sort"));
+    assert!(errors[1].contains("unknown sort key colour"));
+    assert!(errors[2].contains("line 1") && errors[2].contains("unknown sort nope"));
+    assert!(config.sorts.is_empty());
+}
@@ -0,0 +1,65 @@
+mod common;
+
+use browrs::config::Config;
+use common::{Harness, test_config};
+use ratatui::style::Color;
+
+fn with_special_files() -> Harness {
+    let config = Config {
+        line_counts: true,
+        ..test_config()
+    };
+    let mut h = Harness::with_config(&["dir/notes.txt=hi"], config);
+    let status = std::process::Command::new("mkfifo")
+        .arg(h.fixture.path("dir/pipe"))
+        .status()
+        .unwrap();
+    assert!(status.success());
+    std::os::unix::net::UnixListener::bind(h.fixture.path("dir/socket")).unwrap();
+    h.select("dir/");
+    h.press("enter");
+    h
+}
+
+#[test]
+fn pipes_and_sockets_are_marked_and_colored() {
+    let mut h = with_special_files();
+    assert_eq!(h.listing(), ["..", "notes.txt", "pipe|", "socket="]);
+    assert_eq!(h.color_of("pipe|"), Color::Yellow);
+    assert_eq!(h.color_of("socket="), Color::Magenta);
+}
+
+#[test]
+fn a_pipe_is_described_instead_of_read() {
+    let mut h = with_special_files();
+    // Reading it would wait for a writer that never comes
+    h.select("pipe|");
+    let screen = h.screen();
+    assert!(screen.contains("pipe | named pipe"), "{}", screen);
+    assert!(screen.contains("Not read for the preview"), "{}", screen);
+    assert!(!screen.contains("lines"), "{}", screen);
+
+    // Nor copied, which would read it too
+    h.press("c ctrl+u");
+    h.type_text("copy");
+    h.press("enter");
+    h.finish_jobs();
+    let screen = h.screen();
+    assert!(screen.contains("Unable to copy"), "{}", screen);
+    assert!(!h.fixture.exists("dir/copy"));
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn devices_show_their_numbers() {
+    let mut h = Harness::new(&["links/"]);
+    std::os::unix::fs::symlink("/dev", h.fixture.path("links/dev")).unwrap();
+    h.select("links/");
+    h.press("enter");
+    h.select("dev/");
+    h.press("enter");
+    h.select("null");
+    let screen = h.screen();
+    assert!(screen.contains("null | character device"), "{}", screen);
+    assert!(screen.contains("Device: 1:3"), "{}", screen);
+}
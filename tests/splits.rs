@@ -0,0 +1,94 @@
+mod common;
+
+use common::Harness;
+
+const TREE: &[&str] = &["a/", "b/", "c/"];
+
+fn enter(h: &mut Harness, dir: &str) {
+    h.select(dir);
+    h.press("enter");
+}
+
+#[test]
+fn splitting_shows_another_pane_with_its_own_directory() {
+    let mut h = Harness::new(TREE);
+    h.press("ctrl+x s");
+    assert_eq!(h.screen().matches("Directory:").count(), 3);
+    enter(&mut h, "a/");
+    assert_eq!(h.current_dir(), h.fixture.path("a"));
+    h.press("ctrl+x k");
+    assert_eq!(h.current_dir(), h.fixture.root);
+    h.press("ctrl+x j");
+    assert_eq!(h.current_dir(), h.fixture.path("a"));
+}
+
+#[test]
+fn focus_moves_to_the_pane_on_that_side() {
+    let mut h = Harness::new(TREE);
+    h.press("|");
+    enter(&mut h, "a/");
+    h.press("tab");
+    enter(&mut h, "b/");
+    // The right pane split in two: b above, c below
+    h.press("ctrl+x s");
+    enter(&mut h, "..");
+    enter(&mut h, "c/");
+
+    h.press("ctrl+x h");
+    assert_eq!(h.current_dir(), h.fixture.path("a"));
+    h.press("ctrl+x h");
+    assert_eq!(h.current_dir(), h.fixture.path("a"));
+    h.press("ctrl+x l");
+    assert_eq!(h.current_dir(), h.fixture.path("b"));
+    h.press("ctrl+x down");
+    assert_eq!(h.current_dir(), h.fixture.path("c"));
+    h.press("tab");
+    assert_eq!(h.current_dir(), h.fixture.path("a"));
+}
+
+#[test]
+fn closing_a_split_gives_its_space_back() {
+    let mut h = Harness::new(TREE);
+    h.press("ctrl+x v");
+    enter(&mut h, "c/");
+    h.press("ctrl+x x");
+    assert_eq!(h.screen().matches("Directory:").count(), 2);
+    assert_ne!(h.current_dir(), h.fixture.path("c"));
+    h.press("ctrl+x x");
+    assert_eq!(h.screen().matches("Directory:").count(), 1);
+    h.press("ctrl+x x");
+    assert_eq!(h.screen().matches("Directory:").count(), 1);
+}
+
+#[test]
+fn closing_a_pane_next_to_a_nested_split_keeps_every_pane() {
+    let mut h = Harness::new(TREE);
+    enter(&mut h, "c/");
+    // c, then a and b splitting the new pane, then the second pane
+    h.press("ctrl+x s");
+    enter(&mut h, "..");
+    enter(&mut h, "a/");
+    h.press("ctrl+x s");
+    enter(&mut h, "..");
+    enter(&mut h, "b/");
+    h.press("tab tab");
+    assert_eq!(h.current_dir(), h.fixture.path("c"));
+
+    h.press("ctrl+x x");
+    // The split below took its place, and the focus
+    assert_eq!(h.current_dir(), h.fixture.path("a"));
+    let mut seen = vec![];
+    for _ in 0..3 {
+        seen.push(h.current_dir().to_path_buf());
+        h.press("tab");
+    }
+    seen.sort();
+    assert_eq!(
+        seen,
+        [
+            h.fixture.root.clone(),
+            h.fixture.path("a"),
+            h.fixture.path("b")
+        ]
+    );
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use std::time::{Duration, SystemTime};
+
+use browrs::config::Config;
+use common::{Harness, test_config};
+use ratatui::style::Color;
+
+fn with_stale_days(stale_days: u64) -> Harness {
+    let config = Config {
+        stale_days,
+        ..test_config()
+    };
+    let h = Harness::with_config(&["old.txt", "new.txt", "sub/"], config);
+    let year_ago = SystemTime::now() - Duration::from_secs(365 * 86_400);
+    let file = std::fs::File::options()
+        .write(true)
+        .open(h.fixture.path("old.txt"))
+        .unwrap();
+    file.set_modified(year_ago).unwrap();
+    h
+}
+
+#[test]
+fn entries_untouched_for_the_stale_days_are_dimmed() {
+    let mut h = with_stale_days(30);
+    // A new tab lists the directory again, with old.txt aged
+    h.press("t");
+    h.select("sub/");
+    assert_eq!(h.color_of("old.txt"), Color::DarkGray);
+    assert_eq!(h.color_of("new.txt"), Color::Reset);
+}
+
+#[test]
+fn nothing_is_dimmed_by_default() {
+    let mut h = with_stale_days(0);
+    h.press("t");
+    h.select("sub/");
+    assert_eq!(h.color_of("old.txt"), Color::Reset);
+}
+
+#[test]
+fn the_stale_color_is_themed() {
+    let config = Config::parse("stale_days = 7\n[colors]\nstale = \"#445566\"\n");
+    assert_eq!(config.stale_days, 7);
+    assert_eq!(config.theme.stale, Color::Rgb(0x44, 0x55, 0x66));
+}
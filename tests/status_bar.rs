@@ -0,0 +1,43 @@
+mod common;
+
+use common::Harness;
+
+fn status_bar(h: &mut Harness) -> String {
+    h.screen().lines().last().unwrap_or_default().to_string()
+}
+
+#[test]
+fn shows_the_selected_entry_and_its_position() {
+    let mut h = Harness::new(&["a.txt", "b.txt", "sub/"]);
+    std::fs::write(h.fixture.path("b.txt"), "hello").unwrap();
+    h.press("R");
+    h.select("b.txt");
+    let bar = status_bar(&mut h);
+    assert!(bar.contains("file  5 B"), "{}", bar);
+    assert!(bar.contains("2/3"), "{}", bar);
+    assert!(bar.contains("Quit <Q>"), "{}", bar);
+
+    h.select("sub/");
+    let bar = status_bar(&mut h);
+    assert!(bar.contains("directory"), "{}", bar);
+    assert!(bar.contains("3/3"), "{}", bar);
+}
+
+#[cfg(unix)]
+#[test]
+fn shows_the_free_space() {
+    let mut h = Harness::new(&["a.txt"]);
+    let bar = status_bar(&mut h);
+    assert!(bar.contains(" free"), "{}", bar);
+}
+
+#[test]
+fn shows_what_an_operation_did_in_place_of_the_entry() {
+    let mut h = Harness::new(&["a.txt", "b.txt"]);
+    h.select("a.txt");
+    h.press("D y");
+    h.finish_jobs();
+    let bar = status_bar(&mut h);
+    assert!(bar.contains("1 entries"), "{}", bar);
+    assert!(!bar.contains("file "), "{}", bar);
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use std::os::unix::fs::{MetadataExt, symlink};
+
+use common::Harness;
+use ratatui::style::Color;
+
+fn with_links() -> Harness {
+    let mut h = Harness::new(&["dir/notes.txt=hello", "dir/sub/inner.txt", "other/"]);
+    symlink("notes.txt", h.fixture.path("dir/to-notes")).unwrap();
+    symlink("sub", h.fixture.path("dir/to-sub")).unwrap();
+    symlink("gone.txt", h.fixture.path("dir/dangling")).unwrap();
+    h.select("dir/");
+    h.press("enter");
+    h
+}
+
+#[test]
+fn links_show_where_they_point() {
+    let mut h = with_links();
+    assert_eq!(
+        h.listing(),
+        ["..", "dangling", "notes.txt", "sub/", "to-notes", "to-sub/"]
+    );
+    let screen = h.screen();
+    assert!(screen.contains("to-notes -> notes.txt"), "{}", screen);
+    assert!(screen.contains("to-sub/ -> sub"), "{}", screen);
+    assert_eq!(h.color_of("to-notes"), Color::Cyan);
+    assert_eq!(h.color_of("dangling"), Color::Red);
+
+    // A link to a directory is entered like one
+    h.select("to-sub/");
+    h.press("enter");
+    assert_eq!(h.listing(), ["..", "inner.txt"]);
+}
+
+#[test]
+fn the_preview_shows_the_target() {
+    let mut h = with_links();
+    h.select("to-notes");
+    let screen = h.screen();
+    assert!(screen.contains("🔗 Link to notes.txt"), "{}", screen);
+    assert!(screen.contains("hello"), "{}", screen);
+
+    h.select("dangling");
+    let screen = h.screen();
+    assert!(screen.contains("dangling | broken link"), "{}", screen);
+    assert!(screen.contains("Target: gone.txt"), "{}", screen);
+}
+
+#[test]
+fn links_are_made_in_another_directory() {
+    let mut h = with_links();
+    h.select("notes.txt");
+    h.press("alt+s ctrl+u");
+    h.type_text("../other");
+    h.press("enter");
+    let link = h.fixture.path("other/notes.txt");
+    assert_eq!(
+        std::fs::read_link(&link).unwrap(),
+        h.fixture.path("dir/notes.txt")
+    );
+    assert_eq!(h.fixture.read("other/notes.txt"), "hello");
+
+    h.press("alt+h ctrl+u");
+    h.type_text("../other/hard.txt");
+    h.press("enter");
+    let metadata = std::fs::symlink_metadata(h.fixture.path("other/hard.txt")).unwrap();
+    assert!(metadata.is_file());
+    assert_eq!(metadata.nlink(), 2);
+    assert!(h.screen().contains("Updated 1 entries"));
+}
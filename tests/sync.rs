@@ -0,0 +1,59 @@
+mod common;
+
+use common::Harness;
+
+const TREE: &[&str] = &[
+    "one/src/lib/",
+    "one/src/main.rs",
+    "one/only/",
+    "two/src/lib/",
+    "two/src/main.rs",
+];
+
+/// Left pane in `one`, right pane in `two`, synced, left focused.
+fn synced() -> Harness {
+    let mut h = Harness::new(TREE);
+    h.press("|");
+    h.select("one/");
+    h.press("enter");
+    h.press("tab");
+    h.select("two/");
+    h.press("enter");
+    h.press("= tab");
+    h
+}
+
+#[test]
+fn the_other_pane_follows_under_its_own_root() {
+    let mut h = synced();
+    h.select("src/");
+    h.press("enter");
+    h.select("main.rs");
+    h.press("tab");
+    assert_eq!(h.current_dir(), h.fixture.path("two/src"));
+    assert_eq!(h.selected(), "main.rs");
+    h.press("backspace");
+    h.press("tab");
+    assert_eq!(h.current_dir(), h.fixture.path("one"));
+}
+
+#[test]
+fn a_pane_without_the_directory_stays_and_says_so() {
+    let mut h = synced();
+    h.select("only/");
+    h.press("enter");
+    assert!(h.screen().contains("⇄ no only here"));
+    h.press("tab");
+    assert_eq!(h.current_dir(), h.fixture.path("two"));
+}
+
+#[test]
+fn syncing_can_be_turned_off() {
+    let mut h = synced();
+    assert!(h.screen().contains("⇄ synced"));
+    h.press("=");
+    assert!(!h.screen().contains("⇄"));
+    h.select("src/");
+    h.press("enter tab");
+    assert_eq!(h.current_dir(), h.fixture.path("two"));
+}
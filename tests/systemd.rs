@@ -0,0 +1,48 @@
+mod common;
+
+use common::Harness;
+use ratatui::style::Color;
+
+const UNIT: &str = "# Serves the site\n\
+[Unit]\n\
+Description=Static site\n\
+After=network.target\n\
+\n\
+[Service]\n\
+ExecStart=/usr/bin/site \\\n    --port 8080\n\
+Restart=on-failure\n\
+\n\
+[Install]\n\
+WantedBy=multi-user.target\n";
+
+#[test]
+fn units_preview_their_settings_by_section() {
+    let mut h = Harness::new(&[&format!("site.service={}", UNIT)]);
+    h.select("site.service");
+    let screen = h.screen();
+    assert!(
+        screen.contains("site.service | systemd service"),
+        "{}",
+        screen
+    );
+    assert!(screen.contains("[Service]"), "{}", screen);
+    // Continued lines are joined and comments left out
+    assert!(
+        screen.contains("ExecStart /usr/bin/site --port 8080"),
+        "{}",
+        screen
+    );
+    assert!(screen.contains("Restart   on-failure"), "{}", screen);
+    assert!(!screen.contains("Serves the site"), "{}", screen);
+    assert_eq!(h.color_of("[Install]"), Color::Cyan);
+    assert_eq!(h.color_of("WantedBy"), Color::Yellow);
+}
+
+#[test]
+fn other_files_with_a_unit_extension_preview_as_text() {
+    let mut h = Harness::new(&["notes.path=just some text"]);
+    h.select("notes.path");
+    let screen = h.screen();
+    assert!(screen.contains("just some text"), "{}", screen);
+    assert!(!screen.contains("systemd"), "{}", screen);
+}
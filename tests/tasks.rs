@@ -0,0 +1,83 @@
+mod common;
+
+use common::Harness;
+
+const MAKEFILE: &str = "Makefile=CC := cc
+.PHONY: build clean
+
+# Build everything
+build: main.o
+\t@echo building the app
+
+clean: ## Remove build output
+\trm -f made
+
+%.o: %.c
+\t$(CC) -c $<
+
+made:
+\t@touch made
+";
+
+const JUSTFILE: &str = "justfile=set shell := [\"bash\", \"-c\"]
+version := \"1.0\"
+
+# Run the tests
+test filter='': build
+    cargo test {{filter}}
+
+[private]
+helper:
+    echo hidden
+
+_secret:
+    echo hidden too
+";
+
+#[test]
+fn targets_and_recipes_are_listed_with_their_comments() {
+    let mut h = Harness::new(&[MAKEFILE, JUSTFILE]);
+    h.press("K m");
+    let screen = h.screen();
+    let build = screen.find("make build").expect(&screen);
+    let made = screen.find("make made").expect(&screen);
+    let test = screen.find("just test").expect(&screen);
+    assert!(build < made && made < test, "{}", screen);
+    assert!(
+        screen.contains("make build  Build everything"),
+        "{}",
+        screen
+    );
+    assert!(
+        screen.contains("make clean  Remove build output"),
+        "{}",
+        screen
+    );
+    assert!(screen.contains("just test   Run the tests"), "{}", screen);
+    for hidden in [
+        ".PHONY", "%.o", "make CC", "helper", "_secret", "just set", "version",
+    ] {
+        assert!(!screen.contains(hidden), "{}\n{}", hidden, screen);
+    }
+}
+
+#[test]
+fn a_target_runs_with_its_output_kept_or_on_the_terminal() {
+    let mut h = Harness::new(&[MAKEFILE]);
+    h.press("K m enter");
+    h.finish_jobs();
+    let screen = h.screen();
+    assert!(screen.contains("make build ✖"), "{}", screen);
+    assert!(screen.contains("No rule to make target"), "{}", screen);
+
+    // build, clean, made
+    h.press("esc K m down down t");
+    assert!(h.fixture.exists("made"));
+}
+
+#[test]
+fn without_a_makefile_there_is_nothing_to_run() {
+    let mut h = Harness::new(&["notes.txt"]);
+    h.press("K m");
+    assert!(h.screen().contains("No Makefile or justfile targets"));
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use browrs::config::Config;
+use common::{Harness, test_config};
+
+#[test]
+fn the_title_follows_the_current_directory() {
+    let mut h = Harness::new(&["docs/guide.md"]);
+    let root = h
+        .fixture
+        .root
+        .file_name()
+        .unwrap()
+        .to_string_lossy()
+        .to_string();
+    let title = h.app.window_title().unwrap();
+    assert!(title.starts_with("browrs: /"), "{}", title);
+    assert!(title.ends_with(&format!("/{}", root)), "{}", title);
+
+    h.select("docs/");
+    h.press("enter");
+    // Directories above are cut short
+    let title = h.app.window_title().unwrap();
+    assert!(title.starts_with("browrs: /"), "{}", title);
+    assert!(title.ends_with("/b/docs"), "{}", title);
+}
+
+#[test]
+fn the_template_names_the_directory_or_leaves_the_title_alone() {
+    let config = Config {
+        terminal_title: Some("{name} in {path}".to_string()),
+        ..test_config()
+    };
+    let mut h = Harness::with_config(&["docs/"], config);
+    h.select("docs/");
+    h.press("enter");
+    assert_eq!(
+        h.app.window_title().unwrap(),
+        format!("docs in {}", h.fixture.path("docs").display())
+    );
+
+    let config = Config {
+        terminal_title: None,
+        ..test_config()
+    };
+    let h = Harness::with_config(&["docs/"], config);
+    assert_eq!(h.app.window_title(), None);
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use browrs::{
+    config::Config,
+    settings,
+    theme::{self, Theme},
+};
+use common::{Harness, test_config};
+use ratatui::style::Color;
+
+fn themed(entries: &[&str], content: &str) -> Harness {
+    let config = Config {
+        theme: Config::parse(content).theme,
+        ..test_config()
+    };
+    Harness::with_config(entries, config)
+}
+
+#[test]
+fn a_named_theme_colors_the_selection_title_and_previews() {
+    let mut h = themed(&["main.rs=fn main() {}\n"], "theme = \"gruvbox\"\n");
+    assert_eq!(h.style_of("..").bg, Some(Color::Rgb(0x45, 0x85, 0x88)));
+    assert_eq!(h.color_of("Browrs"), Color::Rgb(0xb8, 0xbb, 0x26));
+
+    h.select("main.rs");
+    // Read on a thread of its own, in the same colors
+    assert_eq!(h.color_of("fn main"), Color::Rgb(0xfb, 0x49, 0x34));
+}
+
+#[test]
+fn colors_apply_on_top_of_the_theme_even_when_it_changes() {
+    let mut config = Config::parse("theme = \"dracula\"\n[colors]\naccent = \"red\"\n");
+    assert_eq!(config.theme.name, "dracula");
+    assert_eq!(config.theme.accent, Color::Red);
+    assert_eq!(config.theme.marked, Theme::named("dracula").unwrap().marked);
+
+    settings::find("theme")
+        .unwrap()
+        .apply(&mut config, "solarized")
+        .unwrap();
+    assert_eq!(config.theme.name, "solarized");
+    assert_eq!(config.theme.accent, Color::Red);
+    assert_eq!(
+        config.theme.selection,
+        Theme::named("solarized").unwrap().selection
+    );
+}
+
+#[test]
+fn unknown_themes_and_colors_are_reported() {
+    let (config, errors) = Config::parse_checked("theme = \"neon\"\n[colors]\nsparkle = \"red\"\n");
+    assert_eq!(config.theme, Theme::default());
+    let errors: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+    assert_eq!(
+        errors,
+        [
+            format!(
+                "Config line 1: unknown theme neon, expected one of {}",
+                theme::NAMES.join(", ")
+            ),
+            "Config line 3: unknown color sparkle".to_string(),
+        ]
+    );
+}
@@ -0,0 +1,58 @@
+mod common;
+
+use std::{
+    fs,
+    time::{Duration, UNIX_EPOCH},
+};
+
+use common::Harness;
+
+fn touch(h: &mut Harness, spec: &str) {
+    h.press("T");
+    h.press("ctrl+u");
+    h.type_text(spec);
+    h.press("enter");
+}
+
+fn modified(h: &Harness, name: &str) -> u64 {
+    fs::metadata(h.fixture.path(name))
+        .unwrap()
+        .modified()
+        .unwrap()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+#[test]
+fn times_out_of_range_are_refused() {
+    let mut h = Harness::new(&["a.txt"]);
+    h.select("a.txt");
+    let before = modified(&h, "a.txt");
+    for (spec, error) in [
+        ("+99999999999999d", "Time out of range"),
+        ("+18446744073709551615h", "Time out of range"),
+        ("@18446744073709551615", "Time out of range"),
+        ("-99999999999999d", "Time before the epoch"),
+        ("99999-01-01 00:00", "Date out of range"),
+    ] {
+        touch(&mut h, spec);
+        let screen = h.screen();
+        assert!(screen.contains(error), "{}: {}", spec, screen);
+        h.press("esc");
+    }
+    assert_eq!(modified(&h, "a.txt"), before);
+}
+
+#[test]
+fn epoch_and_relative_times_are_set() {
+    let mut h = Harness::new(&["a.txt"]);
+    h.select("a.txt");
+    touch(&mut h, "@1000000000");
+    assert_eq!(modified(&h, "a.txt"), 1_000_000_000);
+
+    touch(&mut h, "-2h");
+    let now = UNIX_EPOCH.elapsed().unwrap();
+    let set = Duration::from_secs(modified(&h, "a.txt"));
+    assert!(now - set >= Duration::from_secs(7190) && now - set <= Duration::from_secs(7210));
+}
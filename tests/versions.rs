@@ -0,0 +1,52 @@
+mod common;
+
+use browrs::config::Config;
+use common::{Fixture, Harness, test_config};
+
+fn keeping(entries: &[&str]) -> Harness {
+    let config = Config {
+        keep_versions: 2,
+        ..test_config()
+    };
+    Harness::with_config(entries, config)
+}
+
+// One test, as it points the whole process's cache somewhere else
+#[test]
+fn deleting_keeps_what_fits_in_the_history_and_never_fails_for_it() {
+    let cache = Fixture::new(&[]);
+    unsafe { std::env::set_var("XDG_CACHE_HOME", cache.path("")) };
+    let history = cache.path("browrs/history");
+
+    let mut h = keeping(&["small.txt=keep me", "big/"]);
+    for i in 0..1100 {
+        h.fixture.add(&format!("big/{i}.txt={i}"));
+    }
+    h.select("small.txt");
+    h.press("D y");
+    h.finish_jobs();
+    assert!(!h.fixture.exists("small.txt"));
+    assert_eq!(std::fs::read_dir(&history).unwrap().count(), 1);
+
+    h.select("big/");
+    h.press("D y");
+    h.finish_jobs();
+    assert!(!h.fixture.exists("big"));
+    assert!(
+        h.screen().contains("1 entries went without a copy"),
+        "{}",
+        h.screen()
+    );
+    assert_eq!(std::fs::read_dir(&history).unwrap().count(), 1001);
+
+    // With nowhere to keep copies the delete still goes ahead
+    std::fs::remove_dir_all(&history).unwrap();
+    cache.add("browrs/history=not a directory");
+    h.fixture.add("other.txt=gone anyway");
+    h.press("R");
+    h.select("other.txt");
+    h.press("D y");
+    h.finish_jobs();
+    assert!(!h.fixture.exists("other.txt"));
+    assert!(!h.screen().contains("❌"), "{}", h.screen());
+}
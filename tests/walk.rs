@@ -0,0 +1,102 @@
+mod common;
+
+use std::{os::unix::fs::symlink, path::Path};
+
+use browrs::{config::Config, walk::Policy};
+use common::{Fixture, Harness, test_config};
+
+fn walked(policy: &Policy, root: &Path) -> Vec<String> {
+    policy
+        .walk(root, false)
+        .map(|found| found.path.strip_prefix(root).unwrap().display().to_string())
+        .collect()
+}
+
+#[test]
+fn depth_entries_and_patterns_limit_the_walk() {
+    let fixture = Fixture::new(&[
+        "a/b/c/deep.txt",
+        "a/top.txt",
+        "build/out.o",
+        "x.log",
+        "y.txt",
+    ]);
+    let root = fixture.path("");
+    let policy = Policy {
+        max_depth: 2,
+        ..Policy::default()
+    };
+    assert_eq!(
+        walked(&policy, &root),
+        [
+            "a",
+            "build",
+            "x.log",
+            "y.txt",
+            "build/out.o",
+            "a/b",
+            "a/top.txt"
+        ]
+    );
+
+    let policy = Policy {
+        exclude: vec!["build/".to_string(), "*.log".to_string()],
+        ..Policy::default()
+    };
+    let all = walked(&policy, &root);
+    assert!(
+        !all.iter()
+            .any(|p| p.starts_with("build") || p.ends_with(".log"))
+    );
+    assert!(all.contains(&"a/b/c/deep.txt".to_string()), "{:?}", all);
+
+    let policy = Policy {
+        max_entries: 3,
+        ..Policy::default()
+    };
+    let mut walk = policy.walk(&root, false);
+    assert_eq!(walk.by_ref().count(), 3);
+    assert!(walk.stopped());
+}
+
+#[test]
+fn followed_links_in_circles_end() {
+    let fixture = Fixture::new(&["tree/file.txt", "other/far.txt"]);
+    symlink(fixture.path("tree"), fixture.path("tree/loop")).unwrap();
+    symlink(fixture.path("other"), fixture.path("tree/away")).unwrap();
+    let root = fixture.path("tree");
+
+    assert_eq!(
+        walked(&Policy::default(), &root),
+        ["away", "file.txt", "loop"]
+    );
+    let policy = Policy {
+        follow_symlinks: true,
+        ..Policy::default()
+    };
+    assert_eq!(
+        walked(&policy, &root),
+        ["away", "file.txt", "loop", "away/far.txt"]
+    );
+}
+
+#[test]
+fn config_limits_apply_to_the_recursive_tools() {
+    let config = Config {
+        walk: Config::parse("max_depth = 1\nexclude = \"*.bak\"\n").walk,
+        ..test_config()
+    };
+    let mut h = Harness::with_config(&["big/inner.txt", "small.txt", "old.bak"], config);
+    std::fs::write(h.fixture.path("big/inner.txt"), "12345").unwrap();
+    std::fs::write(h.fixture.path("small.txt"), "1").unwrap();
+    std::fs::write(h.fixture.path("old.bak"), "123").unwrap();
+    h.press(":");
+    h.type_text("export du usage.csv");
+    h.press("enter");
+    h.finish_jobs();
+    // Nothing below the top is gone through, so the directory adds up to 0
+    assert_eq!(
+        h.fixture.read("usage.csv"),
+        "name,type,size\nsmall.txt,file,1\nbig,directory,0\n"
+    );
+}
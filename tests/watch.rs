@@ -0,0 +1,55 @@
+mod common;
+
+use std::time::Duration;
+
+use browrs::config::Config;
+use common::{Harness, test_config};
+
+fn watching(entries: &[&str]) -> Harness {
+    let config = Config {
+        watch_interval_ms: 20,
+        ..test_config()
+    };
+    Harness::with_config(entries, config)
+}
+
+/// Lets the watcher take its first look at what is shown.
+fn settle(h: &mut Harness) {
+    for _ in 0..5 {
+        h.app.tick();
+        std::thread::sleep(Duration::from_millis(20));
+    }
+}
+
+#[test]
+fn entries_made_or_removed_elsewhere_show_up_by_themselves() {
+    let mut h = watching(&["a.txt", "c.txt", "sub/"]);
+    h.select("c.txt");
+    settle(&mut h);
+    h.fixture.add("b.txt");
+    h.wait_until(|h| h.listing().contains(&"b.txt".to_string()));
+    assert_eq!(h.selected(), "c.txt");
+
+    std::fs::remove_file(h.fixture.path("a.txt")).unwrap();
+    h.wait_until(|h| !h.listing().contains(&"a.txt".to_string()));
+    assert_eq!(h.selected(), "c.txt");
+}
+
+#[test]
+fn the_preview_follows_a_file_changed_elsewhere() {
+    let mut h = watching(&["notes.txt=first draft"]);
+    h.select("notes.txt");
+    assert!(h.screen().contains("first draft"));
+    settle(&mut h);
+    std::fs::write(h.fixture.path("notes.txt"), "second draft, longer").unwrap();
+    h.wait_until(|h| h.screen().contains("second draft, longer"));
+}
+
+#[test]
+fn nothing_is_watched_with_no_interval() {
+    let mut h = Harness::new(&["a.txt"]);
+    settle(&mut h);
+    h.fixture.add("b.txt");
+    settle(&mut h);
+    assert!(!h.listing().contains(&"b.txt".to_string()));
+}